@@ -0,0 +1,80 @@
+use crate::i18n::tr;
+
+// Перечислители программных устройств: такие адаптеры создаёт драйвер
+// (туннели, VPN, виртуальные коммутаторы), а не оборудование
+const VIRTUAL_PREFIXES: [&str; 2] = ["ROOT\\", "SWD\\"];
+
+/// Драйверы перехвата пакетов (Win32_SystemDriver.Name) и их названия
+pub const CAPTURE_DRIVERS: [(&str, &str); 3] = [
+    ("npcap", "Npcap"),
+    ("npcap_wifi", "Npcap"),
+    ("npf", "WinPcap"),
+];
+
+/// Адаптер создан программно, а не устройством
+pub fn is_virtual(pnp_device_id: &str) -> bool {
+    VIRTUAL_PREFIXES
+        .iter()
+        .any(|prefix| pnp_device_id.to_uppercase().starts_with(prefix))
+}
+
+/// Название драйвера перехвата по имени системного драйвера
+pub fn capture_driver(name: &str) -> Option<&'static str> {
+    CAPTURE_DRIVERS
+        .iter()
+        .find(|(driver, _)| driver.eq_ignore_ascii_case(name))
+        .map(|&(_, title)| title)
+}
+
+/// Адаптер самого драйвера перехвата, например Npcap Loopback Adapter
+pub fn capture_adapter(service: &str, name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    if service.eq_ignore_ascii_case("npcap_loopback") || name.contains("npcap") {
+        Some("Npcap")
+    } else if name.contains("winpcap") {
+        Some("WinPcap")
+    } else {
+        None
+    }
+}
+
+/// Состояние подключения по NetConnectionStatus
+pub fn connection_state(code: i64) -> Option<&'static str> {
+    let key = match code {
+        0 => "netstatus.disconnected",
+        1 => "netstatus.connecting",
+        2 => "netstatus.connected",
+        3 => "netstatus.disconnecting",
+        4 => "netstatus.not_present",
+        5 => "netstatus.disabled",
+        6 => "netstatus.malfunction",
+        7 => "netstatus.media_disconnected",
+        8 => "netstatus.authenticating",
+        9 => "netstatus.authenticated",
+        10 => "netstatus.auth_failed",
+        11 => "netstatus.invalid_address",
+        12 => "netstatus.credentials_required",
+        _ => return None,
+    };
+    Some(tr(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn software_adapters_and_capture_drivers() {
+        assert!(is_virtual("ROOT\\NET\\0000"));
+        assert!(is_virtual("swd\\msrras\\ms_ndiswanip"));
+        assert!(!is_virtual(
+            "PCI\\VEN_8086&DEV_15BC&SUBSYS_08B01028&REV_10\\3&11583659&0&FE"
+        ));
+        assert_eq!(capture_driver("NPF"), Some("WinPcap"));
+        assert_eq!(capture_driver("ndis"), None);
+        assert_eq!(capture_adapter("", "Npcap Loopback Adapter"), Some("Npcap"));
+        assert_eq!(capture_adapter("e1dexpress", "Intel(R) Ethernet"), None);
+        assert_eq!(connection_state(2), Some(tr("netstatus.connected")));
+        assert_eq!(connection_state(99), None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::i18n::trf;
+use std::fmt;
+use std::marker::PhantomData;
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT};
+use wmi::COMLibrary;
+
+// Коды возврата CoInitializeEx
+const S_OK: i32 = 0;
+const S_FALSE: i32 = 1;
+const RPC_E_CHANGED_MODE: i32 = 0x80010106_u32 as i32;
+
+/// Чем закончился вызов CoInitializeEx, после которого COM потока можно использовать
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Init {
+    /// S_OK: COM инициализирован этим вызовом
+    Initialized,
+    /// S_FALSE: поток уже был инициализирован в том же режиме, счётчик увеличен
+    AlreadyInitialized,
+    /// RPC_E_CHANGED_MODE: поток уже работает в другом режиме, счётчик не изменился
+    ChangedMode,
+}
+
+/// Ошибка инициализации COM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitError {
+    /// CoInitializeEx вернул код ошибки
+    Failed(i32),
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::Failed(code) => {
+                let code = format!("0x{:08X}", *code as u32);
+                f.write_str(&trf("error.com_init", &[("code", &code)]))
+            }
+        }
+    }
+}
+
+impl std::error::Error for InitError {}
+
+/// Разбор кода CoInitializeEx
+pub fn classify(code: i32) -> Result<Init, InitError> {
+    match code {
+        S_OK => Ok(Init::Initialized),
+        S_FALSE => Ok(Init::AlreadyInitialized),
+        RPC_E_CHANGED_MODE => Ok(Init::ChangedMode),
+        code => Err(InitError::Failed(code)),
+    }
+}
+
+// Кто отвечает за COM потока. S_OK и S_FALSE увеличивают счётчик инициализаций,
+// и каждый такой вызов должен быть закрыт своим CoUninitialize. При RPC_E_CHANGED_MODE
+// COM потока настроил кто-то другой, и освобождать чужую инициализацию нельзя
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Owned,
+    Borrowed,
+    Released,
+}
+
+impl State {
+    fn new(init: Init) -> Self {
+        match init {
+            Init::Initialized | Init::AlreadyInitialized => State::Owned,
+            Init::ChangedMode => State::Borrowed,
+        }
+    }
+
+    // `true`, если нужно вызвать CoUninitialize; повторное освобождение ничего не делает
+    fn release(&mut self) -> bool {
+        let owned = *self == State::Owned;
+        if owned {
+            *self = State::Released;
+        }
+        owned
+    }
+}
+
+/// Инициализация COM текущего потока. При удалении вызывает CoUninitialize, если
+/// инициализация была своя. Подключения к WMI должны быть удалены раньше
+pub struct Apartment {
+    state: State,
+    _thread: PhantomData<*mut ()>, // CoUninitialize вызывается в том же потоке
+}
+
+impl Apartment {
+    /// Инициализирует COM текущего потока в режиме `flags`
+    pub fn init(flags: COINIT) -> Result<Self, InitError> {
+        let init = classify(unsafe { CoInitializeEx(None, flags) }.0)?;
+        tracing::info!(?init, "COM инициализирован");
+        Ok(Self {
+            state: State::new(init),
+            _thread: PhantomData,
+        })
+    }
+
+    pub fn com(&self) -> COMLibrary {
+        // COM этого потока инициализирован, пока жив `self`
+        unsafe { COMLibrary::assume_initialized() }
+    }
+}
+
+impl Drop for Apartment {
+    fn drop(&mut self) {
+        if self.state.release() {
+            unsafe { CoUninitialize() };
+            tracing::info!("COM освобождён");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_classified() {
+        assert_eq!(classify(S_OK), Ok(Init::Initialized));
+        assert_eq!(classify(S_FALSE), Ok(Init::AlreadyInitialized));
+        assert_eq!(classify(RPC_E_CHANGED_MODE), Ok(Init::ChangedMode));
+        let e_outofmemory = 0x8007000E_u32 as i32;
+        assert_eq!(
+            classify(e_outofmemory),
+            Err(InitError::Failed(e_outofmemory))
+        );
+    }
+
+    #[test]
+    fn own_initialization_is_released_once() {
+        for init in [Init::Initialized, Init::AlreadyInitialized] {
+            let mut state = State::new(init);
+            assert_eq!(state, State::Owned);
+            assert!(state.release());
+            assert_eq!(state, State::Released);
+            assert!(!state.release());
+        }
+    }
+
+    #[test]
+    fn foreign_initialization_is_never_released() {
+        let mut state = State::new(Init::ChangedMode);
+        assert_eq!(state, State::Borrowed);
+        assert!(!state.release());
+        assert_eq!(state, State::Borrowed);
+    }
+}
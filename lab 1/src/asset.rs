@@ -0,0 +1,215 @@
+use crate::i18n::tr;
+use crate::model::{ResultSet, Value};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Сведение для учёта оборудования: ключ в JSON, подпись и свойство WMI
+pub struct Field {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub class: &'static str,
+    pub property: &'static str,
+    checked: bool, // Проверяется ли на заглушку производителя
+}
+
+pub const FIELDS: [Field; 9] = [
+    Field {
+        key: "hostname",
+        label: "asset.hostname",
+        class: "Win32_OperatingSystem",
+        property: "CSName",
+        checked: false,
+    },
+    Field {
+        key: "uuid",
+        label: "asset.uuid",
+        class: "Win32_ComputerSystemProduct",
+        property: "UUID",
+        checked: true,
+    },
+    Field {
+        key: "vendor",
+        label: "asset.vendor",
+        class: "Win32_ComputerSystemProduct",
+        property: "Vendor",
+        checked: true,
+    },
+    Field {
+        key: "model",
+        label: "asset.model",
+        class: "Win32_ComputerSystemProduct",
+        property: "Name",
+        checked: true,
+    },
+    Field {
+        key: "identifying_number",
+        label: "asset.identifying_number",
+        class: "Win32_ComputerSystemProduct",
+        property: "IdentifyingNumber",
+        checked: true,
+    },
+    Field {
+        key: "bios_serial",
+        label: "asset.bios_serial",
+        class: "Win32_BIOS",
+        property: "SerialNumber",
+        checked: true,
+    },
+    Field {
+        key: "asset_tag",
+        label: "asset.asset_tag",
+        class: "Win32_SystemEnclosure",
+        property: "SMBIOSAssetTag",
+        checked: true,
+    },
+    Field {
+        key: "mac",
+        label: "asset.mac",
+        class: "Win32_NetworkAdapterConfiguration",
+        property: "MACAddress",
+        checked: false,
+    },
+    Field {
+        key: "os_product_id",
+        label: "asset.os_product_id",
+        class: "Win32_OperatingSystem",
+        property: "SerialNumber",
+        checked: false,
+    },
+];
+
+// Значения, которые производители оставляют в SMBIOS вместо настоящих, в нижнем регистре
+const PLACEHOLDERS: [&str; 17] = [
+    "default string",
+    "to be filled by o.e.m.",
+    "to be filled by oem",
+    "o.e.m.",
+    "oem",
+    "system manufacturer",
+    "system product name",
+    "system serial number",
+    "chassis serial number",
+    "not specified",
+    "not applicable",
+    "no asset tag",
+    "no asset information",
+    "none",
+    "n/a",
+    "123456789",
+    // UUID из образца прошивки AMI
+    "03000200-0400-0500-0006-000700080009",
+];
+
+/// Заглушка вместо идентификатора: известные значения по умолчанию и номера
+/// из одних нулей или F, например UUID 00000000-0000-0000-0000-000000000000
+pub fn placeholder(value: &str) -> bool {
+    let value = value.trim().to_lowercase();
+    let digits: Vec<char> = value.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+    PLACEHOLDERS.contains(&value.as_str())
+        || (!digits.is_empty() && digits.iter().all(|&c| c == '0'))
+        || (digits.len() > 1 && digits.iter().all(|&c| c == 'f'))
+}
+
+/// Сводка «сведение — значение» в порядке [`FIELDS`]. Пустые значения заглушкой
+/// не считаются: метки инвентаризации у большинства компьютеров просто нет
+pub fn result_set(values: &HashMap<&str, Value>) -> ResultSet {
+    let mut set =
+        ResultSet::new(&["Indicator", "Observed", "Source", "Placeholder"]).with_extra(&["Key"]);
+    for field in &FIELDS {
+        let value = values.get(field.key).cloned().unwrap_or(Value::Null);
+        let flag = match &value {
+            Value::Text(text) if field.checked => Value::Bool(placeholder(text)),
+            _ => Value::Null,
+        };
+        set.rows.push(vec![
+            tr(field.label).into(),
+            value,
+            format!("{}.{}", field.class, field.property).into(),
+            flag,
+        ]);
+        set.extra.rows.push(vec![field.key.into()]);
+    }
+    set
+}
+
+// Запись для CMDB: поля в порядке FIELDS, затем ключи ненадёжных значений
+#[derive(Serialize)]
+struct Record<'a> {
+    hostname: Option<&'a str>,
+    uuid: Option<&'a str>,
+    vendor: Option<&'a str>,
+    model: Option<&'a str>,
+    identifying_number: Option<&'a str>,
+    bios_serial: Option<&'a str>,
+    asset_tag: Option<&'a str>,
+    mac: Option<&'a str>,
+    os_product_id: Option<&'a str>,
+    unreliable: Vec<&'a str>,
+}
+
+/// Сводка в JSON для вставки в базу учёта. Ключи не зависят от языка окна
+pub fn to_json(set: &ResultSet) -> String {
+    let rows: HashMap<&str, usize> = (0..set.rows.len())
+        .filter_map(|row| match set.extra_value(row, "Key") {
+            Some(Value::Text(key)) => Some((key.as_str(), row)),
+            _ => None,
+        })
+        .collect();
+    let text = |key: &str| match set.value(*rows.get(key)?, "Observed") {
+        Some(Value::Text(text)) => Some(text.as_str()),
+        _ => None,
+    };
+    let record = Record {
+        hostname: text("hostname"),
+        uuid: text("uuid"),
+        vendor: text("vendor"),
+        model: text("model"),
+        identifying_number: text("identifying_number"),
+        bios_serial: text("bios_serial"),
+        asset_tag: text("asset_tag"),
+        mac: text("mac"),
+        os_product_id: text("os_product_id"),
+        unreliable: FIELDS
+            .iter()
+            .map(|field| field.key)
+            .filter(|key| {
+                rows.get(key).and_then(|&row| set.value(row, "Placeholder"))
+                    == Some(&Value::Bool(true))
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&record).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendor_defaults_are_placeholders() {
+        assert!(placeholder("Default string"));
+        assert!(placeholder(" To be filled by O.E.M. "));
+        assert!(placeholder("00000000-0000-0000-0000-000000000000"));
+        assert!(placeholder("FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF"));
+        assert!(!placeholder("4C4C4544-0052-3510-8057-B4C04F4B3732"));
+        assert!(!placeholder("PF3ABCDE"));
+        // Одиночная F — настоящее значение, а не заполнение
+        assert!(!placeholder("F"));
+    }
+
+    #[test]
+    fn json_keeps_keys_and_lists_unreliable() {
+        let values = HashMap::from([
+            ("hostname", "WS-01".into()),
+            ("uuid", "4C4C4544-0052-3510-8057-B4C04F4B3732".into()),
+            ("bios_serial", "Default string".into()),
+        ]);
+        let set = result_set(&values);
+        assert_eq!(set.value(5, "Placeholder"), Some(&Value::Bool(true)));
+        assert_eq!(set.value(6, "Placeholder"), Some(&Value::Null));
+        let json: serde_json::Value = serde_json::from_str(&to_json(&set)).unwrap();
+        assert_eq!(json["hostname"], "WS-01");
+        assert_eq!(json["asset_tag"], serde_json::Value::Null);
+        assert_eq!(json["unreliable"], serde_json::json!(["bios_serial"]));
+    }
+}
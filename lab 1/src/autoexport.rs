@@ -0,0 +1,125 @@
+use crate::config;
+use crate::export;
+use crate::i18n::tr;
+use crate::model::ExportFile;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PREFIX: &str = "autoexport_";
+
+/// Сколько последних автоэкспортов хранится; более старые удаляются
+pub const KEEP: usize = 10;
+
+/// Раздел `[autoexport]`: снимок всех категорий с данными при закрытии программы
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub dir: String, // Пусто — каталог autoexport рядом с программой
+}
+
+impl Settings {
+    pub fn dir(&self) -> PathBuf {
+        match self.dir.trim() {
+            "" => config::exe_dir().join("autoexport"),
+            dir => PathBuf::from(dir),
+        }
+    }
+
+    /// Поля для меню настроек
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, tr("autoexport.enabled"))
+            .on_hover_text(tr("autoexport.hint"));
+        let default = self.dir().display().to_string();
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("autoexport.dir"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.dir)
+                        .hint_text(default)
+                        .desired_width(180.0),
+                );
+            });
+        });
+    }
+}
+
+/// Имя файла: время идёт перед компьютером, поэтому по имени файлы упорядочены по времени
+pub fn file_name(host: &str, time: DateTime<Local>) -> String {
+    format!("{PREFIX}{}_{host}.json", time.format("%Y%m%d_%H%M%S"))
+}
+
+/// Файлы автоэкспорта в каталоге, от новых к старым
+pub fn list(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(PREFIX) && name.ends_with(".json"))
+        })
+        .collect();
+    files.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    files
+}
+
+/// Последний автоэкспорт; `None`, если их ещё не было
+pub fn latest(dir: &Path) -> Option<PathBuf> {
+    list(dir).into_iter().next()
+}
+
+// Удаляет автоэкспорты старше `keep` последних
+fn rotate(dir: &Path, keep: usize) {
+    for old in list(dir).into_iter().skip(keep) {
+        if let Err(e) = std::fs::remove_file(&old) {
+            tracing::warn!(path = %old.display(), error = %e, "старый автоэкспорт не удалён");
+        }
+    }
+}
+
+/// Записывает файл в каталог и оставляет [`KEEP`] последних
+pub fn write(dir: &Path, file: &ExportFile, time: DateTime<Local>) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(&file.hostname, time));
+    export::write_json(&path, file)?;
+    rotate(dir, KEEP);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn only_latest_exports_are_kept() {
+        let dir = std::env::temp_dir().join("lab1_autoexport_test");
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        // Чужой файл в каталоге не трогается
+        std::fs::write(dir.join("notes.json"), "{}").unwrap();
+        let file = ExportFile::new("WS-01".to_string(), Vec::new());
+        for minute in 0..12 {
+            let time = Local.with_ymd_and_hms(2024, 5, 1, 12, minute, 0).unwrap();
+            write(&dir, &file, time).unwrap();
+        }
+        let kept = list(&dir);
+        let latest = latest(&dir);
+        let foreign = dir.join("notes.json").exists();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(kept.len(), KEEP);
+        assert_eq!(
+            latest.unwrap().file_name().unwrap(),
+            "autoexport_20240501_121100_WS-01.json"
+        );
+        assert!(kept
+            .iter()
+            .all(|path| path.file_name().unwrap() > "autoexport_20240501_120100"));
+        assert!(foreign);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::cache;
+use crate::connection::Connection;
+use crate::error::AppError;
+use crate::i18n::{tr, trf};
+use crate::model::ResultSet;
+use crate::queries;
+use crate::table::TableView;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Обзор классов пространства имён и вывод экземпляров выбранного класса
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassBrowser {
+    search: String,
+    view: TableView,
+    #[serde(skip)]
+    classes: HashMap<String, Result<Vec<String>, AppError>>, // Список классов по пространствам имён
+    #[serde(skip)]
+    selected: Option<String>,
+    #[serde(skip)]
+    result: Option<Result<ResultSet, AppError>>,
+    #[serde(skip)]
+    revision: u64, // Версия результата для порядка строк таблицы
+}
+
+impl ClassBrowser {
+    /// Заголовки результата после смены языка
+    pub fn retitle(&mut self) {
+        if let Some(Ok(set)) = &mut self.result {
+            set.retitle();
+        }
+    }
+
+    /// Сбрасывает выбранный класс при смене пространства имён
+    pub fn reset(&mut self) {
+        self.selected = None;
+        self.result = None;
+    }
+
+    fn select(&mut self, con: &Connection, class: &str) {
+        self.selected = Some(class.to_string());
+        self.view.selected = None;
+        self.view.page = 0;
+        self.result = Some(queries::raw(con, &format!("SELECT * FROM {class}")));
+        self.revision = cache::next_revision();
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, namespace: &str, con: &Connection) {
+        let mut reload = !self.classes.contains_key(namespace);
+        let mut chosen = None;
+
+        egui::SidePanel::left("class_list")
+            .resizable(true)
+            .default_width(220.0)
+            .show_inside(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.search)
+                            .hint_text(tr("browser.search"))
+                            .desired_width(140.0),
+                    );
+                    reload |= ui.button("⟳").on_hover_text(tr("browser.reload")).clicked();
+                });
+                match self.classes.get(namespace) {
+                    Some(Ok(classes)) => {
+                        let needle = self.search.to_lowercase();
+                        let shown: Vec<_> = classes
+                            .iter()
+                            .filter(|c| c.to_lowercase().contains(&needle))
+                            .collect();
+                        ui.weak(trf("browser.count", &[("count", &shown.len())]));
+                        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                        egui::ScrollArea::vertical().show_rows(
+                            ui,
+                            row_height,
+                            shown.len(),
+                            |ui, range| {
+                                for class in &shown[range] {
+                                    let active = self.selected.as_ref() == Some(*class);
+                                    if ui.selectable_label(active, class.as_str()).clicked() {
+                                        chosen = Some(class.to_string());
+                                    }
+                                }
+                            },
+                        );
+                    }
+                    Some(Err(error)) => error.show(ui),
+                    None => {}
+                }
+            });
+
+        if reload {
+            let classes = queries::classes(con);
+            self.classes.insert(namespace.to_string(), classes);
+        }
+        if let Some(class) = chosen {
+            self.select(con, &class);
+        }
+
+        match (&self.selected, &self.result) {
+            (Some(class), Some(Ok(set))) => {
+                ui.strong(trf(
+                    "browser.instances",
+                    &[("class", class), ("count", &set.rows.len())],
+                ));
+                self.view
+                    .show_result(ui, set, self.revision, "browser_result");
+            }
+            (_, Some(Err(error))) => error.show(ui),
+            _ => {
+                ui.label(tr("browser.empty"));
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use crate::i18n::{tr, trf};
+use crate::model::ResultSet;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// Номера версий общие для всех кэшей: данные окна и просматриваемого файла
+// не получают одинаковых номеров
+static REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// Новый номер версии данных для результатов вне кэша
+pub fn next_revision() -> u64 {
+    REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Последний удачный результат каждой категории и время его получения
+pub struct Cache<K> {
+    entries: HashMap<K, Entry>,
+}
+
+struct Entry {
+    set: ResultSet,
+    time: DateTime<Local>, // Для показа пользователю
+    at: Instant,           // Для срока годности; не зависит от перевода часов
+    revision: u64,         // Меняется при каждой правке данных
+}
+
+impl<K> Default for Cache<K> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> Cache<K> {
+    pub fn get(&self, key: &K) -> Option<&ResultSet> {
+        self.entries.get(key).map(|entry| &entry.set)
+    }
+
+    /// Правка данных на месте; время получения не меняется. `edit` возвращает
+    /// `true`, если данные изменились, и тогда у них появляется новая версия
+    pub fn update(&mut self, key: &K, edit: impl FnOnce(&mut ResultSet) -> bool) -> bool {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return false;
+        };
+        let changed = edit(&mut entry.set);
+        if changed {
+            entry.revision = next_revision();
+        }
+        changed
+    }
+
+    /// Версия данных категории; 0 — данных нет. Таблица пересчитывает порядок
+    /// строк, только когда версия меняется
+    pub fn revision(&self, key: &K) -> u64 {
+        self.entries.get(key).map_or(0, |entry| entry.revision)
+    }
+
+    pub fn insert(&mut self, key: K, set: ResultSet) {
+        self.insert_at(key, set, Instant::now(), Local::now());
+    }
+
+    /// Данные из снимка: показываются как полученные в `time`, а срок годности
+    /// отсчитывается от загрузки, чтобы они не запрашивались сразу заново
+    pub fn restore(&mut self, key: K, set: ResultSet, time: DateTime<Local>) {
+        self.insert_at(key, set, Instant::now(), time);
+    }
+
+    fn insert_at(&mut self, key: K, set: ResultSet, at: Instant, time: DateTime<Local>) {
+        let revision = next_revision();
+        self.entries.insert(
+            key,
+            Entry {
+                set,
+                time,
+                at,
+                revision,
+            },
+        );
+    }
+
+    /// Когда получены данные категории
+    pub fn fetched(&self, key: &K) -> Option<DateTime<Local>> {
+        self.entries.get(key).map(|entry| entry.time)
+    }
+
+    /// Сколько прошло с получения данных категории к моменту `now`
+    pub fn age(&self, key: &K, now: DateTime<Local>) -> Option<Duration> {
+        self.fetched(key)
+            .map(|time| (now - time).to_std().unwrap_or_default())
+    }
+
+    /// Нужен ли запрос при выборе категории: данных нет или они старше `ttl`.
+    /// Некэшируемые категории (`cached == false`) запрашиваются каждый раз
+    pub fn needs_query(&self, key: &K, cached: bool, ttl: Ttl, now: Instant) -> bool {
+        let fresh = self
+            .entries
+            .get(key)
+            .is_some_and(|entry| now.saturating_duration_since(entry.at) < ttl.duration());
+        !cached || !fresh
+    }
+
+    pub fn sets_mut(&mut self) -> impl Iterator<Item = &mut ResultSet> {
+        self.entries.values_mut().map(|entry| &mut entry.set)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Сколько секунд данные категории считаются свежими; 0 — запрашивать при каждом выборе
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ttl(u64);
+
+impl Default for Ttl {
+    fn default() -> Self {
+        Ttl(60)
+    }
+}
+
+impl Ttl {
+    pub fn duration(self) -> Duration {
+        Duration::from_secs(self.0)
+    }
+
+    /// Поле настройки для меню параметров
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("cache.ttl_label"));
+            ui.add(
+                egui::DragValue::new(&mut self.0)
+                    .range(0..=3600)
+                    .suffix(tr("query.seconds")),
+            );
+        });
+    }
+}
+
+/// Насколько устарели показанные данные
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Age {
+    Fresh,
+    Aging, // Старше срока годности
+    Stale, // Старше предела категории
+}
+
+/// Пороги устаревания категории; `None` — порога нет
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub aging: Option<Duration>,
+    pub stale: Option<Duration>,
+}
+
+impl Thresholds {
+    pub fn age(&self, elapsed: Duration) -> Age {
+        let past = |limit: Option<Duration>| limit.is_some_and(|limit| elapsed > limit);
+        if past(self.stale) {
+            Age::Stale
+        } else if past(self.aging) {
+            Age::Aging
+        } else {
+            Age::Fresh
+        }
+    }
+}
+
+/// Давность вроде «5 мин назад»
+pub fn ago(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    match seconds {
+        0..60 => trf("age.seconds", &[("n", &seconds)]),
+        60..3600 => trf("age.minutes", &[("n", &(seconds / 60))]),
+        _ => trf("age.hours", &[("n", &(seconds / 3600))]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Источник данных вместо WMI: считает запросы по категориям
+    #[derive(Default)]
+    struct FakeSource {
+        queries: HashMap<&'static str, usize>,
+    }
+
+    impl FakeSource {
+        fn query(&mut self, key: &'static str) -> ResultSet {
+            *self.queries.entry(key).or_default() += 1;
+            ResultSet::new(&["Name"])
+        }
+
+        fn count(&self, key: &str) -> usize {
+            self.queries.get(key).copied().unwrap_or(0)
+        }
+    }
+
+    // То же, что выбор категории в окне
+    fn select(
+        cache: &mut Cache<&'static str>,
+        source: &mut FakeSource,
+        key: &'static str,
+        cached: bool,
+        now: Instant,
+    ) {
+        if cache.needs_query(&key, cached, Ttl::default(), now) {
+            cache.insert_at(key, source.query(key), now, Local::now());
+        }
+    }
+
+    #[test]
+    fn fresh_data_is_not_queried_again() {
+        let (mut cache, mut source) = (Cache::default(), FakeSource::default());
+        let start = Instant::now();
+        select(&mut cache, &mut source, "env", true, start);
+        select(&mut cache, &mut source, "devices", true, start);
+        select(
+            &mut cache,
+            &mut source,
+            "env",
+            true,
+            start + Duration::from_secs(59),
+        );
+        assert_eq!(source.count("env"), 1);
+        assert_eq!(source.count("devices"), 1);
+        assert!(cache.get(&"env").is_some());
+    }
+
+    #[test]
+    fn stale_data_is_queried_again() {
+        let (mut cache, mut source) = (Cache::default(), FakeSource::default());
+        let start = Instant::now();
+        select(&mut cache, &mut source, "env", true, start);
+        select(
+            &mut cache,
+            &mut source,
+            "env",
+            true,
+            start + Duration::from_secs(60),
+        );
+        assert_eq!(source.count("env"), 2);
+    }
+
+    #[test]
+    fn uncached_category_is_always_queried() {
+        let (mut cache, mut source) = (Cache::default(), FakeSource::default());
+        let start = Instant::now();
+        select(&mut cache, &mut source, "log", false, start);
+        select(&mut cache, &mut source, "log", false, start);
+        assert_eq!(source.count("log"), 2);
+    }
+
+    #[test]
+    fn zero_ttl_disables_cache() {
+        let mut cache = Cache::default();
+        let now = Instant::now();
+        cache.insert_at("env", ResultSet::new(&["Name"]), now, Local::now());
+        assert!(cache.needs_query(&"env", true, Ttl(0), now));
+        assert!(!cache.needs_query(&"env", true, Ttl(1), now));
+    }
+
+    #[test]
+    fn age_passes_thresholds_in_order() {
+        let minutes = |n: u64| Duration::from_secs(n * 60);
+        let thresholds = Thresholds {
+            aging: Some(minutes(1)),
+            stale: Some(minutes(5)),
+        };
+        assert_eq!(thresholds.age(minutes(1)), Age::Fresh);
+        assert_eq!(thresholds.age(minutes(2)), Age::Aging);
+        assert_eq!(thresholds.age(minutes(6)), Age::Stale);
+        let unlimited = Thresholds {
+            aging: None,
+            stale: None,
+        };
+        assert_eq!(unlimited.age(minutes(600)), Age::Fresh);
+    }
+
+    #[test]
+    fn revision_changes_with_data() {
+        let mut cache = Cache::default();
+        assert_eq!(cache.revision(&"env"), 0);
+        cache.insert("env", ResultSet::new(&["Name"]));
+        let first = cache.revision(&"env");
+        assert!(!cache.update(&"env", |_| false));
+        assert_eq!(cache.revision(&"env"), first);
+        assert!(cache.update(&"env", |set| {
+            set.rows.push(vec!["PATH".into()]);
+            true
+        }));
+        let second = cache.revision(&"env");
+        assert_ne!(second, first);
+        cache.insert("env", ResultSet::new(&["Name"]));
+        assert_ne!(cache.revision(&"env"), second);
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let mut cache = Cache::default();
+        cache.insert("env", ResultSet::new(&["Name"]));
+        assert!(cache.fetched(&"env").is_some());
+        cache.clear();
+        assert!(cache.get(&"env").is_none());
+        assert!(cache.needs_query(&"env", true, Ttl::default(), Instant::now()));
+    }
+}
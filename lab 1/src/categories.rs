@@ -0,0 +1,1836 @@
+use crate::adapters;
+use crate::asset;
+use crate::connectors::{self, Port};
+use crate::cooling;
+use crate::defender;
+use crate::devices;
+use crate::error::AppError;
+use crate::extensions::{self, Extension};
+use crate::i18n::{tr, trf};
+use crate::model::{wmi_datetime, Property, ResultSet, Value};
+use crate::power::{self, Plan};
+use crate::quota;
+use crate::recycle;
+use crate::source::{self, DataSource, Object, Row, CIMV2};
+use crate::uptime;
+use crate::virtualization;
+use crate::wql::quote;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+async fn rows(
+    source: &impl DataSource,
+    class: &str,
+    filter: Option<&str>,
+) -> Result<Vec<Row>, AppError> {
+    source
+        .query_rows(class, CIMV2, &source::select(class, filter))
+        .await
+}
+
+// Свойства объекта в порядке `names`; отсутствующие пусты
+fn cells(row: &mut Row, names: &[&str]) -> Vec<Value> {
+    names
+        .iter()
+        .map(|&name| row.remove(name).unwrap_or(Value::Null))
+        .collect()
+}
+
+// Таблица с основными столбцами `names` и свойствами подсказки `extra`
+async fn table(
+    source: &impl DataSource,
+    class: &str,
+    filter: Option<&str>,
+    names: &[&str],
+    extra: &[&str],
+) -> Result<ResultSet, AppError> {
+    let mut set = ResultSet::new(names).with_extra(extra);
+    (set.rows, set.extra.rows) = rows(source, class, filter)
+        .await?
+        .into_iter()
+        .map(|mut row| (cells(&mut row, names), cells(&mut row, extra)))
+        .unzip();
+    Ok(set)
+}
+
+pub async fn environment(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    let mut set = table(
+        source,
+        "Win32_Environment",
+        filter,
+        &["Name", "VariableValue", "UserName"],
+        &["SystemVariable", "Status"],
+    )
+    .await?;
+    // null у устаревших переменных пользователей
+    for row in &mut set.rows {
+        if row[1] == Value::Null {
+            row[1] = tr("value.empty").into();
+        }
+    }
+    Ok(set)
+}
+
+/// Число учётных записей каждого типа SID по возрастанию типа
+pub async fn sid_counts(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    let mut counts: BTreeMap<i64, i64> = BTreeMap::new();
+    for account in rows(source, "Win32_Account", filter).await? {
+        if let Some(Value::Int(sid_type)) = account.get("SIDType") {
+            *counts.entry(*sid_type).or_default() += 1;
+        }
+    }
+    let mut set = ResultSet::new(&["SIDType", "Count"]);
+    set.rows = counts
+        .into_iter()
+        .map(|(sid_type, count)| vec![Value::Int(sid_type), Value::Int(count)])
+        .collect();
+    Ok(set)
+}
+
+// У многих устройств Plug and Play часть свойств равна null
+pub async fn buses(source: &impl DataSource, filter: Option<&str>) -> Result<ResultSet, AppError> {
+    table(
+        source,
+        "Win32_PnPEntity",
+        filter,
+        &[
+            "DeviceID",
+            "Name",
+            "PNPClass",
+            "Manufacturer",
+            "Status",
+            "ConfigManagerErrorCode",
+        ],
+        &["Description", "Service", "HardwareID", "Present"],
+    )
+    .await
+}
+
+/// Устройства с ненулевым кодом ошибки диспетчера устройств и описанием ошибки.
+/// Без таких устройств таблица состоит из одной строки «проблемных устройств нет»
+pub async fn problem_devices(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let filter = "ConfigManagerErrorCode <> 0";
+    let mut set = ResultSet::new(&["Name", "PNPClass", "Problem", "DeviceID"]).with_extra(&[
+        "ConfigManagerErrorCode",
+        "Manufacturer",
+        "Status",
+    ]);
+    for mut device in rows(source, "Win32_PnPEntity", Some(filter)).await? {
+        let code = match device.get("ConfigManagerErrorCode") {
+            Some(Value::Int(code)) if *code != 0 => *code,
+            _ => continue,
+        };
+        let mut row = cells(&mut device, &["Name", "PNPClass"]);
+        row.push(devices::problem_text(code).into());
+        row.extend(cells(&mut device, &["DeviceID"]));
+        set.rows.push(row);
+        set.extra.rows.push(cells(
+            &mut device,
+            &["ConfigManagerErrorCode", "Manufacturer", "Status"],
+        ));
+    }
+    if set.rows.is_empty() {
+        set.rows.push(vec![
+            Value::Null,
+            Value::Null,
+            tr("devices.no_problems").into(),
+            Value::Null,
+        ]);
+        set.extra.rows.push(vec![Value::Null; 3]);
+    }
+    Ok(set)
+}
+
+// Путь к исполняемому файлу и командная строка закрыты для процессов
+// других пользователей без прав администратора и приходят как null
+pub async fn processes(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    table(
+        source,
+        "Win32_Process",
+        filter,
+        &[
+            "ProcessId",
+            "Name",
+            "ExecutablePath",
+            "CommandLine",
+            "ParentProcessId",
+            "ThreadCount",
+        ],
+        &["SessionId", "Priority", "HandleCount"],
+    )
+    .await
+}
+
+pub async fn services(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    table(
+        source,
+        "Win32_Service",
+        filter,
+        &[
+            "Name",
+            "DisplayName",
+            "State",
+            "StartMode",
+            "PathName",
+            "StartName",
+            "ProcessId",
+        ],
+        &[
+            "Description",
+            "ServiceType",
+            "AcceptStop",
+            "DelayedAutoStart",
+        ],
+    )
+    .await
+}
+
+// Программы из разделов удаления реестра; в отличие от Win32_Product запрос
+// не запускает проверку установщиком Windows. Версия и издатель бывают пустыми
+pub async fn software(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    table(
+        source,
+        "Win32_InstalledWin32Program",
+        filter,
+        &["Name", "Version", "Vendor", "Language"],
+        &["ProgramId", "MsiProductCode"],
+    )
+    .await
+}
+
+/// Сетевые адаптеры вместе с адресами из Win32_NetworkAdapterConfiguration того же
+/// адаптера (по Index, а без него — по MAC-адресу). Npcap и WinPcap подключаются ко
+/// всем физическим адаптерам, поэтому установленный драйвер перехвата указан у каждого
+pub async fn network_adapters(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    let mut set = ResultSet::new(&[
+        "Name",
+        "MACAddress",
+        "AdapterType",
+        "PNPDeviceID",
+        "ConnectionState",
+        "Virtual",
+        "IPAddress",
+        "DefaultIPGateway",
+        "CaptureDriver",
+    ])
+    .with_extra(&[
+        "DeviceID",
+        "NetConnectionID",
+        "Manufacturer",
+        "NetConnectionStatus",
+        "ServiceName",
+        "DHCPEnabled",
+        "DNSServerSearchOrder",
+    ]);
+    let adapters = rows(source, "Win32_NetworkAdapter", filter).await?;
+    let configs = rows(source, "Win32_NetworkAdapterConfiguration", None).await?;
+    let names: Vec<String> = adapters::CAPTURE_DRIVERS
+        .iter()
+        .map(|(name, _)| format!("Name = {}", quote(name)))
+        .collect();
+    let mut capture: Vec<&str> = Vec::new();
+    for driver in rows(source, "Win32_SystemDriver", Some(&names.join(" OR "))).await? {
+        if let Some(Value::Text(name)) = driver.get("Name") {
+            if let Some(title) = adapters::capture_driver(name) {
+                if !capture.contains(&title) {
+                    capture.push(title);
+                }
+            }
+        }
+    }
+    let capture = (!capture.is_empty()).then(|| capture.join(", "));
+    for mut adapter in adapters {
+        let text = |row: &Row, name| match row.get(name) {
+            Some(Value::Text(text)) => text.clone(),
+            _ => String::new(),
+        };
+        let mac = text(&adapter, "MACAddress");
+        let index = adapter.get("Index").filter(|index| **index != Value::Null);
+        let config = configs
+            .iter()
+            .find(|config| index.is_some() && config.get("Index") == index)
+            .or_else(|| {
+                configs.iter().find(|config| {
+                    !mac.is_empty() && text(config, "MACAddress").eq_ignore_ascii_case(&mac)
+                })
+            });
+        let pnp = text(&adapter, "PNPDeviceID");
+        let virtual_adapter = (!pnp.is_empty()).then(|| adapters::is_virtual(&pnp));
+        let state = match adapter.get("NetConnectionStatus") {
+            Some(Value::Int(code)) => adapters::connection_state(*code),
+            _ => None,
+        };
+        let driver = match adapters::capture_adapter(
+            &text(&adapter, "ServiceName"),
+            &text(&adapter, "Name"),
+        ) {
+            Some(title) => Some(title.to_string()),
+            None if virtual_adapter == Some(false) => capture.clone(),
+            None => None,
+        };
+        let mut config = config.cloned().unwrap_or_default();
+        let [address, gateway] = ["IPAddress", "DefaultIPGateway"]
+            .map(|name| config.remove(name).unwrap_or(Value::Null));
+        let mut row = cells(
+            &mut adapter,
+            &["Name", "MACAddress", "AdapterType", "PNPDeviceID"],
+        );
+        row.extend([
+            Value::from(state),
+            Value::from(virtual_adapter),
+            address,
+            gateway,
+            Value::from(driver),
+        ]);
+        let mut extra = cells(
+            &mut adapter,
+            &[
+                "DeviceID",
+                "NetConnectionID",
+                "Manufacturer",
+                "NetConnectionStatus",
+                "ServiceName",
+            ],
+        );
+        extra.extend(cells(&mut config, &["DHCPEnabled", "DNSServerSearchOrder"]));
+        set.rows.push(row);
+        set.extra.rows.push(extra);
+    }
+    Ok(set)
+}
+
+// Имя программы и хеш пути запуска из имени файла «ПРОГРАММА.EXE-1A2B3C4D.pf»
+fn prefetch_name(file_name: &str) -> (String, Option<String>) {
+    let stem = file_name.strip_suffix(".pf").unwrap_or(file_name);
+    match stem.rsplit_once('-') {
+        Some((program, hash)) if hash.len() == 8 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            (program.to_uppercase(), Some(hash.to_uppercase()))
+        }
+        _ => (stem.to_uppercase(), None),
+    }
+}
+
+/// Файлы предвыборки: что и когда запускалось, новые сверху. Файл создаётся при
+/// первом запуске и перезаписывается при следующих, поэтому изменение позже создания
+/// означает повторный запуск; несколько файлов одной программы — запуск из разных мест.
+/// Без каталога — пустая таблица (предвыборка выключена), её отмечает проверка
+pub async fn prefetch(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let os = rows(source, "Win32_OperatingSystem", None).await?;
+    let windows = os
+        .first()
+        .and_then(|os| os.get("WindowsDirectory"))
+        .map(Value::to_string)
+        .unwrap_or_else(|| "C:\\Windows".to_string());
+    let dir = format!("{windows}\\Prefetch");
+    let mut set = ResultSet::new(&[
+        "Executable",
+        "FileName",
+        "PathHash",
+        "LastModified",
+        "CreationDate",
+        "FileSize",
+        "Copies",
+        "RanAgain",
+    ])
+    .with_extra(&["Name", "LastAccessed"]);
+    let found = rows(
+        source,
+        "Win32_Directory",
+        Some(&format!("Name = {}", quote(&dir))),
+    )
+    .await?;
+    if found.is_empty() {
+        return Ok(set);
+    }
+    let (drive, path) = dir.split_at(dir.find('\\').unwrap_or(0));
+    let filter = format!(
+        "Drive = {} AND Path = {} AND Extension = 'pf'",
+        quote(drive),
+        quote(&format!("{path}\\"))
+    );
+    let files = rows(source, "CIM_DataFile", Some(&filter)).await?;
+    if files.is_empty() {
+        return Err(AppError::Hidden { path: dir });
+    }
+    let mut copies: BTreeMap<String, i64> = BTreeMap::new();
+    let mut found: Vec<(Vec<Value>, Vec<Value>)> = files
+        .into_iter()
+        .map(|mut file| {
+            let name = match file.remove("FileName") {
+                Some(Value::Text(name)) => format!("{name}.pf"),
+                _ => String::new(),
+            };
+            let (program, hash) = prefetch_name(&name);
+            *copies.entry(program.clone()).or_default() += 1;
+            let [modified, created, size] = ["LastModified", "CreationDate", "FileSize"]
+                .map(|p| file.remove(p).unwrap_or(Value::Null));
+            let time = |value: &Value| match value {
+                Value::Text(text) => wmi_datetime(text),
+                _ => None,
+            };
+            let again = match (time(&modified), time(&created)) {
+                (Some(m), Some(c)) => Value::Bool(m - c > chrono::TimeDelta::minutes(1)),
+                _ => Value::Null,
+            };
+            let row = vec![
+                program.into(),
+                name.into(),
+                Value::from(hash),
+                modified,
+                created,
+                size,
+                Value::Null,
+                again,
+            ];
+            (row, cells(&mut file, &["Name", "LastAccessed"]))
+        })
+        .collect();
+    for (row, _) in &mut found {
+        row[6] = Value::Int(copies[&row[0].to_string()]);
+    }
+    // Новые сверху; файлы без даты в конце
+    found.sort_by(|(a, _), (b, _)| {
+        (a[3] == Value::Null)
+            .cmp(&(b[3] == Value::Null))
+            .then_with(|| b[3].compare(&a[3]))
+    });
+    (set.rows, set.extra.rows) = found.into_iter().unzip();
+    Ok(set)
+}
+
+// Окончание имени, общее у пары $I и $R: «$IA1B2C3.docx» → «a1b2c3.docx»
+fn recycle_key(name: &str) -> Option<(char, String)> {
+    let name = name.rsplit('\\').next().unwrap_or(name);
+    let rest = name.strip_prefix('$')?;
+    let mut chars = rest.chars();
+    let kind = chars.next()?.to_ascii_uppercase();
+    Some((kind, chars.as_str().to_lowercase()))
+}
+
+// Имя владельца корзины по SID; неизвестный SID остаётся как есть
+async fn account(source: &impl DataSource, sid: &str) -> String {
+    let filter = format!("SID = {}", quote(sid));
+    let found = rows(source, "Win32_SID", Some(&filter))
+        .await
+        .unwrap_or_default();
+    found
+        .iter()
+        .filter(|row| matches!(row.get("SID"), Some(Value::Text(s)) if s.eq_ignore_ascii_case(sid)))
+        .find_map(|row| {
+            let text = |name| match row.get(name) {
+                Some(Value::Text(text)) if !text.is_empty() => Some(text.as_str()),
+                _ => None,
+            };
+            let account = text("AccountName")?;
+            Some(match text("ReferencedDomainName") {
+                Some(domain) => format!("{domain}\\{account}"),
+                None => account.to_string(),
+            })
+        })
+        .unwrap_or_else(|| sid.to_string())
+}
+
+/// Содержимое корзин пользователей на системном диске: файл $I хранит исходный
+/// путь, время удаления и размер, парный $R — сам объект. Показываются последние
+/// [`recycle::LIMIT`] объектов по владельцам, новые сверху; TotalItems — сколько их всего.
+/// Чужие корзины видны только администратору
+pub async fn recycle_bin(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let os = rows(source, "Win32_OperatingSystem", None).await?;
+    let drive = os
+        .first()
+        .and_then(|os| os.get("SystemDrive"))
+        .map(Value::to_string)
+        .unwrap_or_else(|| "C:".to_string());
+    let mut set = ResultSet::new(&["Owner", "OriginalPath", "DeletedAt", "Size", "Payload"])
+        .with_extra(&["SID", "Name", "TotalItems"]);
+    let filter = format!(
+        "Drive = {} AND Path = {}",
+        quote(&drive),
+        quote("\\$Recycle.Bin\\")
+    );
+    let mut items = Vec::new();
+    for mut bin in rows(source, "Win32_Directory", Some(&filter)).await? {
+        // Каталоги корзин названы SID владельца
+        let (Some(Value::Text(dir)), Some(Value::Text(sid))) =
+            (bin.remove("Name"), bin.remove("FileName"))
+        else {
+            continue;
+        };
+        let sid = sid.to_uppercase();
+        if !sid.starts_with("S-1-") {
+            continue;
+        }
+        let owner = account(source, &sid).await;
+        let path = format!(
+            "{}\\",
+            dir.split_once(':').map_or(dir.as_str(), |(_, path)| path)
+        );
+        let filter = format!("Drive = {} AND Path = {}", quote(&drive), quote(&path));
+        // Удалённая папка хранится как каталог $R, файл — как файл $R
+        let mut entries = rows(source, "CIM_DataFile", Some(&filter)).await?;
+        entries.extend(rows(source, "Win32_Directory", Some(&filter)).await?);
+        let prefix = format!("{}\\", dir.to_lowercase());
+        let names: Vec<String> = entries
+            .into_iter()
+            .filter_map(|mut entry| match entry.remove("Name") {
+                Some(Value::Text(name)) if name.to_lowercase().starts_with(&prefix) => Some(name),
+                _ => None,
+            })
+            .collect();
+        let payloads: HashSet<String> = names
+            .iter()
+            .filter_map(|name| recycle_key(name))
+            .filter_map(|(kind, key)| (kind == 'R').then_some(key))
+            .collect();
+        for name in names {
+            let Some(('I', key)) = recycle_key(&name) else {
+                continue;
+            };
+            let deleted = match source.read_file(&name) {
+                Ok(data) => recycle::parse(&data),
+                Err(e) => {
+                    tracing::debug!(file = %name, error = %e, "файл корзины не прочитан");
+                    None
+                }
+            };
+            let (path, time, size) = match deleted {
+                Some(item) => (
+                    Value::from(item.path),
+                    Value::from(item.deleted.map(recycle::cim_datetime)),
+                    Value::Int(item.size as i64),
+                ),
+                None => (Value::Null, Value::Null, Value::Null),
+            };
+            let row = vec![
+                owner.as_str().into(),
+                path,
+                time,
+                size,
+                Value::Bool(payloads.contains(&key)),
+            ];
+            items.push((row, vec![sid.as_str().into(), name.into()]));
+        }
+    }
+    let total = items.len() as i64;
+    // Последние удалённые, затем по владельцам; порядок внутри владельца сохраняется.
+    // Объекты с непрочитанным $I идут в конце
+    items.sort_by(|(a, _), (b, _)| {
+        (a[2] == Value::Null)
+            .cmp(&(b[2] == Value::Null))
+            .then_with(|| b[2].compare(&a[2]))
+    });
+    items.truncate(recycle::LIMIT);
+    items.sort_by(|(a, _), (b, _)| a[0].compare(&b[0]));
+    (set.rows, set.extra.rows) = items
+        .into_iter()
+        .map(|(row, mut extra)| {
+            extra.push(Value::Int(total));
+            (row, extra)
+        })
+        .unzip();
+    Ok(set)
+}
+
+// Вложенные каталоги `dir`: имя и полный путь. Записанные ответы условие не
+// учитывают, поэтому строки ещё раз сверяются с каталогом
+async fn subdirs(source: &impl DataSource, dir: &str) -> Result<Vec<(String, String)>, AppError> {
+    let (drive, path) = dir.split_at(dir.find('\\').unwrap_or(0));
+    let filter = format!(
+        "Drive = {} AND Path = {}",
+        quote(drive),
+        quote(&format!("{path}\\"))
+    );
+    let parent = dir.to_lowercase();
+    Ok(rows(source, "Win32_Directory", Some(&filter))
+        .await?
+        .into_iter()
+        .filter_map(|mut row| {
+            let Some(Value::Text(full)) = row.remove("Name") else {
+                return None;
+            };
+            // У каталогов FileName без «расширения», поэтому имя берётся из пути
+            let (base, name) = full.rsplit_once('\\')?;
+            (base.to_lowercase() == parent).then(|| (name.to_string(), full.clone()))
+        })
+        .collect())
+}
+
+// Расширение из профиля браузера; ошибка — причина, по которой файл не прочитан
+struct Installed {
+    browser: &'static str,
+    profile: String,
+    path: String,
+    extension: Result<Extension, String>,
+}
+
+// Расширения всех браузеров в профиле пользователя `home`. Ошибка — профиль не
+// читается целиком: в настоящем профиле всегда есть хотя бы каталог AppData
+async fn user_extensions(source: &impl DataSource, home: &str) -> Result<Vec<Installed>, String> {
+    let text = |e: AppError| e.to_string();
+    if subdirs(source, home).await.map_err(text)?.is_empty() {
+        return Err(tr("extensions.profile_denied").to_string());
+    }
+    let mut found = Vec::new();
+    for (browser, data) in extensions::CHROMIUM {
+        let root = format!("{home}\\{data}");
+        for (profile, dir) in subdirs(source, &root).await.map_err(text)? {
+            let ids = subdirs(source, &format!("{dir}\\Extensions"))
+                .await
+                .map_err(text)?;
+            for (id, dir) in ids
+                .into_iter()
+                .filter(|(id, _)| extensions::chromium_id(id))
+            {
+                // Во время обновления рядом лежат две версии; действует новая
+                let versions = subdirs(source, &dir).await.map_err(text)?;
+                let Some((_, version)) = versions
+                    .into_iter()
+                    .max_by_key(|(name, _)| extensions::version_key(name))
+                else {
+                    continue;
+                };
+                let path = format!("{version}\\manifest.json");
+                let extension = match source.read_file(&path) {
+                    Ok(data) => extensions::chromium_manifest(&id, &data)
+                        .ok_or_else(|| tr("extensions.bad_file").to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                found.push(Installed {
+                    browser,
+                    profile: profile.clone(),
+                    path,
+                    extension,
+                });
+            }
+        }
+    }
+    let root = format!("{home}\\{}", extensions::FIREFOX);
+    for (profile, dir) in subdirs(source, &root).await.map_err(text)? {
+        let path = format!("{dir}\\extensions.json");
+        let addons = match source.read_file(&path) {
+            // Firefox с этим профилем ещё не запускался
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => Err(e.to_string()),
+            Ok(data) => extensions::firefox_addons(&data)
+                .ok_or_else(|| tr("extensions.bad_file").to_string()),
+        };
+        let installed = |extension| Installed {
+            browser: "Firefox",
+            profile: profile.clone(),
+            path: path.clone(),
+            extension,
+        };
+        match addons {
+            Ok(addons) => found.extend(addons.into_iter().map(|addon| installed(Ok(addon)))),
+            Err(e) => found.push(installed(Err(e))),
+        }
+    }
+    Ok(found)
+}
+
+/// Расширения Chrome, Edge и Firefox из профилей всех пользователей. Профили
+/// других пользователей без прав администратора не читаются: такой профиль или
+/// непрочитанный файл даёт строку с ошибкой, а остальные расширения остаются
+pub async fn browser_extensions(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let mut set = ResultSet::new(&[
+        "User",
+        "Browser",
+        "Profile",
+        "Name",
+        "Version",
+        "Permissions",
+        "Enabled",
+        "Error",
+    ])
+    .with_extra(&["ExtensionId", "Path"]);
+    let profiles = rows(source, "Win32_UserProfile", Some("Special = FALSE")).await?;
+    for mut profile in profiles {
+        if profile.get("Special") == Some(&Value::Bool(true)) {
+            continue;
+        }
+        let (Some(Value::Text(home)), Some(Value::Text(sid))) =
+            (profile.remove("LocalPath"), profile.remove("SID"))
+        else {
+            continue;
+        };
+        let user = account(source, &sid).await;
+        let found = match user_extensions(source, &home).await {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::debug!(profile = %home, error = %e, "профиль не прочитан");
+                set.rows.push(vec![
+                    user.as_str().into(),
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    e.into(),
+                ]);
+                set.extra.rows.push(vec![Value::Null, home.into()]);
+                continue;
+            }
+        };
+        for installed in found {
+            let (id, cells) = match installed.extension {
+                Ok(extension) => (
+                    Value::from(extension.id),
+                    [
+                        extension.name.into(),
+                        Value::from(extension.version),
+                        Value::from(
+                            Some(extension.permissions.join(", ")).filter(|p| !p.is_empty()),
+                        ),
+                        Value::from(extension.enabled),
+                        Value::Null,
+                    ],
+                ),
+                Err(e) => (
+                    Value::Null,
+                    [Value::Null, Value::Null, Value::Null, Value::Null, e.into()],
+                ),
+            };
+            let mut row = vec![
+                user.as_str().into(),
+                installed.browser.into(),
+                installed.profile.into(),
+            ];
+            row.extend(cells);
+            set.rows.push(row);
+            set.extra.rows.push(vec![id, installed.path.into()]);
+        }
+    }
+    Ok(set)
+}
+
+/// Исключения Microsoft Defender: папки, расширения и процессы, которые он не
+/// проверяет. Без пространства имён Defender — таблица с пояснением, а не ошибка
+pub async fn defender_exclusions(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let wql = source::select(defender::CLASS, None);
+    match source
+        .query_objects(defender::CLASS, defender::NAMESPACE, &wql)
+        .await
+    {
+        Ok(objects) => Ok(defender::result_set(objects.first())),
+        Err(AppError::NamespaceUnavailable { .. }) => Ok(defender::unavailable()),
+        Err(e) => Err(e),
+    }
+}
+
+// Строка-признак: что проверено, что найдено и на какой гипервизор это указывает
+fn evidence(indicator: &str, observed: Value, hypervisor: Option<&str>) -> Vec<Value> {
+    vec![indicator.into(), observed, Value::from(hypervisor)]
+}
+
+/// Признаки виртуальной машины: модель компьютера, серийный номер BIOS и устройства
+/// виртуального оборудования. Первая строка — вывод по ним, за ней признаки, затем
+/// состояние Hyper-V на этом компьютере и его запущенные виртуальные машины
+pub async fn virtualization(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let text = |row: Option<&Row>, name| match row.and_then(|row| row.get(name)) {
+        Some(Value::Text(text)) => text.trim().to_string(),
+        _ => String::new(),
+    };
+    let mut votes = Vec::new();
+    let mut found = Vec::new();
+
+    let system = rows(source, "Win32_ComputerSystem", None).await?;
+    let system = system.first();
+    let (manufacturer, model) = (text(system, "Manufacturer"), text(system, "Model"));
+    let by_model = virtualization::by_model(&manufacturer, &model);
+    found.push(evidence(
+        tr("virt.model"),
+        format!("{manufacturer} {model}").trim().into(),
+        by_model,
+    ));
+
+    let bios = rows(source, "Win32_BIOS", None).await?;
+    let serial = text(bios.first(), "SerialNumber");
+    let by_serial = virtualization::by_serial(&serial);
+    found.push(evidence(tr("virt.serial"), serial.into(), by_serial));
+    votes.extend(by_model.into_iter().chain(by_serial));
+
+    // Каждый гипервизор получает от устройств один голос, сколько бы их ни было
+    let mut devices = Vec::new();
+    for device in rows(source, "Win32_PnPEntity", None).await? {
+        let id = text(Some(&device), "DeviceID");
+        if let Some(hypervisor) = virtualization::by_device(&id) {
+            let name = Some(text(Some(&device), "Name")).filter(|name| !name.is_empty());
+            found.push(evidence(
+                tr("virt.device"),
+                name.unwrap_or(id).into(),
+                Some(hypervisor),
+            ));
+            if !devices.contains(&hypervisor) {
+                devices.push(hypervisor);
+            }
+        }
+    }
+    if devices.is_empty() {
+        found.push(evidence(
+            tr("virt.device"),
+            tr("virt.no_devices").into(),
+            None,
+        ));
+    }
+    votes.extend(devices);
+
+    let present = system
+        .and_then(|system| system.get("HypervisorPresent"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    found.push(evidence(tr("virt.hypervisor_present"), present, None));
+
+    let features = rows(
+        source,
+        "Win32_OptionalFeature",
+        Some(&format!("Name = {}", quote(virtualization::HYPERV_FEATURE))),
+    )
+    .await?;
+    let feature = features
+        .iter()
+        .find(|feature| text(Some(feature), "Name") == virtualization::HYPERV_FEATURE);
+    let state = match feature.and_then(|feature| feature.get("InstallState")) {
+        Some(Value::Int(code)) => virtualization::install_state(*code),
+        _ => tr("virt.feature.absent"),
+    };
+    found.push(evidence(tr("virt.hyperv_feature"), state.into(), None));
+
+    let services = rows(source, "Win32_Service", Some("Name = 'vmms'")).await?;
+    let service = services
+        .iter()
+        .find(|service| text(Some(service), "Name").eq_ignore_ascii_case("vmms"));
+    let state = match service {
+        Some(service) => text(Some(service), "State"),
+        None => tr("virt.no_service").to_string(),
+    };
+    found.push(evidence(tr("virt.vmms"), state.into(), None));
+
+    // Пространство имён есть только там, где включена роль Hyper-V; без прав
+    // администратора запрос к нему отклоняется, и это тоже попадает в таблицу
+    let wql = source::select(
+        "Msvm_ComputerSystem",
+        Some("Caption = 'Virtual Machine' AND EnabledState = 2"),
+    );
+    match source
+        .query_rows(
+            "Msvm_ComputerSystem",
+            virtualization::HYPERV_NAMESPACE,
+            &wql,
+        )
+        .await
+    {
+        Ok(machines) => {
+            for machine in machines {
+                let running = text(Some(&machine), "Caption") == "Virtual Machine"
+                    && machine.get("EnabledState") == Some(&Value::Int(2));
+                if running {
+                    let name = text(Some(&machine), "ElementName");
+                    found.push(evidence(tr("virt.hyperv_vm"), name.into(), Some("Hyper-V")));
+                }
+            }
+        }
+        Err(AppError::NamespaceUnavailable { .. }) => {}
+        Err(e) => found.push(evidence(tr("virt.hyperv_vm"), e.to_string().into(), None)),
+    }
+
+    let verdict = virtualization::verdict(&votes);
+    let conclusion = match verdict {
+        Some(hypervisor) => trf("virt.vm", &[("hypervisor", &hypervisor)]),
+        None => tr("virt.physical").to_string(),
+    };
+    let mut set = ResultSet::new(&["Indicator", "Observed", "Hypervisor"]);
+    set.rows
+        .push(evidence(tr("virt.verdict"), conclusion.into(), verdict));
+    set.rows.extend(found);
+    Ok(set)
+}
+
+// Температура из свойства датчика и пометка о неправдоподобном или отсутствующем значении
+fn temperature(value: Option<&Value>, celsius: fn(i64) -> f64) -> (Value, Option<String>) {
+    match value {
+        Some(Value::Int(value)) => {
+            let (text, note) = cooling::reading(celsius(*value));
+            (text.into(), note)
+        }
+        _ => (Value::Null, Some(tr("cooling.no_reading").to_string())),
+    }
+}
+
+/// Вентиляторы и датчики температуры из Win32_Fan, Win32_TemperatureProbe и
+/// температурных зон ACPI. Большинство компьютеров заполняет только часть классов,
+/// поэтому класс без экземпляров или с ошибкой запроса даёт строку-пояснение, а
+/// неправдоподобные температуры помечаются
+pub async fn cooling(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let mut set = ResultSet::new(&["Source", "Name", "Reading", "Status", "Note"]);
+    let classes = [
+        ("Win32_Fan", CIMV2),
+        ("Win32_TemperatureProbe", CIMV2),
+        ("MSAcpi_ThermalZoneTemperature", cooling::ACPI_NAMESPACE),
+    ];
+    for (class, namespace) in classes {
+        let note = |text: String| {
+            vec![
+                class.into(),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                text.into(),
+            ]
+        };
+        // Температурные зоны доступны только администратору и есть не везде
+        let instances = match source
+            .query_rows(class, namespace, &source::select(class, None))
+            .await
+        {
+            Ok(instances) if instances.is_empty() => {
+                set.rows.push(note(tr("cooling.no_instances").to_string()));
+                continue;
+            }
+            Ok(instances) => instances,
+            Err(e) => {
+                set.rows.push(note(e.to_string()));
+                continue;
+            }
+        };
+        for mut instance in instances {
+            let name = ["Name", "InstanceName", "DeviceID"]
+                .into_iter()
+                .filter_map(|property| instance.remove(property))
+                .find(|name| *name != Value::Null)
+                .unwrap_or(Value::Null);
+            let (reading, note) = match class {
+                "Win32_Fan" => match instance.get("Availability") {
+                    Some(Value::Int(code)) => (cooling::availability(*code).into(), None),
+                    _ => (Value::Null, None),
+                },
+                "Win32_TemperatureProbe" => {
+                    temperature(instance.get("CurrentReading"), cooling::from_tenths_celsius)
+                }
+                _ => temperature(
+                    instance.get("CurrentTemperature"),
+                    cooling::from_tenths_kelvin,
+                ),
+            };
+            let status = instance.remove("Status").unwrap_or(Value::Null);
+            set.rows
+                .push(vec![class.into(), name, reading, status, Value::from(note)]);
+        }
+    }
+    Ok(set)
+}
+
+/// Тома файловых систем. Тома без буквы показываются по папке подключения, а
+/// неподключённые — по пути с GUID тома
+pub async fn volumes(
+    source: &impl DataSource,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    let names = [
+        "Label",
+        "FileSystem",
+        "DirtyBitSet",
+        "Compressed",
+        "BlockSize",
+        "BootVolume",
+        "SystemVolume",
+        "DeviceID",
+    ];
+    let extra = ["DriveLetter", "DriveType", "Capacity", "FreeSpace"];
+    let mut set = ResultSet::new(&[&["MountPath"], &names[..]].concat()).with_extra(&extra);
+    for mut volume in rows(source, "Win32_Volume", filter).await? {
+        let path = ["Name", "DriveLetter", "DeviceID"]
+            .into_iter()
+            .filter_map(|name| volume.get(name))
+            .find(|path| **path != Value::Null)
+            .cloned()
+            .unwrap_or(Value::Null);
+        let mut row = vec![path];
+        row.extend(cells(&mut volume, &names));
+        set.rows.push(row);
+        set.extra.rows.push(cells(&mut volume, &extra));
+    }
+    Ok(set)
+}
+
+/// Квоты локальных дисков: строка тома с режимом и пределами по умолчанию, за ней
+/// [`quota::TOP`] пользователей, занимающих больше всего места. Записи квот ссылаются
+/// на диск и учётную запись путями объектов WMI
+pub async fn disk_quotas(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let text = |row: &Row, name| match row.get(name) {
+        Some(Value::Text(text)) => text.clone(),
+        _ => String::new(),
+    };
+    let disks = rows(source, "Win32_LogicalDisk", Some("DriveType = 3")).await?;
+    let settings = rows(source, "Win32_QuotaSetting", None).await?;
+    let mut entries: HashMap<String, Vec<Row>> = HashMap::new();
+    for entry in rows(source, "Win32_DiskQuota", None).await? {
+        if let Some(disk) = quota::volume(&text(&entry, "QuotaVolume")) {
+            entries.entry(disk.to_uppercase()).or_default().push(entry);
+        }
+    }
+    let mut set = ResultSet::new(&[
+        "Volume",
+        "QuotaState",
+        "User",
+        "DiskSpaceUsed",
+        "Limit",
+        "WarningLimit",
+        "QuotaStatus",
+    ]);
+    for disk in disks
+        .iter()
+        .filter(|disk| disk.get("DriveType") == Some(&Value::Int(3)))
+    {
+        let letter = text(disk, "DeviceID");
+        let setting = settings.iter().find(|setting| {
+            let path = text(setting, "VolumePath");
+            path.trim_end_matches('\\').eq_ignore_ascii_case(&letter)
+        });
+        let supported = disk.get("SupportsDiskQuotas") == Some(&Value::Bool(true));
+        let Some(setting) = setting.filter(|_| supported) else {
+            set.rows.push(vec![
+                letter.into(),
+                tr("quota.unsupported").into(),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+            ]);
+            continue;
+        };
+        let state = match setting.get("State") {
+            Some(Value::Int(code)) => quota::state(*code).into(),
+            _ => Value::Null,
+        };
+        set.rows.push(vec![
+            letter.clone().into(),
+            state,
+            Value::Null,
+            Value::Null,
+            quota::limit(setting.get("DefaultLimit")),
+            quota::limit(setting.get("DefaultWarningLimit")),
+            Value::Null,
+        ]);
+        let mut users = entries.remove(&letter.to_uppercase()).unwrap_or_default();
+        users.sort_by_key(|entry| {
+            std::cmp::Reverse(quota::bytes(entry.get("DiskSpaceUsed")).unwrap_or(0))
+        });
+        let hidden = users.len().saturating_sub(quota::TOP);
+        for entry in users.into_iter().take(quota::TOP) {
+            let user = quota::account(&text(&entry, "User")).map(Value::from);
+            let used = quota::bytes(entry.get("DiskSpaceUsed"))
+                .and_then(|n| i64::try_from(n).ok())
+                .map_or(Value::Null, Value::Int);
+            let status = match entry.get("Status") {
+                Some(Value::Int(code)) => quota::status(*code).into(),
+                _ => Value::Null,
+            };
+            set.rows.push(vec![
+                letter.clone().into(),
+                Value::Null,
+                user.unwrap_or(Value::Null),
+                used,
+                quota::limit(entry.get("Limit")),
+                quota::limit(entry.get("WarningLimit")),
+                status,
+            ]);
+        }
+        if hidden > 0 {
+            set.rows.push(vec![
+                letter.into(),
+                Value::Null,
+                trf("quota.more", &[("count", &hidden)]).into(),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Null,
+            ]);
+        }
+    }
+    Ok(set)
+}
+
+/// Внешние разъёмы корпуса по таблице SMBIOS с числом разъёмов каждого вида
+pub async fn port_connectors(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let wql = source::select(connectors::CLASS, None);
+    let ports: Vec<Port> = source
+        .query_objects(connectors::CLASS, CIMV2, &wql)
+        .await?
+        .iter()
+        .map(Port::from_object)
+        .collect();
+    Ok(connectors::result_set(&ports))
+}
+
+/// Сведения, по которым компьютер узнаётся в базе учёта оборудования. Основной адрес
+/// MAC — у адаптера со шлюзом по умолчанию и наименьшей метрикой
+pub async fn asset(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let mut values: HashMap<&str, Value> = HashMap::new();
+    let mut objects: HashMap<&str, Option<Row>> = HashMap::new();
+    for field in &asset::FIELDS {
+        if field.class == "Win32_NetworkAdapterConfiguration" || objects.contains_key(field.class) {
+            continue;
+        }
+        let object = rows(source, field.class, None).await?.into_iter().next();
+        objects.insert(field.class, object);
+    }
+    let mut adapters = rows(
+        source,
+        "Win32_NetworkAdapterConfiguration",
+        Some("IPEnabled = TRUE"),
+    )
+    .await?;
+    adapters.retain(|adapter| matches!(adapter.get("MACAddress"), Some(Value::Text(_))));
+    let metric = |adapter: &Row| {
+        let gateway = matches!(adapter.get("DefaultIPGateway"), Some(Value::Text(_)));
+        let metric = match adapter.get("IPConnectionMetric") {
+            Some(Value::Int(metric)) => *metric,
+            _ => i64::MAX,
+        };
+        (!gateway, metric)
+    };
+    adapters.sort_by_key(metric);
+    for field in &asset::FIELDS {
+        let object = match field.class {
+            "Win32_NetworkAdapterConfiguration" => adapters.first(),
+            class => objects.get(class).and_then(Option::as_ref),
+        };
+        let value = object
+            .and_then(|object| object.get(field.property))
+            .cloned();
+        values.insert(field.key, value.unwrap_or(Value::Null));
+    }
+    Ok(asset::result_set(&values))
+}
+
+/// Время работы системы, ожидаемая перезагрузка и последние неожиданные завершения.
+/// Дни считаются по часам самого компьютера из LocalDateTime
+pub async fn uptime(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let os = rows(source, "Win32_OperatingSystem", None).await?;
+    let os = os.first();
+    let datetime = |name| match os.and_then(|os| os.get(name)) {
+        Some(Value::Text(text)) => wmi_datetime(text),
+        _ => None,
+    };
+    let now = datetime("LocalDateTime").unwrap_or_else(|| chrono::Local::now().fixed_offset());
+    let product_type = match os.and_then(|os| os.get("ProductType")) {
+        Some(Value::Int(code)) => Some(*code),
+        _ => None,
+    };
+    let hotfix = rows(source, "Win32_QuickFixEngineering", None)
+        .await?
+        .iter()
+        .filter_map(|fix| match fix.get("InstalledOn") {
+            Some(Value::Text(text)) => uptime::installed_on(text),
+            _ => None,
+        })
+        .max();
+    let since = now - chrono::Duration::days(uptime::lookback_days());
+    let filter = format!(
+        "Logfile = 'System' AND EventCode = 6008 AND TimeGenerated >= '{}'",
+        recycle::cim_datetime(since.with_timezone(&chrono::Utc))
+    );
+    let class = "Win32_NTLogEvent";
+    let mut events = source
+        .query_objects(class, CIMV2, &source::select(class, Some(&filter)))
+        .await?;
+    let generated = |event: &Object| match event.get("TimeGenerated") {
+        Some(Property::Scalar(Value::Text(text))) => wmi_datetime(text),
+        _ => None,
+    };
+    // В WQL нет ORDER BY
+    events.sort_by_key(|event| std::cmp::Reverse(generated(event)));
+    let shutdowns = events
+        .iter()
+        .take(uptime::SHUTDOWNS)
+        .filter_map(|event| {
+            let strings = match event.get("InsertionStrings") {
+                Some(Property::Array(strings)) => uptime::shutdown_time(strings),
+                _ => None,
+            };
+            strings.or_else(|| Some(generated(event)?.format("%Y-%m-%d %H:%M").to_string()))
+        })
+        .collect();
+    Ok(uptime::result_set(&uptime::Status {
+        now,
+        boot: datetime("LastBootUpTime"),
+        product_type,
+        pending: pending_reboot(source),
+        hotfix,
+        shutdowns,
+    }))
+}
+
+// Найденные признаки ожидаемой перезагрузки. Ошибка чтения реестра прерывает
+// проверку: без остальных признаков ответ «нет» был бы ложным
+fn pending_reboot(source: &impl DataSource) -> Result<Vec<&'static str>, String> {
+    let mut found = Vec::new();
+    for indicator in &uptime::PENDING {
+        let (method, params) = indicator.request();
+        let output = source
+            .call(source::REGISTRY, "StdRegProv", method, &params)
+            .map_err(|e| e.to_string())?;
+        if indicator.present(&output)? {
+            found.push(indicator.name);
+        }
+    }
+    Ok(found)
+}
+
+/// Схемы электропитания с временем до сна и паролем при пробуждении. Значения,
+/// которых нет в WMI, у активной схемы дочитываются из реестра. Без пространства
+/// имён схем активная схема целиком читается из реестра под строкой-пояснением
+pub async fn power_plans(source: &impl DataSource) -> Result<ResultSet, AppError> {
+    let battery = !rows(source, "Win32_Battery", None).await?.is_empty();
+    let wql = source::select(power::PLAN_CLASS, None);
+    let plans = match source
+        .query_rows(power::PLAN_CLASS, power::NAMESPACE, &wql)
+        .await
+    {
+        Ok(plans) => plans,
+        Err(AppError::NamespaceUnavailable { .. }) => {
+            let plans: Vec<Plan> = registry_plan(source).into_iter().collect();
+            let note = tr("power.no_namespace");
+            return Ok(power::result_set(&plans, battery, Some(note)));
+        }
+        Err(e) => return Err(e),
+    };
+    let mut plans: Vec<Plan> = plans.iter().filter_map(Plan::from_row).collect();
+    let filter = power::Setting::ALL
+        .iter()
+        .map(|setting| format!("InstanceID LIKE '%{{{}}}'", setting.guid()))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    let wql = source::select(power::INDEX_CLASS, Some(&filter));
+    for index in source
+        .query_rows(power::INDEX_CLASS, power::NAMESPACE, &wql)
+        .await?
+    {
+        let (Some(Value::Text(id)), Some(Value::Int(value))) =
+            (index.get("InstanceID"), index.get("SettingIndexValue"))
+        else {
+            continue;
+        };
+        let Some((guid, ac, setting)) = power::data_index(id) else {
+            continue;
+        };
+        if let Some(plan) = plans.iter_mut().find(|plan| plan.guid == guid) {
+            plan.set(setting, ac, *value);
+        }
+    }
+    if let Some(plan) = plans
+        .iter_mut()
+        .find(|plan| plan.active && !plan.complete())
+    {
+        registry_settings(source, plan);
+    }
+    Ok(power::result_set(&plans, battery, None))
+}
+
+// Активная схема по значению ActivePowerScheme; `None`, если реестр не прочитать
+fn registry_plan(source: &impl DataSource) -> Option<Plan> {
+    let params = [
+        ("sSubKeyName", power::SCHEMES_KEY.into()),
+        ("sValueName", "ActivePowerScheme".into()),
+    ];
+    let output = source
+        .call(source::REGISTRY, "StdRegProv", "GetStringValue", &params)
+        .ok()?;
+    let Some(Property::Scalar(Value::Text(guid))) = output.get("sValue") else {
+        return None;
+    };
+    let mut plan = Plan {
+        guid: power::guid(guid),
+        active: true,
+        ..Plan::default()
+    };
+    registry_settings(source, &mut plan);
+    Some(plan)
+}
+
+// Недостающие значения схемы из реестра: сначала изменённые, затем по умолчанию.
+// Непрочитанное значение остаётся пустым
+fn registry_settings(source: &impl DataSource, plan: &mut Plan) {
+    for setting in power::Setting::ALL {
+        for ac in [true, false] {
+            if plan.get(setting, ac).is_some() {
+                continue;
+            }
+            let name = if ac {
+                "ACSettingIndex"
+            } else {
+                "DCSettingIndex"
+            };
+            let value = setting
+                .registry_keys(&plan.guid)
+                .into_iter()
+                .find_map(|key| {
+                    let params = [("sSubKeyName", key.into()), ("sValueName", name.into())];
+                    let output = source
+                        .call(source::REGISTRY, "StdRegProv", "GetDWORDValue", &params)
+                        .ok()?;
+                    match (output.get("ReturnValue"), output.get("uValue")) {
+                        (
+                            Some(Property::Scalar(Value::Int(0))),
+                            Some(Property::Scalar(Value::Int(value))),
+                        ) => Some(*value),
+                        _ => None,
+                    }
+                });
+            if let Some(value) = value {
+                plan.set(setting, ac, value);
+                plan.registry = true;
+            }
+        }
+    }
+}
+
+/// Название и версия ОС для сводки отчёта
+pub async fn os_caption(source: &impl DataSource) -> Result<String, AppError> {
+    let os = rows(source, "Win32_OperatingSystem", None).await?;
+    Ok(os
+        .first()
+        .map(|os| {
+            let text = |name| os.get(name).map(Value::to_string).unwrap_or_default();
+            format!("{} ({})", text("Caption"), text("Version"))
+        })
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::{self, Severity};
+    use crate::source::Fixtures;
+    use crate::watch;
+    use crate::ActiveData;
+    use futures::executor::block_on;
+
+    fn workstation() -> Fixtures {
+        Fixtures::new("workstation")
+    }
+
+    #[test]
+    fn device_with_null_properties() {
+        let set = block_on(buses(&workstation(), None)).unwrap();
+        // Виртуальное устройство без драйвера
+        let row = (0..set.rows.len())
+            .find(|&i| set.value(i, "DeviceID") == Some(&"ROOT\\UNKNOWN\\0000".into()))
+            .unwrap();
+        assert_eq!(set.value(row, "Status"), Some(&Value::Null));
+        assert_eq!(set.value(row, "Manufacturer"), Some(&Value::Null));
+        assert_eq!(
+            set.value(row, "ConfigManagerErrorCode"),
+            Some(&Value::Int(28))
+        );
+        assert_eq!(set.extra.rows.len(), set.rows.len());
+    }
+
+    #[test]
+    fn only_problem_devices_are_listed() {
+        let set = block_on(problem_devices(&workstation())).unwrap();
+        assert_eq!(set.rows.len(), 1);
+        assert_eq!(
+            set.value(0, "DeviceID"),
+            Some(&"ROOT\\UNKNOWN\\0000".into())
+        );
+        assert_eq!(
+            set.value(0, "Problem"),
+            Some(&devices::problem_text(28).into())
+        );
+        let flagged = findings::row_findings(ActiveData::ProblemDevices, &set);
+        assert_eq!(flagged[&0][0].severity, Severity::Info);
+
+        // У устройств снимка нет кодов ошибок
+        let none = block_on(problem_devices(&Fixtures::new("vmware_guest"))).unwrap();
+        assert_eq!(
+            none.value(0, "Problem"),
+            Some(&tr("devices.no_problems").into())
+        );
+        assert!(findings::row_findings(ActiveData::ProblemDevices, &none).is_empty());
+    }
+
+    #[test]
+    fn environment_without_value() {
+        let set = block_on(environment(&workstation(), None)).unwrap();
+        let value = |name: &str| {
+            let row = (0..set.rows.len()).find(|&i| set.value(i, "Name") == Some(&name.into()));
+            set.value(row.unwrap(), "VariableValue").cloned()
+        };
+        assert_eq!(value("TEMP"), Some(tr("value.empty").into()));
+        assert_eq!(value("Path"), Some("C:\\Windows\\system32".into()));
+    }
+
+    #[test]
+    fn accounts_are_counted_by_sid_type() {
+        let set = block_on(sid_counts(&workstation(), None)).unwrap();
+        assert_eq!(
+            set.rows,
+            vec![
+                vec![Value::Int(1), Value::Int(4)],
+                vec![Value::Int(4), Value::Int(2)],
+            ]
+        );
+    }
+
+    #[test]
+    fn device_checks_flag_fixture_rows() {
+        let set = block_on(buses(&workstation(), None)).unwrap();
+        let flags = findings::row_flags(ActiveData::BusInfo, &set);
+        let flagged: Vec<_> = (0..set.rows.len())
+            .filter(|row| flags.get(row) == Some(&Severity::High))
+            .filter_map(|row| set.value(row, "DeviceID"))
+            .collect();
+        assert_eq!(flagged, [&Value::from("ROOT\\UNKNOWN\\0000")]);
+        assert_eq!(flags.len(), 2); // И устройство со Status = Error
+    }
+
+    #[test]
+    fn later_capture_differs_by_service_state() {
+        let before = block_on(services(&workstation(), None)).unwrap();
+        let after = block_on(services(&Fixtures::new("workstation_later"), None)).unwrap();
+        let marks = watch::diff(&before, &after, &["Name"]);
+        assert!(marks.added.is_empty());
+        assert_eq!(marks.changed.len(), 1);
+        assert_eq!(marks.removed.len(), 1);
+        let (&row, _) = marks.changed.iter().next().unwrap();
+        assert_eq!(after.value(row, "Name"), Some(&"Spooler".into()));
+    }
+
+    #[test]
+    fn programs_without_version_stay_empty() {
+        let set = block_on(software(&workstation(), None)).unwrap();
+        assert_eq!(set.rows.len(), 3);
+        assert_eq!(set.value(2, "Version"), Some(&Value::Null));
+        assert_eq!(
+            set.value(1, "Vendor"),
+            Some(&"Microsoft Corporation".into())
+        );
+    }
+
+    #[test]
+    fn process_ids_keep_integer_type() {
+        let set = block_on(processes(&workstation(), None)).unwrap();
+        assert!(set.rows.iter().all(|row| matches!(row[0], Value::Int(_))));
+        assert_eq!(set.value(0, "ExecutablePath"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn prefetch_newest_first_with_derived_columns() {
+        let set = block_on(prefetch(&workstation())).unwrap();
+        let programs: Vec<String> = (0..set.rows.len())
+            .map(|i| set.value(i, "Executable").unwrap().to_string())
+            .collect();
+        assert_eq!(
+            programs,
+            [
+                "SVCHOST.EXE",
+                "NOTEPAD.EXE",
+                "RUNDLL32.EXE",
+                "NTOSBOOT",
+                "RUNDLL32.EXE"
+            ]
+        );
+        assert_eq!(
+            set.value(1, "FileName"),
+            Some(&"notepad.exe-d8414f97.pf".into())
+        );
+        assert_eq!(set.value(1, "PathHash"), Some(&"D8414F97".into()));
+        assert_eq!(set.value(2, "Copies"), Some(&Value::Int(2)));
+        assert_eq!(set.value(0, "Copies"), Some(&Value::Int(1)));
+        // Перезапись через доли секунды после создания — это ещё первый запуск
+        assert_eq!(set.value(1, "RanAgain"), Some(&Value::Bool(true)));
+        assert_eq!(set.value(2, "RanAgain"), Some(&Value::Bool(false)));
+        assert_eq!(set.value(4, "RanAgain"), Some(&Value::Null));
+        assert!(findings::row_findings(ActiveData::Prefetch, &set).is_empty());
+        // Пустая таблица — каталога нет
+        let empty = ResultSet::new(&["Executable"]);
+        let flagged = findings::row_findings(ActiveData::Prefetch, &empty);
+        assert_eq!(flagged[&0][0].severity, Severity::Medium);
+    }
+
+    #[test]
+    fn recycle_bin_pairs_metadata_with_payload() {
+        let set = block_on(recycle_bin(&Fixtures::new("recycle_bin"))).unwrap();
+        let admin = "S-1-5-21-1004336348-1177238915-682003330-500";
+        let paths: Vec<(String, String)> = (0..set.rows.len())
+            .map(|i| {
+                let text = |name| set.value(i, name).unwrap().to_string();
+                (text("Owner"), text("OriginalPath"))
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (admin, "C:\\Users\\Administrator\\Desktop\\setup.exe"),
+                (admin, ""),
+                ("WS-01\\user", "C:\\Users\\user\\Downloads\\tools"),
+                ("WS-01\\user", "C:\\Users\\user\\Documents\\Отчёт.docx"),
+                (
+                    "WS-01\\user",
+                    "C:\\Users\\user\\AppData\\Local\\Temp\\run.ps1"
+                ),
+            ]
+            .map(|(owner, path)| (owner.to_string(), path.to_string()))
+        );
+        // Удалённая папка: $R — каталог
+        assert_eq!(set.value(2, "Payload"), Some(&Value::Bool(true)));
+        assert_eq!(set.value(4, "Payload"), Some(&Value::Bool(false)));
+        assert_eq!(set.value(3, "Size"), Some(&Value::Int(48213)));
+        assert_eq!(
+            set.value(3, "DeletedAt"),
+            Some(&"20240501101530.250000+000".into())
+        );
+        assert_eq!(set.value(1, "DeletedAt"), Some(&Value::Null));
+        assert_eq!(set.extra_value(0, "TotalItems"), Some(&Value::Int(5)));
+        assert!(findings::row_findings(ActiveData::RecycleBin, &set).is_empty());
+
+        let mut shown = set.clone();
+        shown.rows.truncate(2);
+        shown.extra.rows.truncate(2);
+        let flagged = findings::row_findings(ActiveData::RecycleBin, &shown);
+        assert_eq!(flagged[&0][0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn browser_extensions_survive_unreadable_profiles() {
+        let set = block_on(browser_extensions(&Fixtures::new("browser_extensions"))).unwrap();
+        let listed: Vec<(String, String)> = (0..set.rows.len())
+            .map(|i| {
+                let text = |name| set.value(i, name).unwrap().to_string();
+                (text("Browser"), text("Name"))
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("Chrome", "uBlock Origin"),
+                ("Chrome", "ghbmnnjooekpmoecnnnilnnbdlolhkhi"),
+                ("Chrome", "PDF Helper"),
+                ("Chrome", ""),
+                ("Edge", "uBlock Origin"),
+                ("Firefox", "Web Clipper"),
+                ("", ""),
+            ]
+            .map(|(browser, name)| (browser.to_string(), name.to_string()))
+        );
+        // Из двух каталогов версий берётся новый
+        assert_eq!(set.value(0, "Version"), Some(&"1.57.0".into()));
+        assert_eq!(set.value(5, "Enabled"), Some(&Value::Bool(false)));
+        assert_eq!(set.value(4, "Enabled"), Some(&Value::Null));
+        // Каталог версии без манифеста и чужой профиль — строки с ошибкой
+        assert!(matches!(set.value(3, "Error"), Some(Value::Text(_))));
+        assert_eq!(set.value(6, "User"), Some(&"WS-01\\other".into()));
+        assert_eq!(
+            set.value(6, "Error"),
+            Some(&tr("extensions.profile_denied").into())
+        );
+        assert_eq!(set.value(0, "Error"), Some(&Value::Null));
+
+        let flagged = findings::row_findings(ActiveData::BrowserExtensions, &set);
+        let severity = |row: usize| flagged.get(&row).map(|found| found[0].severity);
+        assert_eq!(severity(0), Some(Severity::High));
+        assert_eq!(severity(1), None);
+        assert_eq!(severity(2), Some(Severity::High));
+        assert_eq!(severity(4), None);
+        assert_eq!(severity(5), Some(Severity::Medium));
+        assert_eq!(flagged.len(), 3);
+    }
+
+    #[test]
+    fn defender_exclusions_keep_array_items_apart() {
+        let set = block_on(defender_exclusions(&workstation())).unwrap();
+        let exclusions: Vec<String> = (0..set.rows.len())
+            .map(|i| set.value(i, "Exclusion").unwrap().to_string())
+            .collect();
+        assert_eq!(
+            exclusions,
+            [
+                "C:\\",
+                "C:\\Users\\user\\Projects\\build",
+                "%TEMP%\\installer",
+                "*.exe",
+                "log",
+                "C:\\Program Files\\Backup\\backup.exe",
+                "powershell.exe"
+            ]
+        );
+        let flagged = findings::row_findings(ActiveData::DefenderExclusions, &set);
+        let mut rows: Vec<usize> = flagged.keys().copied().collect();
+        rows.sort_unstable();
+        assert_eq!(rows, [0, 2, 3, 6]);
+        assert!(flagged
+            .values()
+            .all(|found| found[0].severity == Severity::High));
+        // Ключ находки — из данных, а не из переведённого пояснения
+        let kind = set.value(0, "ExclusionType").unwrap();
+        assert_eq!(flagged[&0][0].identity, format!("{kind}|C:\\"));
+        assert_eq!(flagged[&0][0].slug(), "defender_exclusions");
+    }
+
+    #[test]
+    fn adapters_joined_with_ip_configuration() {
+        let set = block_on(network_adapters(&workstation(), None)).unwrap();
+        assert_eq!(set.rows.len(), 4);
+        assert_eq!(
+            set.value(0, "IPAddress"),
+            Some(&"192.168.1.20, fe80::1c2d:3e4f:5a6b:7c8d".into())
+        );
+        assert_eq!(
+            set.value(0, "ConnectionState"),
+            Some(&tr("netstatus.connected").into())
+        );
+        // Без Index настройки находятся по MAC-адресу без учёта регистра
+        assert_eq!(set.extra_value(3, "DHCPEnabled"), Some(&Value::Bool(true)));
+        assert_eq!(set.value(1, "Virtual"), Some(&Value::Bool(true)));
+        assert_eq!(set.value(1, "ConnectionState"), Some(&Value::Null));
+        let drivers: Vec<&Value> = (0..4)
+            .filter_map(|i| set.value(i, "CaptureDriver"))
+            .collect();
+        assert_eq!(
+            drivers,
+            [
+                &"Npcap".into(),
+                &Value::Null,
+                &"Npcap".into(),
+                &"Npcap".into()
+            ]
+        );
+        let flagged = findings::row_findings(ActiveData::NetworkAdapters, &set);
+        assert_eq!(flagged.len(), 3);
+        assert!(!flagged.contains_key(&1));
+    }
+
+    #[test]
+    fn virtual_machine_is_recognized_before_evidence() {
+        let set = block_on(virtualization(&Fixtures::new("vmware_guest"))).unwrap();
+        let vm = trf("virt.vm", &[("hypervisor", &"VMware")]);
+        assert_eq!(set.value(0, "Observed"), Some(&vm.into()));
+        let devices = (0..set.rows.len())
+            .filter(|&i| set.value(i, "Indicator") == Some(&tr("virt.device").into()))
+            .count();
+        assert_eq!(devices, 2);
+
+        // Хост Hyper-V: гипервизор запущен и есть шина VMBus, но сам компьютер физический
+        let set = block_on(virtualization(&Fixtures::new("hyperv_host"))).unwrap();
+        assert_eq!(set.value(0, "Observed"), Some(&tr("virt.physical").into()));
+        assert_eq!(set.value(0, "Hypervisor"), Some(&Value::Null));
+        let machines: Vec<&Value> = (0..set.rows.len())
+            .filter(|&i| set.value(i, "Indicator") == Some(&tr("virt.hyperv_vm").into()))
+            .filter_map(|i| set.value(i, "Observed"))
+            .collect();
+        assert_eq!(machines, [&"DC01".into()]);
+    }
+
+    #[test]
+    fn cooling_explains_empty_sources() {
+        let set = block_on(cooling(&workstation())).unwrap();
+        assert_eq!(set.rows.len(), 4);
+        assert_eq!(set.value(0, "Source"), Some(&"Win32_Fan".into()));
+        assert_eq!(
+            set.value(0, "Note"),
+            Some(&tr("cooling.no_instances").into())
+        );
+        assert_eq!(set.value(1, "Note"), Some(&tr("cooling.no_reading").into()));
+        assert_eq!(
+            set.value(2, "Name"),
+            Some(&"ACPI\\ThermalZone\\TZ00_0".into())
+        );
+        assert_eq!(set.value(2, "Note"), Some(&Value::Null));
+        // Зона без данных отдаёт 0 К
+        assert!(matches!(set.value(3, "Note"), Some(Value::Text(_))));
+    }
+
+    #[test]
+    fn port_connectors_lead_with_usb_count() {
+        let set = block_on(port_connectors(&workstation())).unwrap();
+        assert_eq!(set.value(0, "Count"), Some(&Value::Int(3)));
+        assert_eq!(set.value(1, "ConnectorKind"), Some(&"USB".into()));
+        assert_eq!(
+            set.value(1, "Designators"),
+            Some(&"USB 1, USB 2, USB-C".into())
+        );
+        let kinds: Vec<String> = (2..set.rows.len())
+            .map(|i| set.value(i, "ConnectorKind").unwrap().to_string())
+            .collect();
+        assert_eq!(kinds.len(), 3);
+        assert!(kinds.contains(&"HDMI".to_string()));
+        let audio = 2 + kinds
+            .iter()
+            .position(|kind| kind == tr("port.audio"))
+            .unwrap();
+        assert_eq!(
+            set.value(audio, "Connectors"),
+            Some(&format!("Mini-jack, {}", tr("port.female")).into())
+        );
+        assert_eq!(set.value(audio, "Designators"), Some(&"JAUDIO1".into()));
+    }
+
+    #[test]
+    fn volumes_without_letter_show_mount_path() {
+        let set = block_on(volumes(&workstation(), None)).unwrap();
+        assert_eq!(set.value(0, "MountPath"), Some(&"C:\\".into()));
+        assert_eq!(
+            set.value(2, "MountPath"),
+            Some(&"C:\\Mount\\Archive\\".into())
+        );
+        assert_eq!(set.extra_value(2, "DriveLetter"), Some(&Value::Null));
+        let flagged = findings::row_findings(ActiveData::Volumes, &set);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[&2][0].detail, "C:\\Mount\\Archive\\");
+    }
+
+    #[test]
+    fn quota_users_sorted_by_usage() {
+        let set = block_on(disk_quotas(&workstation())).unwrap();
+        assert_eq!(set.rows.len(), 4);
+        assert_eq!(
+            set.value(0, "QuotaState"),
+            Some(&tr("quota.tracked").into())
+        );
+        assert_eq!(set.value(0, "Limit"), Some(&tr("quota.no_limit").into()));
+        assert_eq!(set.value(1, "User"), Some(&"WS-01\\user".into()));
+        assert_eq!(
+            set.value(1, "DiskSpaceUsed"),
+            Some(&Value::Int(64424509440))
+        );
+        assert_eq!(set.value(1, "Limit"), Some(&Value::Int(53687091200)));
+        assert_eq!(
+            set.value(2, "User"),
+            Some(&"BUILTIN\\Администраторы".into())
+        );
+        assert_eq!(set.value(3, "Volume"), Some(&"E:".into()));
+        assert_eq!(
+            set.value(3, "QuotaState"),
+            Some(&tr("quota.unsupported").into())
+        );
+    }
+
+    #[test]
+    fn uptime_flags_workstation_waiting_for_reboot() {
+        let set = block_on(uptime(&workstation())).unwrap();
+        assert_eq!(
+            set.value(2, "Observed"),
+            Some(&trf("uptime.duration", &[("days", &37), ("hours", &1)]).into())
+        );
+        assert_eq!(set.value(4, "Observed"), Some(&"2026-02-24".into()));
+        assert_eq!(set.value(4, "Days"), Some(&Value::Int(14)));
+        // Три последних завершения, новые первыми, время — по параметрам события
+        let shutdowns: Vec<&Value> = (5..set.rows.len())
+            .filter_map(|i| set.value(i, "Observed"))
+            .collect();
+        assert_eq!(
+            shutdowns,
+            [
+                &"31.01.2026 23:41:37".into(),
+                &"12.01.2026 8:52:10".into(),
+                &"20.12.2025 7:12:03".into()
+            ]
+        );
+        let flagged = findings::row_findings(ActiveData::Uptime, &set);
+        assert_eq!(
+            flagged[&2][0].title,
+            trf("finding.long_uptime", &[("days", &37)])
+        );
+        assert_eq!(flagged[&3][0].severity, Severity::Medium);
+        assert!(flagged[&3][0].detail.contains("RebootPending"));
+        assert_eq!(flagged[&2][0].identity, "uptime");
+        assert_eq!(flagged[&3][0].identity, "pending");
+        // Данные, собранные на другом языке, проверяются так же
+        let mut english = set.clone();
+        for row in &mut english.rows {
+            row[0] = "Indicator".into();
+        }
+        english.rows[0][1] = "Workstation".into();
+        assert_eq!(
+            findings::row_findings(ActiveData::Uptime, &english).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn asset_flags_vendor_placeholders() {
+        let set = block_on(asset(&workstation())).unwrap();
+        assert_eq!(set.value(0, "Observed"), Some(&"WS-01".into()));
+        assert_eq!(set.value(7, "Observed"), Some(&"3C:52:82:1A:2B:3C".into()));
+        assert_eq!(
+            set.value(8, "Observed"),
+            Some(&"00330-80000-00000-AA412".into())
+        );
+        let flagged = findings::row_findings(ActiveData::Asset, &set);
+        let mut rows: Vec<usize> = flagged.keys().copied().collect();
+        rows.sort_unstable();
+        // Серийный номер продукта и инвентарная метка — заглушки, серийный номер BIOS настоящий
+        assert_eq!(rows, [4, 6]);
+        assert_eq!(flagged[&6][0].detail, "Default string");
+    }
+
+    #[test]
+    fn power_plans_flag_active_plan_only() {
+        let set = block_on(power_plans(&workstation())).unwrap();
+        assert_eq!(
+            set.value(0, "PowerPlan"),
+            Some(&tr("power.balanced").into())
+        );
+        assert_eq!(set.value(0, "SleepDC"), Some(&Value::Int(900)));
+        // Остальные схемы — по имени; у схемы без значений источник не указан
+        assert_eq!(set.value(2, "PowerPlan"), Some(&tr("power.saver").into()));
+        assert_eq!(set.extra_value(1, "SettingsSource"), Some(&Value::Null));
+        assert_eq!(
+            set.extra_value(2, "SettingsSource"),
+            Some(&tr("power.from_wmi").into())
+        );
+        // Схема энергосбережения тоже без пароля, но не активна
+        let flagged = findings::row_findings(ActiveData::PowerPlans, &set);
+        assert_eq!(flagged.keys().collect::<Vec<_>>(), [&0]);
+        let checks: Vec<_> = flagged[&0].iter().map(|f| f.check).collect();
+        assert_eq!(
+            checks,
+            [
+                findings::Check::NeverSleeps,
+                findings::Check::NoWakePassword
+            ]
+        );
+    }
+
+    #[test]
+    fn power_plan_falls_back_to_registry() {
+        let plan = registry_plan(&Fixtures::new("server_core")).unwrap();
+        let set = power::result_set(&[plan], false, Some(tr("power.no_namespace")));
+        assert_eq!(
+            set.value(0, "PowerPlan"),
+            Some(&tr("power.no_namespace").into())
+        );
+        assert_eq!(
+            set.value(1, "PowerPlan"),
+            Some(&tr("power.high_performance").into())
+        );
+        assert_eq!(set.value(1, "WakePasswordAC"), Some(&Value::Bool(true)));
+        assert_eq!(
+            set.extra_value(1, "SettingsSource"),
+            Some(&tr("power.from_registry").into())
+        );
+        let flagged = findings::row_findings(ActiveData::PowerPlans, &set);
+        assert_eq!(flagged[&1].len(), 1);
+        assert_eq!(flagged[&1][0].title, tr("finding.never_sleeps"));
+    }
+}
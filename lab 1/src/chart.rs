@@ -0,0 +1,251 @@
+use crate::i18n::tr;
+use crate::model::{ResultSet, Value};
+use egui_plot::{Bar, BarChart, Legend, Plot};
+
+/// Диаграмма категории: подписи столбиков из одного столбца таблицы, высоты —
+/// из другого или, без него, число строк с одинаковой подписью
+#[derive(Debug, Clone, Copy)]
+pub struct Chart {
+    pub label: &'static str,
+    pub value: Option<&'static str>,
+    pub label_text: fn(&Value) -> String, // Подпись столбика по значению ячейки
+    pub stacked: bool,                    // Один столбик из долей вместо отдельных столбиков
+}
+
+impl Chart {
+    /// Столбики по строкам в порядке отображения таблицы; строки без числа пропускаются.
+    /// Подсчитанные столбики идут по убыванию числа строк
+    pub fn bars(&self, set: &ResultSet, rows: &[usize]) -> Vec<(String, f64)> {
+        let column = |name| set.columns.iter().position(|c| c.name == name);
+        let Some(label) = column(self.label) else {
+            return Vec::new();
+        };
+        let Some(value) = self.value else {
+            let mut counts: Vec<(String, f64)> = Vec::new();
+            for row in rows.iter().filter_map(|&i| set.rows.get(i)) {
+                let Some(cell) = row.get(label) else {
+                    continue;
+                };
+                let text = (self.label_text)(cell);
+                match counts.iter_mut().find(|(label, _)| *label == text) {
+                    Some((_, count)) => *count += 1.0,
+                    None => counts.push((text, 1.0)),
+                }
+            }
+            counts.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            return counts;
+        };
+        let Some(value) = column(value) else {
+            return Vec::new();
+        };
+        rows.iter()
+            .filter_map(|&i| {
+                let row = set.rows.get(i)?;
+                let height = match row.get(value)? {
+                    Value::Int(n) => *n as f64,
+                    Value::Float(n) => *n,
+                    _ => return None,
+                };
+                Some(((self.label_text)(row.get(label)?), height))
+            })
+            .collect()
+    }
+
+    /// Столбчатая диаграмма; при наведении на столбик видно точное значение
+    pub fn show(&self, ui: &mut egui::Ui, set: &ResultSet, rows: &[usize]) {
+        let bars = self.bars(set, rows);
+        if bars.is_empty() {
+            ui.label(tr("results.empty"));
+            return;
+        }
+        if self.stacked {
+            stacked(ui, bars);
+            return;
+        }
+        let labels: Vec<String> = bars.iter().map(|(label, _)| label.clone()).collect();
+        let chart = BarChart::new(
+            bars.into_iter()
+                .enumerate()
+                .map(|(i, (label, height))| Bar::new(i as f64, height).name(label).width(0.6))
+                .collect(),
+        )
+        .color(ui.visuals().selection.bg_fill)
+        .element_formatter(Box::new(|bar, _| format!("{}: {}", bar.name, bar.value)));
+        let value_title = match self.value {
+            Some(value) => set
+                .columns
+                .iter()
+                .find(|c| c.name == value)
+                .map(|c| c.title.clone())
+                .unwrap_or_default(),
+            None => tr("chart.count").to_string(),
+        };
+
+        Plot::new("category_chart")
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .show_grid([false, true])
+            .label_formatter(|_, _| String::new())
+            .x_axis_formatter(move |mark, _| {
+                // Подписи только под столбиками, без промежуточных делений
+                if mark.value.fract() != 0.0 || mark.value < 0.0 {
+                    return String::new();
+                }
+                labels.get(mark.value as usize).cloned().unwrap_or_default()
+            })
+            .y_axis_label(value_title)
+            .show(ui, |plot| plot.bar_chart(chart));
+    }
+}
+
+// Один горизонтальный столбик из долей с легендой: каждая доля — своя диаграмма
+// своего цвета, сдвинутая на сумму предыдущих
+fn stacked(ui: &mut egui::Ui, bars: Vec<(String, f64)>) {
+    let total: f64 = bars.iter().map(|(_, height)| height).sum();
+    let mut offset = 0.0;
+    let charts: Vec<BarChart> = bars
+        .into_iter()
+        .map(|(label, height)| {
+            let bar = Bar::new(0.0, height)
+                .base_offset(offset)
+                .name(&label)
+                .width(0.6);
+            offset += height;
+            BarChart::new(vec![bar])
+                .horizontal()
+                .name(label)
+                .element_formatter(Box::new(move |bar, _| {
+                    let share = bar.value / total * 100.0;
+                    format!("{}: {} ({share:.0}%)", bar.name, bar.value)
+                }))
+        })
+        .collect();
+    Plot::new("category_chart_stacked")
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .show_axes([true, false])
+        .show_grid([true, false])
+        .label_formatter(|_, _| String::new())
+        .legend(Legend::default())
+        .x_axis_label(tr("chart.count"))
+        .show(ui, |plot| {
+            for chart in charts {
+                plot.bar_chart(chart);
+            }
+        });
+}
+
+/// Значение ячейки как есть; пустая ячейка — «н/д»
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => tr("value.na").to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Папка верхнего уровня пути к исполняемому файлу, например `C:\Windows` для
+/// `C:\Windows\System32\svchost.exe`. Путь без папки остаётся как есть
+pub fn top_folder(value: &Value) -> String {
+    let Value::Text(path) = value else {
+        return tr("chart.no_path").to_string();
+    };
+    let mut parts = path.splitn(3, '\\');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(drive), Some(folder), Some(_)) => format!("{drive}\\{folder}"),
+        _ => path.clone(),
+    }
+}
+
+/// Название типа SID из перечисления SID_NAME_USE
+pub fn sid_type(value: &Value) -> String {
+    let key = match value {
+        Value::Int(1) => "sid_type.user",
+        Value::Int(2) => "sid_type.group",
+        Value::Int(3) => "sid_type.domain",
+        Value::Int(4) => "sid_type.alias",
+        Value::Int(5) => "sid_type.well_known_group",
+        Value::Int(6) => "sid_type.deleted_account",
+        Value::Int(7) => "sid_type.invalid",
+        Value::Int(8) => "sid_type.unknown",
+        Value::Int(9) => "sid_type.computer",
+        other => return other.to_string(),
+    };
+    tr(key).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SID_CHART: Chart = Chart {
+        label: "SIDType",
+        value: Some("Count"),
+        label_text: sid_type,
+        stacked: false,
+    };
+
+    fn counts() -> ResultSet {
+        let mut set = ResultSet::new(&["SIDType", "Count"]);
+        set.rows.push(vec![Value::Int(1), Value::Int(7)]);
+        set.rows.push(vec![Value::Int(4), Value::Int(23)]);
+        set.rows.push(vec![Value::Int(42), Value::Null]);
+        set
+    }
+
+    #[test]
+    fn bars_follow_row_order_and_skip_non_numbers() {
+        let bars = SID_CHART.bars(&counts(), &[1, 0, 2]);
+        assert_eq!(
+            bars,
+            vec![
+                (tr("sid_type.alias").to_string(), 23.0),
+                (tr("sid_type.user").to_string(), 7.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn processes_are_counted_by_top_folder() {
+        let mut set = ResultSet::new(&["Name", "ExecutablePath"]);
+        for path in [
+            Value::from("C:\\Windows\\System32\\svchost.exe"),
+            "C:\\Program Files\\Mozilla Firefox\\firefox.exe".into(),
+            "C:\\Windows\\explorer.exe".into(),
+            Value::Null,
+        ] {
+            set.rows.push(vec!["p.exe".into(), path]);
+        }
+        let chart = Chart {
+            label: "ExecutablePath",
+            value: None,
+            label_text: top_folder,
+            stacked: false,
+        };
+        assert_eq!(
+            chart.bars(&set, &[0, 1, 2, 3]),
+            vec![
+                ("C:\\Windows".to_string(), 2.0),
+                ("C:\\Program Files".to_string(), 1.0),
+                (tr("chart.no_path").to_string(), 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_sid_type_keeps_number() {
+        assert_eq!(sid_type(&Value::Int(42)), "42");
+    }
+
+    #[test]
+    fn missing_column_gives_no_bars() {
+        let chart = Chart {
+            label: "Name",
+            ..SID_CHART
+        };
+        assert!(chart.bars(&counts(), &[0, 1]).is_empty());
+    }
+}
@@ -0,0 +1,440 @@
+use crate::config::{self, Config};
+use crate::connection::Target;
+use crate::findings::{Check, Finding, Severity};
+use crate::hunt;
+use crate::i18n::{self, tr, trf, Language};
+use crate::logging;
+use crate::model::{ResultSet, Value};
+use crate::output;
+use crate::process;
+use crate::report::{self, Format, Request};
+use crate::uptime;
+use crate::worker::{Timeout, Worker};
+use crate::{queries, ActiveData};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// Коды завершения: запрос не выполнен; есть находки важности High и выше
+const EXIT_FAILED: u8 = 1;
+const EXIT_FINDINGS: u8 = 2;
+
+// Пароль для --user берётся из окружения, чтобы не попадать в историю команд
+const PASSWORD_VAR: &str = "LAB1_PASSWORD";
+
+/// Запуск без окна: данные категории, проверки или отчёт. Без команды открывается окно.
+/// Код завершения 1 — запрос не выполнен, 2 — есть находки важности «высокая» и выше
+#[derive(Parser)]
+#[command(name = "lab1", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Файл настроек; по умолчанию config.toml рядом с программой
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Удалённый компьютер; без него — локальный
+    #[arg(long, global = true)]
+    host: Option<String>,
+    /// Пользователь удалённого компьютера (ДОМЕН\имя); пароль берётся из LAB1_PASSWORD
+    #[arg(long, global = true)]
+    user: Option<String>,
+    /// Время ожидания одного запроса, в секундах; по умолчанию из файла настроек
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+    /// Язык вывода; по умолчанию из файла настроек
+    #[arg(long, global = true, value_enum)]
+    lang: Option<Lang>,
+    /// Формат данных; для json и csv сводка находок выводится в stderr
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: output::Format,
+    /// Записать данные в файл (UTF-8, для CSV с BOM) вместо stdout
+    #[arg(long, global = true)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Переменные окружения (Win32_Environment)
+    Env,
+    /// Число учётных записей по типам SID (Win32_Account)
+    Sids,
+    /// Устройства Plug and Play (Win32_PnPEntity)
+    Devices,
+    /// Устройства с ошибкой диспетчера устройств и её описанием (Win32_PnPEntity)
+    Problems,
+    /// Вентиляторы и температура (Win32_Fan, Win32_TemperatureProbe, MSAcpi_ThermalZoneTemperature)
+    Cooling,
+    /// Внешние разъёмы корпуса по видам (Win32_PortConnector)
+    Ports,
+    /// Тома: файловая система, бит загрязнения, загрузочный и системный (Win32_Volume)
+    Volumes,
+    /// Квоты дисков и пользователи с наибольшим занятым местом (Win32_QuotaSetting, Win32_DiskQuota)
+    Quotas,
+    /// Запущенные процессы (Win32_Process)
+    Processes,
+    /// Процессы, в командной строке которых есть слово, без учёта регистра (Win32_Process)
+    #[command(name = "findproc")]
+    FindProc {
+        keyword: String,
+        /// Слово — регулярное выражение
+        #[arg(long)]
+        regex: bool,
+    },
+    /// Службы (Win32_Service)
+    Services,
+    /// Установленные программы (Win32_InstalledWin32Program)
+    Software,
+    /// Сетевые адаптеры с адресами IP (Win32_NetworkAdapter, Win32_NetworkAdapterConfiguration)
+    Adapters,
+    /// Признаки виртуальной машины и состояние Hyper-V (Win32_ComputerSystem, Win32_BIOS,
+    /// Win32_PnPEntity, Msvm_ComputerSystem)
+    Virtualization,
+    /// Время работы, ожидаемая перезагрузка и неожиданные завершения (Win32_OperatingSystem,
+    /// Win32_QuickFixEngineering, Win32_NTLogEvent, StdRegProv)
+    Uptime,
+    /// Схемы электропитания: время до сна и пароль при пробуждении (Win32_PowerPlan,
+    /// Win32_PowerSettingDataIndex, StdRegProv)
+    Power,
+    /// Сведения для учёта оборудования: UUID, серийные номера, метка, MAC и код продукта ОС
+    /// (Win32_ComputerSystemProduct, Win32_BIOS, Win32_SystemEnclosure)
+    Asset,
+    /// Файлы предвыборки: что запускалось недавно (CIM_DataFile)
+    Prefetch,
+    /// Содержимое корзин пользователей (CIM_DataFile и файлы $I)
+    RecycleBin,
+    /// Расширения браузеров из профилей пользователей (Win32_UserProfile и файлы профилей)
+    Extensions,
+    /// Исключения Microsoft Defender (MSFT_MpPreference)
+    Defender,
+    /// Все проверки; выводятся только находки
+    Check,
+    /// Отчёт со всеми категориями и проверками; формат по расширению (.html, .md или .json),
+    /// --format и --output не используются
+    Report {
+        #[arg(long)]
+        out: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Lang {
+    Ru,
+    En,
+}
+
+impl From<Lang> for Language {
+    fn from(lang: Lang) -> Self {
+        match lang {
+            Lang::Ru => Language::Ru,
+            Lang::En => Language::En,
+        }
+    }
+}
+
+impl Command {
+    fn category(&self) -> Option<ActiveData> {
+        match self {
+            Command::Env => Some(ActiveData::EnvVars),
+            Command::Sids => Some(ActiveData::SidCounts),
+            Command::Devices => Some(ActiveData::BusInfo),
+            Command::Problems => Some(ActiveData::ProblemDevices),
+            Command::Cooling => Some(ActiveData::Cooling),
+            Command::Ports => Some(ActiveData::PortConnectors),
+            Command::Volumes => Some(ActiveData::Volumes),
+            Command::Quotas => Some(ActiveData::DiskQuotas),
+            Command::Processes => Some(ActiveData::Processes),
+            Command::Services => Some(ActiveData::Services),
+            Command::Software => Some(ActiveData::Software),
+            Command::Adapters => Some(ActiveData::NetworkAdapters),
+            Command::Virtualization => Some(ActiveData::Virtualization),
+            Command::Uptime => Some(ActiveData::Uptime),
+            Command::Power => Some(ActiveData::PowerPlans),
+            Command::Asset => Some(ActiveData::Asset),
+            Command::Prefetch => Some(ActiveData::Prefetch),
+            Command::RecycleBin => Some(ActiveData::RecycleBin),
+            Command::Extensions => Some(ActiveData::BrowserExtensions),
+            Command::Defender => Some(ActiveData::DefenderExclusions),
+            Command::FindProc { .. } | Command::Check | Command::Report { .. } => None,
+        }
+    }
+}
+
+/// Разбирает аргументы, выполняет команду и возвращает код завершения
+pub fn run() -> ExitCode {
+    let cli = Cli::parse();
+    let config_path = config::path(cli.config);
+    let Some(command) = cli.command else {
+        return crate::run_window(config_path);
+    };
+    // Язык из аргументов нужен уже для сообщения об ошибке в файле настроек
+    i18n::set_language(cli.lang.map(Language::from).unwrap_or_default());
+    let config = match config::load(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::from(EXIT_FAILED);
+        }
+    };
+    i18n::set_language(cli.lang.map_or(config.language, Language::from));
+    let _log_guard = logging::init(&config.log);
+    uptime::set_lookback_days(config.audit.event_lookback_days);
+    let target = Target {
+        host: cli.host.unwrap_or_default(),
+        user: cli.user.unwrap_or_default(),
+        password: std::env::var(PASSWORD_VAR).unwrap_or_default(),
+    };
+    let timeout = cli.timeout.map_or(config.timeout, Timeout::from_secs);
+    let host = target.host_name();
+    let sink = Sink {
+        format: cli.format,
+        path: cli.output,
+        host: host.clone(),
+    };
+    tracing::info!(%host, "запуск без окна");
+
+    let code = match command {
+        Command::Report { out } => write_report(target, timeout, out, &config),
+        Command::FindProc { keyword, regex } => {
+            find_processes(&mut Worker::new(target), timeout, &keyword, regex, &sink)
+        }
+        Command::Check => {
+            let mut worker = Worker::new(target);
+            let checks = Check::ALL.to_vec();
+            let (findings, code) = run_checks(&mut worker, timeout, &checks, &mut HashMap::new());
+            let emitted = sink.emit(FINDINGS_SLUG, &findings_set(&findings));
+            if emitted {
+                code
+            } else {
+                EXIT_FAILED
+            }
+        }
+        command => {
+            let data = command.category().unwrap_or_default();
+            let mut worker = Worker::new(target);
+            match query(&mut worker, timeout, data) {
+                Ok(set) if sink.emit(data.slug(), &set) => {
+                    let checks: Vec<_> = Check::ALL
+                        .into_iter()
+                        .filter(|c| c.source() == data)
+                        .collect();
+                    let mut results = HashMap::from([(data, set)]);
+                    let (findings, code) = run_checks(&mut worker, timeout, &checks, &mut results);
+                    print_findings(&findings, sink.format == output::Format::Table);
+                    code
+                }
+                Ok(_) | Err(()) => EXIT_FAILED,
+            }
+        }
+    };
+    ExitCode::from(code)
+}
+
+// Запрос категории через поток запросов, как в окне. Ошибка выводится в stderr
+fn query(worker: &mut Worker, timeout: Timeout, data: ActiveData) -> Result<ResultSet, ()> {
+    worker
+        .run(timeout.duration(), move |session| async move {
+            queries::run(session.connection()?, data, None).await
+        })
+        .unwrap_or_else(|| Err(timeout.error()))
+        .map_err(|e| {
+            eprintln!("{}: {}", data.title(), e.detailed());
+            if let Some(hint) = e.hint() {
+                eprintln!("{hint}");
+            }
+        })
+}
+
+// Поиск по командным строкам процессов. Владельцы запрашиваются только у найденных;
+// число процессов со скрытой командной строкой выводится после данных
+fn find_processes(
+    worker: &mut Worker,
+    timeout: Timeout,
+    keyword: &str,
+    regex: bool,
+    sink: &Sink,
+) -> u8 {
+    let pattern = match hunt::pattern(keyword, regex) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            eprintln!("{e}");
+            return EXIT_FAILED;
+        }
+    };
+    let Ok(set) = query(worker, timeout, ActiveData::Processes) else {
+        return EXIT_FAILED;
+    };
+    let matches = hunt::search(&set, 0..set.rows.len(), &pattern);
+    let pids: Vec<i64> = matches
+        .rows
+        .iter()
+        .filter_map(|&row| match set.value(row, "ProcessId") {
+            Some(Value::Int(pid)) => Some(*pid),
+            _ => None,
+        })
+        .collect();
+    // Процесс мог завершиться или оказаться недоступным: его владелец остаётся неизвестным
+    let owners = worker
+        .run(timeout.duration(), move |session| async move {
+            let Ok(con) = session.connection() else {
+                return HashMap::new();
+            };
+            pids.into_iter()
+                .filter_map(|pid| Some((pid, process::owner(con, pid).ok()??)))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !sink.emit("findproc", &hunt::result_set(&set, &matches, &owners)) {
+        return EXIT_FAILED;
+    }
+    if let Some(note) = matches.hidden_note() {
+        if sink.format == output::Format::Table {
+            println!("\n{note}");
+        } else {
+            eprintln!("{note}");
+        }
+    }
+    0
+}
+
+// Проверки с догрузкой недостающих категорий. Код завершения — по ошибкам запросов
+// и важности находок
+fn run_checks(
+    worker: &mut Worker,
+    timeout: Timeout,
+    checks: &[Check],
+    results: &mut HashMap<ActiveData, ResultSet>,
+) -> (Vec<Finding>, u8) {
+    let mut code = 0;
+    for check in checks {
+        let data = check.source();
+        if let Entry::Vacant(slot) = results.entry(data) {
+            match query(worker, timeout, data) {
+                Ok(set) => {
+                    slot.insert(set);
+                }
+                Err(()) => code = EXIT_FAILED,
+            }
+        }
+    }
+    let findings: Vec<Finding> = checks.iter().flat_map(|c| c.run(results)).collect();
+    if code == 0 && findings.iter().any(|f| f.severity >= Severity::High) {
+        code = EXIT_FINDINGS;
+    }
+    (findings, code)
+}
+
+// Категории отчёта — из файла настроек, кроме журнала самой программы
+fn write_report(target: Target, timeout: Timeout, out: PathBuf, config: &Config) -> u8 {
+    let request = Request {
+        target,
+        timeout,
+        categories: config
+            .report
+            .categories
+            .iter()
+            .copied()
+            .filter(|&d| d != ActiveData::Log)
+            .collect(),
+        checks: Check::ALL.to_vec(),
+        format: Format::from_path(&out),
+        path: out,
+        known: HashMap::new(),
+    };
+    let report = report::collect(&request);
+    if let Err(e) = report::write(&request, &report) {
+        eprintln!("{e}");
+        return EXIT_FAILED;
+    }
+    println!(
+        "{}",
+        trf("report.saved", &[("path", &request.path.display())])
+    );
+    let mut failed = false;
+    for section in &report.sections {
+        if let Err(e) = &section.data {
+            eprintln!("{}: {e}", section.title);
+            failed = true;
+        }
+    }
+    if failed {
+        EXIT_FAILED
+    } else if report.findings.iter().any(|f| f.severity >= Severity::High) {
+        EXIT_FINDINGS
+    } else {
+        0
+    }
+}
+
+// Имя категории находок в JSON-выводе команды check
+const FINDINGS_SLUG: &str = "findings";
+
+// Куда и в каком формате выводятся данные
+struct Sink {
+    format: output::Format,
+    path: Option<PathBuf>,
+    host: String,
+}
+
+impl Sink {
+    // `false`, если файл записать не удалось; ошибка уже выведена в stderr
+    fn emit(&self, category: &str, set: &ResultSet) -> bool {
+        let text = output::render(self.format, &self.host, category, set);
+        let Some(path) = &self.path else {
+            print!("{text}");
+            return true;
+        };
+        match output::write(path, self.format, &text) {
+            Ok(()) => true,
+            Err(e) => {
+                let path = path.display();
+                eprintln!(
+                    "{}",
+                    trf("export.failed", &[("path", &path), ("error", &e)])
+                );
+                false
+            }
+        }
+    }
+}
+
+// Находки команды check в виде таблицы
+fn findings_set(findings: &[Finding]) -> ResultSet {
+    let mut set = ResultSet::new(&["Severity", "Category", "Title", "Detail"]);
+    set.rows = findings
+        .iter()
+        .map(|finding| {
+            vec![
+                Value::from(finding.severity.title()),
+                finding.category.as_str().into(),
+                finding.title.as_str().into(),
+                finding.detail.as_str().into(),
+            ]
+        })
+        .collect();
+    set
+}
+
+// Сводка находок после данных категории. Для json и csv — в stderr,
+// чтобы stdout оставался разбираемым
+fn print_findings(findings: &[Finding], to_stdout: bool) {
+    if findings.is_empty() {
+        return;
+    }
+    let mut text = format!("\n{}\n", tr("report.findings"));
+    for finding in findings {
+        text.push_str(&format!(
+            "[{}] {}: {} — {}\n",
+            finding.severity.title(),
+            finding.category,
+            finding.title,
+            finding.detail
+        ));
+    }
+    if to_stdout {
+        print!("{text}");
+    } else {
+        eprint!("{text}");
+    }
+}
@@ -0,0 +1,625 @@
+use crate::autoexport;
+use crate::cache::Ttl;
+use crate::eventlog;
+use crate::findings::Check;
+use crate::history;
+use crate::i18n::{trf, Language};
+use crate::logging;
+use crate::notify;
+use crate::perf;
+use crate::profiles;
+use crate::report::Format;
+use crate::schedule;
+use crate::syslog;
+use crate::theme::Theme;
+use crate::uptime;
+use crate::watch;
+use crate::webhook;
+use crate::worker::Timeout;
+use crate::ActiveData;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "config.toml";
+
+/// Значения по умолчанию из config.toml. Отсутствующие ключи берутся из `Default`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub timeout: Timeout,
+    pub cache_ttl: Ttl,
+    pub language: Language,
+    pub theme: Theme,
+    pub hosts: Vec<String>, // Удалённые компьютеры для окна подключения
+    pub connections: Vec<profiles::Profile>,
+    pub report: ReportConfig,
+    pub performance: PerfConfig,
+    pub watch: WatchConfig,
+    pub audit: AuditConfig,
+    pub log: logging::Settings,
+    pub notifications: notify::Settings,
+    pub history: history::Settings,
+    pub syslog: syslog::Settings,
+    pub eventlog: eventlog::Settings,
+    pub webhook: webhook::Settings,
+    pub schedule: schedule::Settings,
+    pub autoexport: autoexport::Settings,
+    pub columns: Vec<CategoryColumns>, // Только категории с изменённым набором столбцов
+}
+
+/// Таблица `[[columns]]`: порядок и скрытые столбцы таблицы категории
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryColumns {
+    pub category: ActiveData,
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub hidden: Vec<String>,
+}
+
+/// Раздел `[report]`: что включено в отчёт по умолчанию и сохранённые профили
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    pub categories: Vec<ActiveData>,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            categories: ActiveData::ALL.to_vec(),
+            profiles: Vec::new(),
+        }
+    }
+}
+
+/// Раздел `[performance]`: полоса графика загрузки
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerfConfig {
+    pub pause_when_hidden: bool,
+    pub interval: u64, // Секунд между замерами
+}
+
+impl Default for PerfConfig {
+    fn default() -> Self {
+        Self {
+            pause_when_hidden: true,
+            interval: perf::INTERVAL_SECS,
+        }
+    }
+}
+
+/// Раздел `[watch]`: период повтора запросов при наблюдении за категориями
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    pub interval: u64,
+    pub categories: Vec<CategoryWatch>, // Только категории со своим периодом
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: watch::INTERVAL,
+            categories: Vec::new(),
+        }
+    }
+}
+
+/// Таблица `[[watch.categories]]`: период наблюдения одной категории
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryWatch {
+    pub category: ActiveData,
+    pub interval: u64,
+}
+
+/// Раздел `[audit]`: пороги проверок
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    pub event_lookback_days: i64,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            event_lookback_days: uptime::SHUTDOWN_WINDOW_DAYS,
+        }
+    }
+}
+
+/// Именованный набор разделов отчёта из конструктора отчёта
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub categories: Vec<ActiveData>, // В порядке разделов отчёта
+    #[serde(default)]
+    pub checks: Vec<Check>,
+    #[serde(default)]
+    pub format: Format,
+}
+
+/// Ошибка чтения или записи файла настроек
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        error: String,
+    },
+    /// Строка и столбец считаются с единицы
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Write {
+        path: PathBuf,
+        error: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ConfigError::Read { path, error } => trf(
+                "config.read_failed",
+                &[("path", &path.display()), ("error", error)],
+            ),
+            ConfigError::Parse {
+                path,
+                line,
+                column,
+                message,
+            } => trf(
+                "config.parse_failed",
+                &[
+                    ("path", &path.display()),
+                    ("line", line),
+                    ("column", column),
+                    ("error", message),
+                ],
+            ),
+            ConfigError::Write { path, error } => trf(
+                "config.write_failed",
+                &[("path", &path.display()), ("error", error)],
+            ),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Каталог исполняемого файла; если он неизвестен — временный каталог
+pub fn exe_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Путь к файлу настроек: из `--config` или рядом с исполняемым файлом
+pub fn path(custom: Option<PathBuf>) -> PathBuf {
+    custom.unwrap_or_else(|| exe_dir().join(FILE_NAME))
+}
+
+/// Читает файл настроек. Если файла нет, он создаётся со значениями по умолчанию
+/// и комментариями; не удалось создать — используются значения по умолчанию
+pub fn load(path: &Path) -> Result<Config, ConfigError> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse(path, &text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let config = Config::default();
+            match save(path, &config) {
+                Ok(()) => tracing::info!(path = %path.display(), "создан файл настроек"),
+                Err(e) => tracing::warn!("{e}"),
+            }
+            Ok(config)
+        }
+        Err(e) => Err(ConfigError::Read {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        }),
+    }
+}
+
+/// Записывает настройки вместе с комментариями. Свои комментарии в файле не сохраняются
+pub fn save(path: &Path, config: &Config) -> Result<(), ConfigError> {
+    std::fs::write(path, render(config)).map_err(|e| ConfigError::Write {
+        path: path.to_path_buf(),
+        error: e.to_string(),
+    })
+}
+
+pub fn parse(path: &Path, text: &str) -> Result<Config, ConfigError> {
+    toml::from_str(text).map_err(|e| {
+        let offset = e.span().map_or(0, |span| span.start);
+        let (line, column) = position(text, offset);
+        ConfigError::Parse {
+            path: path.to_path_buf(),
+            line,
+            column,
+            message: e.message().to_string(),
+        }
+    })
+}
+
+// Строка и столбец символа по смещению в байтах
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[start..].chars().count() + 1)
+}
+
+// Значение в записи TOML: строки в кавычках с экранированием, списки в скобках
+fn value<T: Serialize>(value: &T) -> String {
+    toml::Value::try_from(value)
+        .map(|v| v.to_string())
+        .unwrap_or_default()
+}
+
+// Таблицы `[[columns]]`, каждая с пустой строкой перед
+fn columns(columns: &[CategoryColumns]) -> String {
+    columns
+        .iter()
+        .map(|entry| {
+            format!(
+                "\n[[columns]]\ncategory = {}\norder = {}\nhidden = {}\n",
+                value(&entry.category),
+                value(&entry.order),
+                value(&entry.hidden),
+            )
+        })
+        .collect()
+}
+
+// Таблицы `[[watch.categories]]`, каждая с пустой строкой после
+fn watches(watches: &[CategoryWatch]) -> String {
+    watches
+        .iter()
+        .map(|entry| {
+            format!(
+                "[[watch.categories]]\ncategory = {}\ninterval = {}\n\n",
+                value(&entry.category),
+                value(&entry.interval),
+            )
+        })
+        .collect()
+}
+
+// Таблицы `[[report.profiles]]`, каждая с пустой строкой после
+fn profiles(profiles: &[Profile]) -> String {
+    profiles
+        .iter()
+        .map(|profile| {
+            format!(
+                "[[report.profiles]]\nname = {}\ncategories = {}\nchecks = {}\nformat = {}\n\n",
+                value(&profile.name),
+                value(&profile.categories),
+                value(&profile.checks),
+                value(&profile.format),
+            )
+        })
+        .collect()
+}
+
+// Таблицы `[[connections]]`, каждая с пустой строкой перед
+fn connections(profiles: &[profiles::Profile]) -> String {
+    profiles
+        .iter()
+        .map(|profile| {
+            format!(
+                "\n[[connections]]\nname = {}\nhost = {}\nuser = {}\ndomain = {}\nnamespace = {}\nsave_password = {}\n",
+                value(&profile.name),
+                value(&profile.host),
+                value(&profile.user),
+                value(&profile.domain),
+                value(&profile.namespace),
+                value(&profile.save_password),
+            )
+        })
+        .collect()
+}
+
+/// Текст файла настроек с пояснениями к каждому ключу
+pub fn render(config: &Config) -> String {
+    format!(
+        "\
+# Настройки WMI Lab. Файл перезаписывается, когда настройки меняются в окне;
+# после правки вручную выберите «Перечитать config.toml» в меню ⚙
+
+# Время ожидания одного запроса, в секундах
+timeout = {timeout}
+
+# Сколько секунд данные категории показываются без повторного запроса; 0 — всегда запрашивать
+cache_ttl = {cache_ttl}
+
+# Язык интерфейса: \"Ru\" или \"En\"
+language = {language}
+
+# Тема оформления: \"System\", \"Light\" или \"Dark\"
+theme = {theme}
+
+# Удалённые компьютеры, предлагаемые в окне подключения
+hosts = {hosts}
+
+[report]
+# Категории отчёта по умолчанию: \"EnvVars\", \"SidCounts\", \"BusInfo\", \"Processes\",
+# \"Services\", \"Log\"
+categories = {categories}
+
+# Профили конструктора отчёта, например:
+# [[report.profiles]]
+# name = \"Ежемесячный аудит\"
+# categories = [\"BusInfo\", \"SidCounts\"]
+# checks = [\"DeviceStatus\", \"ProblemDevices\"]
+# format = \"Html\"  # \"Html\", \"Json\" или \"Markdown\"
+{profiles}
+[performance]
+# Не опрашивать счётчики, пока полоса графика загрузки скрыта
+pause_when_hidden = {pause_when_hidden}
+# Секунд между замерами загрузки процессора и памяти
+interval = {perf_interval}
+
+[watch]
+# Через сколько секунд повторяется запрос категории, за которой включено наблюдение
+interval = {watch_interval}
+
+# Свой период категории, задаётся в окне рядом с кнопкой наблюдения, например:
+# [[watch.categories]]
+# category = \"Processes\"
+# interval = 5
+{watches}
+[audit]
+# За сколько дней просматривается журнал событий (неожиданные завершения в «Время работы»)
+event_lookback_days = {event_lookback_days}
+
+[log]
+# Уровень журнала: \"Error\", \"Warn\", \"Info\" или \"Debug\"
+level = {level}
+# Каталог файлов журнала; пустая строка — рядом с программой
+dir = {dir}
+
+[notifications]
+# Всплывающие уведомления о событиях наблюдения, пока окно свёрнуто или неактивно
+enabled = {notify_enabled}
+# Наименьшая важность события: \"Info\", \"Low\", \"Medium\", \"High\" или \"Critical\"
+min_severity = {min_severity}
+
+[history]
+# Записывать снимки (каждый отчёт и кнопку «Сохранить снимок») в history.sqlite3 рядом с этим файлом
+enabled = {history_enabled}
+# Сколько последних запусков хранится; более старые удаляются
+keep_runs = {keep_runs}
+
+[syslog]
+# Пересылать находки отчётов и события наблюдения на сервер syslog (RFC 5424)
+enabled = {syslog_enabled}
+server = {syslog_server}
+port = {syslog_port}
+# \"Udp\" или \"Tcp\"
+transport = {syslog_transport}
+
+[eventlog]
+# Писать находки отчётов в журнал Application от имени источника WMI-Lab-Audit.
+# Источник регистрируется кнопкой в настройках (нужны права администратора);
+# без него события пишутся от имени источника Application. Одинаковые находки
+# записываются один раз за сеанс
+enabled = {eventlog_enabled}
+min_severity = {eventlog_severity}
+
+[webhook]
+# POST в формате JSON на адрес Slack, Teams или свой обработчик для важных находок
+# отчётов и событий наблюдения; доставка повторяется до трёх раз, ошибки пишутся в журнал
+enabled = {webhook_enabled}
+url = {webhook_url}
+# Наименьшая важность: \"Info\", \"Low\", \"Medium\", \"High\" или \"Critical\"
+min_severity = {webhook_severity}
+# Проверки, находки которых не отправляются, например [\"DeviceStatus\"]
+muted = {webhook_muted}
+# Раз в 30 секунд проверяются группа «Администраторы» и получатели событий WMI;
+# о новых участниках и получателях сообщается сразу. Выключить: [\"AdminMember\", \"EventConsumer\"]
+muted_triggers = {webhook_muted_triggers}
+
+[schedule]
+# Формировать отчёт по профилю из [[report.profiles]], пока программа открыта.
+# Пропущенный запуск (компьютер спал) выполняется один раз при первой возможности
+enabled = {schedule_enabled}
+profile = {schedule_profile}
+# \"Daily\" — каждый день во время at, \"Hours\" — каждые hours часов
+repeat = {schedule_repeat}
+at = {schedule_at}
+hours = {schedule_hours}
+# Каталог отчётов, имена файлов с датой и временем; пустая строка — рядом с программой
+dir = {schedule_dir}
+
+[autoexport]
+# При закрытии программы сохранять полученные данные всех категорий в JSON без новых
+# запросов. Хранятся 10 последних файлов; ошибки записи попадают только в журнал
+enabled = {autoexport_enabled}
+# Пустая строка — каталог autoexport рядом с программой
+dir = {autoexport_dir}
+
+# Порядок и скрытые столбцы таблиц категорий, задаются в окне кнопкой «Столбцы», например:
+# [[columns]]
+# category = \"Processes\"
+# order = [\"Name\", \"ProcessId\"]  # Остальные столбцы идут следом
+# hidden = [\"CommandLine\"]
+{columns}
+# Профили подключения к компьютерам, задаются в окне подключения, например:
+# [[connections]]
+# name = \"Сервер бухгалтерии\"
+# host = \"SRV-01\"
+# user = \"admin\"  # Пусто — текущие учётные данные
+# domain = \"CORP\"
+# namespace = \"root\\\\cimv2\"  # Для консоли и обзора классов; пусто — не менять
+# save_password = true  # Пароль хранится в диспетчере учётных данных Windows, не здесь
+{connections}",
+        timeout = value(&config.timeout),
+        cache_ttl = value(&config.cache_ttl),
+        language = value(&config.language),
+        theme = value(&config.theme),
+        hosts = value(&config.hosts),
+        categories = value(&config.report.categories),
+        profiles = profiles(&config.report.profiles),
+        pause_when_hidden = value(&config.performance.pause_when_hidden),
+        perf_interval = value(&config.performance.interval),
+        watch_interval = value(&config.watch.interval),
+        watches = watches(&config.watch.categories),
+        event_lookback_days = value(&config.audit.event_lookback_days),
+        level = value(&config.log.level),
+        dir = value(&config.log.dir),
+        notify_enabled = value(&config.notifications.enabled),
+        min_severity = value(&config.notifications.min_severity),
+        history_enabled = value(&config.history.enabled),
+        keep_runs = value(&config.history.keep_runs),
+        syslog_enabled = value(&config.syslog.enabled),
+        syslog_server = value(&config.syslog.server),
+        syslog_port = value(&config.syslog.port),
+        syslog_transport = value(&config.syslog.transport),
+        eventlog_enabled = value(&config.eventlog.enabled),
+        eventlog_severity = value(&config.eventlog.min_severity),
+        webhook_enabled = value(&config.webhook.enabled),
+        webhook_url = value(&config.webhook.url),
+        webhook_severity = value(&config.webhook.min_severity),
+        webhook_muted = value(&config.webhook.muted),
+        webhook_muted_triggers = value(&config.webhook.muted_triggers),
+        schedule_enabled = value(&config.schedule.enabled),
+        schedule_profile = value(&config.schedule.profile),
+        schedule_repeat = value(&config.schedule.repeat),
+        schedule_at = value(&config.schedule.at),
+        schedule_hours = value(&config.schedule.hours),
+        schedule_dir = value(&config.schedule.dir),
+        autoexport_enabled = value(&config.autoexport.enabled),
+        autoexport_dir = value(&config.autoexport.dir),
+        columns = columns(&config.columns),
+        connections = connections(&config.connections),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::Severity;
+    use crate::tripwire::Trigger;
+
+    fn parse_str(text: &str) -> Result<Config, ConfigError> {
+        parse(Path::new(FILE_NAME), text)
+    }
+
+    #[test]
+    fn rendered_defaults_parse_back() {
+        let text = render(&Config::default());
+        assert_eq!(parse_str(&text), Ok(Config::default()));
+    }
+
+    #[test]
+    fn rendered_values_round_trip() {
+        let config = Config {
+            timeout: Timeout::from_secs(90),
+            cache_ttl: Ttl::default(),
+            language: Language::En,
+            theme: Theme::Dark,
+            hosts: vec!["SRV-01".to_string(), "10.0.0.5".to_string()],
+            connections: vec![profiles::Profile {
+                name: "Сервер \"бухгалтерии\"".to_string(),
+                host: "SRV-01".to_string(),
+                user: "admin".to_string(),
+                domain: "CORP".to_string(),
+                namespace: "root\\StandardCimv2".to_string(),
+                save_password: true,
+            }],
+            report: ReportConfig {
+                categories: vec![ActiveData::BusInfo],
+                profiles: vec![Profile {
+                    name: "Ежемесячный аудит".to_string(),
+                    categories: vec![ActiveData::SidCounts, ActiveData::EnvVars],
+                    checks: vec![Check::ProblemDevices],
+                    format: Format::Markdown,
+                }],
+            },
+            performance: PerfConfig {
+                pause_when_hidden: false,
+                interval: 5,
+            },
+            watch: WatchConfig {
+                interval: 30,
+                categories: vec![CategoryWatch {
+                    category: ActiveData::Processes,
+                    interval: 4,
+                }],
+            },
+            audit: AuditConfig {
+                event_lookback_days: 30,
+            },
+            log: logging::Settings {
+                level: logging::LogLevel::Debug,
+                dir: "C:\\Logs\\\"lab\"".to_string(),
+            },
+            notifications: notify::Settings {
+                enabled: true,
+                min_severity: Severity::High,
+            },
+            history: history::Settings {
+                enabled: true,
+                keep_runs: 5,
+            },
+            syslog: syslog::Settings {
+                enabled: true,
+                server: "siem.corp.local".to_string(),
+                port: 6514,
+                transport: syslog::Transport::Tcp,
+            },
+            eventlog: eventlog::Settings {
+                enabled: true,
+                min_severity: Severity::Critical,
+            },
+            webhook: webhook::Settings {
+                enabled: true,
+                url: "https://hooks.example.com/T000/B000".to_string(),
+                min_severity: Severity::Medium,
+                muted: vec![Check::DeviceStatus],
+                muted_triggers: vec![Trigger::EventConsumer],
+            },
+            schedule: schedule::Settings {
+                enabled: true,
+                profile: "Ежемесячный аудит".to_string(),
+                repeat: schedule::Repeat::Hours,
+                at: "07:30".to_string(),
+                hours: 6,
+                dir: "D:\\Audits".to_string(),
+            },
+            autoexport: autoexport::Settings {
+                enabled: true,
+                dir: "D:\\Расследование\\авто".to_string(),
+            },
+            columns: vec![CategoryColumns {
+                category: ActiveData::BusInfo,
+                order: vec!["Status".to_string(), "DeviceID".to_string()],
+                hidden: vec!["Name".to_string()],
+            }],
+        };
+        assert_eq!(parse_str(&render(&config)), Ok(config));
+    }
+
+    #[test]
+    fn missing_keys_take_defaults() {
+        let config = parse_str("timeout = 10\n").unwrap();
+        assert_eq!(config.timeout, Timeout::from_secs(10));
+        assert_eq!(config.report, ReportConfig::default());
+    }
+
+    #[test]
+    fn parse_error_has_position() {
+        let text = "timeout = 10\ntheme = \"Blue\"\n";
+        let Err(ConfigError::Parse { line, column, .. }) = parse_str(text) else {
+            panic!("ожидалась ошибка разбора");
+        };
+        assert_eq!((line, column), (2, 9));
+    }
+}
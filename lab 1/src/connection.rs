@@ -0,0 +1,527 @@
+use crate::error::AppError;
+use crate::export;
+use crate::i18n::{tr, trf};
+use crate::profiles::Profile;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use windows::core::{w, Interface, BSTR, PCWSTR};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoSetProxyBlanket, CLSCTX_INPROC_SERVER, COAUTHIDENTITY, EOAC_NONE,
+    RPC_C_AUTHN_LEVEL_PKT_PRIVACY, RPC_C_IMP_LEVEL_IMPERSONATE,
+};
+use windows::Win32::System::Rpc::{
+    RPC_C_AUTHN_WINNT, RPC_C_AUTHZ_NONE, SEC_WINNT_AUTH_IDENTITY_UNICODE,
+};
+use windows::Win32::System::Services::{
+    CloseServiceHandle, OpenSCManagerW, OpenServiceW, QueryServiceStatus, SC_MANAGER_CONNECT,
+    SERVICE_QUERY_STATUS, SERVICE_RUNNING, SERVICE_STATUS,
+};
+use windows::Win32::System::Wmi::{
+    IEnumWbemClassObject, IWbemLocator, WbemLocator, WBEM_FLAG_CONNECT_USE_MAX_WAIT,
+    WBEM_FLAG_FORWARD_ONLY, WBEM_FLAG_RETURN_IMMEDIATELY,
+};
+use wmi::result_enumerator::{IWbemClassWrapper, QueryResultEnumerator};
+use wmi::{COMLibrary, Variant, WMIConnection, WMIError};
+
+/// Компьютер, к которому подключается приложение. Пустое имя — локальный компьютер.
+/// Пароль не сохраняется между запусками
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Target {
+    pub host: String,
+    pub user: String,
+    #[serde(skip)]
+    pub password: String,
+}
+
+impl Target {
+    pub fn is_local(&self) -> bool {
+        self.host.trim().is_empty()
+    }
+
+    /// Имя компьютера для заголовка окна, экспорта и отчётов
+    pub fn host_name(&self) -> String {
+        if self.is_local() {
+            export::hostname()
+        } else {
+            self.host.trim().to_string()
+        }
+    }
+}
+
+// Учётные данные в UTF-16 для DCOM. Буферы должны жить столько же, сколько прокси,
+// поэтому хранятся вместе с подключением
+struct Identity {
+    user: Vec<u16>,
+    domain: Vec<u16>,
+    password: Vec<u16>,
+}
+
+impl Identity {
+    // «ДОМЕН\пользователь» делится на домен и имя; «user@domain» передаётся целиком
+    fn new(user: &str, password: &str) -> Self {
+        let (domain, user) = user.split_once('\\').unwrap_or(("", user));
+        Self {
+            user: user.encode_utf16().collect(),
+            domain: domain.encode_utf16().collect(),
+            password: password.encode_utf16().collect(),
+        }
+    }
+
+    // COM только читает буферы, изменяемые указатели требует лишь объявление структуры
+    fn auth(&self) -> COAUTHIDENTITY {
+        COAUTHIDENTITY {
+            User: self.user.as_ptr() as *mut u16,
+            UserLength: self.user.len() as u32,
+            Domain: self.domain.as_ptr() as *mut u16,
+            DomainLength: self.domain.len() as u32,
+            Password: self.password.as_ptr() as *mut u16,
+            PasswordLength: self.password.len() as u32,
+            Flags: SEC_WINNT_AUTH_IDENTITY_UNICODE.0,
+        }
+    }
+
+    // Учётные данные задаются на каждом прокси: на службе и на каждом перечислителе
+    fn apply(&self, proxy: &windows::core::IUnknown) -> Result<(), WMIError> {
+        let auth = self.auth();
+        unsafe {
+            CoSetProxyBlanket(
+                proxy,
+                RPC_C_AUTHN_WINNT,
+                RPC_C_AUTHZ_NONE,
+                PCWSTR::null(),
+                RPC_C_AUTHN_LEVEL_PKT_PRIVACY,
+                RPC_C_IMP_LEVEL_IMPERSONATE,
+                Some(&auth as *const COAUTHIDENTITY as *const _),
+                EOAC_NONE,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Подключение к WMI локального или удалённого компьютера
+pub struct Connection {
+    con: WMIConnection,
+    identity: Option<Identity>,
+    host: Option<String>,
+    namespace: String,
+    com: COMLibrary,
+    target: Target,
+    siblings: RefCell<HashMap<String, Rc<Connection>>>, // Другие пространства имён компьютера
+}
+
+impl Connection {
+    /// Имя удалённого компьютера; `None` для локального
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Подключение к другому пространству имён того же компьютера с теми же
+    /// учётными данными; открывается при первом обращении. `None` — это же пространство
+    pub fn sibling(&self, namespace: &str) -> Result<Option<Rc<Connection>>, AppError> {
+        if namespace.eq_ignore_ascii_case(&self.namespace) {
+            return Ok(None);
+        }
+        let key = namespace.to_lowercase();
+        if let Some(sibling) = self.siblings.borrow().get(&key) {
+            return Ok(Some(sibling.clone()));
+        }
+        let sibling = Rc::new(connect(self.com, &self.target, namespace)?);
+        self.siblings.borrow_mut().insert(key, sibling.clone());
+        Ok(Some(sibling))
+    }
+
+    /// Запрос WQL; класс, время выполнения и число строк или ошибка пишутся в журнал
+    pub fn raw_query<T: DeserializeOwned>(&self, query: &str) -> Result<Vec<T>, WMIError> {
+        let started = Instant::now();
+        let result = self.execute(query);
+        self.log(query, started, &result);
+        result
+    }
+
+    /// Частый опрос, например счётчиков производительности. В журнал не пишется,
+    /// чтобы замеры не вытесняли остальные записи; ошибки записывает вызывающий
+    pub fn poll_query<T: DeserializeOwned>(&self, query: &str) -> Result<Vec<T>, WMIError> {
+        self.execute(query)
+    }
+
+    /// Асинхронный запрос: WMI передаёт строки в IWbemObjectSink из своих потоков, поэтому
+    /// COM потока, где ждут результат, должен быть в MTA. С учётными данными запрос
+    /// выполняется синхронно: обратные вызовы приёмника с удалённого компьютера
+    /// не получили бы этих учётных данных
+    pub async fn raw_query_async<T: DeserializeOwned>(
+        &self,
+        query: &str,
+    ) -> Result<Vec<T>, WMIError> {
+        let started = Instant::now();
+        let result = match self.identity {
+            Some(_) => self.execute(query),
+            None => self.con.async_raw_query(query).await,
+        };
+        self.log(query, started, &result);
+        result
+    }
+
+    /// Вызов метода объекта по его пути, например `Win32_Process.Handle="42"`.
+    /// Возвращает ReturnValue метода; без него вызов считается неудачным, чтобы
+    /// не сообщить об успехе действия, которое не выполнилось. Вызов пишется в журнал
+    pub fn exec_method(
+        &self,
+        path: &str,
+        method: &str,
+        params: HashMap<String, Variant>,
+    ) -> Result<u32, WMIError> {
+        let started = Instant::now();
+        let class = class_of_path(path);
+        let result = self
+            .con
+            .exec_method_native_wrapper(class, path, method, params)
+            .and_then(|output| output.ok_or(WMIError::ResultEmpty))
+            .and_then(|output| output.get_property("ReturnValue"))
+            .and_then(|value| match value {
+                Variant::UI4(code) => Ok(code),
+                Variant::I4(code) => Ok(code as u32),
+                Variant::Empty | Variant::Null => Err(WMIError::ResultEmpty),
+                other => Err(WMIError::ConvertVariantError(format!(
+                    "ReturnValue: {other:?}"
+                ))),
+            });
+        let host = self.host().unwrap_or("localhost");
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(code) => tracing::info!(path, method, host, elapsed_ms, code, "метод вызван"),
+            Err(error) => tracing::warn!(
+                path,
+                method,
+                host,
+                elapsed_ms,
+                %error,
+                "вызов метода завершился ошибкой"
+            ),
+        }
+        result
+    }
+
+    /// Подписка на события WQL (`SELECT * FROM __InstanceOperationEvent ...`).
+    /// События ждут по одному с ограничением времени, чтобы поток мог остановиться
+    pub fn notification(&self, query: &str) -> Result<Events, WMIError> {
+        let enumerator = unsafe {
+            self.con.svc.ExecNotificationQuery(
+                &BSTR::from("WQL"),
+                &BSTR::from(query),
+                WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+                None,
+            )
+        };
+        let host = self.host().unwrap_or("localhost");
+        let class = class_of(query);
+        match &enumerator {
+            Ok(_) => tracing::info!(class, host, "подписка на события оформлена"),
+            Err(error) => tracing::warn!(class, host, %error, "подписка на события не удалась"),
+        }
+        let enumerator = enumerator?;
+        if let Some(identity) = &self.identity {
+            identity.apply(&enumerator.cast()?)?;
+        }
+        Ok(Events { enumerator })
+    }
+
+    /// Вызов метода, который только читает, например GetOwner или методы StdRegProv.
+    /// Возвращает выходные параметры вместе с ReturnValue. Такие методы вызываются
+    /// для многих объектов подряд, поэтому вызов пишется в журнал только на уровне отладки
+    pub fn invoke(
+        &self,
+        path: &str,
+        method: &str,
+        params: HashMap<String, Variant>,
+    ) -> Result<HashMap<String, Variant>, WMIError> {
+        let started = Instant::now();
+        let result = self
+            .con
+            .exec_method_native_wrapper(class_of_path(path), path, method, params)
+            .and_then(|output| match output {
+                Some(output) => output.into_desr(),
+                None => Ok(HashMap::new()),
+            });
+        let host = self.host().unwrap_or("localhost");
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(_) => tracing::debug!(path, method, host, elapsed_ms, "метод вызван"),
+            Err(error) => tracing::debug!(
+                path,
+                method,
+                host,
+                elapsed_ms,
+                %error,
+                "вызов метода завершился ошибкой"
+            ),
+        }
+        result
+    }
+
+    fn log<T>(&self, query: &str, started: Instant, result: &Result<Vec<T>, WMIError>) {
+        let class = class_of(query);
+        let namespace = self.namespace.as_str();
+        let host = self.host().unwrap_or("localhost");
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(rows) => tracing::info!(
+                class,
+                namespace,
+                host,
+                elapsed_ms,
+                rows = rows.len(),
+                "запрос выполнен"
+            ),
+            Err(error) => tracing::warn!(
+                class,
+                namespace,
+                host,
+                elapsed_ms,
+                %error,
+                "запрос завершился ошибкой"
+            ),
+        }
+    }
+
+    // Для удалённого компьютера с учётными данными перечислитель
+    // получает тот же уровень проверки подлинности, что и служба
+    fn execute<T: DeserializeOwned>(&self, query: &str) -> Result<Vec<T>, WMIError> {
+        let Some(identity) = &self.identity else {
+            return self.con.raw_query(query);
+        };
+        let enumerator = unsafe {
+            self.con.svc.ExecQuery(
+                &BSTR::from("WQL"),
+                &BSTR::from(query),
+                WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+                None,
+            )?
+        };
+        identity.apply(&enumerator.cast()?)?;
+        QueryResultEnumerator::new(&self.con, enumerator)
+            .map(|object| object?.into_desr())
+            .collect()
+    }
+}
+
+/// События подписки [`Connection::notification`]
+pub struct Events {
+    enumerator: IEnumWbemClassObject,
+}
+
+impl Events {
+    /// Следующее событие; `None`, если за `timeout` его не было
+    pub fn next(&self, timeout: Duration) -> Result<Option<IWbemClassWrapper>, WMIError> {
+        let mut objects = [None; 1];
+        let mut returned = 0;
+        let millis = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+        // По истечении времени Next возвращает WBEM_S_TIMEDOUT — успех без объекта
+        unsafe { self.enumerator.Next(millis, &mut objects, &mut returned) }.ok()?;
+        let [object] = objects;
+        Ok(object.filter(|_| returned > 0).map(IWbemClassWrapper::new))
+    }
+}
+
+/// Подключается к пространству имён на целевом компьютере
+pub fn connect(com: COMLibrary, target: &Target, namespace: &str) -> Result<Connection, AppError> {
+    if target.is_local() {
+        let con = WMIConnection::with_namespace_path(namespace, com)
+            .map_err(|e| AppError::connect(None, namespace, e))?;
+        return Ok(Connection {
+            con,
+            identity: None,
+            host: None,
+            namespace: namespace.to_string(),
+            com,
+            target: target.clone(),
+            siblings: RefCell::default(),
+        });
+    }
+    // Пространства имён удалённого компьютера может не быть на локальном
+    let shell = WMIConnection::with_namespace_path("root\\cimv2", com)
+        .map_err(|e| AppError::connect(None, "root\\cimv2", e))?;
+    connect_remote(com, shell, target, namespace)
+        .map_err(|e| AppError::connect(Some(target.host.trim()), namespace, e))
+}
+
+// Подключение wmi умеет только локальный компьютер, поэтому удалённая служба
+// открывается через IWbemLocator и подставляется в уже созданное подключение
+fn connect_remote(
+    com: COMLibrary,
+    mut con: WMIConnection,
+    target: &Target,
+    namespace: &str,
+) -> Result<Connection, WMIError> {
+    let path = format!("\\\\{}\\{namespace}", target.host.trim());
+    let (user, password) = if target.user.is_empty() {
+        (BSTR::new(), BSTR::new())
+    } else {
+        (
+            BSTR::from(target.user.as_str()),
+            BSTR::from(target.password.as_str()),
+        )
+    };
+    let svc = unsafe {
+        let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)?;
+        locator.ConnectServer(
+            &BSTR::from(path),
+            &user,
+            &password,
+            &BSTR::new(),
+            WBEM_FLAG_CONNECT_USE_MAX_WAIT.0,
+            &BSTR::new(),
+            None,
+        )?
+    };
+    let identity = if target.user.is_empty() {
+        None
+    } else {
+        let identity = Identity::new(&target.user, &target.password);
+        identity.apply(&svc.cast()?)?;
+        Some(identity)
+    };
+    con.svc = svc;
+    Ok(Connection {
+        con,
+        identity,
+        host: Some(target.host.trim().to_string()),
+        namespace: namespace.to_string(),
+        com,
+        target: target.clone(),
+        siblings: RefCell::default(),
+    })
+}
+
+/// Класс из запроса WQL для журнала и текста ошибки; если его не найти, запрос целиком
+pub fn class_of(wql: &str) -> &str {
+    let words: Vec<&str> = wql.split_whitespace().collect();
+    words
+        .iter()
+        .position(|word| word.eq_ignore_ascii_case("FROM"))
+        .and_then(|i| words.get(i + 1))
+        .copied()
+        .unwrap_or(wql)
+}
+
+/// Класс из пути объекта, полного или относительного: `Win32_Process.Handle="42"` → `Win32_Process`
+pub fn class_of_path(path: &str) -> &str {
+    // Значения ключей и имя компьютера могут содержать двоеточия и точки
+    let head = path.split_once('=').map_or(path, |(head, _)| head);
+    let relative = head.rsplit_once(':').map_or(head, |(_, relative)| relative);
+    relative
+        .split_once('.')
+        .map_or(relative, |(class, _)| class)
+}
+
+/// Запущена ли локальная служба WMI (Winmgmt); `None`, если состояние узнать не удалось
+pub fn wmi_service_running() -> Option<bool> {
+    unsafe {
+        let manager = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), SC_MANAGER_CONNECT).ok()?;
+        let service = OpenServiceW(manager, w!("Winmgmt"), SERVICE_QUERY_STATUS);
+        let running = service.ok().and_then(|service| {
+            let mut status = SERVICE_STATUS::default();
+            let queried = QueryServiceStatus(service, &mut status);
+            let _ = CloseServiceHandle(service);
+            queried
+                .ok()
+                .map(|()| status.dwCurrentState == SERVICE_RUNNING)
+        });
+        let _ = CloseServiceHandle(manager);
+        running
+    }
+}
+
+/// Решение, принятое в окне подключения
+pub enum DialogAction {
+    Connect(Target),
+    Cancel,
+}
+
+/// Окно выбора компьютера: имя, пользователь и пароль
+pub struct Dialog {
+    draft: Target,
+    hosts: Vec<String>, // Компьютеры из файла настроек
+    pub error: Option<AppError>,
+    pub profile: Option<Profile>, // Профиль, для которого запрошен пароль
+}
+
+impl Dialog {
+    pub fn new(draft: Target, hosts: Vec<String>) -> Self {
+        Self {
+            draft,
+            hosts,
+            error: None,
+            profile: None,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<DialogAction> {
+        let mut action = None;
+        egui::Window::new(tr("remote.title"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("remote_fields")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr("remote.host"));
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.draft.host);
+                            if !self.hosts.is_empty() {
+                                egui::ComboBox::from_id_salt("remote_hosts")
+                                    .selected_text(tr("remote.recent"))
+                                    .show_ui(ui, |ui| {
+                                        for host in &self.hosts {
+                                            let selected = self.draft.host == *host;
+                                            if ui.selectable_label(selected, host).clicked() {
+                                                self.draft.host = host.clone();
+                                            }
+                                        }
+                                    });
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr("remote.user"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.draft.user)
+                                .hint_text("DOMAIN\\user"),
+                        );
+                        ui.end_row();
+                        ui.label(tr("remote.password"));
+                        ui.add(egui::TextEdit::singleline(&mut self.draft.password).password(true));
+                        ui.end_row();
+                    });
+                match &self.profile {
+                    Some(profile) => {
+                        ui.weak(trf("profiles.password_for", &[("name", &profile.name)]))
+                    }
+                    None => ui.weak(tr("remote.hint")),
+                };
+                if let Some(error) = &self.error {
+                    error.show(ui);
+                }
+                ui.horizontal(|ui| {
+                    let can_connect = !self.draft.is_local();
+                    if ui
+                        .add_enabled(can_connect, egui::Button::new(tr("remote.connect")))
+                        .clicked()
+                    {
+                        action = Some(DialogAction::Connect(self.draft.clone()));
+                    }
+                    if ui.button(tr("remote.local")).clicked() {
+                        action = Some(DialogAction::Connect(Target::default()));
+                    }
+                    if ui.button(tr("remote.cancel")).clicked() {
+                        action = Some(DialogAction::Cancel);
+                    }
+                });
+            });
+        action
+    }
+}
@@ -0,0 +1,253 @@
+use crate::i18n::{tr, trf};
+use crate::model::{Property, ResultSet, Value};
+use crate::source::Object;
+use std::collections::BTreeMap;
+
+/// Класс разъёмов из таблицы SMBIOS (тип 8)
+pub const CLASS: &str = "Win32_PortConnector";
+
+// Названия физических разъёмов по ConnectorType (CIM_PhysicalConnector).
+// Шины внутри корпуса (PCI, ATA и другие) на внешних панелях не встречаются
+const CONNECTORS: [(i64, &str); 17] = [
+    (21, "DB-9"),
+    (22, "DB-15"),
+    (23, "DB-25"),
+    (24, "DB-36"),
+    (25, "RS-232C"),
+    (37, "BNC"),
+    (38, "RJ-11"),
+    (39, "RJ-45"),
+    (53, "USB"),
+    (54, "IEEE 1394"),
+    (58, "DIN"),
+    (59, "Mini-DIN"),
+    (60, "Micro-DIN"),
+    (61, "PS/2"),
+    (62, "IrDA"),
+    (66, "Centronics"),
+    (97, "Mini-jack"),
+];
+
+// Названия видеоразъёмов в обозначении на корпусе; у PortType для них одно «Video Port»
+const VIDEO: [(&str, &str); 4] = [
+    ("HDMI", "HDMI"),
+    ("DISPLAYPORT", "DisplayPort"),
+    ("DVI", "DVI"),
+    ("VGA", "VGA"),
+];
+
+/// Назначение порта по PortType; `None` — не указано
+pub fn port_type(code: i64) -> Option<String> {
+    let name = match code {
+        0 => return None,
+        1..=5 => tr("port.parallel"),
+        6..=9 | 32 | 33 | 160 | 161 => tr("port.serial"),
+        10 | 15 | 23 | 24 => "SCSI",
+        11 => "MIDI",
+        12 => tr("port.joystick"),
+        13 => tr("port.keyboard"),
+        14 => tr("port.mouse"),
+        16 => "USB",
+        17 => "IEEE 1394",
+        18..=20 => "PCMCIA",
+        21 => "CardBus",
+        22 => "Access.bus",
+        25..=27 => "PC-98",
+        28 => tr("port.video"),
+        29 => tr("port.audio"),
+        30 => tr("port.modem"),
+        31 => tr("port.network"),
+        255 | 65535 => tr("port.other"),
+        _ => return Some(trf("port.code", &[("code", &code)])),
+    };
+    Some(name.to_string())
+}
+
+/// Физический разъём по ConnectorType; `None` — неизвестно или «другой»
+pub fn connector(code: i64) -> Option<String> {
+    match code {
+        0 | 1 => None,
+        2 => Some(tr("port.male").to_string()),
+        3 => Some(tr("port.female").to_string()),
+        _ => Some(
+            CONNECTORS
+                .iter()
+                .find(|(known, _)| *known == code)
+                .map(|&(_, name)| name.to_string())
+                .unwrap_or_else(|| trf("port.code", &[("code", &code)])),
+        ),
+    }
+}
+
+// Числа свойства-массива uint16; одиночное значение — массив из одного элемента
+fn codes(property: Option<&Property>) -> Vec<i64> {
+    let values = match property {
+        Some(Property::Array(values)) => values.as_slice(),
+        Some(Property::Scalar(value)) => std::slice::from_ref(value),
+        None => &[],
+    };
+    values
+        .iter()
+        .filter_map(|value| match value {
+            Value::Int(code) => Some(*code),
+            _ => None,
+        })
+        .collect()
+}
+
+fn text(object: &Object, name: &str) -> String {
+    match object.get(name) {
+        Some(Property::Scalar(Value::Text(text))) => text.trim().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Разъём на корпусе, сведённый к виду, по которому считаются одинаковые
+#[derive(Debug, Clone, PartialEq)]
+pub struct Port {
+    pub kind: String,
+    pub connectors: Vec<String>,
+    pub designator: String,
+}
+
+impl Port {
+    /// Вид разъёма: видеоразъём по обозначению, затем назначение порта,
+    /// затем первый известный физический разъём
+    pub fn from_object(object: &Object) -> Port {
+        let designator = match text(object, "ExternalReferenceDesignator") {
+            external if external.is_empty() => text(object, "InternalReferenceDesignator"),
+            external => external,
+        };
+        let types: Vec<i64> = codes(object.get("PortType"));
+        let connectors: Vec<String> = codes(object.get("ConnectorType"))
+            .into_iter()
+            .filter_map(connector)
+            .collect();
+        let upper = designator.to_uppercase();
+        let video = types.iter().all(|&code| code == 28 || code == 0);
+        let kind = VIDEO
+            .iter()
+            .find(|(sign, _)| video && upper.contains(sign))
+            .map(|&(_, name)| name.to_string())
+            .or_else(|| types.iter().find_map(|&code| port_type(code)))
+            .or_else(|| {
+                codes(object.get("ConnectorType"))
+                    .into_iter()
+                    .find(|code| *code > 3)
+                    .and_then(connector)
+            })
+            .unwrap_or_else(|| tr("port.unknown").to_string());
+        Port {
+            kind,
+            connectors,
+            designator,
+        }
+    }
+}
+
+/// Сводка по разъёмам: первой строкой число портов USB и всех разъёмов, затем
+/// виды разъёмов по убыванию числа. Пустой класс объясняется строкой о том,
+/// что микропрограмма его не заполняет
+pub fn result_set(ports: &[Port]) -> ResultSet {
+    let mut set = ResultSet::new(&["ConnectorKind", "Count", "Connectors", "Designators"]);
+    if ports.is_empty() {
+        set.rows.push(vec![
+            tr("port.no_data").into(),
+            Value::Null,
+            Value::Null,
+            Value::Null,
+        ]);
+        return set;
+    }
+    let mut kinds: BTreeMap<&str, Vec<&Port>> = BTreeMap::new();
+    for port in ports {
+        kinds.entry(&port.kind).or_default().push(port);
+    }
+    let usb = kinds.get("USB").map_or(0, Vec::len);
+    set.rows.push(vec![
+        trf("port.usb_count", &[("count", &usb)]).into(),
+        Value::Int(usb as i64),
+        Value::Null,
+        trf("port.total", &[("count", &ports.len())]).into(),
+    ]);
+    let mut kinds: Vec<_> = kinds.into_iter().collect();
+    kinds.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+    for (kind, ports) in kinds {
+        let mut connectors: Vec<&str> = Vec::new();
+        let mut designators: Vec<&str> = Vec::new();
+        for port in &ports {
+            for name in &port.connectors {
+                if !connectors.contains(&name.as_str()) {
+                    connectors.push(name);
+                }
+            }
+            if !port.designator.is_empty() && !designators.contains(&port.designator.as_str()) {
+                designators.push(&port.designator);
+            }
+        }
+        set.rows.push(vec![
+            kind.into(),
+            Value::Int(ports.len() as i64),
+            Value::from((!connectors.is_empty()).then(|| connectors.join(", "))),
+            Value::from((!designators.is_empty()).then(|| designators.join(", "))),
+        ]);
+    }
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(port_type: i64, connectors: &[i64], designator: &str) -> Object {
+        let connectors = connectors.iter().map(|&code| Value::Int(code)).collect();
+        [
+            ("PortType", Property::Scalar(Value::Int(port_type))),
+            ("ConnectorType", Property::Array(connectors)),
+            (
+                "ExternalReferenceDesignator",
+                Property::Scalar(designator.into()),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, property)| (name.to_string(), property))
+        .collect()
+    }
+
+    #[test]
+    fn connector_codes_are_decoded() {
+        assert_eq!(port_type(16).as_deref(), Some("USB"));
+        assert_eq!(port_type(0), None);
+        assert_eq!(connector(39).as_deref(), Some("RJ-45"));
+        assert_eq!(connector(3).as_deref(), Some(tr("port.female")));
+        assert_eq!(connector(1), None);
+        let hdmi = Port::from_object(&object(28, &[], "HDMI 1"));
+        assert_eq!(hdmi.kind, "HDMI");
+        // Без назначения порта вид берётся по физическому разъёму
+        let lan = Port::from_object(&object(0, &[39], "LAN"));
+        assert_eq!(lan.kind, "RJ-45");
+    }
+
+    #[test]
+    fn ports_are_counted_by_kind() {
+        let ports: Vec<Port> = [
+            object(16, &[53], "USB 1"),
+            object(16, &[53], "USB 2"),
+            object(16, &[53], "USB 1"),
+            object(31, &[39], "LAN"),
+        ]
+        .iter()
+        .map(Port::from_object)
+        .collect();
+        let set = result_set(&ports);
+        assert_eq!(
+            set.value(0, "ConnectorKind"),
+            Some(&trf("port.usb_count", &[("count", &3)]).into())
+        );
+        assert_eq!(set.value(1, "ConnectorKind"), Some(&"USB".into()));
+        assert_eq!(set.value(1, "Count"), Some(&Value::Int(3)));
+        assert_eq!(set.value(1, "Designators"), Some(&"USB 1, USB 2".into()));
+        assert_eq!(set.value(2, "Connectors"), Some(&"RJ-45".into()));
+        assert_eq!(result_set(&[]).rows.len(), 1);
+    }
+}
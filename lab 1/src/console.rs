@@ -0,0 +1,99 @@
+use crate::cache;
+use crate::connection::Connection;
+use crate::error::AppError;
+use crate::i18n::{tr, trf};
+use crate::model::ResultSet;
+use crate::queries;
+use crate::table::TableView;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+const HISTORY_LEN: usize = 20;
+
+/// Вкладка произвольных запросов WQL
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Console {
+    query: String,
+    history: Vec<String>, // Последние выполненные запросы, новые первыми
+    view: TableView,
+    #[serde(skip)]
+    result: Option<Result<ResultSet, AppError>>,
+    #[serde(skip)]
+    revision: u64, // Версия результата для порядка строк таблицы
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            query: "SELECT Name, State FROM Win32_Service WHERE StartMode='Auto'".to_string(),
+            history: Vec::new(),
+            view: TableView::default(),
+            result: None,
+            revision: 0,
+        }
+    }
+}
+
+impl Console {
+    fn run(&mut self, con: &Connection) {
+        let query = self.query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        self.history.retain(|q| *q != query);
+        self.history.insert(0, query.clone());
+        self.history.truncate(HISTORY_LEN);
+
+        self.view.selected = None;
+        self.view.page = 0;
+        self.result = Some(queries::raw(con, &query));
+        self.revision = cache::next_revision();
+    }
+
+    /// Заголовки результата после смены языка
+    pub fn retitle(&mut self) {
+        if let Some(Ok(set)) = &mut self.result {
+            set.retitle();
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, con: &Connection) {
+        let run_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter);
+        let mut run = ui.input_mut(|input| input.consume_shortcut(&run_shortcut));
+
+        ui.add(
+            egui::TextEdit::multiline(&mut self.query)
+                .code_editor()
+                .desired_rows(4)
+                .desired_width(f32::INFINITY),
+        );
+        ui.horizontal(|ui| {
+            run |= ui.button(tr("console.run")).clicked();
+            egui::ComboBox::from_id_salt("console_history")
+                .selected_text(tr("console.history"))
+                .width(ui.available_width().min(400.0))
+                .show_ui(ui, |ui| {
+                    for query in &self.history {
+                        if ui.selectable_label(false, query).clicked() {
+                            self.query = query.clone();
+                        }
+                    }
+                });
+        });
+        if run {
+            self.run(con);
+        }
+
+        ui.separator();
+        match &self.result {
+            None => {}
+            Some(Err(error)) => error.show(ui),
+            Some(Ok(set)) => {
+                ui.label(trf("console.rows", &[("count", &set.rows.len())]));
+                self.view
+                    .show_result(ui, set, self.revision, "console_result");
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::i18n::{tr, trf};
+
+/// Пространство имён с температурой зон ACPI (MSAcpi_ThermalZoneTemperature)
+pub const ACPI_NAMESPACE: &str = "root\\WMI";
+
+/// Правдоподобная температура внутри работающего компьютера, °C. Встроенные
+/// датчики без данных часто отдают 0 или тысячи градусов
+pub const PLAUSIBLE: std::ops::RangeInclusive<f64> = 5.0..=110.0;
+
+/// Состояние устройства по свойству Availability классов CIM
+pub fn availability(code: i64) -> String {
+    let key = match code {
+        1 => "availability.other",
+        2 => "availability.unknown",
+        3 => "availability.running",
+        4 => "availability.warning",
+        5 => "availability.in_test",
+        6 => "availability.not_applicable",
+        7 => "availability.power_off",
+        8 => "availability.off_line",
+        10 => "availability.degraded",
+        11 => "availability.not_installed",
+        12 => "availability.install_error",
+        13..=15 | 17 => "availability.power_save",
+        _ => return trf("availability.code", &[("code", &code)]),
+    };
+    tr(key).to_string()
+}
+
+/// Градусы Цельсия из десятых долей кельвина (MSAcpi_ThermalZoneTemperature.CurrentTemperature)
+pub fn from_tenths_kelvin(value: i64) -> f64 {
+    value as f64 / 10.0 - 273.15
+}
+
+/// Градусы Цельсия из десятых долей градуса (Win32_TemperatureProbe.CurrentReading)
+pub fn from_tenths_celsius(value: i64) -> f64 {
+    value as f64 / 10.0
+}
+
+/// Показание и пометка `None`, если значение правдоподобно
+pub fn reading(celsius: f64) -> (String, Option<String>) {
+    let note = (!PLAUSIBLE.contains(&celsius)).then(|| {
+        trf(
+            "cooling.implausible",
+            &[("min", PLAUSIBLE.start()), ("max", PLAUSIBLE.end())],
+        )
+    });
+    (format!("{celsius:.1} °C"), note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperatures_are_converted_and_checked() {
+        assert!((from_tenths_kelvin(3132) - 40.05).abs() < 1e-9);
+        assert_eq!(
+            reading(from_tenths_celsius(455)),
+            ("45.5 °C".to_string(), None)
+        );
+        // Датчик без данных: 0 К
+        assert!(reading(from_tenths_kelvin(0)).1.is_some());
+        assert!(reading(150.0).1.is_some());
+        assert_eq!(availability(3), tr("availability.running"));
+        assert_eq!(availability(99), trf("availability.code", &[("code", &99)]));
+    }
+}
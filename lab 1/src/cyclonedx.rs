@@ -0,0 +1,177 @@
+use crate::model::{ResultSet, Value};
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Версия спецификации, которой соответствует файл
+pub const SPEC_VERSION: &str = "1.5";
+
+/// Спецификация ПО компьютера (SBOM) в формате CycloneDX JSON
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: Metadata,
+    components: Vec<Component>,
+}
+
+#[derive(Debug, Serialize)]
+struct Metadata {
+    timestamp: String,
+    tools: Tools,
+    component: Component, // Сам компьютер
+}
+
+#[derive(Debug, Serialize)]
+struct Tools {
+    components: Vec<Component>,
+}
+
+#[derive(Debug, Serialize)]
+struct Component {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher: Option<String>,
+}
+
+// Непустой текст ячейки; null и пустая строка — нет значения
+fn text(value: Option<&Value>) -> Option<String> {
+    value
+        .filter(|v| **v != Value::Null)
+        .map(|v| v.display().trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl Bom {
+    /// Компоненты из таблицы установленных программ (Name, Version, Vendor).
+    /// Неизвестная версия не выдумывается, а пропускается
+    pub fn new(host: &str, time: DateTime<Local>, software: &ResultSet) -> Self {
+        let column = |name| software.columns.iter().position(|c| c.name == name);
+        let (name, version, vendor) = (column("Name"), column("Version"), column("Vendor"));
+        let components = software
+            .rows
+            .iter()
+            .enumerate()
+            .filter_map(|(index, row)| {
+                let name = text(name.and_then(|i| row.get(i)))?;
+                // ИД программы уникален; без него ссылкой служит номер строки
+                let id = text(software.extra_value(index, "ProgramId"))
+                    .unwrap_or_else(|| index.to_string());
+                Some(Component {
+                    kind: "application",
+                    bom_ref: format!("program-{id}"),
+                    name,
+                    version: text(version.and_then(|i| row.get(i))),
+                    publisher: text(vendor.and_then(|i| row.get(i))),
+                })
+            })
+            .collect();
+        Self {
+            bom_format: "CycloneDX",
+            spec_version: SPEC_VERSION,
+            version: 1,
+            metadata: Metadata {
+                timestamp: time.to_rfc3339_opts(SecondsFormat::Secs, false),
+                tools: Tools {
+                    components: vec![Component {
+                        kind: "application",
+                        bom_ref: "wmilab".to_string(),
+                        name: "WMI Lab".to_string(),
+                        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                        publisher: None,
+                    }],
+                },
+                component: Component {
+                    kind: "device",
+                    bom_ref: format!("host-{host}"),
+                    name: host.to_string(),
+                    version: None,
+                    publisher: None,
+                },
+            },
+            components,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories;
+    use crate::source::Fixtures;
+    use chrono::TimeZone;
+    use futures::executor::block_on;
+    use jsonschema::{Draft, JSONSchema};
+
+    fn schema(name: &str) -> serde_json::Value {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join("cyclonedx")
+            .join(name);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn bom() -> serde_json::Value {
+        let software = block_on(categories::software(&Fixtures::new("workstation"), None)).unwrap();
+        let time = Local.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        serde_json::to_value(Bom::new("WS-01", time, &software)).unwrap()
+    }
+
+    #[test]
+    fn bom_matches_cyclonedx_schema() {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .with_document(
+                "http://cyclonedx.org/schema/spdx.schema.json".to_string(),
+                schema("spdx.schema.json"),
+            )
+            .with_document(
+                "http://cyclonedx.org/schema/jsf-0.82.schema.json".to_string(),
+                schema("jsf-0.82.schema.json"),
+            )
+            .compile(&schema("bom-1.5.schema.json"))
+            .unwrap();
+        let bom = bom();
+        let errors: Vec<String> = match compiled.validate(&bom) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .map(|e| format!("{}: {e}", e.instance_path))
+                .collect(),
+        };
+        assert!(errors.is_empty(), "{errors:#?}");
+    }
+
+    #[test]
+    fn machine_is_top_level_and_missing_version_is_omitted() {
+        let bom = bom();
+        assert_eq!(bom["specVersion"], "1.5");
+        assert_eq!(bom["metadata"]["component"]["name"], "WS-01");
+        assert_eq!(bom["metadata"]["component"]["type"], "device");
+        assert!(bom["metadata"]["timestamp"]
+            .as_str()
+            .unwrap()
+            .starts_with("2024-05-01T12:30:00"));
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0]["version"], "23.01");
+        assert_eq!(components[0]["publisher"], "Igor Pavlov");
+        assert!(components[2].get("version").is_none());
+        assert!(components[2].get("publisher").is_none());
+        assert!(components.iter().all(|c| c["type"] == "application"));
+    }
+}
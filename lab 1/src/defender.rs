@@ -0,0 +1,232 @@
+use crate::i18n::tr;
+use crate::model::{Property, ResultSet, Value};
+use crate::source::Object;
+
+/// Пространство имён настроек Microsoft Defender
+pub const NAMESPACE: &str = "root\\Microsoft\\Windows\\Defender";
+
+/// Класс настроек Defender; его объект на компьютере один
+pub const CLASS: &str = "MSFT_MpPreference";
+
+// Расширения исполняемых файлов и сценариев: такое исключение снимает проверку
+// со всех программ этого типа
+const EXECUTABLE: [&str; 9] = ["exe", "dll", "scr", "com", "ps1", "bat", "cmd", "vbs", "js"];
+
+// Программы, через которые запускаются сценарии; исключение такого процесса
+// снимает проверку со всего, что он открывает
+const SCRIPT_HOSTS: [&str; 8] = [
+    "powershell.exe",
+    "pwsh.exe",
+    "cmd.exe",
+    "wscript.exe",
+    "cscript.exe",
+    "mshta.exe",
+    "rundll32.exe",
+    "regsvr32.exe",
+];
+
+/// Вид исключения и свойство MSFT_MpPreference со списком таких исключений
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Path,
+    Extension,
+    Process,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 3] = [Kind::Path, Kind::Extension, Kind::Process];
+
+    pub fn property(self) -> &'static str {
+        match self {
+            Kind::Path => "ExclusionPath",
+            Kind::Extension => "ExclusionExtension",
+            Kind::Process => "ExclusionProcess",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Kind::Path => tr("defender.kind.path"),
+            Kind::Extension => tr("defender.kind.extension"),
+            Kind::Process => tr("defender.kind.process"),
+        }
+    }
+}
+
+// Без прав администратора, а с политикой HideExclusionsFromLocalAdmins и для
+// администратора Defender вместо списка возвращает один элемент
+// «N/A: Must be an administrator to view exclusions»
+fn hidden(item: &str) -> bool {
+    item.trim_start().starts_with("N/A")
+}
+
+// Почему исключение папки слишком широкое: целый диск, профили пользователей
+// или временные каталоги, куда может писать любая программа
+fn broad_path(path: &str) -> Option<&'static str> {
+    let path = path.trim().trim_end_matches(['\\', '*']).to_lowercase();
+    if path.is_empty() || (path.len() == 2 && path.ends_with(':')) {
+        return Some("defender.broad.drive");
+    }
+    if path.ends_with(":\\users") || path == "%userprofile%" || path == "%systemdrive%\\users" {
+        return Some("defender.broad.users");
+    }
+    if path
+        .split('\\')
+        .any(|part| matches!(part, "temp" | "tmp" | "%temp%" | "%tmp%"))
+    {
+        return Some("defender.broad.temp");
+    }
+    None
+}
+
+/// Ключ перевода с причиной, по которой исключение слишком широкое; `None` — обычное
+pub fn broad(kind: Kind, item: &str) -> Option<&'static str> {
+    match kind {
+        Kind::Path => broad_path(item),
+        Kind::Extension => {
+            let extension = item.trim().trim_start_matches(['*', '.']).to_lowercase();
+            EXECUTABLE
+                .contains(&extension.as_str())
+                .then_some("defender.broad.executable")
+        }
+        Kind::Process => {
+            let (dir, name) = item.trim().rsplit_once('\\').unwrap_or(("", item.trim()));
+            let name = name.to_lowercase();
+            if SCRIPT_HOSTS.contains(&name.as_str()) {
+                Some("defender.broad.script_host")
+            } else if name.contains('*') {
+                Some("defender.broad.wildcard")
+            } else if dir.is_empty() {
+                None
+            } else {
+                broad_path(dir)
+            }
+        }
+    }
+}
+
+fn items(preference: Option<&Object>, kind: Kind) -> Vec<String> {
+    match preference.and_then(|object| object.get(kind.property())) {
+        Some(Property::Array(items)) => items.iter().map(Value::to_string).collect(),
+        Some(Property::Scalar(Value::Text(item))) => vec![item.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn table() -> ResultSet {
+    ResultSet::new(&["ExclusionType", "Exclusion", "Broad", "Note"])
+}
+
+// Строка-пояснение вместо исключения
+fn note(kind: Option<Kind>, text: &str) -> Vec<Value> {
+    vec![
+        Value::from(kind.map(Kind::title)),
+        Value::Null,
+        Value::Null,
+        text.into(),
+    ]
+}
+
+/// Все исключения из объекта настроек. Скрытый список даёт строку «значения скрыты»,
+/// а пустые списки — одну строку «исключений нет», чтобы отсутствие исключений
+/// не путалось с непрочитанными данными
+pub fn result_set(preference: Option<&Object>) -> ResultSet {
+    let mut set = table();
+    for kind in Kind::ALL {
+        let items = items(preference, kind);
+        if items.iter().any(|item| hidden(item)) {
+            set.rows.push(note(Some(kind), tr("defender.hidden")));
+            continue;
+        }
+        for item in items.into_iter().filter(|item| !item.trim().is_empty()) {
+            let reason = broad(kind, &item).map(tr);
+            set.rows.push(vec![
+                kind.title().into(),
+                item.into(),
+                Value::from(reason),
+                Value::Null,
+            ]);
+        }
+    }
+    if set.rows.is_empty() {
+        set.rows.push(note(None, tr("defender.none")));
+    }
+    set
+}
+
+/// Пространства имён Defender нет: он удалён или его заменил другой антивирус
+pub fn unavailable() -> ResultSet {
+    let mut set = table();
+    set.rows.push(note(None, tr("defender.no_namespace")));
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preference(pairs: &[(&str, &[&str])]) -> Object {
+        pairs
+            .iter()
+            .map(|(name, items)| {
+                let items = items.iter().map(|&item| item.into()).collect();
+                (name.to_string(), Property::Array(items))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn broad_exclusions_are_recognized() {
+        assert_eq!(broad(Kind::Path, "C:\\"), Some("defender.broad.drive"));
+        assert_eq!(broad(Kind::Path, "D:\\*"), Some("defender.broad.drive"));
+        assert_eq!(
+            broad(Kind::Path, "C:\\Users\\"),
+            Some("defender.broad.users")
+        );
+        assert_eq!(
+            broad(Kind::Path, "%USERPROFILE%\\AppData\\Local\\Temp"),
+            Some("defender.broad.temp")
+        );
+        assert_eq!(broad(Kind::Path, "C:\\Users\\user\\Projects"), None);
+        assert_eq!(
+            broad(Kind::Extension, "*.EXE"),
+            Some("defender.broad.executable")
+        );
+        assert_eq!(broad(Kind::Extension, ".log"), None);
+        assert_eq!(
+            broad(Kind::Process, "powershell.exe"),
+            Some("defender.broad.script_host")
+        );
+        assert_eq!(
+            broad(Kind::Process, "C:\\Windows\\Temp\\agent.exe"),
+            Some("defender.broad.temp")
+        );
+        assert_eq!(
+            broad(Kind::Process, "C:\\Program Files\\Backup\\backup.exe"),
+            None
+        );
+    }
+
+    #[test]
+    fn hidden_and_empty_lists_are_explained() {
+        let hidden = preference(&[
+            (
+                "ExclusionPath",
+                &["N/A: Must be an administrator to view exclusions"],
+            ),
+            ("ExclusionExtension", &[]),
+        ]);
+        let set = result_set(Some(&hidden));
+        assert_eq!(set.rows.len(), 1);
+        assert_eq!(
+            set.value(0, "ExclusionType"),
+            Some(&Kind::Path.title().into())
+        );
+        assert_eq!(set.value(0, "Note"), Some(&tr("defender.hidden").into()));
+
+        let empty = result_set(Some(&preference(&[("ExclusionPath", &[])])));
+        assert_eq!(empty.rows, [note(None, tr("defender.none"))]);
+        assert_eq!(result_set(None).rows, empty.rows);
+        assert_ne!(unavailable().rows, empty.rows);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::error::AppError;
+use crate::hunt;
+use crate::i18n::tr;
+use crate::model::{Property, Value};
+use crate::queries;
+use crate::worker::{Ticket, Timeout, Worker};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+
+type Properties = Vec<(String, Property)>;
+
+/// Что показать в панели подробностей
+pub enum Selection {
+    None,
+    Unsupported,    // Строки категории не соответствуют объектам WMI
+    Object(String), // Запрос всех свойств выбранного объекта
+}
+
+enum State {
+    Empty,
+    Unsupported,
+    Loading {
+        ticket: Ticket<Result<Option<Properties>, AppError>>, // Удаление отменяет запрос
+        deadline: Instant,
+    },
+    Ready(Properties),
+    Missing, // Объект удалён после запроса категории
+    Failed(AppError),
+}
+
+/// Панель справа со всеми свойствами объекта выбранной строки
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct DetailPanel {
+    pub open: bool,
+    #[serde(skip)]
+    query: Option<String>, // Запрос, результат которого показан или ожидается
+    #[serde(skip)]
+    state: State,
+}
+
+impl Default for DetailPanel {
+    fn default() -> Self {
+        Self {
+            open: true,
+            query: None,
+            state: State::Empty,
+        }
+    }
+}
+
+impl DetailPanel {
+    /// Запрашивает объект, если выбор изменился. Прежний запрос отменяется
+    pub fn select(&mut self, selection: Selection, worker: &mut Worker, timeout: Timeout) {
+        let query = match &selection {
+            Selection::Object(query) => Some(query.as_str()),
+            Selection::None | Selection::Unsupported => None,
+        };
+        if self.query.as_deref() == query && !matches!(self.state, State::Empty) {
+            return;
+        }
+        self.query = query.map(str::to_string);
+        self.state = match selection {
+            Selection::None => State::Empty,
+            Selection::Unsupported => State::Unsupported,
+            Selection::Object(query) => State::Loading {
+                ticket: worker.submit(move |session| async move {
+                    queries::object(session.connection()?, &query).await
+                }),
+                deadline: Instant::now() + timeout.duration(),
+            },
+        };
+    }
+
+    /// Приём ответа потока запросов; вызывается каждый кадр
+    pub fn poll(&mut self, ctx: &egui::Context, timeout: Timeout) {
+        let State::Loading { ticket, deadline } = &self.state else {
+            return;
+        };
+        self.state = match ticket.try_recv() {
+            Ok(Ok(Some(properties))) => State::Ready(properties),
+            Ok(Ok(None)) => State::Missing,
+            Ok(Err(error)) => State::Failed(error),
+            Err(TryRecvError::Empty) if Instant::now() < *deadline => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                return;
+            }
+            Err(TryRecvError::Empty) => State::Failed(timeout.error()),
+            // Поток запросов заменён; запрос повторится при следующем выборе
+            Err(TryRecvError::Disconnected) => State::Empty,
+        };
+    }
+
+    /// `hunt` — шаблон быстрого поиска, совпадения с которым выделяются в командной строке
+    pub fn show(&self, ctx: &egui::Context, visible: bool, hunt: Option<&Regex>) {
+        egui::SidePanel::right("detail")
+            .resizable(true)
+            .default_width(320.0)
+            .show_animated(ctx, self.open && visible, |ui| {
+                ui.heading(tr("detail.title"));
+                ui.separator();
+                match &self.state {
+                    State::Empty => {
+                        ui.weak(tr("detail.no_selection"));
+                    }
+                    State::Unsupported => {
+                        ui.weak(tr("detail.unsupported"));
+                    }
+                    State::Loading { .. } => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(tr("detail.loading"));
+                        });
+                    }
+                    State::Missing => {
+                        ui.label(tr("detail.missing"));
+                    }
+                    State::Failed(error) => error.show(ui),
+                    State::Ready(properties) => properties_grid(ui, properties, hunt),
+                }
+            });
+    }
+}
+
+// Имя, значение и кнопка копирования каждого свойства
+fn properties_grid(ui: &mut egui::Ui, properties: &Properties, hunt: Option<&Regex>) {
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("detail_properties")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (name, property) in properties {
+                    ui.strong(name);
+                    match property {
+                        // Длинная командная строка переносится, совпадения выделены
+                        Property::Scalar(Value::Text(text)) if name == hunt::COLUMN => {
+                            let job = match hunt {
+                                Some(pattern) => hunt::highlighted(ui, text, pattern),
+                                None => egui::text::LayoutJob::simple_singleline(
+                                    text.clone(),
+                                    egui::TextStyle::Body.resolve(ui.style()),
+                                    ui.visuals().text_color(),
+                                ),
+                            };
+                            ui.add(egui::Label::new(job).wrap());
+                        }
+                        Property::Scalar(value) => value_label(ui, value),
+                        Property::Array(items) if items.is_empty() => {
+                            ui.weak("[ ]");
+                        }
+                        Property::Array(items) => {
+                            ui.vertical(|ui| {
+                                for item in items {
+                                    value_label(ui, item);
+                                }
+                            });
+                        }
+                    }
+                    if ui
+                        .small_button("📋")
+                        .on_hover_text(tr("detail.copy"))
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(property.text());
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+fn value_label(ui: &mut egui::Ui, value: &Value) {
+    if *value == Value::Null {
+        ui.weak(tr("value.na"));
+    } else {
+        ui.label(value.display().as_ref());
+    }
+}
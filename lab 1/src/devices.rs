@@ -0,0 +1,221 @@
+use crate::i18n::{tr, trf};
+use crate::model::{ResultSet, Value};
+use std::collections::BTreeMap;
+
+// Коды ошибок диспетчера устройств (ConfigManagerErrorCode, CM_PROB_*) и ключи
+// перевода их описаний; редкие и устаревшие коды описываются общей строкой
+const PROBLEMS: [(i64, &str); 27] = [
+    (1, "devproblem.1"),
+    (3, "devproblem.3"),
+    (10, "devproblem.10"),
+    (12, "devproblem.12"),
+    (14, "devproblem.14"),
+    (16, "devproblem.16"),
+    (18, "devproblem.18"),
+    (19, "devproblem.19"),
+    (21, "devproblem.21"),
+    (22, "devproblem.22"),
+    (24, "devproblem.24"),
+    (28, "devproblem.28"),
+    (29, "devproblem.29"),
+    (31, "devproblem.31"),
+    (32, "devproblem.32"),
+    (33, "devproblem.33"),
+    (34, "devproblem.34"),
+    (35, "devproblem.35"),
+    (37, "devproblem.37"),
+    (38, "devproblem.38"),
+    (39, "devproblem.39"),
+    (40, "devproblem.40"),
+    (41, "devproblem.41"),
+    (43, "devproblem.43"),
+    (45, "devproblem.45"),
+    (48, "devproblem.48"),
+    (52, "devproblem.52"),
+];
+
+/// Описание кода ошибки диспетчера устройств вместе с самим кодом
+pub fn problem_text(code: i64) -> String {
+    match PROBLEMS.iter().find(|(known, _)| *known == code) {
+        Some(&(_, key)) => format!("{code}: {}", tr(key)),
+        None => trf("devproblem.other", &[("code", &code)]),
+    }
+}
+
+/// Одинаковые экземпляры устройства, показанные одной строкой
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub row: usize, // Первая строка таблицы с этим устройством
+    pub count: usize,
+}
+
+/// Устройства Win32_PnPEntity по классам PNPClass. Проблемные устройства
+/// вынесены в отдельный раздел независимо от класса
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Grouping {
+    pub problems: Vec<Entry>,
+    pub classes: BTreeMap<String, Vec<Entry>>, // Пустая строка — класс не указан
+}
+
+fn text(set: &ResultSet, row: usize, name: &str) -> String {
+    set.value(row, name)
+        .map(|value| value.display().into_owned())
+        .unwrap_or_default()
+}
+
+// Идентификатор оборудования из DeviceID: перечислитель и код устройства без
+// номера экземпляра, например USB\VID_046D&PID_C52B
+fn hardware_id(device_id: &str) -> &str {
+    match device_id.rmatch_indices('\\').next() {
+        Some((end, _)) if device_id[..end].contains('\\') => &device_id[..end],
+        _ => device_id,
+    }
+}
+
+fn problem(set: &ResultSet, row: usize) -> bool {
+    matches!(set.value(row, "ConfigManagerErrorCode"), Some(Value::Int(code)) if *code != 0)
+}
+
+/// Группирует строки `rows` в их порядке. Экземпляры с тем же именем
+/// и идентификатором оборудования объединяются
+pub fn group(set: &ResultSet, rows: &[usize]) -> Grouping {
+    let mut grouping = Grouping::default();
+    // Ключ раздела и устройства → место устройства в разделе
+    let mut seen: BTreeMap<(bool, String, String, String), usize> = BTreeMap::new();
+    for &row in rows {
+        let problem = problem(set, row);
+        let class = text(set, row, "PNPClass");
+        let key = (
+            problem,
+            class.clone(),
+            text(set, row, "Name"),
+            hardware_id(&text(set, row, "DeviceID")).to_string(),
+        );
+        let entries = if problem {
+            &mut grouping.problems
+        } else {
+            grouping.classes.entry(class).or_default()
+        };
+        match seen.get(&key) {
+            Some(&index) => entries[index].count += 1,
+            None => {
+                seen.insert(key, entries.len());
+                entries.push(Entry { row, count: 1 });
+            }
+        }
+    }
+    grouping
+}
+
+impl Grouping {
+    /// Разделы с числом устройств; щелчок по устройству выбирает его строку
+    pub fn show(&self, ui: &mut egui::Ui, set: &ResultSet, selected: &mut Option<usize>) {
+        if !self.problems.is_empty() {
+            let title = trf("devices.problems", &[("count", &count(&self.problems))]);
+            let title = egui::RichText::new(title).color(ui.visuals().warn_fg_color);
+            egui::CollapsingHeader::new(title)
+                .id_salt("devices_problems")
+                .default_open(true)
+                .show(ui, |ui| entries(ui, set, &self.problems, selected, true));
+        }
+        for (class, items) in &self.classes {
+            let name = if class.is_empty() {
+                tr("devices.no_class")
+            } else {
+                class.as_str()
+            };
+            let title = format!("{name} ({})", count(items));
+            egui::CollapsingHeader::new(title)
+                .id_salt(("devices_class", class))
+                .show(ui, |ui| entries(ui, set, items, selected, false));
+        }
+    }
+}
+
+fn count(entries: &[Entry]) -> usize {
+    entries.iter().map(|entry| entry.count).sum()
+}
+
+fn entries(
+    ui: &mut egui::Ui,
+    set: &ResultSet,
+    entries: &[Entry],
+    selected: &mut Option<usize>,
+    problems: bool,
+) {
+    for entry in entries {
+        let name = text(set, entry.row, "Name");
+        let name = if name.is_empty() {
+            tr("value.na").to_string()
+        } else {
+            name
+        };
+        let label = match entry.count {
+            1 => name,
+            count => format!("{name} ×{count}"),
+        };
+        ui.horizontal(|ui| {
+            if ui
+                .selectable_label(*selected == Some(entry.row), label)
+                .clicked()
+            {
+                *selected = Some(entry.row);
+            }
+            if problems {
+                if let Some(Value::Int(code)) = set.value(entry.row, "ConfigManagerErrorCode") {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        trf("finding.problem_device", &[("code", code)]),
+                    );
+                }
+            }
+            ui.weak(text(set, entry.row, "DeviceID"));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn devices(rows: &[(&str, &str, &str, i64)]) -> ResultSet {
+        let mut set = ResultSet::new(&["DeviceID", "Name", "PNPClass", "ConfigManagerErrorCode"]);
+        for &(id, name, class, code) in rows {
+            set.rows
+                .push(vec![id.into(), name.into(), class.into(), Value::Int(code)]);
+        }
+        set
+    }
+
+    #[test]
+    fn problem_codes_are_decoded() {
+        assert_eq!(problem_text(28), format!("28: {}", tr("devproblem.28")));
+        assert_eq!(problem_text(22), format!("22: {}", tr("devproblem.22")));
+        assert_eq!(problem_text(57), trf("devproblem.other", &[("code", &57)]));
+        // Каждому коду таблицы есть перевод
+        assert!(PROBLEMS.iter().all(|&(_, key)| tr(key) != key));
+    }
+
+    #[test]
+    fn hardware_id_drops_instance() {
+        assert_eq!(
+            hardware_id("USB\\VID_046D&PID_C52B\\5&1A2B&0&2"),
+            "USB\\VID_046D&PID_C52B"
+        );
+        assert_eq!(hardware_id("ROOT\\0000"), "ROOT\\0000");
+    }
+
+    #[test]
+    fn instances_are_merged_and_problems_lifted() {
+        let set = devices(&[
+            ("HID\\VID_1&PID_2\\7&1", "HID-совместимая мышь", "Mouse", 0),
+            ("HID\\VID_1&PID_2\\7&2", "HID-совместимая мышь", "Mouse", 0),
+            ("PCI\\VEN_8086&DEV_15BC\\3&0", "Intel Ethernet", "Net", 0),
+            ("PCI\\VEN_10EC&DEV_8168\\4&0", "Realtek PCIe", "Net", 22),
+        ]);
+        let grouping = group(&set, &[0, 1, 2, 3]);
+        assert_eq!(grouping.problems, vec![Entry { row: 3, count: 1 }]);
+        assert_eq!(grouping.classes["Mouse"], vec![Entry { row: 0, count: 2 }]);
+        assert_eq!(grouping.classes["Net"], vec![Entry { row: 2, count: 1 }]);
+    }
+}
@@ -0,0 +1,269 @@
+use crate::i18n::{tr, trf};
+use crate::model::{ResultSet, Value};
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+
+/// Переменные, значение которых — список через «;»
+const LISTS: [&str; 3] = ["PATH", "PSModulePath", "PATHEXT"];
+
+/// Переменные окружения по областям: системные, затем по пользователям
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Grouping {
+    pub system: Vec<usize>,
+    pub users: BTreeMap<String, Vec<usize>>,
+}
+
+fn text(set: &ResultSet, row: usize, name: &str) -> String {
+    set.value(row, name)
+        .map(|value| value.display().into_owned())
+        .unwrap_or_default()
+}
+
+// Область берётся из SystemVariable, а если его нет — из UserName:
+// у системных переменных там «<SYSTEM>»
+fn is_system(set: &ResultSet, row: usize) -> bool {
+    match set.extra_value(row, "SystemVariable") {
+        Some(Value::Bool(system)) => *system,
+        _ => text(set, row, "UserName") == "<SYSTEM>",
+    }
+}
+
+/// Группирует строки `rows` в их порядке
+pub fn group(set: &ResultSet, rows: &[usize]) -> Grouping {
+    let mut grouping = Grouping::default();
+    for &row in rows {
+        if is_system(set, row) {
+            grouping.system.push(row);
+        } else {
+            let user = text(set, row, "UserName");
+            grouping.users.entry(user).or_default().push(row);
+        }
+    }
+    grouping
+}
+
+/// Элементы списка у переменных вроде PATH; у остальных переменных `None`
+pub fn segments<'a>(name: &str, value: &'a str) -> Option<Vec<&'a str>> {
+    if !LISTS.iter().any(|list| list.eq_ignore_ascii_case(name)) {
+        return None;
+    }
+    Some(
+        value
+            .split(';')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .collect(),
+    )
+}
+
+/// Вид события WMI по переменной
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl Operation {
+    /// Вид по классу события, например `__InstanceModificationEvent`
+    pub fn of_class(class: &str) -> Option<Operation> {
+        match class {
+            "__InstanceCreationEvent" => Some(Operation::Created),
+            "__InstanceModificationEvent" => Some(Operation::Modified),
+            "__InstanceDeletionEvent" => Some(Operation::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// Изменение переменной по событию WMI. Процесса, внёсшего изменение, событие не сообщает
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub at: DateTime<Local>,
+    pub operation: Operation,
+    pub name: String,
+    pub user: String,        // UserName; у системных переменных «<SYSTEM>»
+    pub old: Option<String>, // Значение до события; у добавления его нет
+    pub new: Option<String>, // Значение после события; у удаления его нет
+}
+
+impl Change {
+    /// Область переменной: системная или пользователя
+    pub fn scope(&self) -> String {
+        if self.user == "<SYSTEM>" {
+            tr("envwatch.system").to_string()
+        } else {
+            self.user.clone()
+        }
+    }
+
+    /// Описание для журнала без времени. У списков вроде PATH при изменении
+    /// дописываются добавленные и удалённые элементы
+    pub fn text(&self) -> String {
+        let old = self.old.as_deref().unwrap_or_default();
+        let new = self.new.as_deref().unwrap_or_default();
+        let scope = self.scope();
+        let key = match self.operation {
+            Operation::Created => "envwatch.created",
+            Operation::Modified => "envwatch.modified",
+            Operation::Deleted => "envwatch.deleted",
+        };
+        let mut text = trf(
+            key,
+            &[
+                ("name", &self.name),
+                ("scope", &scope),
+                ("old", &old),
+                ("new", &new),
+            ],
+        );
+        if let (Operation::Modified, Some(before), Some(after)) = (
+            self.operation,
+            segments(&self.name, old),
+            segments(&self.name, new),
+        ) {
+            let missing = |from: &[&str], to: &[&str]| -> Vec<String> {
+                from.iter()
+                    .filter(|item| !to.iter().any(|other| other.eq_ignore_ascii_case(item)))
+                    .map(|item| item.to_string())
+                    .collect()
+            };
+            let added = missing(&after, &before);
+            let removed = missing(&before, &after);
+            if !added.is_empty() {
+                text += &trf("envwatch.items_added", &[("items", &added.join("; "))]);
+            }
+            if !removed.is_empty() {
+                text += &trf("envwatch.items_removed", &[("items", &removed.join("; "))]);
+            }
+        }
+        text
+    }
+}
+
+impl Grouping {
+    /// Разделы с числом переменных. Щелчок по переменной выбирает её строку,
+    /// по элементу списка — копирует его
+    pub fn show(&self, ui: &mut egui::Ui, set: &ResultSet, selected: &mut Option<usize>) {
+        let title = trf("env.system", &[("count", &self.system.len())]);
+        egui::CollapsingHeader::new(title)
+            .id_salt("env_system")
+            .default_open(true)
+            .show(ui, |ui| variables(ui, set, &self.system, selected));
+        for (user, rows) in &self.users {
+            let name = if user.is_empty() {
+                tr("value.na")
+            } else {
+                user.as_str()
+            };
+            let title = format!("{name} ({})", rows.len());
+            egui::CollapsingHeader::new(title)
+                .id_salt(("env_user", user))
+                .show(ui, |ui| variables(ui, set, rows, selected));
+        }
+    }
+}
+
+fn variables(ui: &mut egui::Ui, set: &ResultSet, rows: &[usize], selected: &mut Option<usize>) {
+    for &row in rows {
+        let name = text(set, row, "Name");
+        let value = text(set, row, "VariableValue");
+        let items = segments(&name, &value);
+        ui.horizontal(|ui| {
+            if ui.selectable_label(*selected == Some(row), &name).clicked() {
+                *selected = Some(row);
+            }
+            if items.is_none() {
+                ui.weak(&value);
+            }
+        });
+        for item in items.into_iter().flatten() {
+            ui.indent(("env_item", row), |ui| {
+                let response = ui
+                    .add(egui::Label::new(item).sense(egui::Sense::click()))
+                    .on_hover_text(tr("env.copy_item"));
+                if response.clicked() {
+                    ui.ctx().copy_text(item.to_string());
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_are_grouped_by_scope() {
+        let mut set =
+            ResultSet::new(&["Name", "VariableValue", "UserName"]).with_extra(&["SystemVariable"]);
+        for (name, user, system) in [
+            ("Path", "<SYSTEM>", Value::Bool(true)),
+            ("TEMP", "WS-01\\user", Value::Bool(false)),
+            ("windir", "<SYSTEM>", Value::Null),
+            ("TMP", "WS-01\\user", Value::Bool(false)),
+        ] {
+            set.rows.push(vec![name.into(), "".into(), user.into()]);
+            set.extra.rows.push(vec![system]);
+        }
+        let grouping = group(&set, &[0, 1, 2, 3]);
+        assert_eq!(grouping.system, [0, 2]);
+        assert_eq!(grouping.users["WS-01\\user"], [1, 3]);
+    }
+
+    #[test]
+    fn path_is_split_into_items() {
+        assert_eq!(
+            segments("Path", "C:\\Windows\\system32;;C:\\Windows; "),
+            Some(vec!["C:\\Windows\\system32", "C:\\Windows"])
+        );
+        assert_eq!(segments("TEMP", "C:\\Temp;D:\\Temp"), None);
+    }
+
+    #[test]
+    fn path_change_lists_new_items() {
+        assert_eq!(
+            Operation::of_class("__InstanceDeletionEvent"),
+            Some(Operation::Deleted)
+        );
+        assert_eq!(Operation::of_class("__InstanceOperationEvent"), None);
+        let change = Change {
+            at: Local::now(),
+            operation: Operation::Modified,
+            name: "Path".into(),
+            user: "WS-01\\user".into(),
+            old: Some("C:\\Windows;C:\\Tools".into()),
+            new: Some("C:\\Users\\user\\AppData\\x;c:\\windows".into()),
+        };
+        let text = change.text();
+        assert!(text.contains("WS-01\\user"));
+        assert!(text.ends_with(&format!(
+            "{}{}",
+            trf(
+                "envwatch.items_added",
+                &[("items", &"C:\\Users\\user\\AppData\\x")]
+            ),
+            trf("envwatch.items_removed", &[("items", &"C:\\Tools")])
+        )));
+        let created = Change {
+            operation: Operation::Created,
+            name: "COR_PROFILER".into(),
+            user: "<SYSTEM>".into(),
+            old: None,
+            new: Some("{guid}".into()),
+            ..change
+        };
+        assert_eq!(
+            created.text(),
+            trf(
+                "envwatch.created",
+                &[
+                    ("name", &"COR_PROFILER"),
+                    ("scope", &tr("envwatch.system")),
+                    ("new", &"{guid}"),
+                ]
+            )
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use crate::apartment::Apartment;
+use crate::connection::{self, Events, Target};
+use crate::envvars::{Change, Operation};
+use crate::error::AppError;
+use crate::i18n::tr;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use windows::Win32::System::Com::COINIT_MULTITHREADED;
+use wmi::result_enumerator::IWbemClassWrapper;
+use wmi::Variant;
+
+const CLASS: &str = "Win32_Environment";
+
+// Своих событий у класса нет: WMI сравнивает экземпляры раз в WITHIN секунд
+const QUERY: &str = "SELECT * FROM __InstanceOperationEvent WITHIN 2 \
+    WHERE TargetInstance ISA 'Win32_Environment'";
+
+/// Сколько ждать события, прежде чем проверить остановку потока
+const WAIT: Duration = Duration::from_millis(500);
+
+/// Пауза перед новой подпиской после ошибки
+const RETRY: Duration = Duration::from_secs(10);
+
+/// Сколько изменений хранится в окне
+const HISTORY: usize = 200;
+
+// Общее состояние потока подписки и окна
+#[derive(Default)]
+struct Shared {
+    changes: Mutex<VecDeque<Change>>, // Новые сверху
+    error: Mutex<Option<AppError>>,
+    stop: AtomicBool,
+}
+
+/// Фоновый поток подписки со своим подключением к WMI. Удаление останавливает
+/// поток, самое позднее через время ожидания события
+struct Listener {
+    target: Target,
+    shared: Arc<Shared>,
+}
+
+impl Listener {
+    fn start(ctx: &egui::Context, target: Target) -> Self {
+        let shared = Arc::new(Shared::default());
+        let thread_shared = Arc::clone(&shared);
+        let thread_target = target.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || run(&ctx, &thread_target, &thread_shared));
+        Self { target, shared }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Цикл потока: подписка повторяется, пока не удастся, и после обрыва
+fn run(ctx: &egui::Context, target: &Target, shared: &Shared) {
+    // Подписка объявлена после COM и удаляется раньше него
+    let apartment = Apartment::init(COINIT_MULTITHREADED).map_err(AppError::ComInit);
+    let mut events: Option<Events> = None;
+    let mut retry = Instant::now();
+    while !shared.stop.load(Ordering::Relaxed) {
+        let result = match &events {
+            Some(events) => events
+                .next(WAIT)
+                .map_err(|e| AppError::query(host(target), CLASS, e)),
+            None if Instant::now() < retry => {
+                std::thread::sleep(WAIT);
+                continue;
+            }
+            None => match subscribe(&apartment, target) {
+                Ok(subscribed) => {
+                    events = Some(subscribed);
+                    *shared.error.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                    ctx.request_repaint();
+                    continue;
+                }
+                Err(e) => Err(e),
+            },
+        };
+        match result {
+            Ok(None) => {}
+            Ok(Some(event)) => {
+                let Some(change) = change(&event) else {
+                    continue;
+                };
+                tracing::warn!(
+                    operation = ?change.operation,
+                    name = %change.name,
+                    user = %change.user,
+                    old = change.old.as_deref().unwrap_or_default(),
+                    new = change.new.as_deref().unwrap_or_default(),
+                    "переменная окружения изменена"
+                );
+                let mut changes = shared.changes.lock().unwrap_or_else(|e| e.into_inner());
+                changes.push_front(change);
+                changes.truncate(HISTORY);
+                ctx.request_repaint();
+            }
+            Err(e) => {
+                let mut error = shared.error.lock().unwrap_or_else(|e| e.into_inner());
+                // Ошибка пишется в журнал один раз, а не на каждой попытке
+                if error.as_ref().map(ToString::to_string) != Some(e.to_string()) {
+                    tracing::warn!(error = %e, "подписка на переменные окружения прервалась");
+                }
+                *error = Some(e);
+                events = None;
+                retry = Instant::now() + RETRY;
+                ctx.request_repaint();
+            }
+        }
+    }
+}
+
+fn host(target: &Target) -> Option<&str> {
+    (!target.is_local()).then(|| target.host.trim())
+}
+
+fn subscribe(apartment: &Result<Apartment, AppError>, target: &Target) -> Result<Events, AppError> {
+    let apartment = apartment.as_ref().map_err(AppError::clone)?;
+    let con = connection::connect(apartment.com(), target, "root\\cimv2")?;
+    con.notification(QUERY)
+        .map_err(|e| AppError::query(con.host(), CLASS, e))
+}
+
+// Изменение по событию; у изменения есть и прежний экземпляр, и новый
+fn change(event: &IWbemClassWrapper) -> Option<Change> {
+    let operation = Operation::of_class(&event.class().ok()?)?;
+    let target = instance(event, "TargetInstance")?;
+    let value = |object: &IWbemClassWrapper| text(object, "VariableValue");
+    let (old, new) = match operation {
+        Operation::Created => (None, value(&target)),
+        Operation::Modified => (
+            instance(event, "PreviousInstance").and_then(|previous| value(&previous)),
+            value(&target),
+        ),
+        Operation::Deleted => (value(&target), None),
+    };
+    Some(Change {
+        at: Local::now(),
+        operation,
+        name: text(&target, "Name").unwrap_or_default(),
+        user: text(&target, "UserName").unwrap_or_default(),
+        old,
+        new,
+    })
+}
+
+// Вложенный объект события: TargetInstance или PreviousInstance
+fn instance(event: &IWbemClassWrapper, name: &str) -> Option<IWbemClassWrapper> {
+    match event.get_property(name).ok()? {
+        Variant::Object(object) => Some(object),
+        _ => None,
+    }
+}
+
+fn text(object: &IWbemClassWrapper, name: &str) -> Option<String> {
+    match object.get_property(name).ok()? {
+        Variant::String(text) => Some(text),
+        _ => None,
+    }
+}
+
+/// Наблюдение за переменными окружения по событиям WMI. Сохраняется только переключатель
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvWatch {
+    pub enabled: bool,
+    #[serde(skip)]
+    listener: Option<Listener>,
+}
+
+impl EnvWatch {
+    /// Запускает или останавливает подписку. Вызывается на каждом кадре, чтобы
+    /// события приходили и при выбранной другой категории
+    pub fn poll(&mut self, ctx: &egui::Context, target: &Target) {
+        if !self.enabled {
+            self.listener = None;
+            return;
+        }
+        // События идут с компьютера, к которому подключено окно
+        if self.listener.as_ref().is_none_or(|l| l.target != *target) {
+            self.listener = Some(Listener::start(ctx, target.clone()));
+        }
+    }
+
+    pub fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.enabled, tr("envwatch.toggle"))
+            .on_hover_text(tr("envwatch.hint"));
+    }
+
+    /// Изменения с момента включения, новые сверху
+    pub fn history(&self, ui: &mut egui::Ui) {
+        let Some(listener) = &self.listener else {
+            return;
+        };
+        let shared = &listener.shared;
+        ui.horizontal(|ui| {
+            ui.strong(tr("envwatch.history"));
+            if ui.small_button(tr("watch.clear")).clicked() {
+                shared
+                    .changes
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clear();
+            }
+            ui.weak(tr("envwatch.no_process"));
+        });
+        let error = shared.error.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(error) = error.as_ref() {
+            ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+        }
+        let changes = shared.changes.lock().unwrap_or_else(|e| e.into_inner());
+        if changes.is_empty() {
+            ui.weak(tr("watch.no_changes"));
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("env_watch")
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for change in changes.iter() {
+                    let time = change.at.format("%H:%M:%S");
+                    ui.label(format!("{time}  {}", change.text()));
+                }
+            });
+    }
+}
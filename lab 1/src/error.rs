@@ -0,0 +1,269 @@
+use crate::apartment::InitError;
+use crate::i18n::{tr, trf};
+use std::fmt;
+use std::sync::Arc;
+use wmi::WMIError;
+
+// HRESULT, для которых есть отдельный вариант ошибки или подсказка
+const E_ACCESSDENIED: u32 = 0x80070005;
+const WBEM_E_ACCESS_DENIED: u32 = 0x80041003;
+const WBEM_E_NOT_FOUND: u32 = 0x80041002;
+const WBEM_E_INVALID_NAMESPACE: u32 = 0x8004100E;
+const RPC_S_SERVER_UNAVAILABLE: u32 = 0x800706BA;
+const ERROR_LOGON_FAILURE: u32 = 0x8007052E;
+
+/// Ошибка для пользователя. Хранится отдельно от данных таблиц и сохраняет
+/// исходную ошибку WMI для раздела подробностей. `host` — имя удалённого компьютера,
+/// `None` для локального
+#[derive(Debug, Clone)]
+pub enum AppError {
+    /// Не удалось инициализировать COM
+    ComInit(InitError),
+    /// Не удалось подключиться к службе WMI
+    WmiInit {
+        host: Option<String>,
+        source: Arc<WMIError>,
+    },
+    /// Запрос к классу завершился ошибкой
+    Query {
+        class: String,
+        source: Arc<WMIError>,
+    },
+    /// Пространства имён нет или оно недоступно
+    NamespaceUnavailable {
+        host: Option<String>,
+        namespace: String,
+        source: Arc<WMIError>,
+    },
+    /// Недостаточно прав
+    AccessDenied {
+        host: Option<String>,
+        source: Arc<WMIError>,
+    },
+    /// Объекта, у которого вызывался метод, больше нет
+    Gone {
+        class: String,
+        source: Arc<WMIError>,
+    },
+    /// Метод объекта вернул код ошибки
+    MethodFailed {
+        class: String,
+        method: String,
+        code: u32,
+    },
+    /// Службу нельзя остановить, пока работают зависящие от неё службы
+    DependentServices {
+        service: String,
+        dependents: Vec<String>,
+    },
+    /// Запрос не уложился во время ожидания
+    Timeout { secs: u64 },
+    /// Каталог есть, но его файлы не видны: без прав администратора WMI их не возвращает
+    Hidden { path: String },
+}
+
+fn hresult(error: &WMIError) -> Option<u32> {
+    match error {
+        WMIError::HResultError { hres } => Some(*hres as u32),
+        _ => None,
+    }
+}
+
+fn is_access_denied(error: &WMIError) -> bool {
+    matches!(hresult(error), Some(E_ACCESSDENIED | WBEM_E_ACCESS_DENIED))
+}
+
+impl AppError {
+    /// Ошибка подключения к пространству имён
+    pub fn connect(host: Option<&str>, namespace: &str, error: WMIError) -> Self {
+        let host = host.map(str::to_string);
+        let source = Arc::new(error);
+        if is_access_denied(&source) {
+            AppError::AccessDenied { host, source }
+        } else if hresult(&source) == Some(WBEM_E_INVALID_NAMESPACE) {
+            AppError::NamespaceUnavailable {
+                host,
+                namespace: namespace.to_string(),
+                source,
+            }
+        } else {
+            AppError::WmiInit { host, source }
+        }
+    }
+
+    /// Ошибка запроса к классу
+    pub fn query(host: Option<&str>, class: &str, error: WMIError) -> Self {
+        if is_access_denied(&error) {
+            return AppError::AccessDenied {
+                host: host.map(str::to_string),
+                source: Arc::new(error),
+            };
+        }
+        AppError::Query {
+            class: class.to_string(),
+            source: Arc::new(error),
+        }
+    }
+
+    /// Ошибка вызова метода объекта класса `class`
+    pub fn method(host: Option<&str>, class: &str, error: WMIError) -> Self {
+        if hresult(&error) == Some(WBEM_E_NOT_FOUND) {
+            return AppError::Gone {
+                class: class.to_string(),
+                source: Arc::new(error),
+            };
+        }
+        AppError::query(host, class, error)
+    }
+
+    /// Что проверить, чтобы устранить ошибку
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            AppError::WmiInit {
+                host: Some(host),
+                source,
+            } => match hresult(source)? {
+                RPC_S_SERVER_UNAVAILABLE => Some(trf("remote.rpc_unavailable", &[("host", host)])),
+                ERROR_LOGON_FAILURE => Some(tr("remote.logon_failure").to_string()),
+                _ => None,
+            },
+            AppError::NamespaceUnavailable { host: Some(_), .. } => {
+                Some(tr("remote.invalid_namespace").to_string())
+            }
+            AppError::AccessDenied { host: None, .. } => Some(tr("error.elevate").to_string()),
+            AppError::AccessDenied { host: Some(_), .. } => {
+                Some(tr("remote.access_denied").to_string())
+            }
+            AppError::MethodFailed { class, code, .. } if method_denied(class, *code) => {
+                Some(tr("error.elevate").to_string())
+            }
+            AppError::DependentServices { .. } => Some(tr("service.dependents_hint").to_string()),
+            AppError::Hidden { .. } => Some(tr("error.hidden_hint").to_string()),
+            _ => None,
+        }
+    }
+
+    /// Исходная ошибка WMI, если она есть
+    pub fn wmi_error(&self) -> Option<&WMIError> {
+        match self {
+            AppError::WmiInit { source, .. }
+            | AppError::Query { source, .. }
+            | AppError::NamespaceUnavailable { source, .. }
+            | AppError::AccessDenied { source, .. }
+            | AppError::Gone { source, .. } => Some(source),
+            AppError::ComInit(_)
+            | AppError::MethodFailed { .. }
+            | AppError::DependentServices { .. }
+            | AppError::Timeout { .. }
+            | AppError::Hidden { .. } => None,
+        }
+    }
+
+    /// Сообщение вместе с исходной ошибкой, для журнала и отчётов
+    pub fn detailed(&self) -> String {
+        match self.wmi_error() {
+            Some(error) => format!("{self} ({error})"),
+            None => self.to_string(),
+        }
+    }
+
+    /// Сообщение, подсказка и сворачиваемые подробности
+    pub fn show(&self, ui: &mut egui::Ui) {
+        ui.colored_label(ui.visuals().error_fg_color, self.to_string());
+        if let Some(hint) = self.hint() {
+            ui.label(hint);
+        }
+        if let Some(error) = self.wmi_error() {
+            egui::CollapsingHeader::new(tr("error.details"))
+                .id_salt(("error_details", self.to_string()))
+                .show(ui, |ui| {
+                    ui.monospace(error.to_string());
+                    ui.monospace(format!("{error:?}"));
+                });
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            AppError::ComInit(error) => trf("error.wmi_init", &[("error", error)]),
+            AppError::WmiInit { host: None, .. } => tr("error.connect_local").to_string(),
+            AppError::WmiInit {
+                host: Some(host), ..
+            } => trf("error.connect", &[("host", host)]),
+            AppError::Query { class, .. } => trf("error.query", &[("class", class)]),
+            AppError::NamespaceUnavailable { namespace, .. } => {
+                trf("error.namespace", &[("namespace", namespace)])
+            }
+            AppError::AccessDenied { .. } => tr("error.access_denied").to_string(),
+            AppError::Gone { class, .. } => trf("error.gone", &[("class", class)]),
+            AppError::MethodFailed {
+                class,
+                method,
+                code,
+            } => match method_reason(class, method, *code) {
+                Some(reason) => trf(
+                    "error.method_reason",
+                    &[
+                        ("class", class),
+                        ("method", method),
+                        ("reason", &tr(reason)),
+                        ("code", code),
+                    ],
+                ),
+                None => trf(
+                    "error.method",
+                    &[("class", class), ("method", method), ("code", code)],
+                ),
+            },
+            AppError::DependentServices {
+                service,
+                dependents,
+            } => trf(
+                "service.dependents",
+                &[("service", service), ("dependents", &dependents.join(", "))],
+            ),
+            AppError::Timeout { secs } => trf("query.timeout", &[("secs", secs)]),
+            AppError::Hidden { path } => trf("error.hidden", &[("path", path)]),
+        };
+        f.write_str(&text)
+    }
+}
+
+// Коды возврата у каждого класса свои; описаны те, что встречаются на практике
+fn method_reason(class: &str, method: &str, code: u32) -> Option<&'static str> {
+    let key = match (class, method, code) {
+        ("Win32_Process", "Terminate", 2 | 3) => "process.denied",
+        ("Win32_Service", _, 1) => "service.code.not_supported",
+        ("Win32_Service", _, 2) => "service.code.access_denied",
+        ("Win32_Service", _, 4 | 5) => "service.code.cannot_accept",
+        ("Win32_Service", _, 6) => "service.code.not_active",
+        ("Win32_Service", _, 7) => "service.code.timeout",
+        ("Win32_Service", _, 10) => "service.code.already_running",
+        ("Win32_Service", _, 11) => "service.code.database_locked",
+        ("Win32_Service", _, 12 | 13) => "service.code.dependency_failed",
+        ("Win32_Service", _, 14) => "service.code.disabled",
+        ("Win32_Service", _, 15) => "service.code.logon_failed",
+        ("Win32_Service", _, 16) => "service.code.marked_for_deletion",
+        _ => return None,
+    };
+    Some(key)
+}
+
+// Коды, означающие нехватку прав
+fn method_denied(class: &str, code: u32) -> bool {
+    matches!(
+        (class, code),
+        ("Win32_Process", 2 | 3) | ("Win32_Service", 2)
+    )
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::ComInit(error) => Some(error),
+            _ => self.wmi_error().map(|e| e as _),
+        }
+    }
+}
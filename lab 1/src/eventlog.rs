@@ -0,0 +1,447 @@
+use crate::findings::{Finding, Severity};
+use crate::i18n::{tr, trf};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use windows::core::{HSTRING, PCWSTR};
+use windows::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_SUCCESS, HANDLE};
+use windows::Win32::System::EventLog::{
+    DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+    EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, REPORT_EVENT_TYPE,
+};
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegOpenKeyExW, RegSetValueExW, HKEY, HKEY_LOCAL_MACHINE,
+    KEY_READ, KEY_SET_VALUE, REG_DWORD, REG_EXPAND_SZ, REG_OPTION_NON_VOLATILE,
+};
+
+/// Источник событий программы в журнале Application
+pub const SOURCE: &str = "WMI-Lab-Audit";
+
+// Без зарегистрированного источника события пишутся от имени общего источника журнала
+const FALLBACK: &str = "Application";
+
+const KEY: &str = r"SYSTEM\CurrentControlSet\Services\EventLog\Application\WMI-Lab-Audit";
+
+// Сообщения EventCreate.exe для кодов 1–1000 состоят из одной вставки %1,
+// поэтому отдельная библиотека сообщений не нужна
+const MESSAGE_FILE: &str = r"%SystemRoot%\System32\EventCreate.exe";
+
+/// Код тестового события
+pub const TEST_EVENT: u32 = 1;
+
+/// Раздел `[eventlog]`: запись находок отчётов в журнал событий Windows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub min_severity: Severity,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_severity: Severity::Medium,
+        }
+    }
+}
+
+impl Settings {
+    pub fn wants(&self, finding: &Finding) -> bool {
+        self.enabled && finding.severity >= self.min_severity
+    }
+
+    /// Поля для меню настроек: порог, регистрация источника и тестовое событие
+    pub fn show(&mut self, ui: &mut egui::Ui, writer: &mut Writer) {
+        ui.checkbox(&mut self.enabled, tr("eventlog.enabled"))
+            .on_hover_text(tr("eventlog.hint"));
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("notify.min_severity"));
+                egui::ComboBox::from_id_salt("eventlog_severity")
+                    .selected_text(self.min_severity.title())
+                    .show_ui(ui, |ui| {
+                        for severity in Severity::ALL {
+                            ui.selectable_value(&mut self.min_severity, severity, severity.title());
+                        }
+                    });
+            });
+            writer.controls(ui);
+        });
+    }
+}
+
+/// Уровень события по важности находки
+pub fn event_type(severity: Severity) -> REPORT_EVENT_TYPE {
+    match severity {
+        Severity::Critical | Severity::High => EVENTLOG_ERROR_TYPE,
+        Severity::Medium => EVENTLOG_WARNING_TYPE,
+        Severity::Low | Severity::Info => EVENTLOG_INFORMATION_TYPE,
+    }
+}
+
+/// Код события: 100 и выше по возрастанию важности, чтобы фильтровать журнал по коду
+pub fn event_id(severity: Severity) -> u32 {
+    100 + severity as u32
+}
+
+/// Событие журнала: текст сообщения и вставки. Первая вставка — текст,
+/// остальные видны в EventData как отдельные поля
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub severity: Severity,
+    pub strings: Vec<String>,
+}
+
+impl Event {
+    /// Категория и ключ строки пишутся одинаково при любом языке интерфейса
+    pub fn finding(host: &str, finding: &Finding) -> Self {
+        let mut text = format!(
+            "[{:?}] {}: {} — {}",
+            finding.severity,
+            finding.slug(),
+            finding.title,
+            finding.detail
+        );
+        if !finding.identity.is_empty() {
+            text.push_str(&format!(
+                "\r\n{}: {}",
+                tr("eventlog.identity"),
+                finding.identity
+            ));
+        }
+        text.push_str(&format!("\r\n{}: {}", tr("eventlog.host"), host));
+        Self {
+            severity: finding.severity,
+            strings: vec![
+                text,
+                finding.slug().to_string(),
+                format!("{:?}", finding.check),
+                finding.identity.clone(),
+                finding.title.clone(),
+                finding.detail.clone(),
+                format!("{:?}", finding.severity),
+                host.to_string(),
+            ],
+        }
+    }
+}
+
+/// Находки, уже записанные в этом сеансе. Повторный отчёт с теми же находками
+/// не дублирует события. Находка узнаётся по проверке, ключу строки и важности,
+/// поэтому смена языка интерфейса не делает её новой
+#[derive(Debug, Default)]
+pub struct Reported(HashSet<u64>);
+
+impl Reported {
+    fn key(host: &str, finding: &Finding) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (host, finding.check, &finding.identity, finding.severity).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Записывает находку через `write`, если она ещё не записывалась. Находка
+    /// запоминается только после удачной записи, неудачная повторится в следующем отчёте
+    pub fn report(
+        &mut self,
+        host: &str,
+        finding: &Finding,
+        write: impl FnOnce() -> Result<(), String>,
+    ) -> Result<(), String> {
+        let key = Self::key(host, finding);
+        if self.0.contains(&key) {
+            return Ok(());
+        }
+        write()?;
+        self.0.insert(key);
+        Ok(())
+    }
+}
+
+// Зарегистрирован ли источник; ключ читается без прав администратора
+fn registered() -> bool {
+    let mut key = HKEY::default();
+    let status = unsafe {
+        RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            &HSTRING::from(KEY),
+            None,
+            KEY_READ,
+            &mut key,
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return false;
+    }
+    let _ = unsafe { RegCloseKey(key) };
+    true
+}
+
+/// Создаёт источник событий в реестре. Нужны права администратора
+pub fn register() -> Result<(), String> {
+    let mut key = HKEY::default();
+    let status = unsafe {
+        RegCreateKeyExW(
+            HKEY_LOCAL_MACHINE,
+            &HSTRING::from(KEY),
+            None,
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE,
+            None,
+            &mut key,
+            None,
+        )
+    };
+    if status == ERROR_ACCESS_DENIED {
+        return Err(tr("eventlog.need_admin").to_string());
+    }
+    status.ok().map_err(|e| e.message())?;
+    let file: Vec<u8> = MESSAGE_FILE
+        .encode_utf16()
+        .chain([0])
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    // Ошибки, предупреждения и сведения
+    let types = 7u32.to_le_bytes();
+    let result = unsafe {
+        RegSetValueExW(
+            key,
+            &HSTRING::from("EventMessageFile"),
+            None,
+            REG_EXPAND_SZ,
+            Some(&file),
+        )
+        .ok()
+        .and_then(|()| {
+            RegSetValueExW(
+                key,
+                &HSTRING::from("TypesSupported"),
+                None,
+                REG_DWORD,
+                Some(&types),
+            )
+            .ok()
+        })
+    };
+    let _ = unsafe { RegCloseKey(key) };
+    result.map_err(|e| e.message())
+}
+
+// Событие от имени источника `handle`; источник открывается при первой записи
+fn report_event(
+    handle: &mut Option<HANDLE>,
+    registered: bool,
+    event: &Event,
+    id: u32,
+) -> Result<(), String> {
+    if handle.is_none() {
+        let source = if registered { SOURCE } else { FALLBACK };
+        let opened = unsafe { RegisterEventSourceW(PCWSTR::null(), &HSTRING::from(source)) }
+            .map_err(|e| e.message())?;
+        *handle = Some(opened);
+    }
+    let strings: Vec<HSTRING> = event.strings.iter().map(HSTRING::from).collect();
+    let pointers: Vec<PCWSTR> = strings.iter().map(|s| PCWSTR(s.as_ptr())).collect();
+    unsafe {
+        ReportEventW(
+            handle.unwrap(),
+            event_type(event.severity),
+            0,
+            id,
+            None,
+            0,
+            Some(&pointers),
+            None,
+        )
+    }
+    .map_err(|e| e.message())
+}
+
+/// Запись событий. Источник открывается при первой записи; без регистрации
+/// события уходят от имени общего источника Application
+pub struct Writer {
+    handle: Option<HANDLE>,
+    registered: bool,
+    reported: Reported,
+    status: Option<Result<String, String>>, // Итог регистрации или тестового события
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        Self {
+            handle: None,
+            registered: registered(),
+            reported: Reported::default(),
+            status: None,
+        }
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl Writer {
+    fn close(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = unsafe { DeregisterEventSource(handle) };
+        }
+    }
+
+    fn write(&mut self, event: &Event, id: u32) -> Result<(), String> {
+        report_event(&mut self.handle, self.registered, event, id)
+    }
+
+    /// Записывает находку, если в этом сеансе она ещё не записывалась
+    pub fn finding(&mut self, host: &str, finding: &Finding) {
+        let event = Event::finding(host, finding);
+        let id = event_id(finding.severity);
+        let (handle, registered) = (&mut self.handle, self.registered);
+        let written = self.reported.report(host, finding, || {
+            report_event(handle, registered, &event, id)
+        });
+        if let Err(e) = written {
+            tracing::warn!(error = %e, "не удалось записать событие в журнал");
+        }
+    }
+
+    // Состояние источника, кнопка регистрации и тестовое событие
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        if self.registered {
+            ui.label(trf("eventlog.registered", &[("source", &SOURCE)]));
+        } else {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                trf("eventlog.fallback", &[("source", &SOURCE)]),
+            );
+            if ui
+                .button(tr("eventlog.register"))
+                .on_hover_text(tr("eventlog.register_hint"))
+                .clicked()
+            {
+                self.status = Some(match register() {
+                    Ok(()) => {
+                        self.registered = true;
+                        // Следующая запись откроет уже зарегистрированный источник
+                        self.close();
+                        tracing::info!(source = SOURCE, "источник событий зарегистрирован");
+                        Ok(trf("eventlog.register_done", &[("source", &SOURCE)]))
+                    }
+                    Err(e) => Err(trf("eventlog.register_failed", &[("error", &e)])),
+                });
+            }
+        }
+        if ui.button(tr("eventlog.test")).clicked() {
+            let event = Event {
+                severity: Severity::Info,
+                strings: vec![tr("eventlog.test_text").to_string()],
+            };
+            self.status = Some(match self.write(&event, TEST_EVENT) {
+                Ok(()) => Ok(tr("eventlog.test_done").to_string()),
+                Err(e) => Err(trf("eventlog.test_failed", &[("error", &e)])),
+            });
+        }
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, message);
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::{self, Check};
+
+    fn finding(detail: &str) -> Finding {
+        findings::sample(Check::ProblemDevices, Severity::High, detail)
+    }
+
+    #[test]
+    fn unchanged_findings_are_written_once() {
+        let mut reported = Reported::default();
+        let mut written = Vec::new();
+        for (host, detail) in [
+            ("WS-01", "ROOT\\UNKNOWN\\0000"),
+            ("WS-01", "ROOT\\UNKNOWN\\0000"),
+            ("WS-02", "ROOT\\UNKNOWN\\0000"),
+            ("WS-01", "ROOT\\UNKNOWN\\0001"),
+        ] {
+            let write = || {
+                written.push((host, detail));
+                Ok(())
+            };
+            reported.report(host, &finding(detail), write).unwrap();
+        }
+        assert_eq!(written.len(), 3);
+    }
+
+    #[test]
+    fn language_does_not_renew_findings() {
+        let mut reported = Reported::default();
+        let mut writes = 0;
+        let russian = finding("ROOT\\UNKNOWN\\0000");
+        let english = Finding {
+            category: "Devices".to_string(),
+            title: "Error code 28".to_string(),
+            ..russian.clone()
+        };
+        for found in [&russian, &english] {
+            let write = || {
+                writes += 1;
+                Ok(())
+            };
+            reported.report("WS-01", found, write).unwrap();
+        }
+        assert_eq!(writes, 1);
+    }
+
+    #[test]
+    fn failed_write_is_retried() {
+        let mut reported = Reported::default();
+        let found = finding("ROOT\\UNKNOWN\\0000");
+        let denied = reported.report("WS-01", &found, || Err("Отказано в доступе".to_string()));
+        assert!(denied.is_err());
+        let mut writes = 0;
+        for _ in 0..2 {
+            let write = || {
+                writes += 1;
+                Ok(())
+            };
+            reported.report("WS-01", &found, write).unwrap();
+        }
+        assert_eq!(writes, 1);
+    }
+
+    #[test]
+    fn event_carries_fields_as_strings() {
+        let event = Event::finding("WS-01", &finding("ROOT\\UNKNOWN\\0000"));
+        assert_eq!(event_type(event.severity), EVENTLOG_ERROR_TYPE);
+        assert_eq!(event_id(Severity::Info), 100);
+        assert_eq!(event_id(Severity::Critical), 104);
+        assert!(
+            event.strings[0].starts_with("[High] bus_info: Код ошибки 28 — ROOT\\UNKNOWN\\0000")
+        );
+        assert_eq!(
+            event.strings[1..],
+            [
+                "bus_info",
+                "ProblemDevices",
+                "ROOT\\UNKNOWN\\0000",
+                "Код ошибки 28",
+                "ROOT\\UNKNOWN\\0000",
+                "High",
+                "WS-01"
+            ]
+        );
+    }
+}
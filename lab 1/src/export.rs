@@ -0,0 +1,136 @@
+use crate::i18n::trf;
+use crate::model::{ExportFile, ResultSet, Value};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+// Excel в русской локали ожидает ';' как разделитель списка
+const CSV_DELIMITER: char = ';';
+pub const UTF8_BOM: &str = "\u{feff}";
+
+/// Имя компьютера, с которого получены данные
+pub fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "localhost".to_string())
+}
+
+/// Имя файла по умолчанию: `<категория>_<компьютер>_<время>.<расширение>`
+pub fn default_file_name(host: &str, category: &str, extension: &str) -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    format!("{category}_{host}_{timestamp}.{extension}")
+}
+
+/// Сообщение о результате записи файла для строки состояния
+pub fn outcome(path: &Path, result: std::io::Result<()>) -> Result<String, String> {
+    match result {
+        Ok(()) => Ok(trf("export.saved", &[("path", &path.display())])),
+        Err(e) => Err(trf(
+            "export.failed",
+            &[("path", &path.display()), ("error", &e)],
+        )),
+    }
+}
+
+/// Поле CSV в кавычках, если оно содержит разделитель, запятую, кавычку или перевод строки
+fn csv_field(value: &str) -> String {
+    if value.contains([CSV_DELIMITER, ',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Ячейки одной строки CSV через разделитель, без перевода строки
+pub fn csv_line<'a>(cells: impl Iterator<Item = std::borrow::Cow<'a, str>>) -> String {
+    cells
+        .map(|cell| csv_field(&cell))
+        .collect::<Vec<_>>()
+        .join(&CSV_DELIMITER.to_string())
+}
+
+/// Заголовок и переданные строки в формате CSV (без BOM)
+pub fn to_csv(set: &ResultSet, rows: &[usize]) -> String {
+    let mut out = csv_line(set.columns.iter().map(|c| c.title.as_str().into()));
+    out.push_str("\r\n");
+    for &i in rows {
+        out.push_str(&csv_line(set.rows[i].iter().map(Value::display)));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Запись CSV в UTF-8 с BOM, чтобы Excel правильно определил кодировку
+pub fn write_csv(path: &Path, set: &ResultSet, rows: &[usize]) -> std::io::Result<()> {
+    std::fs::write(path, format!("{UTF8_BOM}{}", to_csv(set, rows)))
+}
+
+/// Запись структурированного экспорта в JSON
+pub fn write_json(path: &Path, file: &ExportFile) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), file)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ResultSet {
+        let mut set = ResultSet::new(&["Name", "VariableValue"]);
+        set.rows
+            .push(vec!["PATH".into(), "C:\\Windows;C:\\Tools".into()]);
+        set.rows
+            .push(vec!["Сообщение".into(), "скажи \"привет\"".into()]);
+        set.rows
+            .push(vec!["Описание".into(), "строка 1\nстрока 2".into()]);
+        set
+    }
+
+    #[test]
+    fn plain_fields_are_not_quoted() {
+        assert_eq!(csv_field("Переменная"), "Переменная");
+    }
+
+    #[test]
+    fn semicolons_are_quoted() {
+        assert_eq!(
+            csv_field("C:\\Windows;C:\\Tools"),
+            "\"C:\\Windows;C:\\Tools\""
+        );
+    }
+
+    #[test]
+    fn commas_are_quoted() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+
+    #[test]
+    fn quotes_are_doubled() {
+        assert_eq!(csv_field("скажи \"привет\""), "\"скажи \"\"привет\"\"\"");
+    }
+
+    #[test]
+    fn newlines_are_quoted() {
+        assert_eq!(csv_field("строка 1\nстрока 2"), "\"строка 1\nстрока 2\"");
+    }
+
+    #[test]
+    fn csv_has_header_and_selected_rows_in_order() {
+        let csv = to_csv(&sample(), &[1, 0]);
+        assert_eq!(
+            csv,
+            "Имя;Значение\r\n\
+             Сообщение;\"скажи \"\"привет\"\"\"\r\n\
+             PATH;\"C:\\Windows;C:\\Tools\"\r\n"
+        );
+    }
+
+    #[test]
+    fn written_file_starts_with_bom() {
+        let path = std::env::temp_dir().join("lab1_export_test.csv");
+        write_csv(&path, &sample(), &[2]).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(bytes.starts_with(&[0xEF, 0xBB, 0xBF]));
+        let text = String::from_utf8(bytes[3..].to_vec()).unwrap();
+        assert_eq!(text, "Имя;Значение\r\nОписание;\"строка 1\nстрока 2\"\r\n");
+    }
+}
@@ -0,0 +1,184 @@
+use serde_json::Value as Json;
+
+/// Браузеры на движке Chromium: название и каталог данных относительно профиля пользователя
+pub const CHROMIUM: [(&str, &str); 2] = [
+    ("Chrome", r"AppData\Local\Google\Chrome\User Data"),
+    ("Edge", r"AppData\Local\Microsoft\Edge\User Data"),
+];
+
+/// Каталог профилей Firefox относительно профиля пользователя
+pub const FIREFOX: &str = r"AppData\Roaming\Mozilla\Firefox\Profiles";
+
+/// Разрешения, с которыми расширение читает или меняет все страницы, трафик,
+/// буфер обмена или запускает программы на компьютере
+pub const HIGH_RISK: [&str; 4] = [
+    "<all_urls>",
+    "webRequest",
+    "nativeMessaging",
+    "clipboardRead",
+];
+
+// Шаблоны адресов, равносильные <all_urls>
+const ALL_HOSTS: [&str; 3] = ["*://*/*", "http://*/*", "https://*/*"];
+
+/// Установленное расширение
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extension {
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub permissions: Vec<String>,
+    pub enabled: Option<bool>, // Chromium хранит состояние в Preferences, здесь оно неизвестно
+}
+
+/// Опасные разрешения из списка; доступ ко всем адресам приводится к `<all_urls>`
+pub fn risky<'a>(permissions: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let mut found = Vec::new();
+    for permission in permissions {
+        let permission = if ALL_HOSTS.contains(&permission) {
+            "<all_urls>"
+        } else {
+            permission
+        };
+        if let Some(&known) = HIGH_RISK.iter().find(|&&known| known == permission) {
+            if !found.contains(&known) {
+                found.push(known);
+            }
+        }
+    }
+    found
+}
+
+/// Каталог расширения Chromium назван его идентификатором: 32 буквы от a до p
+pub fn chromium_id(name: &str) -> bool {
+    name.len() == 32 && name.bytes().all(|b| (b'a'..=b'p').contains(&b))
+}
+
+/// Порядок каталогов версий Chromium «1.10.0_0»: по числам, а не по тексту
+pub fn version_key(dir: &str) -> Vec<u64> {
+    dir.split(['.', '_'])
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn strings(value: Option<&Json>) -> impl Iterator<Item = String> + '_ {
+    value
+        .and_then(Json::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(str::to_string))
+}
+
+// Название из манифеста; ссылка на перевод `__MSG_имя__` заменяется идентификатором
+fn display_name(name: Option<&str>, id: &str) -> String {
+    match name.map(str::trim) {
+        Some(name) if !name.is_empty() && !name.starts_with("__MSG_") => name.to_string(),
+        _ => id.to_string(),
+    }
+}
+
+/// Разбирает `manifest.json` расширения Chromium из каталога `id`. Адреса из
+/// `host_permissions` (манифест версии 3) считаются разрешениями наравне с остальными
+pub fn chromium_manifest(id: &str, data: &[u8]) -> Option<Extension> {
+    // Манифест может начинаться с метки порядка байтов
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let manifest: Json = serde_json::from_slice(data).ok()?;
+    let manifest = manifest.as_object()?;
+    let permissions = strings(manifest.get("permissions"))
+        .chain(strings(manifest.get("host_permissions")))
+        .collect();
+    Some(Extension {
+        id: id.to_string(),
+        name: display_name(manifest.get("name").and_then(Json::as_str), id),
+        version: manifest
+            .get("version")
+            .and_then(Json::as_str)
+            .map(str::to_string),
+        permissions,
+        enabled: None,
+    })
+}
+
+/// Расширения из `extensions.json` профиля Firefox. Темы, словари и встроенные
+/// дополнения самого Firefox пропускаются
+pub fn firefox_addons(data: &[u8]) -> Option<Vec<Extension>> {
+    let file: Json = serde_json::from_slice(data).ok()?;
+    let addons = file.get("addons")?.as_array()?;
+    Some(
+        addons
+            .iter()
+            .filter(|addon| addon.get("type").and_then(Json::as_str) == Some("extension"))
+            .filter(|addon| {
+                !addon
+                    .get("location")
+                    .and_then(Json::as_str)
+                    .is_some_and(|location| {
+                        location.starts_with("app-builtin") || location == "app-system-defaults"
+                    })
+            })
+            .filter_map(|addon| {
+                let id = addon.get("id")?.as_str()?;
+                let name = addon
+                    .get("defaultLocale")
+                    .and_then(|locale| locale.get("name"))
+                    .and_then(Json::as_str);
+                let granted = addon.get("userPermissions");
+                let permissions = strings(granted.and_then(|p| p.get("permissions")))
+                    .chain(strings(granted.and_then(|p| p.get("origins"))))
+                    .collect();
+                Some(Extension {
+                    id: id.to_string(),
+                    name: display_name(name, id),
+                    version: addon
+                        .get("version")
+                        .and_then(Json::as_str)
+                        .map(str::to_string),
+                    permissions,
+                    enabled: addon.get("active").and_then(Json::as_bool),
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localized_name_falls_back_to_id() {
+        let id = "cjpalhdlnbpafiamejdnhcphjbkeiagm";
+        let manifest = br#"{"name": "__MSG_extName__", "version": "1.57.0",
+            "permissions": ["storage", "webRequest"], "host_permissions": ["<all_urls>"]}"#;
+        let extension = chromium_manifest(id, manifest).unwrap();
+        assert_eq!(extension.name, id);
+        assert_eq!(extension.version.as_deref(), Some("1.57.0"));
+        assert_eq!(
+            risky(extension.permissions.iter().map(String::as_str)),
+            ["webRequest", "<all_urls>"]
+        );
+        assert!(chromium_id(id));
+        assert!(!chromium_id("Temp"));
+        assert!(version_key("1.10.0_0") > version_key("1.9.2_0"));
+        assert_eq!(chromium_manifest(id, b"{not json"), None);
+    }
+
+    #[test]
+    fn firefox_keeps_only_user_extensions() {
+        let data = br#"{"addons": [
+            {"id": "uBlock0@raymondhill.net", "type": "extension", "version": "1.58.0",
+             "active": true, "location": "app-profile", "defaultLocale": {"name": "uBlock Origin"},
+             "userPermissions": {"permissions": ["storage"], "origins": ["*://*/*"]}},
+            {"id": "default-theme@mozilla.org", "type": "theme", "location": "app-builtin"},
+            {"id": "screenshots@mozilla.org", "type": "extension", "location": "app-builtin-addons"}
+        ]}"#;
+        let addons = firefox_addons(data).unwrap();
+        assert_eq!(addons.len(), 1);
+        assert_eq!(addons[0].name, "uBlock Origin");
+        assert_eq!(addons[0].enabled, Some(true));
+        assert_eq!(
+            risky(addons[0].permissions.iter().map(String::as_str)),
+            ["<all_urls>"]
+        );
+    }
+}
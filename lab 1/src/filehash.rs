@@ -0,0 +1,221 @@
+use crate::i18n::tr;
+use crate::model::{Column, ResultSet, Value};
+use crate::queries;
+use crate::shell;
+use crate::ActiveData;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver, Sender};
+use windows::Win32::Security::Cryptography::{
+    BCryptCreateHash, BCryptDestroyHash, BCryptFinishHash, BCryptHashData, BCRYPT_HASH_HANDLE,
+    BCRYPT_SHA256_ALG_HANDLE,
+};
+
+/// Столбец хеша; идёт сразу за столбцом пути к файлу
+pub const COLUMN: &str = "SHA256";
+
+// Файл читается кусками, а не целиком: исполняемые файлы бывают большими
+const CHUNK: usize = 64 * 1024;
+
+/// Столбец пути к исполняемому файлу категории; у остальных категорий хешей нет
+pub fn path_column(data: ActiveData) -> Option<&'static str> {
+    match data {
+        ActiveData::Processes => Some("ExecutablePath"),
+        ActiveData::Services => Some("PathName"),
+        _ => None,
+    }
+}
+
+// Путь к файлу из ячейки: у служб это командная строка с аргументами
+fn file(data: ActiveData, text: &str) -> Option<String> {
+    match data {
+        ActiveData::Services => shell::file_path(text).map(|path| path.display().to_string()),
+        _ => Some(text.trim().to_string()).filter(|path| !path.is_empty()),
+    }
+}
+
+// Хеш освобождается и при ошибке чтения
+struct Hash(BCRYPT_HASH_HANDLE);
+
+impl Drop for Hash {
+    fn drop(&mut self) {
+        let _ = unsafe { BCryptDestroyHash(self.0) };
+    }
+}
+
+/// SHA-256 всего, что читается из `reader`, строчными шестнадцатеричными
+/// цифрами, как в адресах VirusTotal
+pub fn sha256(mut reader: impl Read) -> std::io::Result<String> {
+    let mut handle = BCRYPT_HASH_HANDLE::default();
+    unsafe { BCryptCreateHash(BCRYPT_SHA256_ALG_HANDLE, &mut handle, None, None, 0) }
+        .ok()
+        .map_err(std::io::Error::other)?;
+    let hash = Hash(handle);
+    let mut buffer = vec![0u8; CHUNK];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        unsafe { BCryptHashData(hash.0, &buffer[..read], 0) }
+            .ok()
+            .map_err(std::io::Error::other)?;
+    }
+    let mut digest = [0u8; 32];
+    unsafe { BCryptFinishHash(hash.0, &mut digest, 0) }
+        .ok()
+        .map_err(std::io::Error::other)?;
+    Ok(digest.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+// Поток хеширования: файлы по одному, пока окно не забудет очередь
+fn run(files: Receiver<String>, reply: Sender<(String, Option<String>)>, ctx: egui::Context) {
+    while let Ok(path) = files.recv() {
+        let hash = std::fs::File::open(&path).and_then(sha256);
+        if let Err(e) = &hash {
+            tracing::debug!(%path, error = %e, "хеш файла не посчитан");
+        }
+        if reply.send((path, hash.ok())).is_err() {
+            return;
+        }
+        ctx.request_repaint();
+    }
+}
+
+/// Хеши исполняемых файлов категории для проверки в VirusTotal. Файлы читаются
+/// в своём потоке уже после того, как список показан, и не задерживают запросы
+/// WMI. Хеш файла считается один раз, пока его путь есть в списке
+pub struct Hashes {
+    data: ActiveData,
+    host: Option<String>, // Удалённые файлы читаются через \\host\C$
+    known: HashMap<String, Option<String>>, // `None` — файл прочитать не удалось
+    queued: HashSet<String>,
+    files: Option<Sender<String>>,
+    results: Receiver<(String, Option<String>)>,
+    reply: Sender<(String, Option<String>)>,
+}
+
+impl Hashes {
+    pub fn new(data: ActiveData, host: Option<String>) -> Self {
+        let (reply, results) = mpsc::channel();
+        Self {
+            data,
+            host,
+            known: HashMap::new(),
+            queued: HashSet::new(),
+            files: None,
+            results,
+            reply,
+        }
+    }
+
+    // Путь для чтения файла строки; без пути в ячейке — `None`
+    fn key(&self, set: &ResultSet, column: usize, row: usize) -> Option<String> {
+        match &set.rows[row][column] {
+            Value::Text(text) => {
+                file(self.data, text).map(|path| queries::file_path(self.host.as_deref(), &path))
+            }
+            _ => None,
+        }
+    }
+
+    /// Добавляет столбец хеша в новый список, заполняет его известными хешами
+    /// и ставит остальные файлы в очередь; хеши файлов, которых больше нет в
+    /// списке, забываются
+    pub fn fill(&mut self, ctx: &egui::Context, set: &mut ResultSet) {
+        let Some(column) =
+            path_column(self.data).and_then(|name| set.columns.iter().position(|c| c.name == name))
+        else {
+            return;
+        };
+        if !set.columns.iter().any(|c| c.name == COLUMN) {
+            set.columns.insert(column + 1, Column::new(COLUMN));
+            for row in &mut set.rows {
+                row.insert(column + 1, Value::Null);
+            }
+        }
+        let present: HashSet<String> = (0..set.rows.len())
+            .filter_map(|row| self.key(set, column, row))
+            .collect();
+        self.known.retain(|path, _| present.contains(path));
+        let files = self.files.get_or_insert_with(|| {
+            let (files, queue) = mpsc::channel();
+            let (reply, ctx) = (self.reply.clone(), ctx.clone());
+            std::thread::spawn(move || run(queue, reply, ctx));
+            files
+        });
+        for path in present {
+            if !self.known.contains_key(&path)
+                && !self.queued.contains(&path)
+                && files.send(path.clone()).is_ok()
+            {
+                self.queued.insert(path);
+            }
+        }
+        self.apply(set);
+    }
+
+    /// Забирает готовые хеши и вписывает их в список. `true`, если что-то добавилось
+    pub fn poll(&mut self, set: &mut ResultSet) -> bool {
+        let mut changed = false;
+        while let Ok((path, hash)) = self.results.try_recv() {
+            self.queued.remove(&path);
+            self.known.insert(path, hash);
+            changed = true;
+        }
+        if changed {
+            self.apply(set);
+        }
+        changed
+    }
+
+    /// Сколько файлов ещё ждут хеширования
+    pub fn remaining(&self) -> usize {
+        self.queued.len()
+    }
+
+    fn apply(&self, set: &mut ResultSet) {
+        let Some(column) =
+            path_column(self.data).and_then(|name| set.columns.iter().position(|c| c.name == name))
+        else {
+            return;
+        };
+        if set.columns.get(column + 1).is_none_or(|c| c.name != COLUMN) {
+            return;
+        }
+        for row in 0..set.rows.len() {
+            let Some(found) = self
+                .key(set, column, row)
+                .and_then(|path| self.known.get(&path))
+            else {
+                continue;
+            };
+            set.rows[row][column + 1] = match found {
+                Some(hash) => hash.as_str().into(),
+                None => tr("value.na").into(),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_digests() {
+        assert_eq!(
+            sha256(&b""[..]).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        // Больше одного куска
+        assert_eq!(
+            sha256(&vec![b'a'; 1_000_000][..]).unwrap(),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+        assert_eq!(
+            sha256(&b"abc"[..]).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,698 @@
+use crate::extensions;
+use crate::i18n::{tr, trf};
+use crate::model::{ResultSet, Value};
+use crate::uptime;
+use crate::ActiveData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Важность находки, по возрастанию
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 5] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+        Severity::Info,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Severity::Info => tr("severity.info"),
+            Severity::Low => tr("severity.low"),
+            Severity::Medium => tr("severity.medium"),
+            Severity::High => tr("severity.high"),
+            Severity::Critical => tr("severity.critical"),
+        }
+    }
+}
+
+/// Результат проверки: что обнаружено и где
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub check: Check, // Проверка, которая нашла
+    pub severity: Severity,
+    pub category: String,
+    pub title: String,
+    pub detail: String,
+    // Ключ строки на языке данных, а не интерфейса: значения ключевых столбцов
+    // через «|». У находок обо всей категории пусто
+    pub identity: String,
+}
+
+impl Finding {
+    /// Имя категории для файлов и получателей; не зависит от языка интерфейса
+    pub fn slug(&self) -> &'static str {
+        self.check.source().slug()
+    }
+}
+
+/// Находка для тестов получателей: устройство с кодом ошибки 28
+#[cfg(test)]
+pub fn sample(check: Check, severity: Severity, detail: &str) -> Finding {
+    Finding {
+        check,
+        severity,
+        category: "Устройства".to_string(),
+        title: "Код ошибки 28".to_string(),
+        detail: detail.to_string(),
+        identity: detail.to_string(),
+    }
+}
+
+/// Проверки, выполняемые над данными категорий
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Check {
+    DeviceStatus,
+    ProblemDevices,
+    PrefetchMissing,
+    RecycleBinTruncated,
+    AlternateStreams,
+    RiskyExtensions,
+    DefenderExclusions,
+    PacketCapture,
+    ProblemDeviceCount,
+    DirtyVolumes,
+    LongUptime,
+    StaleReboot,
+    PlaceholderIdentity,
+    NeverSleeps,
+    NoWakePassword,
+}
+
+impl Check {
+    pub const ALL: [Check; 15] = [
+        Check::DeviceStatus,
+        Check::ProblemDevices,
+        Check::PrefetchMissing,
+        Check::RecycleBinTruncated,
+        Check::AlternateStreams,
+        Check::RiskyExtensions,
+        Check::DefenderExclusions,
+        Check::PacketCapture,
+        Check::ProblemDeviceCount,
+        Check::DirtyVolumes,
+        Check::LongUptime,
+        Check::StaleReboot,
+        Check::PlaceholderIdentity,
+        Check::NeverSleeps,
+        Check::NoWakePassword,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Check::DeviceStatus => tr("check.device_status"),
+            Check::ProblemDevices => tr("check.problem_devices"),
+            Check::PrefetchMissing => tr("check.prefetch_missing"),
+            Check::RecycleBinTruncated => tr("check.recycle_truncated"),
+            Check::AlternateStreams => tr("check.alternate_streams"),
+            Check::RiskyExtensions => tr("check.risky_extensions"),
+            Check::DefenderExclusions => tr("check.defender_exclusions"),
+            Check::PacketCapture => tr("check.packet_capture"),
+            Check::ProblemDeviceCount => tr("check.problem_device_count"),
+            Check::DirtyVolumes => tr("check.dirty_volumes"),
+            Check::LongUptime => tr("check.long_uptime"),
+            Check::StaleReboot => tr("check.stale_reboot"),
+            Check::PlaceholderIdentity => tr("check.placeholder_identity"),
+            Check::NeverSleeps => tr("check.never_sleeps"),
+            Check::NoWakePassword => tr("check.no_wake_password"),
+        }
+    }
+
+    /// Категория, данные которой нужны проверке
+    pub fn source(self) -> ActiveData {
+        match self {
+            Check::DeviceStatus | Check::ProblemDevices => ActiveData::BusInfo,
+            Check::PrefetchMissing => ActiveData::Prefetch,
+            Check::RecycleBinTruncated => ActiveData::RecycleBin,
+            Check::AlternateStreams => ActiveData::Streams,
+            Check::RiskyExtensions => ActiveData::BrowserExtensions,
+            Check::DefenderExclusions => ActiveData::DefenderExclusions,
+            Check::PacketCapture => ActiveData::NetworkAdapters,
+            Check::ProblemDeviceCount => ActiveData::ProblemDevices,
+            Check::DirtyVolumes => ActiveData::Volumes,
+            Check::LongUptime | Check::StaleReboot => ActiveData::Uptime,
+            Check::PlaceholderIdentity => ActiveData::Asset,
+            Check::NeverSleeps | Check::NoWakePassword => ActiveData::PowerPlans,
+        }
+    }
+
+    pub fn run(self, results: &HashMap<ActiveData, ResultSet>) -> Vec<Finding> {
+        let Some(set) = results.get(&self.source()) else {
+            return Vec::new();
+        };
+        self.flagged(set)
+            .into_iter()
+            .map(|(_, finding)| finding)
+            .collect()
+    }
+
+    // Находки вместе с индексом строки, на которой они сработали
+    fn flagged(self, set: &ResultSet) -> Vec<(usize, Finding)> {
+        match self {
+            Check::DeviceStatus => device_status(set),
+            Check::ProblemDevices => problem_devices(set),
+            Check::PrefetchMissing => prefetch_missing(set),
+            Check::RecycleBinTruncated => recycle_truncated(set),
+            Check::AlternateStreams => alternate_streams(set),
+            Check::RiskyExtensions => risky_extensions(set),
+            Check::DefenderExclusions => defender_exclusions(set),
+            Check::PacketCapture => packet_capture(set),
+            Check::ProblemDeviceCount => problem_device_count(set),
+            Check::DirtyVolumes => dirty_volumes(set),
+            Check::LongUptime => long_uptime(set),
+            Check::StaleReboot => stale_reboot(set),
+            Check::PlaceholderIdentity => placeholder_identity(set),
+            Check::NeverSleeps => never_sleeps(set),
+            Check::NoWakePassword => no_wake_password(set),
+        }
+    }
+}
+
+/// Наибольшая важность находок для каждой строки таблицы категории
+pub fn row_flags(data: ActiveData, set: &ResultSet) -> HashMap<usize, Severity> {
+    row_findings(data, set)
+        .into_iter()
+        .filter_map(|(row, found)| Some((row, found.iter().map(|f| f.severity).max()?)))
+        .collect()
+}
+
+/// Все находки проверок категории, сгруппированные по строкам таблицы
+pub fn row_findings(data: ActiveData, set: &ResultSet) -> HashMap<usize, Vec<Finding>> {
+    let mut rows: HashMap<usize, Vec<Finding>> = HashMap::new();
+    for check in Check::ALL.into_iter().filter(|c| c.source() == data) {
+        for (row, finding) in check.flagged(set) {
+            rows.entry(row).or_default().push(finding);
+        }
+    }
+    rows
+}
+
+fn column(set: &ResultSet, name: &str) -> Option<usize> {
+    set.columns.iter().position(|c| c.name == name)
+}
+
+// Ключ строки `row` из столбцов `keys`, в том числе дополнительных; пустые пропускаются
+fn identity(set: &ResultSet, row: usize, keys: &[&str]) -> String {
+    keys.iter()
+        .filter_map(|key| set.value(row, key).or_else(|| set.extra_value(row, key)))
+        .filter(|value| **value != Value::Null)
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+// Устройства, сообщающие о неисправности (Status отличен от OK и Unknown).
+// Устройства без Status не проверяются
+fn device_status(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(id), Some(status)) = (column(set, "DeviceID"), column(set, "Status")) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let state = row.get(status).filter(|v| **v != Value::Null)?.display();
+            if state == "OK" || state == "Unknown" {
+                return None;
+            }
+            let finding = Finding {
+                check: Check::DeviceStatus,
+                severity: Severity::Medium,
+                category: ActiveData::BusInfo.title().to_string(),
+                title: trf("finding.device_status", &[("state", &state)]),
+                detail: row.get(id)?.to_string(),
+                identity: identity(set, index, &["DeviceID"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Проблемные устройства: диспетчер устройств сообщает ненулевой код ошибки
+fn problem_devices(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(id), Some(code)) = (
+        column(set, "DeviceID"),
+        column(set, "ConfigManagerErrorCode"),
+    ) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let Value::Int(code) = row.get(code)? else {
+                return None;
+            };
+            if *code == 0 {
+                return None;
+            }
+            let finding = Finding {
+                check: Check::ProblemDevices,
+                severity: Severity::High,
+                category: ActiveData::BusInfo.title().to_string(),
+                title: trf("finding.problem_device", &[("code", code)]),
+                detail: row.get(id)?.to_string(),
+                identity: identity(set, index, &["DeviceID"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Каталога предвыборки нет (таблица пуста): предвыборку выключили политикой или
+// для SSD, это серверная редакция — или следы запуска программ стёрли.
+// Находка не относится к строке, поэтому указывает на первую
+fn prefetch_missing(set: &ResultSet) -> Vec<(usize, Finding)> {
+    if !set.rows.is_empty() {
+        return Vec::new();
+    }
+    let finding = Finding {
+        check: Check::PrefetchMissing,
+        severity: Severity::Medium,
+        category: ActiveData::Prefetch.title().to_string(),
+        title: tr("finding.prefetch_missing").to_string(),
+        detail: tr("finding.prefetch_missing_detail").to_string(),
+        identity: String::new(),
+    };
+    vec![(0, finding)]
+}
+
+// В корзинах больше объектов, чем показано: таблица ограничена последними
+// удалёнными. Это примечание, а не нарушение, поэтому важность — сведения
+fn recycle_truncated(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let Some(Value::Int(total)) = set.extra_value(0, "TotalItems") else {
+        return Vec::new();
+    };
+    if *total as usize <= set.rows.len() {
+        return Vec::new();
+    }
+    let finding = Finding {
+        check: Check::RecycleBinTruncated,
+        severity: Severity::Info,
+        category: ActiveData::RecycleBin.title().to_string(),
+        title: tr("finding.recycle_truncated").to_string(),
+        detail: trf(
+            "finding.recycle_truncated_detail",
+            &[("shown", &set.rows.len()), ("total", total)],
+        ),
+        identity: String::new(),
+    };
+    vec![(0, finding)]
+}
+
+// Потоки, которые пишут сама Windows и распространённые программы
+const KNOWN_STREAMS: [&str; 11] = [
+    "SmartScreen",
+    "com.dropbox.attrs",
+    "com.dropbox.attributes",
+    "AFP_AfpInfo",
+    "AFP_Resource",
+    "encryptable",
+    "favicon",
+    "ms-properties",
+    "OECustomProperty",
+    "\u{5}SummaryInformation",
+    "\u{5}DocumentSummaryInformation",
+];
+
+// Расширения в имени потока, под которыми прячут исполняемое содержимое
+const EXECUTABLE_STREAMS: [&str; 9] =
+    ["exe", "dll", "scr", "ps1", "bat", "cmd", "vbs", "js", "hta"];
+
+// Сводка категории проблемных устройств: сколько их всего. Каждое из них отдельно
+// отмечает проверка ProblemDevices над всеми устройствами, поэтому здесь только сведения.
+// Строка-пояснение без DeviceID устройством не считается
+fn problem_device_count(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(id), Some(problem)) = (column(set, "DeviceID"), column(set, "Problem")) else {
+        return Vec::new();
+    };
+    let problems: Vec<String> = set
+        .rows
+        .iter()
+        .filter(|row| matches!(row.get(id), Some(Value::Text(_))))
+        .filter_map(|row| Some(row.get(problem)?.to_string()))
+        .collect();
+    let count = problems.len();
+    if count == 0 {
+        return Vec::new();
+    }
+    let finding = Finding {
+        check: Check::ProblemDeviceCount,
+        severity: Severity::Info,
+        category: ActiveData::ProblemDevices.title().to_string(),
+        title: trf("finding.problem_device_count", &[("count", &count)]),
+        detail: problems.join("; "),
+        identity: String::new(),
+    };
+    vec![(0, finding)]
+}
+
+// Том с установленным битом загрязнения: компьютер выключили, не завершив запись,
+// или файловая система повреждена. При загрузке Windows запустит chkdsk
+fn dirty_volumes(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(path), Some(dirty)) = (column(set, "MountPath"), column(set, "DirtyBitSet")) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.get(dirty) == Some(&Value::Bool(true)))
+        .filter_map(|(index, row)| {
+            let finding = Finding {
+                check: Check::DirtyVolumes,
+                severity: Severity::Medium,
+                category: ActiveData::Volumes.title().to_string(),
+                title: tr("finding.dirty_volume").to_string(),
+                detail: row.get(path)?.to_string(),
+                identity: identity(set, index, &["DeviceID"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Строка сводки времени работы с кодом `code` и число дней в ней. Строки ищутся
+// по коду, а не по подписи: данные могли быть собраны на другом языке
+fn indicator(set: &ResultSet, code: &str) -> Option<(usize, Option<i64>)> {
+    let row = (0..set.rows.len()).find(|&row| set.value(row, "Code") == Some(&code.into()))?;
+    let days = match set.value(row, "Days") {
+        Some(Value::Int(days)) => Some(*days),
+        _ => None,
+    };
+    Some((row, days))
+}
+
+// Рабочая станция давно не перезагружалась: обновления, требующие перезагрузки,
+// не действуют. Серверы перезагружают по расписанию обслуживания, их это не касается
+fn long_uptime(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let workstation = indicator(set, uptime::WORKSTATION_ROLE).is_some();
+    let Some((row, Some(days))) = indicator(set, "uptime") else {
+        return Vec::new();
+    };
+    if !workstation || days <= uptime::LONG_UPTIME_DAYS {
+        return Vec::new();
+    }
+    let boot = indicator(set, "last_boot")
+        .and_then(|(row, _)| set.value(row, "Observed"))
+        .map(Value::to_string)
+        .unwrap_or_default();
+    let finding = Finding {
+        check: Check::LongUptime,
+        severity: Severity::Low,
+        category: ActiveData::Uptime.title().to_string(),
+        title: trf("finding.long_uptime", &[("days", &days)]),
+        detail: trf("finding.long_uptime_detail", &[("boot", &boot)]),
+        identity: identity(set, row, &["Code"]),
+    };
+    vec![(row, finding)]
+}
+
+// Перезагрузка ожидается дольше недели после последнего обновления: установленные
+// исправления ещё не действуют. Дни считаются от даты этого обновления
+fn stale_reboot(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let Some((row, Some(days))) = indicator(set, "pending") else {
+        return Vec::new();
+    };
+    if days <= uptime::STALE_REBOOT_DAYS {
+        return Vec::new();
+    }
+    let finding = Finding {
+        check: Check::StaleReboot,
+        severity: Severity::Medium,
+        category: ActiveData::Uptime.title().to_string(),
+        title: trf("finding.stale_reboot", &[("days", &days)]),
+        detail: set
+            .value(row, "Observed")
+            .map(Value::to_string)
+            .unwrap_or_default(),
+        identity: identity(set, row, &["Code"]),
+    };
+    vec![(row, finding)]
+}
+
+// Заглушка производителя вместо серийного номера или UUID: по такому значению
+// компьютер невозможно отличить от других той же модели в базе учёта
+fn placeholder_identity(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(label), Some(flag)) = (column(set, "Indicator"), column(set, "Placeholder")) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.get(flag) == Some(&Value::Bool(true)))
+        .filter_map(|(index, row)| {
+            let finding = Finding {
+                check: Check::PlaceholderIdentity,
+                severity: Severity::Low,
+                category: ActiveData::Asset.title().to_string(),
+                title: trf(
+                    "finding.placeholder_identity",
+                    &[("field", &row.get(label)?.to_string())],
+                ),
+                detail: set.value(index, "Observed")?.to_string(),
+                identity: identity(set, index, &["Source"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Столбцы параметра активной схемы, которые действуют: от батареи — только
+// на компьютере с батареей. Вместе со строкой схемы и её именем
+fn active_plan<'a>(
+    set: &'a ResultSet,
+    ac: &'static str,
+    dc: &'static str,
+) -> Vec<(usize, String, &'a Value)> {
+    let Some(row) =
+        (0..set.rows.len()).find(|&row| set.value(row, "Active") == Some(&Value::Bool(true)))
+    else {
+        return Vec::new();
+    };
+    let name = set
+        .value(row, "PowerPlan")
+        .map(Value::to_string)
+        .unwrap_or_default();
+    let mut columns = vec![ac];
+    if set.value(row, "Battery") == Some(&Value::Bool(true)) {
+        columns.push(dc);
+    }
+    columns
+        .into_iter()
+        .filter_map(|column| set.value(row, column))
+        .map(|value| (row, name.clone(), value))
+        .collect()
+}
+
+// Активная схема не переводит компьютер в сон: оставленный без присмотра сеанс
+// остаётся открытым, а ноутбук в сумке разряжается. Достаточно одной находки на схему
+fn never_sleeps(set: &ResultSet) -> Vec<(usize, Finding)> {
+    active_plan(set, "SleepAC", "SleepDC")
+        .into_iter()
+        .find(|(_, _, value)| **value == Value::Int(0))
+        .map(|(row, plan, _)| {
+            let finding = Finding {
+                check: Check::NeverSleeps,
+                severity: Severity::Low,
+                category: ActiveData::PowerPlans.title().to_string(),
+                title: tr("finding.never_sleeps").to_string(),
+                detail: trf("finding.power_plan_detail", &[("plan", &plan)]),
+                identity: identity(set, row, &["PlanGuid"]),
+            };
+            (row, finding)
+        })
+        .into_iter()
+        .collect()
+}
+
+// После сна активная схема не спрашивает пароль: вернувшийся из сна компьютер
+// сразу открывает сеанс тому, кто его взял
+fn no_wake_password(set: &ResultSet) -> Vec<(usize, Finding)> {
+    active_plan(set, "WakePasswordAC", "WakePasswordDC")
+        .into_iter()
+        .find(|(_, _, value)| **value == Value::Bool(false))
+        .map(|(row, plan, _)| {
+            let finding = Finding {
+                check: Check::NoWakePassword,
+                severity: Severity::Medium,
+                category: ActiveData::PowerPlans.title().to_string(),
+                title: tr("finding.no_wake_password").to_string(),
+                detail: trf("finding.power_plan_detail", &[("plan", &plan)]),
+                identity: identity(set, row, &["PlanGuid"]),
+            };
+            (row, finding)
+        })
+        .into_iter()
+        .collect()
+}
+
+// Нестандартные дополнительные потоки NTFS: в них можно спрятать данные или программу,
+// не меняя содержимого файла. Поток с расширением исполняемого файла важнее
+fn alternate_streams(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(path), Some(stream)) = (column(set, "FilePath"), column(set, "Stream")) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let name = row.get(stream)?.to_string();
+            if KNOWN_STREAMS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(&name))
+            {
+                return None;
+            }
+            let executable = name.rsplit_once('.').is_some_and(|(_, ext)| {
+                EXECUTABLE_STREAMS
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(ext))
+            });
+            let finding = Finding {
+                check: Check::AlternateStreams,
+                severity: if executable {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                category: ActiveData::Streams.title().to_string(),
+                title: trf("finding.alternate_stream", &[("stream", &name)]),
+                detail: row.get(path)?.to_string(),
+                identity: identity(set, index, &["FilePath", "Stream"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Расширения с опасными разрешениями. Доступ ко всем страницам вместе с перехватом
+// запросов или связь с программами на компьютере важнее одиночного разрешения
+fn risky_extensions(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(name), Some(permissions)) = (column(set, "Name"), column(set, "Permissions")) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let granted = match row.get(permissions)? {
+                Value::Text(text) => text,
+                _ => return None,
+            };
+            let risky = extensions::risky(granted.split(", "));
+            if risky.is_empty() {
+                return None;
+            }
+            let severity = if risky.contains(&"nativeMessaging")
+                || (risky.contains(&"<all_urls>") && risky.contains(&"webRequest"))
+            {
+                Severity::High
+            } else {
+                Severity::Medium
+            };
+            let text = |name| {
+                set.value(index, name)
+                    .map(Value::to_string)
+                    .unwrap_or_default()
+            };
+            let finding = Finding {
+                check: Check::RiskyExtensions,
+                severity,
+                category: ActiveData::BrowserExtensions.title().to_string(),
+                title: trf(
+                    "finding.risky_extension",
+                    &[
+                        ("name", &row.get(name)?.to_string()),
+                        ("browser", &text("Browser")),
+                    ],
+                ),
+                detail: trf(
+                    "finding.risky_extension_detail",
+                    &[("permissions", &risky.join(", ")), ("user", &text("User"))],
+                ),
+                identity: identity(set, index, &["User", "Browser", "Profile", "ExtensionId"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Слишком широкие исключения Defender: через них вредоносная программа прячется
+// от проверки. Скрытый список — примечание: проверить его не удалось
+fn defender_exclusions(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(kind), Some(exclusion), Some(broad)) = (
+        column(set, "ExclusionType"),
+        column(set, "Exclusion"),
+        column(set, "Broad"),
+    ) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let kind = row.get(kind)?;
+            let (severity, title, detail) = match (row.get(exclusion)?, row.get(broad)?) {
+                (Value::Text(item), Value::Text(reason)) => (
+                    Severity::High,
+                    trf(
+                        "finding.defender_exclusion",
+                        &[("kind", &kind.to_string()), ("exclusion", item)],
+                    ),
+                    reason.clone(),
+                ),
+                (Value::Null, _) if *kind != Value::Null => (
+                    Severity::Info,
+                    trf("finding.defender_hidden", &[("kind", &kind.to_string())]),
+                    tr("defender.hidden").to_string(),
+                ),
+                _ => return None,
+            };
+            let finding = Finding {
+                check: Check::DefenderExclusions,
+                severity,
+                category: ActiveData::DefenderExclusions.title().to_string(),
+                title,
+                detail,
+                identity: identity(set, index, &["ExclusionType", "Exclusion"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
+
+// Адаптеры, доступные драйверу перехвата пакетов (Npcap, WinPcap). На рабочей станции
+// пользователя такой драйвер обычно не нужен и может остаться от средства атаки
+fn packet_capture(set: &ResultSet) -> Vec<(usize, Finding)> {
+    let (Some(name), Some(driver)) = (column(set, "Name"), column(set, "CaptureDriver")) else {
+        return Vec::new();
+    };
+    set.rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let Value::Text(driver) = row.get(driver)? else {
+                return None;
+            };
+            let finding = Finding {
+                check: Check::PacketCapture,
+                severity: Severity::Medium,
+                category: ActiveData::NetworkAdapters.title().to_string(),
+                title: trf("finding.packet_capture", &[("driver", driver)]),
+                detail: row.get(name)?.to_string(),
+                identity: identity(set, index, &["PNPDeviceID"]),
+            };
+            Some((index, finding))
+        })
+        .collect()
+}
@@ -0,0 +1,745 @@
+use crate::i18n::{self, tr, trf};
+use crate::model::{CategoryExport, Column, ExportFile, Value};
+use chrono::{DateTime, Local};
+use egui_plot::{Line, Plot, PlotPoints};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "history.sqlite3";
+
+/// Изменения схемы по порядку; число применённых хранится в `PRAGMA user_version`.
+/// Строки всех категорий лежат в одной таблице: ключ — запуск, категория и
+/// значения ключевых столбцов, ячейки — объект JSON с именами свойств WMI
+const MIGRATIONS: &[&str] = &["
+CREATE TABLE runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    host TEXT NOT NULL,
+    time TEXT NOT NULL,
+    kind TEXT NOT NULL
+);
+CREATE TABLE categories (
+    run INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    columns TEXT NOT NULL,
+    PRIMARY KEY (run, category)
+);
+CREATE TABLE rows (
+    run INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    identity TEXT NOT NULL,
+    cells TEXT NOT NULL,
+    PRIMARY KEY (run, category, identity)
+);
+"];
+
+/// База истории лежит рядом с файлом настроек
+pub fn path(config: &Path) -> PathBuf {
+    config.with_file_name(FILE_NAME)
+}
+
+/// Раздел `[history]`: запись снимков в базу SQLite
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub keep_runs: usize, // Более старые запуски удаляются при записи нового
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep_runs: 30,
+        }
+    }
+}
+
+impl Settings {
+    /// Переключатель и срок хранения для меню настроек
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, tr("history.enabled"))
+            .on_hover_text(tr("history.hint"));
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("history.keep_runs"));
+                ui.add(egui::DragValue::new(&mut self.keep_runs).range(1..=1000));
+            });
+        });
+    }
+}
+
+/// Откуда взялся запуск
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Report,
+    Manual,
+    Scheduled,
+}
+
+impl Kind {
+    fn key(self) -> &'static str {
+        match self {
+            Kind::Report => "report",
+            Kind::Manual => "manual",
+            Kind::Scheduled => "scheduled",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Kind::Report => tr("history.kind_report"),
+            Kind::Manual => tr("history.kind_manual"),
+            Kind::Scheduled => tr("history.kind_scheduled"),
+        }
+    }
+}
+
+/// Записанный запуск
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub id: i64,
+    pub host: String,
+    pub time: DateTime<Local>,
+    pub kind: Kind,
+    pub categories: Vec<String>,
+}
+
+impl Run {
+    fn label(&self) -> String {
+        format!(
+            "#{} {} {}",
+            self.id,
+            self.time.format("%d.%m.%Y %H:%M"),
+            self.host
+        )
+    }
+}
+
+/// Различия строк категории между двумя запусками
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Comparison {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<(String, Vec<String>)>, // Строка и заголовки изменившихся столбцов
+}
+
+type Cells = BTreeMap<String, Value>;
+
+fn failed(e: impl std::fmt::Display) -> String {
+    trf("history.failed", &[("error", &e)])
+}
+
+// Ключ строки — значения ключевых столбцов; без них — номер строки.
+// Повторяющийся ключ получает номер, чтобы строка не потерялась
+fn identities(rows: &[Cells], keys: &[&str]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let cells: Option<Vec<String>> = keys
+                .iter()
+                .map(|&key| row.get(key).map(Value::to_string))
+                .collect();
+            let identity = match cells.filter(|cells| !cells.is_empty()) {
+                Some(cells) => cells.join(" / "),
+                None => format!("#{}", index + 1),
+            };
+            let count = seen.entry(identity.clone()).or_default();
+            *count += 1;
+            match *count {
+                1 => identity,
+                n => format!("{identity} #{n}"),
+            }
+        })
+        .collect()
+}
+
+/// База истории снимков
+pub struct History {
+    db: Connection,
+}
+
+impl History {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let db = Connection::open(path).map_err(|e| {
+            trf(
+                "history.open_failed",
+                &[("path", &path.display()), ("error", &e)],
+            )
+        })?;
+        Self::new(db).map_err(failed)
+    }
+
+    fn new(mut db: Connection) -> rusqlite::Result<Self> {
+        let applied: usize = db.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+            let tx = db.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", version + 1)?;
+            tx.commit()?;
+        }
+        Ok(Self { db })
+    }
+
+    /// Записывает категории файла как новый запуск и оставляет последние `keep` запусков.
+    /// `keys` — ключевые столбцы категории по её короткому имени
+    pub fn record(
+        &mut self,
+        file: &ExportFile,
+        kind: Kind,
+        keys: impl Fn(&str) -> &'static [&'static str],
+        keep: usize,
+    ) -> Result<i64, String> {
+        let tx = self.db.transaction().map_err(failed)?;
+        tx.execute(
+            "INSERT INTO runs (host, time, kind) VALUES (?1, ?2, ?3)",
+            params![file.hostname, file.timestamp.to_rfc3339(), kind.key()],
+        )
+        .map_err(failed)?;
+        let run = tx.last_insert_rowid();
+        for category in &file.categories {
+            let columns = serde_json::to_string(&category.columns).map_err(failed)?;
+            tx.execute(
+                "INSERT INTO categories (run, category, columns) VALUES (?1, ?2, ?3)",
+                params![run, category.category, columns],
+            )
+            .map_err(failed)?;
+            let identities = identities(&category.rows, keys(&category.category));
+            for (identity, row) in identities.iter().zip(&category.rows) {
+                let cells = serde_json::to_string(row).map_err(failed)?;
+                tx.execute(
+                    "INSERT INTO rows (run, category, identity, cells) VALUES (?1, ?2, ?3, ?4)",
+                    params![run, category.category, identity, cells],
+                )
+                .map_err(failed)?;
+            }
+        }
+        for table in ["rows", "categories"] {
+            tx.execute(
+                &format!(
+                    "DELETE FROM {table} WHERE run NOT IN \
+                     (SELECT id FROM runs ORDER BY id DESC LIMIT ?1)"
+                ),
+                [keep.max(1)],
+            )
+            .map_err(failed)?;
+        }
+        tx.execute(
+            "DELETE FROM runs WHERE id NOT IN (SELECT id FROM runs ORDER BY id DESC LIMIT ?1)",
+            [keep.max(1)],
+        )
+        .map_err(failed)?;
+        tx.commit().map_err(failed)?;
+        Ok(run)
+    }
+
+    /// Запуски, новые сверху
+    pub fn runs(&self) -> Result<Vec<Run>, String> {
+        let mut categories: HashMap<i64, Vec<String>> = HashMap::new();
+        let mut statement = self
+            .db
+            .prepare("SELECT run, category FROM categories ORDER BY rowid")
+            .map_err(failed)?;
+        let pairs = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(failed)?;
+        for pair in pairs {
+            let (run, category) = pair.map_err(failed)?;
+            categories.entry(run).or_default().push(category);
+        }
+        let mut statement = self
+            .db
+            .prepare("SELECT id, host, time, kind FROM runs ORDER BY id DESC")
+            .map_err(failed)?;
+        let runs = statement
+            .query_map([], |row| {
+                let time: String = row.get(2)?;
+                let kind: String = row.get(3)?;
+                Ok((row.get(0)?, row.get(1)?, time, kind))
+            })
+            .map_err(failed)?;
+        runs.map(|run| {
+            let (id, host, time, kind) = run.map_err(failed)?;
+            Ok(Run {
+                id,
+                host,
+                time: DateTime::parse_from_rfc3339(&time)
+                    .map_err(failed)?
+                    .with_timezone(&Local),
+                kind: [Kind::Report, Kind::Scheduled]
+                    .into_iter()
+                    .find(|k| k.key() == kind)
+                    .unwrap_or(Kind::Manual),
+                categories: categories.remove(&id).unwrap_or_default(),
+            })
+        })
+        .collect()
+    }
+
+    // Строки категории запуска по ключам
+    fn rows(&self, run: i64, category: &str) -> Result<Vec<(String, Cells)>, String> {
+        let mut statement = self
+            .db
+            .prepare(
+                "SELECT identity, cells FROM rows WHERE run = ?1 AND category = ?2 ORDER BY rowid",
+            )
+            .map_err(failed)?;
+        let rows = statement
+            .query_map(params![run, category], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(failed)?;
+        rows.map(|row| {
+            let (identity, cells) = row.map_err(failed)?;
+            Ok((identity, serde_json::from_str(&cells).map_err(failed)?))
+        })
+        .collect()
+    }
+
+    /// Данные категории запуска в схеме экспорта; `None`, если её в запуске нет
+    pub fn category(&self, run: i64, category: &str) -> Result<Option<CategoryExport>, String> {
+        let columns: Option<String> = self
+            .db
+            .query_row(
+                "SELECT columns FROM categories WHERE run = ?1 AND category = ?2",
+                params![run, category],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(failed)?;
+        let Some(columns) = columns else {
+            return Ok(None);
+        };
+        Ok(Some(CategoryExport {
+            category: category.to_string(),
+            columns: serde_json::from_str::<Vec<Column>>(&columns).map_err(failed)?,
+            rows: self
+                .rows(run, category)?
+                .into_iter()
+                .map(|(_, cells)| cells)
+                .collect(),
+        }))
+    }
+
+    /// Все категории запуска в схеме экспорта
+    pub fn export(&self, run: &Run) -> Result<ExportFile, String> {
+        let mut categories = Vec::new();
+        for category in &run.categories {
+            categories.extend(self.category(run.id, category)?);
+        }
+        Ok(ExportFile {
+            timestamp: run.time.fixed_offset(),
+            ..ExportFile::new(run.host.clone(), categories)
+        })
+    }
+
+    /// Различия строк категории между запусками `older` и `newer` по их ключам
+    pub fn compare(&self, older: i64, newer: i64, category: &str) -> Result<Comparison, String> {
+        let before: HashMap<String, Cells> = self.rows(older, category)?.into_iter().collect();
+        let after = self.rows(newer, category)?;
+        let mut comparison = Comparison::default();
+        for (identity, cells) in &after {
+            let Some(old) = before.get(identity) else {
+                comparison.added.push(identity.clone());
+                continue;
+            };
+            let mut names: Vec<&String> = cells.keys().chain(old.keys()).collect();
+            names.sort_unstable();
+            names.dedup();
+            let changed: Vec<String> = names
+                .into_iter()
+                .filter(|&name| cells.get(name) != old.get(name))
+                .map(|name| i18n::column(name))
+                .collect();
+            if !changed.is_empty() {
+                comparison.changed.push((identity.clone(), changed));
+            }
+        }
+        let after: HashMap<&String, ()> =
+            after.iter().map(|(identity, _)| (identity, ())).collect();
+        let mut removed: Vec<String> = before
+            .into_keys()
+            .filter(|identity| !after.contains_key(identity))
+            .collect();
+        removed.sort_unstable();
+        comparison.removed = removed;
+        Ok(comparison)
+    }
+
+    /// Число строк категории в каждом запуске, где она есть, от старых к новым.
+    /// С `filter` считаются только строки, где столбец равен значению
+    pub fn counts(
+        &self,
+        category: &str,
+        filter: Option<(&str, &str)>,
+    ) -> Result<Vec<(DateTime<Local>, usize)>, String> {
+        let mut runs = self.runs()?;
+        runs.retain(|run| run.categories.iter().any(|c| c == category));
+        runs.reverse();
+        runs.into_iter()
+            .map(|run| {
+                let rows = self.rows(run.id, category)?;
+                let count = rows
+                    .iter()
+                    .filter(|(_, cells)| match filter {
+                        Some((column, value)) => cells
+                            .get(column)
+                            .is_some_and(|cell| cell.display().eq_ignore_ascii_case(value)),
+                        None => true,
+                    })
+                    .count();
+                Ok((run.time, count))
+            })
+            .collect()
+    }
+}
+
+/// Что вкладка истории просит сделать вызывающего
+pub enum Action {
+    Save,      // Записать текущие данные как новый запуск
+    Open(Run), // Показать данные запуска в таблицах категорий
+}
+
+/// Вкладка истории: список запусков, сравнение двух и изменение числа строк
+#[derive(Default)]
+pub struct Browser {
+    runs: Option<Vec<Run>>, // Перечитывается после записи нового запуска
+    older: Option<i64>,
+    newer: Option<i64>,
+    category: String,
+    comparison: Option<Comparison>,
+    column: String,
+    value: String,
+    trend: Vec<(DateTime<Local>, usize)>,
+    status: Option<Result<String, String>>,
+}
+
+impl Browser {
+    /// Запуски нужно перечитать, например после записи нового
+    pub fn reload(&mut self) {
+        self.runs = None;
+    }
+
+    pub fn set_status(&mut self, status: Result<String, String>) {
+        self.status = Some(status);
+    }
+
+    /// `categories` — короткие имена и заголовки категорий. Запись текущих данных
+    /// и показ запуска в таблицах выполняет вызывающий
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        history: Option<&History>,
+        categories: &[(&'static str, &'static str)],
+    ) -> Option<Action> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(history.is_some(), egui::Button::new(tr("history.save")))
+                .on_hover_text(tr("history.save_hint"))
+                .clicked()
+            {
+                action = Some(Action::Save);
+            }
+            if ui.button(tr("history.reload")).clicked() {
+                self.reload();
+            }
+            match &self.status {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                None => {}
+            }
+        });
+        let Some(history) = history else {
+            ui.weak(tr("history.disabled"));
+            return action;
+        };
+        if self.runs.is_none() {
+            match history.runs() {
+                Ok(runs) => self.runs = Some(runs),
+                Err(e) => {
+                    self.status = Some(Err(e));
+                    self.runs = Some(Vec::new());
+                }
+            }
+        }
+        let runs = self.runs.clone().unwrap_or_default();
+        if runs.is_empty() {
+            ui.weak(tr("history.empty"));
+            return action;
+        }
+        let title = |slug: &str| {
+            categories
+                .iter()
+                .find(|(s, _)| *s == slug)
+                .map_or(slug.to_string(), |(_, title)| title.to_string())
+        };
+
+        egui::ScrollArea::vertical()
+            .id_salt("history_runs")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                egui::Grid::new("history_runs_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["history.run", "history.host", "history.kind"] {
+                            ui.strong(tr(header));
+                        }
+                        ui.strong(tr("history.categories"));
+                        ui.end_row();
+                        for run in &runs {
+                            ui.label(format!(
+                                "#{} {}",
+                                run.id,
+                                run.time.format("%d.%m.%Y %H:%M:%S")
+                            ));
+                            ui.label(&run.host);
+                            ui.label(run.kind.title());
+                            let names: Vec<String> =
+                                run.categories.iter().map(|c| title(c)).collect();
+                            ui.label(names.join(", "));
+                            if ui
+                                .small_button(tr("history.open"))
+                                .on_hover_text(tr("history.open_hint"))
+                                .clicked()
+                            {
+                                action = Some(Action::Open(run.clone()));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("history.category"));
+            egui::ComboBox::from_id_salt("history_category")
+                .selected_text(title(&self.category))
+                .show_ui(ui, |ui| {
+                    for &(slug, name) in categories {
+                        ui.selectable_value(&mut self.category, slug.to_string(), name);
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            run_combo(
+                ui,
+                "history_older",
+                tr("history.older"),
+                &runs,
+                &mut self.older,
+            );
+            run_combo(
+                ui,
+                "history_newer",
+                tr("history.newer"),
+                &runs,
+                &mut self.newer,
+            );
+            let ready = self.older.is_some() && self.newer.is_some() && !self.category.is_empty();
+            if ui
+                .add_enabled(ready, egui::Button::new(tr("history.compare")))
+                .clicked()
+            {
+                if let (Some(older), Some(newer)) = (self.older, self.newer) {
+                    match history.compare(older, newer, &self.category) {
+                        Ok(comparison) => self.comparison = Some(comparison),
+                        Err(e) => self.status = Some(Err(e)),
+                    }
+                }
+            }
+        });
+        if let Some(comparison) = &self.comparison {
+            comparison_view(ui, comparison);
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("history.trend"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.column)
+                    .hint_text(tr("history.column_hint"))
+                    .desired_width(120.0),
+            );
+            ui.label("=");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.value)
+                    .hint_text(tr("history.value_hint"))
+                    .desired_width(120.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.category.is_empty(),
+                    egui::Button::new(tr("history.plot")),
+                )
+                .clicked()
+            {
+                let column = self.column.trim();
+                let filter = (!column.is_empty()).then_some((column, self.value.trim()));
+                match history.counts(&self.category, filter) {
+                    Ok(trend) => self.trend = trend,
+                    Err(e) => self.status = Some(Err(e)),
+                }
+            }
+        });
+        if !self.trend.is_empty() {
+            trend_plot(ui, &self.trend);
+        }
+        action
+    }
+}
+
+fn run_combo(ui: &mut egui::Ui, id: &str, label: &str, runs: &[Run], chosen: &mut Option<i64>) {
+    ui.label(label);
+    let text = chosen
+        .and_then(|id| runs.iter().find(|run| run.id == id))
+        .map_or(String::new(), Run::label);
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(text)
+        .width(220.0)
+        .show_ui(ui, |ui| {
+            for run in runs {
+                ui.selectable_value(chosen, Some(run.id), run.label());
+            }
+        });
+}
+
+fn comparison_view(ui: &mut egui::Ui, comparison: &Comparison) {
+    if comparison == &Comparison::default() {
+        ui.weak(tr("history.no_changes"));
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .id_salt("history_comparison")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for identity in &comparison.added {
+                ui.label(trf("watch.added", &[("row", identity)]));
+            }
+            for identity in &comparison.removed {
+                ui.label(trf("watch.removed", &[("row", identity)]));
+            }
+            for (identity, columns) in &comparison.changed {
+                ui.label(trf(
+                    "watch.changed",
+                    &[("row", identity), ("columns", &columns.join(", "))],
+                ));
+            }
+        });
+}
+
+// Время запуска по оси X — секунды Unix
+fn trend_plot(ui: &mut egui::Ui, trend: &[(DateTime<Local>, usize)]) {
+    let points: PlotPoints = trend
+        .iter()
+        .map(|(time, count)| [time.timestamp() as f64, *count as f64])
+        .collect();
+    Plot::new("history_trend")
+        .height(200.0)
+        .x_axis_formatter(|mark, _| {
+            DateTime::from_timestamp(mark.value as i64, 0)
+                .map(|time| time.with_timezone(&Local).format("%d.%m %H:%M").to_string())
+                .unwrap_or_default()
+        })
+        .show(ui, |plot| {
+            plot.line(Line::new(points).name(tr("history.rows")))
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> History {
+        History::new(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn services(states: &[(&str, &str)]) -> ExportFile {
+        let rows = states
+            .iter()
+            .map(|&(name, state)| {
+                Cells::from([
+                    ("Name".to_string(), name.into()),
+                    ("State".to_string(), state.into()),
+                ])
+            })
+            .collect();
+        let columns = ["Name", "State"]
+            .map(|name| Column {
+                name: name.to_string(),
+                title: name.to_string(),
+            })
+            .to_vec();
+        let category = CategoryExport {
+            category: "services".to_string(),
+            columns,
+            rows,
+        };
+        ExportFile::new("WS-01".to_string(), vec![category])
+    }
+
+    fn keys(_: &str) -> &'static [&'static str] {
+        &["Name"]
+    }
+
+    #[test]
+    fn migrations_run_once() {
+        let history = memory();
+        let History { db } = history;
+        let again = History::new(db).unwrap();
+        let version: usize = again
+            .db
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn runs_are_compared_by_identity() {
+        let mut history = memory();
+        let older = services(&[("Spooler", "Running"), ("Fax", "Stopped")]);
+        let newer = services(&[("Spooler", "Stopped"), ("BITS", "Running")]);
+        let a = history.record(&older, Kind::Manual, keys, 10).unwrap();
+        let b = history.record(&newer, Kind::Report, keys, 10).unwrap();
+        let comparison = history.compare(a, b, "services").unwrap();
+        assert_eq!(comparison.added, ["BITS"]);
+        assert_eq!(comparison.removed, ["Fax"]);
+        assert_eq!(comparison.changed.len(), 1);
+        assert_eq!(comparison.changed[0].0, "Spooler");
+
+        let restored = history.category(a, "services").unwrap().unwrap();
+        assert_eq!(restored, older.categories[0]);
+        assert_eq!(history.category(a, "processes").unwrap(), None);
+    }
+
+    #[test]
+    fn retention_keeps_last_runs() {
+        let mut history = memory();
+        for count in 1..=4 {
+            let states: Vec<_> = (0..count).map(|_| ("Spooler", "Running")).collect();
+            history
+                .record(&services(&states), Kind::Manual, keys, 3)
+                .unwrap();
+        }
+        let runs = history.runs().unwrap();
+        assert_eq!(runs.len(), 3);
+        let counts: Vec<usize> = history
+            .counts("services", None)
+            .unwrap()
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect();
+        // Повторяющийся ключ не теряет строки
+        assert_eq!(counts, [2, 3, 4]);
+        let running = history
+            .counts("services", Some(("State", "running")))
+            .unwrap();
+        assert_eq!(running.last().map(|(_, count)| *count), Some(4));
+    }
+}
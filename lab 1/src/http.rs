@@ -0,0 +1,147 @@
+use std::ffi::c_void;
+use std::time::Duration;
+use windows::core::{HSTRING, PCWSTR};
+use windows::Win32::Networking::WinHttp::{
+    WinHttpCloseHandle, WinHttpConnect, WinHttpCrackUrl, WinHttpOpen, WinHttpOpenRequest,
+    WinHttpQueryDataAvailable, WinHttpQueryHeaders, WinHttpReadData, WinHttpReceiveResponse,
+    WinHttpSendRequest, WinHttpSetTimeouts, URL_COMPONENTS, WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+    WINHTTP_FLAG_SECURE, WINHTTP_INTERNET_SCHEME_HTTPS, WINHTTP_OPEN_REQUEST_FLAGS,
+    WINHTTP_QUERY_FLAG_NUMBER, WINHTTP_QUERY_STATUS_CODE,
+};
+
+/// Ответ сервера: код и тело как текст
+#[derive(Debug)]
+pub struct Response {
+    pub status: u32,
+    pub body: String,
+}
+
+// Дескриптор WinHTTP, закрываемый при выходе из области видимости
+struct Handle(*mut c_void);
+
+impl Handle {
+    fn new(handle: *mut c_void) -> Result<Self, String> {
+        if handle.is_null() {
+            Err(windows::core::Error::from_win32().message())
+        } else {
+            Ok(Self(handle))
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        let _ = unsafe { WinHttpCloseHandle(self.0) };
+    }
+}
+
+// Часть адреса, найденная WinHttpCrackUrl, вместе с завершающим нулём
+fn part(start: windows::core::PWSTR, len: u32) -> Vec<u16> {
+    if start.is_null() {
+        return vec![0];
+    }
+    let slice = unsafe { std::slice::from_raw_parts(start.0, len as usize) };
+    slice.iter().copied().chain([0]).collect()
+}
+
+/// Запрос WinHTTP с системными настройками прокси. `headers` — строки
+/// «Имя: значение», каждая с `\r\n`. Ошибкой считается только сбой связи,
+/// код ответа разбирает вызывающий
+pub fn send(
+    method: &str,
+    url: &str,
+    headers: &str,
+    body: &[u8],
+    timeout: Duration,
+) -> Result<Response, String> {
+    let wide: Vec<u16> = url.encode_utf16().collect();
+    let mut components = URL_COMPONENTS {
+        dwStructSize: std::mem::size_of::<URL_COMPONENTS>() as u32,
+        dwHostNameLength: u32::MAX,
+        dwUrlPathLength: u32::MAX,
+        dwExtraInfoLength: u32::MAX,
+        ..Default::default()
+    };
+    unsafe { WinHttpCrackUrl(&wide, 0, &mut components) }.map_err(|e| e.message())?;
+    let host = part(components.lpszHostName, components.dwHostNameLength);
+    let mut path = part(components.lpszUrlPath, components.dwUrlPathLength);
+    path.pop();
+    path.extend(part(components.lpszExtraInfo, components.dwExtraInfoLength));
+    let flags = if components.nScheme == WINHTTP_INTERNET_SCHEME_HTTPS {
+        WINHTTP_FLAG_SECURE
+    } else {
+        WINHTTP_OPEN_REQUEST_FLAGS(0)
+    };
+    let timeout = timeout.as_millis() as i32;
+    unsafe {
+        let session = Handle::new(WinHttpOpen(
+            &HSTRING::from("WMI Lab"),
+            WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            0,
+        ))?;
+        WinHttpSetTimeouts(session.0, timeout, timeout, timeout, timeout)
+            .map_err(|e| e.message())?;
+        let connection = Handle::new(WinHttpConnect(
+            session.0,
+            PCWSTR(host.as_ptr()),
+            components.nPort,
+            0,
+        ))?;
+        let request = Handle::new(WinHttpOpenRequest(
+            connection.0,
+            &HSTRING::from(method),
+            PCWSTR(path.as_ptr()),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            std::ptr::null(),
+            flags,
+        ))?;
+        let headers: Vec<u16> = headers.encode_utf16().collect();
+        WinHttpSendRequest(
+            request.0,
+            (!headers.is_empty()).then_some(headers.as_slice()),
+            (!body.is_empty()).then_some(body.as_ptr() as *const c_void),
+            body.len() as u32,
+            body.len() as u32,
+            0,
+        )
+        .map_err(|e| e.message())?;
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut()).map_err(|e| e.message())?;
+        let mut status = 0u32;
+        let mut len = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some(&mut status as *mut u32 as *mut c_void),
+            &mut len,
+            std::ptr::null_mut(),
+        )
+        .map_err(|e| e.message())?;
+        let mut data = Vec::new();
+        loop {
+            let mut available = 0u32;
+            WinHttpQueryDataAvailable(request.0, &mut available).map_err(|e| e.message())?;
+            if available == 0 {
+                break;
+            }
+            let start = data.len();
+            data.resize(start + available as usize, 0);
+            let mut read = 0u32;
+            WinHttpReadData(
+                request.0,
+                data[start..].as_mut_ptr() as *mut c_void,
+                available,
+                &mut read,
+            )
+            .map_err(|e| e.message())?;
+            data.truncate(start + read as usize);
+        }
+        Ok(Response {
+            status,
+            body: String::from_utf8_lossy(&data).into_owned(),
+        })
+    }
+}
@@ -0,0 +1,223 @@
+use crate::i18n::{tr, trf};
+use crate::model::{ResultSet, Value};
+use egui::text::{LayoutJob, TextFormat};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Свойство процесса, по которому идёт поиск
+pub const COLUMN: &str = "CommandLine";
+
+/// Шаблон поиска без учёта регистра: подстрока или регулярное выражение
+pub fn pattern(text: &str, regex: bool) -> Result<Regex, regex::Error> {
+    let source = if regex {
+        text.to_string()
+    } else {
+        regex::escape(text)
+    };
+    RegexBuilder::new(&source).case_insensitive(true).build()
+}
+
+/// Строки таблицы процессов с совпадением в командной строке
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Matches {
+    pub rows: Vec<usize>,
+    pub hidden: usize, // Процессы, командная строка которых закрыта без прав администратора
+}
+
+impl Matches {
+    /// Сколько процессов не удалось просмотреть; `None`, если таких нет
+    pub fn hidden_note(&self) -> Option<String> {
+        (self.hidden > 0).then(|| trf("hunt.hidden", &[("count", &self.hidden)]))
+    }
+}
+
+/// Поиск среди строк `rows` в их порядке
+pub fn search(set: &ResultSet, rows: impl IntoIterator<Item = usize>, pattern: &Regex) -> Matches {
+    let mut matches = Matches::default();
+    for row in rows {
+        match set.value(row, COLUMN) {
+            Some(Value::Text(line)) if pattern.is_match(line) => matches.rows.push(row),
+            Some(Value::Text(_)) => {}
+            _ => matches.hidden += 1,
+        }
+    }
+    matches
+}
+
+/// Найденные процессы для вывода: PID, имя, родитель, владелец и командная строка.
+/// Владельцы — по PID; процессы без известного владельца получают «н/д»
+pub fn result_set(set: &ResultSet, matches: &Matches, owners: &HashMap<i64, String>) -> ResultSet {
+    let mut found = ResultSet::new(&[
+        "ProcessId",
+        "Name",
+        "ParentProcessId",
+        "ParentName",
+        "User",
+        COLUMN,
+    ]);
+    let pid = |row: usize, name: &str| match set.value(row, name) {
+        Some(Value::Int(pid)) => Some(*pid),
+        _ => None,
+    };
+    let names: HashMap<i64, &Value> = (0..set.rows.len())
+        .filter_map(|row| Some((pid(row, "ProcessId")?, set.value(row, "Name")?)))
+        .collect();
+    let cell = |row: usize, name: &str| set.value(row, name).cloned().unwrap_or(Value::Null);
+    found.rows = matches
+        .rows
+        .iter()
+        .map(|&row| {
+            let parent = pid(row, "ParentProcessId");
+            vec![
+                cell(row, "ProcessId"),
+                cell(row, "Name"),
+                cell(row, "ParentProcessId"),
+                // Родитель мог завершиться, а его PID — достаться другому процессу
+                parent
+                    .and_then(|parent| names.get(&parent))
+                    .map_or(Value::Null, |name| (*name).clone()),
+                pid(row, "ProcessId")
+                    .and_then(|pid| owners.get(&pid))
+                    .map_or(tr("value.na").into(), |owner| owner.as_str().into()),
+                cell(row, COLUMN),
+            ]
+        })
+        .collect();
+    found
+}
+
+/// Текст с выделенными совпадениями для надписи; перенос задаёт надпись
+pub fn highlighted(ui: &egui::Ui, text: &str, pattern: &Regex) -> LayoutJob {
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    let plain = TextFormat {
+        font_id: font.clone(),
+        color: ui.visuals().text_color(),
+        ..TextFormat::default()
+    };
+    let marked = TextFormat {
+        background: ui.visuals().selection.bg_fill,
+        color: ui.visuals().strong_text_color(),
+        ..plain.clone()
+    };
+    let mut job = LayoutJob::default();
+    let mut start = 0;
+    for found in pattern.find_iter(text).filter(|found| !found.is_empty()) {
+        job.append(&text[start..found.start()], 0.0, plain.clone());
+        job.append(found.as_str(), 0.0, marked.clone());
+        start = found.end();
+    }
+    job.append(&text[start..], 0.0, plain);
+    job
+}
+
+/// Строка быстрого поиска над таблицей процессов. Сохраняется только режим
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hunt {
+    pub use_regex: bool,
+    #[serde(skip)]
+    pub text: String,
+    #[serde(skip)]
+    pattern: Option<Regex>,
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl Hunt {
+    /// Шаблон, если введено корректное условие
+    pub fn pattern(&self) -> Option<&Regex> {
+        self.pattern.as_ref()
+    }
+
+    fn update(&mut self) {
+        self.pattern = None;
+        self.error = None;
+        if self.text.is_empty() {
+            return;
+        }
+        match pattern(&self.text, self.use_regex) {
+            Ok(pattern) => self.pattern = Some(pattern),
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Поле ввода и число найденного среди строк `rows`, которые оно сужает
+    pub fn show(&mut self, ui: &mut egui::Ui, set: &ResultSet, rows: &mut Vec<usize>) {
+        ui.horizontal(|ui| {
+            ui.label(tr("hunt.label"));
+            let edit = egui::TextEdit::singleline(&mut self.text).hint_text(tr("hunt.hint"));
+            let mut changed = ui.add(edit).changed();
+            changed |= ui
+                .checkbox(&mut self.use_regex, tr("filter.regex"))
+                .changed();
+            if changed {
+                self.update();
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            let Some(pattern) = &self.pattern else {
+                return;
+            };
+            let matches = search(set, rows.iter().copied(), pattern);
+            ui.label(trf("hunt.found", &[("count", &matches.rows.len())]));
+            if let Some(note) = matches.hidden_note() {
+                ui.weak(note).on_hover_text(tr("hunt.hidden_hint"));
+            }
+            *rows = matches.rows;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn processes() -> ResultSet {
+        let mut set = ResultSet::new(&["ProcessId", "Name", "ParentProcessId", COLUMN]);
+        for (pid, name, parent, line) in [
+            (4, "System", 0, Value::Null),
+            (900, "explorer.exe", 4, "C:\\Windows\\explorer.exe".into()),
+            (
+                1200,
+                "powershell.exe",
+                900,
+                "powershell.exe -NoP -Enc SQBFAFgA".into(),
+            ),
+            (1300, "m.exe", 77, "m.exe privilege::debug".into()),
+        ] {
+            set.rows
+                .push(vec![Value::Int(pid), name.into(), Value::Int(parent), line]);
+        }
+        set
+    }
+
+    #[test]
+    fn keyword_ignores_case_and_counts_hidden() {
+        let set = processes();
+        let matches = search(&set, 0..set.rows.len(), &pattern("-enc", false).unwrap());
+        assert_eq!(matches.rows, [2]);
+        assert_eq!(matches.hidden, 1);
+        // Без режима регулярного выражения точка — обычный символ
+        let matches = search(&set, 0..set.rows.len(), &pattern("m.exe", false).unwrap());
+        assert_eq!(matches.rows, [3]);
+        let matches = search(&set, 0..set.rows.len(), &pattern(r"::\w+", true).unwrap());
+        assert_eq!(matches.rows, [3]);
+        assert!(pattern("(", true).is_err());
+    }
+
+    #[test]
+    fn found_processes_name_their_parent_and_owner() {
+        let set = processes();
+        let matches = search(&set, 0..set.rows.len(), &pattern("exe", false).unwrap());
+        let owners = HashMap::from([(1200, "CORP\\user".to_string())]);
+        let found = result_set(&set, &matches, &owners);
+        assert_eq!(found.rows.len(), 3);
+        assert_eq!(found.value(1, "ParentName"), Some(&"explorer.exe".into()));
+        assert_eq!(found.value(1, "User"), Some(&"CORP\\user".into()));
+        // Родитель уже завершился
+        assert_eq!(found.value(2, "ParentName"), Some(&Value::Null));
+        assert_eq!(found.value(2, "User"), Some(&tr("value.na").into()));
+    }
+}
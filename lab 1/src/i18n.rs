@@ -0,0 +1,1663 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// Язык интерфейса
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Ru,
+    En,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Ru, Language::En];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Language::Ru => "Русский",
+            Language::En => "English",
+        }
+    }
+
+    fn other(self) -> Language {
+        match self {
+            Language::Ru => Language::En,
+            Language::En => Language::Ru,
+        }
+    }
+
+    fn table(self) -> &'static HashMap<&'static str, &'static str> {
+        static RU_MAP: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        static EN_MAP: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        match self {
+            Language::Ru => RU_MAP.get_or_init(|| RU.iter().copied().collect()),
+            Language::En => EN_MAP.get_or_init(|| EN.iter().copied().collect()),
+        }
+    }
+}
+
+// Язык общий для всех потоков: отчёт формируется вне потока интерфейса
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(language: Language) {
+    CURRENT.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Language::En,
+        _ => Language::Ru,
+    }
+}
+
+fn lookup(key: &str) -> Option<&'static str> {
+    let current = language();
+    if let Some(text) = current.table().get(key) {
+        return Some(text);
+    }
+    let fallback = current.other().table().get(key).copied();
+    tracing::debug!("нет перевода {key:?} для {current:?}");
+    fallback
+}
+
+/// Строка интерфейса на текущем языке; при отсутствии перевода — на другом языке или сам ключ
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(key).unwrap_or(key)
+}
+
+/// Строка с подстановкой именованных параметров `{name}`
+pub fn trf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = tr(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+/// Заголовок столбца по имени свойства WMI; без перевода остаётся само имя
+pub fn column(name: &str) -> String {
+    lookup(&format!("column.{name}"))
+        .map(str::to_string)
+        .unwrap_or_else(|| name.to_string())
+}
+
+const RU: &[(&str, &str)] = &[
+    ("app.heading", "Лабораторная работа — WMI"),
+    ("app.language", "Язык"),
+    ("app.window_title", "WMI Lab — {host}"),
+    ("remote.title", "Подключение к компьютеру"),
+    ("remote.host", "Компьютер:"),
+    ("remote.recent", "Из списка"),
+    ("remote.user", "Пользователь:"),
+    ("remote.password", "Пароль:"),
+    ("remote.hint", "Без имени пользователя используются текущие учётные данные"),
+    ("remote.connect", "Подключиться"),
+    ("remote.local", "Локальный компьютер"),
+    ("remote.cancel", "Отмена"),
+    ("profiles.title", "Профили подключения"),
+    ("profiles.none", "Без профиля"),
+    ("profiles.manage", "Профили…"),
+    ("profiles.add", "Добавить"),
+    ("profiles.delete", "Удалить"),
+    ("profiles.save", "Сохранить"),
+    ("profiles.unnamed", "Без названия"),
+    ("profiles.empty", "Профилей нет. Добавьте первый"),
+    ("profiles.name", "Название:"),
+    ("profiles.domain", "Домен:"),
+    ("profiles.namespace", "Пространство имён:"),
+    ("profiles.save_password", "Хранить пароль в диспетчере учётных данных Windows"),
+    (
+        "profiles.save_password_hint",
+        "Без этого пароль запрашивается при каждом подключении. В файл настроек он не пишется",
+    ),
+    ("profiles.store", "Запомнить пароль"),
+    ("profiles.stored", "Пароль сохранён"),
+    ("profiles.password_for", "Пароль для профиля «{name}»"),
+    (
+        "remote.rpc_unavailable",
+        "Сервер RPC недоступен: проверьте имя {host}, сеть и правило брандмауэра «Инструментарий управления Windows (WMI)»",
+    ),
+    (
+        "remote.access_denied",
+        "Доступ запрещён: учётной записи нужны права администратора на удалённом компьютере и доступ к DCOM/WMI",
+    ),
+    ("remote.logon_failure", "Неверное имя пользователя или пароль"),
+    ("remote.invalid_namespace", "Такого пространства имён нет на удалённом компьютере"),
+    ("theme.label", "Тема оформления"),
+    ("theme.system", "Как в системе"),
+    ("theme.light", "Светлая"),
+    ("theme.dark", "Тёмная"),
+    ("settings.label", "Настройки"),
+    ("perf.toggle", "График загрузки процессора и памяти"),
+    ("perf.cpu", "ЦП: {percent} %"),
+    ("perf.memory", "Выделено памяти: {used} из {total} ГБ ({percent} %)"),
+    ("perf.waiting", "Ожидание первого замера…"),
+    ("perf.cpu_line", "ЦП"),
+    ("perf.memory_line", "Память"),
+    ("shortcuts.title", "Сочетания клавиш"),
+    ("shortcuts.refresh", "Повторить запрос категории"),
+    ("shortcuts.focus_filter", "Перейти к фильтру строк"),
+    ("shortcuts.clear_filter", "Очистить фильтр строк"),
+    ("shortcuts.export", "Экспорт категории в CSV"),
+    ("shortcuts.copy_row", "Копировать выбранную строку"),
+    ("shortcuts.category", "Выбрать категорию по порядку на панели"),
+    (
+        "shortcuts.typing_hint",
+        "Пока курсор в поле ввода, работают только Esc в фильтре и обычное копирование текста",
+    ),
+    ("config.reload", "Перечитать config.toml"),
+    ("config.reloaded", "Настройки перечитаны: {path}"),
+    ("config.read_failed", "Не удалось прочитать {path}: {error}"),
+    ("config.parse_failed", "Ошибка в {path}, строка {line}, столбец {column}: {error}"),
+    ("config.write_failed", "Не удалось записать {path}: {error}"),
+    ("zoom.label", "Масштаб:"),
+    ("zoom.reset", "Сбросить масштаб (Ctrl+0)"),
+    ("nav.toggle", "Список категорий"),
+    ("tab.categories", "Категории"),
+    ("tab.console", "Консоль WQL"),
+    ("tab.classes", "Классы WMI"),
+    ("tab.history", "История"),
+    ("syslog.enabled", "Пересылать в syslog"),
+    (
+        "syslog.hint",
+        "Находки отчётов и события наблюдения отправляются на сервер syslog в формате RFC 5424",
+    ),
+    ("syslog.server", "Сервер:"),
+    ("syslog.test", "Тестовое сообщение"),
+    ("syslog.test_text", "Проверка связи WMI Lab"),
+    ("syslog.test_sent", "Тестовое сообщение отправлено"),
+    ("syslog.disabled", "Пересылка выключена"),
+    ("webhook.enabled", "Отправлять важные находки на веб-хук"),
+    ("webhook.hint", "POST в формате JSON на адрес Slack, Teams или свой обработчик; ошибки доставки пишутся в журнал"),
+    ("webhook.url", "Адрес"),
+    ("webhook.muted", "Не отправлять находки проверок:"),
+    ("webhook.muted_triggers", "Не следить за изменениями:"),
+    ("tripwire.category", "Наблюдатели"),
+    ("tripwire.admin_member", "Новый участник группы «Администраторы»"),
+    ("tripwire.event_consumer", "Новый получатель событий WMI"),
+    ("webhook.test", "Проверить"),
+    ("webhook.test_text", "Проверка веб-хука WMI Lab"),
+    ("webhook.test_sent", "Доставлено, ответ {status}"),
+    ("webhook.failed", "Не доставлено: {error}"),
+    ("webhook.status", "сервер ответил кодом {status}"),
+    ("eventlog.enabled", "Записывать находки в журнал событий Windows"),
+    ("eventlog.hint", "Находки отчётов пишутся в журнал Application; одинаковая находка записывается один раз за сеанс"),
+    ("eventlog.host", "Компьютер"),
+    ("eventlog.identity", "Ключ"),
+    ("eventlog.registered", "Источник {source} зарегистрирован"),
+    ("eventlog.fallback", "Источник {source} не зарегистрирован: события пишутся от имени источника Application"),
+    ("eventlog.register", "Зарегистрировать источник"),
+    ("eventlog.register_hint", "Однократно создаёт источник событий в реестре; нужны права администратора"),
+    ("eventlog.register_done", "Источник {source} зарегистрирован"),
+    ("eventlog.register_failed", "Источник не зарегистрирован: {error}"),
+    ("eventlog.need_admin", "нужны права администратора"),
+    ("eventlog.test", "Тестовое событие"),
+    ("eventlog.test_text", "Тестовое событие WMI Lab"),
+    ("eventlog.test_done", "Тестовое событие записано в журнал Application"),
+    ("eventlog.test_failed", "Событие не записано: {error}"),
+    ("virustotal.key", "Ключ API VirusTotal"),
+    ("virustotal.hint", "Хеши файлов из таблиц проверяются по базе VirusTotal, не больше 4 запросов в минуту. Отправляются только хеши, не файлы. Ключ хранится в диспетчере учётных данных Windows"),
+    ("virustotal.disabled", "Без ключа проверка хешей выключена"),
+    ("virustotal.queue", "Очередь VirusTotal: {count}"),
+    ("virustotal.hashing", "Подсчёт хешей: осталось {count}"),
+    ("virustotal.retry", "Повторить неудачные проверки ({count})"),
+    ("virustotal.queued", "VT: в очереди"),
+    ("virustotal.unknown", "VT: не найден"),
+    ("virustotal.failed", "VT: ошибка"),
+    ("virustotal.network", "VirusTotal недоступен: {error}"),
+    ("virustotal.key_rejected", "VirusTotal не принял ключ API"),
+    ("virustotal.quota", "Квота VirusTotal исчерпана"),
+    ("virustotal.status", "VirusTotal ответил кодом {status}"),
+    ("virustotal.bad_reply", "Непонятный ответ VirusTotal"),
+    ("streams.local_only", "Каталоги просматриваются на этом компьютере, а не на подключённом"),
+    ("streams.remove_dir", "Убрать каталог из поиска"),
+    ("streams.add_dir", "Добавить каталог…"),
+    ("streams.max_depth", "Глубина:"),
+    ("streams.max_files", "Не больше файлов:"),
+    ("streams.start", "Искать потоки"),
+    ("streams.cancel", "Остановить"),
+    ("streams.progress", "Проверено: {count}"),
+    ("streams.summary", "Нестандартных потоков: {found}; проверено файлов и каталогов: {scanned}; загружены из интернета (Zone.Identifier): {marked}"),
+    ("streams.limited", "Поиск остановлен пределом глубины или числа файлов: просмотрены не все файлы"),
+    ("streams.cancelled", "Поиск остановлен: просмотрены не все файлы"),
+    ("streams.errors", "Не удалось прочитать: {count}"),
+    ("autoexport.enabled", "Автоэкспорт при выходе"),
+    ("autoexport.hint", "При закрытии программы полученные данные всех категорий сохраняются в JSON без новых запросов; хранятся 10 последних файлов"),
+    ("autoexport.dir", "Каталог:"),
+    ("autoexport.open_latest", "Открыть последний автоэкспорт"),
+    ("autoexport.none", "Автоэкспортов ещё нет"),
+    ("extensions.profile_denied", "Профиль не читается: нужны права администратора"),
+    ("extensions.bad_file", "Файл расширений повреждён или в неизвестном формате"),
+    ("defender.kind.path", "Папка или файл"),
+    ("defender.kind.extension", "Расширение"),
+    ("defender.kind.process", "Процесс"),
+    ("defender.hidden", "Значения скрыты: нужны права администратора, а если они уже есть — исключения скрывает политика HideExclusionsFromLocalAdmins или защита от изменений"),
+    ("defender.none", "Исключений нет"),
+    ("defender.no_namespace", "Пространство имён Microsoft Defender недоступно: Defender удалён или заменён другим антивирусом"),
+    ("defender.broad.drive", "Исключён весь диск"),
+    ("defender.broad.users", "Исключены профили всех пользователей"),
+    ("defender.broad.temp", "Исключён временный каталог, куда пишет любая программа"),
+    ("defender.broad.executable", "Исключены все файлы этого исполняемого типа"),
+    ("defender.broad.script_host", "Исключена программа запуска сценариев: всё, что она открывает, не проверяется"),
+    ("defender.broad.wildcard", "Шаблон процесса подходит к любым программам"),
+    ("netstatus.disconnected", "Отключён"),
+    ("netstatus.connecting", "Подключается"),
+    ("netstatus.connected", "Подключён"),
+    ("netstatus.disconnecting", "Отключается"),
+    ("netstatus.not_present", "Оборудование отсутствует"),
+    ("netstatus.disabled", "Оборудование отключено"),
+    ("netstatus.malfunction", "Неисправность оборудования"),
+    ("netstatus.media_disconnected", "Кабель не подключён"),
+    ("netstatus.authenticating", "Проверка подлинности"),
+    ("netstatus.authenticated", "Подлинность подтверждена"),
+    ("netstatus.auth_failed", "Ошибка проверки подлинности"),
+    ("netstatus.invalid_address", "Недопустимый адрес"),
+    ("netstatus.credentials_required", "Нужны учётные данные"),
+    ("devproblem.1", "устройство настроено неправильно"),
+    ("devproblem.3", "драйвер повреждён или системе не хватает памяти"),
+    ("devproblem.10", "запуск этого устройства невозможен"),
+    ("devproblem.12", "не хватает свободных ресурсов"),
+    ("devproblem.14", "нужна перезагрузка компьютера"),
+    ("devproblem.16", "не все используемые ресурсы определены"),
+    ("devproblem.18", "драйверы нужно переустановить"),
+    ("devproblem.19", "сведения о настройке в реестре неполны или повреждены"),
+    ("devproblem.21", "устройство удаляется"),
+    ("devproblem.22", "устройство отключено"),
+    ("devproblem.24", "устройство отсутствует, работает неправильно или не все драйверы установлены"),
+    ("devproblem.28", "драйверы не установлены"),
+    ("devproblem.29", "устройство отключено микропрограммой"),
+    ("devproblem.31", "Windows не может загрузить драйверы"),
+    ("devproblem.32", "служба драйвера отключена"),
+    ("devproblem.33", "не удалось определить нужные ресурсы"),
+    ("devproblem.34", "устройство нужно настроить вручную"),
+    ("devproblem.35", "в микропрограмме нет сведений для настройки устройства"),
+    ("devproblem.37", "драйвер сообщил об ошибке"),
+    ("devproblem.38", "предыдущий экземпляр драйвера ещё в памяти"),
+    ("devproblem.39", "драйвер повреждён или отсутствует"),
+    ("devproblem.40", "сведения о службе драйвера в реестре отсутствуют или неверны"),
+    ("devproblem.41", "драйвер загружен, но оборудование не найдено"),
+    ("devproblem.43", "устройство остановлено, так как сообщило о неполадках"),
+    ("devproblem.45", "устройство не подключено"),
+    ("devproblem.48", "драйвер заблокирован из-за известных проблем"),
+    ("devproblem.52", "не удалось проверить цифровую подпись драйвера"),
+    ("devproblem.other", "{code}: ошибка диспетчера устройств"),
+    ("devices.no_problems", "Проблемных устройств нет"),
+    ("cooling.no_instances", "Класс не вернул ни одного экземпляра"),
+    ("cooling.no_reading", "Датчик не сообщает показание"),
+    ("cooling.implausible", "Вне правдоподобного диапазона {min}…{max} °C"),
+    ("availability.other", "Другое"),
+    ("availability.unknown", "Неизвестно"),
+    ("availability.running", "Работает"),
+    ("availability.warning", "Предупреждение"),
+    ("availability.in_test", "Тестирование"),
+    ("availability.not_applicable", "Неприменимо"),
+    ("availability.power_off", "Питание выключено"),
+    ("availability.off_line", "Не в сети"),
+    ("availability.degraded", "Работает с ухудшением"),
+    ("availability.not_installed", "Не установлено"),
+    ("availability.install_error", "Ошибка установки"),
+    ("availability.power_save", "Энергосбережение"),
+    ("availability.code", "Состояние {code}"),
+    ("port.parallel", "Параллельный порт"),
+    ("port.serial", "Последовательный порт"),
+    ("port.joystick", "Порт джойстика"),
+    ("port.keyboard", "Порт клавиатуры"),
+    ("port.mouse", "Порт мыши"),
+    ("port.video", "Видеопорт"),
+    ("port.audio", "Аудиопорт"),
+    ("port.modem", "Порт модема"),
+    ("port.network", "Сетевой порт"),
+    ("port.other", "Другой"),
+    ("port.unknown", "Неизвестный разъём"),
+    ("port.code", "код {code}"),
+    ("port.male", "вилка"),
+    ("port.female", "розетка"),
+    ("port.usb_count", "USB-портов: {count}"),
+    ("port.total", "Всего разъёмов: {count}"),
+    ("port.no_data", "Микропрограмма не заполняет сведения о разъёмах: Win32_PortConnector пуст"),
+    ("quota.unsupported", "квоты не поддерживаются"),
+    ("quota.disabled", "отключены"),
+    ("quota.tracked", "только учёт"),
+    ("quota.enforced", "включены, запись сверх предела запрещена"),
+    ("quota.state", "состояние {code}"),
+    ("uptime.role", "Тип системы"),
+    ("uptime.workstation", "Рабочая станция"),
+    ("uptime.domain_controller", "Контроллер домена"),
+    ("uptime.server", "Сервер"),
+    ("uptime.role_code", "Тип {code}"),
+    ("uptime.last_boot", "Последняя загрузка"),
+    ("uptime.uptime", "Время работы"),
+    ("uptime.duration", "{days} д {hours} ч"),
+    ("uptime.pending", "Ожидается перезагрузка"),
+    ("uptime.pending_yes", "да: {reasons}"),
+    ("uptime.registry_error", "Не удалось прочитать {key} из реестра (код {code})"),
+    ("uptime.hotfix", "Последнее обновление"),
+    ("uptime.shutdown", "Неожиданное завершение"),
+    ("uptime.no_shutdowns", "нет за {days} дн."),
+    ("asset.hostname", "Имя компьютера"),
+    ("asset.uuid", "UUID"),
+    ("asset.vendor", "Производитель"),
+    ("asset.model", "Модель"),
+    ("asset.identifying_number", "Серийный номер"),
+    ("asset.bios_serial", "Серийный номер BIOS"),
+    ("asset.asset_tag", "Инвентарная метка"),
+    ("asset.mac", "Основной MAC-адрес"),
+    ("asset.os_product_id", "Код продукта ОС"),
+    ("power.balanced", "Сбалансированная"),
+    ("power.high_performance", "Высокая производительность"),
+    ("power.saver", "Экономия энергии"),
+    ("power.ultimate", "Максимальная производительность"),
+    ("power.from_wmi", "WMI"),
+    ("power.from_registry", "Реестр"),
+    ("power.no_namespace", "Пространство имён root\\cimv2\\power недоступно (бывает на серверных редакциях); активная схема прочитана из реестра"),
+    ("asset.copy_json", "Копировать как JSON"),
+    ("asset.copy_json_hint", "Сводка для вставки в базу учёта: ключи на английском, заглушки перечислены в unreliable"),
+    ("quota.ok", "в пределах"),
+    ("quota.warning", "превышен порог предупреждения"),
+    ("quota.exceeded", "превышен предел"),
+    ("quota.no_limit", "без ограничения"),
+    ("quota.more", "…и ещё {count}"),
+    ("virt.verdict", "Вывод"),
+    ("virt.physical", "Физическая машина"),
+    ("virt.vm", "ВМ: {hypervisor}"),
+    ("virt.model", "Производитель и модель"),
+    ("virt.serial", "Серийный номер BIOS"),
+    ("virt.device", "Виртуальное устройство"),
+    ("virt.no_devices", "не найдены"),
+    ("virt.hypervisor_present", "Гипервизор запущен (HypervisorPresent)"),
+    ("virt.hyperv_feature", "Компонент Hyper-V"),
+    ("virt.feature.enabled", "включён"),
+    ("virt.feature.disabled", "отключён"),
+    ("virt.feature.absent", "не установлен"),
+    ("virt.vmms", "Служба управления Hyper-V (vmms)"),
+    ("virt.no_service", "не установлена"),
+    ("virt.hyperv_vm", "Запущенная ВМ Hyper-V"),
+    ("syslog.no_address", "адрес сервера не найден"),
+    ("syslog.failed", "Не удалось отправить в syslog: {error}"),
+    ("syslog.buffered", "Ждут отправки: {count}, потеряно: {dropped}"),
+    ("history.enabled", "Вести историю снимков"),
+    (
+        "history.hint",
+        "Каждый отчёт и кнопка «Сохранить снимок» записывают данные в history.sqlite3",
+    ),
+    ("history.keep_runs", "Хранить запусков:"),
+    ("history.kind_report", "отчёт"),
+    ("history.kind_manual", "вручную"),
+    ("schedule.enabled", "Отчёт по расписанию"),
+    ("schedule.hint", "Пока программа открыта, отчёт по выбранному профилю формируется сам, сохраняется в каталог и записывается в историю"),
+    ("schedule.daily", "Каждый день в"),
+    ("schedule.every", "Каждые"),
+    ("schedule.hours", "ч"),
+    ("schedule.bad_time", "ЧЧ:ММ"),
+    ("schedule.dir", "Каталог:"),
+    ("schedule.next", "⏰ Следующий отчёт: {time}"),
+    ("schedule.running", "Отчёт по расписанию…"),
+    ("schedule.saved", "Отчёт по расписанию сохранён: {path}"),
+    ("schedule.no_profile", "Профиль отчёта «{name}» для расписания не найден"),
+    ("history.kind_scheduled", "по расписанию"),
+    ("history.failed", "Ошибка базы истории: {error}"),
+    ("history.open_failed", "Не удалось открыть базу истории {path}: {error}"),
+    ("history.saved", "Снимок #{run} записан, категорий: {count}"),
+    ("history.nothing", "Нет свежих данных категорий отчёта"),
+    ("history.save", "Сохранить снимок"),
+    (
+        "history.save_hint",
+        "Записать свежие данные категорий, выбранных для отчёта",
+    ),
+    ("history.reload", "Обновить список"),
+    ("history.disabled", "История выключена. Включите её в меню ⚙"),
+    ("history.empty", "Запусков пока нет"),
+    ("history.run", "Запуск"),
+    ("history.host", "Компьютер"),
+    ("history.kind", "Источник"),
+    ("history.categories", "Категории"),
+    ("history.category", "Категория:"),
+    ("history.older", "Ранний:"),
+    ("history.newer", "Поздний:"),
+    ("history.compare", "Сравнить"),
+    ("history.no_changes", "Различий нет"),
+    ("history.trend", "Число строк, где"),
+    ("history.column_hint", "столбец"),
+    ("history.value_hint", "значение"),
+    ("history.plot", "Построить"),
+    ("history.rows", "Строк"),
+    ("history.open", "Открыть"),
+    ("history.open_hint", "Показать данные запуска в таблицах категорий"),
+    ("namespace.label", "Пространство имён:"),
+    ("namespace.custom", "другое, например root\\Microsoft"),
+    ("namespace.open", "Открыть"),
+    ("namespace.retry", "Повторить подключение"),
+    ("browser.search", "Поиск класса"),
+    ("browser.reload", "Обновить список классов"),
+    ("browser.count", "Классов: {count}"),
+    ("browser.instances", "{class}: экземпляров {count}"),
+    (
+        "browser.empty",
+        "Выберите класс слева, чтобы вывести его экземпляры",
+    ),
+    ("console.run", "Выполнить (Ctrl+Enter)"),
+    ("console.history", "История"),
+    ("console.rows", "Строк: {count}"),
+    ("section.system", "Система"),
+    ("section.hardware", "Оборудование"),
+    ("section.accounts", "Учётные записи"),
+    ("section.network", "Сеть"),
+    ("section.security", "Безопасность"),
+    ("section.monitoring", "Мониторинг"),
+    ("category.env_vars", "Переменные окружения"),
+    ("category.sid_counts", "Статистика SID"),
+    ("category.bus_info", "Информация о шинах"),
+    ("category.problem_devices", "Проблемные устройства"),
+    ("category.cooling", "Охлаждение"),
+    ("category.port_connectors", "Разъёмы корпуса"),
+    ("category.volumes", "Тома"),
+    ("category.disk_quotas", "Квоты дисков"),
+    ("category.processes", "Процессы"),
+    ("category.services", "Службы"),
+    ("category.software", "Установленные программы"),
+    ("category.network_adapters", "Сетевые адаптеры"),
+    ("category.virtualization", "Виртуализация"),
+    ("category.uptime", "Время работы"),
+    ("category.asset", "Учёт оборудования"),
+    ("category.power_plans", "Схемы электропитания"),
+    ("category.prefetch", "Предвыборка (запуски)"),
+    ("category.recycle_bin", "Корзина"),
+    ("category.streams", "Дополнительные потоки NTFS"),
+    ("category.browser_extensions", "Расширения браузеров"),
+    ("category.defender_exclusions", "Исключения Defender"),
+    ("category.log", "Журнал"),
+    ("column.Time", "Время"),
+    ("column.Level", "Уровень"),
+    ("column.Target", "Источник"),
+    ("column.Message", "Сообщение"),
+    ("log.error", "ошибки"),
+    ("log.warn", "предупреждения"),
+    ("log.info", "информация"),
+    ("log.debug", "отладка"),
+    ("log.level_label", "Уровень журнала:"),
+    ("log.filter_label", "Показывать:"),
+    ("log.dir_label", "Каталог журнала:"),
+    ("log.dir_default", "рядом с программой"),
+    ("log.dir_restart", "Каталог применяется после перезапуска"),
+    ("column.Name", "Имя"),
+    ("column.UserName", "Пользователь"),
+    ("column.VariableValue", "Значение"),
+    ("column.SIDType", "Тип SID"),
+    ("column.Count", "Количество"),
+    ("column.DeviceID", "ID"),
+    ("column.Status", "Статус"),
+    ("column.PNPClass", "Класс"),
+    ("column.Manufacturer", "Производитель"),
+    ("column.ConfigManagerErrorCode", "Код ошибки"),
+    ("column.SystemVariable", "Системная"),
+    ("column.Domain", "Домен"),
+    ("column.LocalAccount", "Локальная"),
+    ("column.ProcessId", "PID"),
+    ("column.ExecutablePath", "Исполняемый файл"),
+    ("column.SHA256", "SHA-256 файла"),
+    ("column.CommandLine", "Командная строка"),
+    ("column.ParentProcessId", "PID родителя"),
+    ("column.ParentName", "Родитель"),
+    ("column.ThreadCount", "Потоков"),
+    ("column.DisplayName", "Отображаемое имя"),
+    ("column.State", "Состояние"),
+    ("column.StartMode", "Тип запуска"),
+    ("column.PathName", "Исполняемый файл"),
+    ("column.StartName", "Учётная запись"),
+    ("column.Severity", "Важность"),
+    ("column.Category", "Категория"),
+    ("column.Title", "Находка"),
+    ("column.Detail", "Подробности"),
+    ("column.Description", "Описание"),
+    ("column.Service", "Драйвер"),
+    ("column.HardwareID", "ИД оборудования"),
+    ("column.Present", "Подключено"),
+    ("column.SessionId", "Сеанс"),
+    ("column.Priority", "Приоритет"),
+    ("column.HandleCount", "Дескрипторов"),
+    ("column.ServiceType", "Тип службы"),
+    ("column.AcceptStop", "Можно остановить"),
+    ("column.DelayedAutoStart", "Отложенный запуск"),
+    ("column.Version", "Версия"),
+    ("column.Vendor", "Издатель"),
+    ("column.Language", "Язык"),
+    ("column.ProgramId", "ИД программы"),
+    ("column.MsiProductCode", "Код продукта MSI"),
+    ("column.Executable", "Программа"),
+    ("column.FileName", "Файл"),
+    ("column.PathHash", "Хеш пути"),
+    ("column.LastModified", "Последний запуск"),
+    ("column.CreationDate", "Первый запуск"),
+    ("column.FileSize", "Размер"),
+    ("column.Copies", "Файлов программы"),
+    ("column.RanAgain", "Запускалась повторно"),
+    ("column.LastAccessed", "Последнее чтение"),
+    ("column.Owner", "Владелец"),
+    ("column.OriginalPath", "Исходный путь"),
+    ("column.DeletedAt", "Удалён"),
+    ("column.Size", "Размер, байт"),
+    ("column.Payload", "Содержимое на месте"),
+    ("column.SID", "SID"),
+    ("column.TotalItems", "Всего в корзинах"),
+    ("column.FilePath", "Файл"),
+    ("column.Stream", "Поток"),
+    ("column.StreamSize", "Размер потока, байт"),
+    ("column.User", "Пользователь"),
+    ("column.Browser", "Браузер"),
+    ("column.Profile", "Профиль браузера"),
+    ("column.Permissions", "Разрешения"),
+    ("column.Enabled", "Включено"),
+    ("column.Error", "Ошибка"),
+    ("column.ExtensionId", "Идентификатор расширения"),
+    ("column.Path", "Путь"),
+    ("column.ExclusionType", "Вид исключения"),
+    ("column.Exclusion", "Исключение"),
+    ("column.Broad", "Слишком широкое"),
+    ("column.Note", "Примечание"),
+    ("column.Source", "Источник"),
+    ("column.Reading", "Показание"),
+    ("column.ConnectorKind", "Вид разъёма"),
+    ("column.Connectors", "Разъёмы"),
+    ("column.Designators", "Обозначения"),
+    ("column.MountPath", "Путь подключения"),
+    ("column.Label", "Метка"),
+    ("column.FileSystem", "Файловая система"),
+    ("column.DirtyBitSet", "Бит загрязнения"),
+    ("column.Compressed", "Сжат"),
+    ("column.BlockSize", "Размер кластера"),
+    ("column.BootVolume", "Загрузочный"),
+    ("column.SystemVolume", "Системный"),
+    ("column.Volume", "Том"),
+    ("column.QuotaState", "Квоты"),
+    ("column.DiskSpaceUsed", "Занято, байт"),
+    ("column.Limit", "Предел"),
+    ("column.WarningLimit", "Порог предупреждения"),
+    ("column.QuotaStatus", "Состояние квоты"),
+    ("column.MACAddress", "MAC-адрес"),
+    ("column.AdapterType", "Тип адаптера"),
+    ("column.PNPDeviceID", "Идентификатор PnP"),
+    ("column.ConnectionState", "Подключение"),
+    ("column.Virtual", "Виртуальный"),
+    ("column.IPAddress", "Адреса IP"),
+    ("column.DefaultIPGateway", "Шлюз"),
+    ("column.CaptureDriver", "Драйвер перехвата"),
+    ("column.Problem", "Неполадка"),
+    ("column.Indicator", "Признак"),
+    ("column.Observed", "Значение"),
+    ("column.Days", "Дней"),
+    ("column.Code", "Код"),
+    ("column.Placeholder", "Заглушка"),
+    ("column.Key", "Ключ"),
+    ("column.PowerPlan", "Схема"),
+    ("column.Active", "Активна"),
+    ("column.SleepAC", "Сон от сети, с (0 — никогда)"),
+    ("column.SleepDC", "Сон от батареи, с (0 — никогда)"),
+    ("column.WakePasswordAC", "Пароль при пробуждении от сети"),
+    ("column.WakePasswordDC", "Пароль при пробуждении от батареи"),
+    ("column.PlanGuid", "GUID схемы"),
+    ("column.SettingsSource", "Источник параметров"),
+    ("column.Battery", "Батарея"),
+    ("column.Hypervisor", "Гипервизор"),
+    ("column.NetConnectionID", "Подключение Windows"),
+    ("column.NetConnectionStatus", "Код состояния"),
+    ("column.ServiceName", "Драйвер"),
+    ("column.DHCPEnabled", "DHCP"),
+    ("column.DNSServerSearchOrder", "Серверы DNS"),
+    ("column.NetEnabled", "Включён"),
+    ("where.title", "Условия запроса (WHERE)"),
+    ("where.apply", "Применить"),
+    ("where.reset", "Сбросить"),
+    ("where.clear", "Убрать условие"),
+    ("where.any", "любое"),
+    ("value.yes", "да"),
+    ("value.no", "нет"),
+    ("value.na", "н/д"),
+    ("value.empty", "(пусто)"),
+    ("value.object", "[объект]"),
+    ("error.wmi_init", "Ошибка инициализации WMI: {error}"),
+    ("error.com_init", "CoInitializeEx вернул ошибку {code}"),
+    ("error.connect_local", "Не удалось подключиться к WMI"),
+    ("error.connect", "Не удалось подключиться к WMI на компьютере {host}"),
+    ("error.query", "Ошибка запроса к {class}"),
+    ("error.namespace", "Пространство имён {namespace} недоступно"),
+    ("error.access_denied", "Доступ запрещён"),
+    ("error.elevate", "Запустите программу от имени администратора"),
+    ("error.hidden", "Файлы каталога {path} не видны"),
+    ("error.hidden_hint", "Без прав администратора WMI не возвращает содержимое каталога; запустите программу от имени администратора. Пустой каталог также бывает, если предвыборка выключена"),
+    ("error.details", "Подробности"),
+    ("error.gone", "Объект {class} больше не существует"),
+    ("error.method", "Метод {class}.{method} вернул код {code}"),
+    ("error.method_reason", "{class}.{method}: {reason} (код {code})"),
+    ("init.retry", "Повторить подключение"),
+    (
+        "init.service_stopped",
+        "Служба WMI (Winmgmt) остановлена; подключение повторится после её запуска",
+    ),
+    ("results.label", "Результаты:"),
+    ("status.elevated", "🛡 Администратор"),
+    ("status.not_elevated", "⚠ Без прав администратора"),
+    ("status.not_elevated_hint", "Часть классов WMI без прав администратора возвращает меньше строк без сообщения об ошибке. Запустите программу от имени администратора"),
+    ("status.rows", "Строк: {shown} из {total}"),
+    ("status.selected", "Выбрано: {count}"),
+    ("status.duration", "Запрос: {secs} с"),
+    ("status.running", "Выполняется запросов: {count}"),
+    ("query.timeout", "Превышено время ожидания ({secs} с)"),
+    ("query.timeout_label", "Время ожидания запроса:"),
+    ("cache.ttl_label", "Данные свежие:"),
+    ("query.seconds", " с"),
+    ("results.refresh", "Обновить"),
+    ("results.fetched", "обновлено {time} ({ago})"),
+    ("results.fetched_hint", "Щелчок обновляет данные"),
+    ("age.seconds", "{n} с назад"),
+    ("age.minutes", "{n} мин назад"),
+    ("age.hours", "{n} ч назад"),
+    ("view.grouped", "По классам"),
+    ("devices.problems", "Проблемные устройства ({count})"),
+    ("devices.no_class", "Класс не указан"),
+    ("env.system", "Системные ({count})"),
+    ("env.copy_item", "Щелчок копирует элемент"),
+    ("envwatch.toggle", "🔔 События"),
+    ("envwatch.hint", "Подписаться на события WMI: каждое добавление, изменение и удаление переменной записывается в журнал. Изменение PATH — известный способ закрепления в системе"),
+    ("envwatch.history", "Изменения переменных"),
+    ("envwatch.no_process", "WMI не сообщает, какой процесс изменил переменную"),
+    ("envwatch.system", "системная"),
+    ("envwatch.created", "добавлена {name} ({scope}): {new}"),
+    ("envwatch.modified", "изменена {name} ({scope}): {old} → {new}"),
+    ("envwatch.deleted", "удалена {name} ({scope}), было: {old}"),
+    ("envwatch.items_added", "; добавлено в список: {items}"),
+    ("envwatch.items_removed", "; удалено из списка: {items}"),
+    ("columns.button", "☷ Столбцы"),
+    ("columns.reset", "Как по умолчанию"),
+    ("export.all_columns", "Экспортировать все столбцы"),
+    ("notify.enabled", "Уведомления о событиях наблюдения"),
+    ("notify.hint", "Всплывающее уведомление, пока окно свёрнуто или неактивно; не чаще одного раза в 10 секунд для каждой категории"),
+    ("notify.min_severity", "Не ниже:"),
+    ("notify.more", "и ещё {count} событий"),
+    ("snapshot.on_exit", "Сохранять результаты при выходе"),
+    ("snapshot.on_exit_hint", "Последние результаты записываются в snapshot.json рядом с config.toml и показываются при следующем запуске без запроса"),
+    ("snapshot.open", "Открыть снимок…"),
+    ("snapshot.label", "офлайн-данные от {time}"),
+    ("snapshot.foreign_label", "офлайн-данные {host} от {time}"),
+    ("snapshot.hint", "Данные из снимка; «Обновить» запрашивает их заново"),
+    ("snapshot.read_only", "Данные другого компьютера из снимка"),
+    ("snapshot.loaded", "Загружено категорий из снимка: {count}"),
+    ("snapshot.empty", "В файле нет данных известных категорий"),
+    ("snapshot.read_failed", "Не удалось прочитать снимок {path}: {error}"),
+    ("snapshot.newer_schema", "Файл создан более новой версией программы: схема {version}, поддерживается до {supported}"),
+    ("snapshot.not_export", "{path} — не файл экспорта WMI Lab: нет версии схемы"),
+    ("menu.file", "Файл"),
+    ("viewer.open", "Открыть экспорт…"),
+    ("viewer.close", "Вернуться к текущим данным"),
+    ("viewer.opened", "Открыт файл экспорта, категорий: {count}"),
+    ("viewer.banner", "📂 Просмотр экспорта: {host}, {time}. Запросы и действия недоступны"),
+    ("viewer.window_title", "WMI Lab — экспорт {host} от {time} (только просмотр)"),
+    ("viewer.disabled", "Недоступно при просмотре файла экспорта"),
+    ("watch.toggle", "👁 Наблюдение"),
+    ("watch.hint", "Повторять запрос и подсвечивать изменения: новые строки — зелёным, исчезнувшие — красным, изменившиеся ячейки — жёлтым"),
+    ("watch.history", "История изменений"),
+    ("watch.clear", "Очистить"),
+    ("watch.no_changes", "Изменений пока нет"),
+    ("watch.added", "появилась строка {row}"),
+    ("watch.removed", "исчезла строка {row}"),
+    ("watch.changed", "{row}: изменились {columns}"),
+    ("view.table", "Таблица"),
+    ("view.chart", "Диаграмма"),
+    ("chart.count", "Количество"),
+    ("chart.no_path", "Путь недоступен"),
+    ("sid_type.user", "Пользователь"),
+    ("sid_type.group", "Группа"),
+    ("sid_type.domain", "Домен"),
+    ("sid_type.alias", "Локальная группа"),
+    ("sid_type.well_known_group", "Встроенная группа"),
+    ("sid_type.deleted_account", "Удалённая учётная запись"),
+    ("sid_type.invalid", "Недействительный"),
+    ("sid_type.unknown", "Неизвестный"),
+    ("sid_type.computer", "Компьютер"),
+    ("detail.toggle", "Подробности"),
+    ("detail.title", "Свойства объекта"),
+    ("detail.no_selection", "Выберите строку таблицы"),
+    ("detail.unsupported", "Строки этой категории не являются объектами WMI"),
+    ("detail.loading", "Запрос свойств…"),
+    ("detail.missing", "Объект больше не существует"),
+    ("detail.copy", "Копировать значение"),
+    ("process.terminate", "Завершить процесс…"),
+    ("process.terminate_title", "Завершение процесса"),
+    ("process.confirm", "Завершить процесс? Несохранённые данные в нём будут потеряны."),
+    ("process.terminate_button", "Завершить"),
+    ("process.terminating", "Завершение процесса…"),
+    ("process.close", "Закрыть"),
+    ("service.start", "▶ Запустить"),
+    ("service.stop", "■ Остановить"),
+    ("service.restart", "⟳ Перезапустить"),
+    ("service.needs_admin", "Управление службами требует прав администратора. Запустите программу от имени администратора"),
+    ("service.working", "{name}: выполняется команда…"),
+    ("service.done", "Служба {name}: {state}"),
+    ("service.dependents", "Службу {service} нельзя остановить: от неё зависят запущенные службы {dependents}"),
+    ("service.dependents_hint", "Сначала остановите зависимые службы"),
+    ("service.code.not_supported", "команда не поддерживается"),
+    ("service.code.access_denied", "доступ запрещён"),
+    ("service.code.cannot_accept", "служба сейчас не может принять эту команду"),
+    ("service.code.not_active", "служба не запущена"),
+    ("service.code.timeout", "служба не ответила вовремя"),
+    ("service.code.already_running", "служба уже запущена"),
+    ("service.code.database_locked", "база данных служб заблокирована"),
+    ("service.code.dependency_failed", "не удалось запустить службу, от которой она зависит"),
+    ("service.code.disabled", "служба отключена"),
+    ("service.code.logon_failed", "не удалось войти под учётной записью службы"),
+    ("service.code.marked_for_deletion", "служба помечена для удаления"),
+    ("process.terminated", "Процесс {name} (PID {pid}) завершён"),
+    ("process.exited", "Процесс уже завершился; список процессов обновлён"),
+    ("process.owners_loading", "Запрос владельцев: осталось {count}"),
+    (
+        "process.denied",
+        "нет прав на завершение: процесс защищён системой или запущен другим пользователем",
+    ),
+    ("results.empty", "Выберите категорию для отображения данных"),
+    ("results.stale", "Данные от {time}, последний запрос завершился ошибкой"),
+    ("results.failed", "Запрос завершился ошибкой"),
+    ("results.dismiss", "Скрыть сообщение"),
+    ("filter.label", "Фильтр:"),
+    ("filter.regex", "Регулярное выражение"),
+    ("hunt.label", "Поиск в командной строке:"),
+    ("hunt.hint", "например, -enc или mimikatz"),
+    ("hunt.found", "Найдено процессов: {count}"),
+    ("hunt.hidden", "Процессов со скрытой командной строкой: {count}"),
+    ("hunt.hidden_hint", "Командные строки процессов других пользователей видны только с правами администратора"),
+    ("filter.column", "Только столбец"),
+    ("table.copy_row", "Копировать строку"),
+    ("table.copy_selected", "Копировать выбранные ({count})"),
+    ("table.no_details", "Других свойств нет"),
+    ("table.copy_cell", "Копировать ячейку"),
+    ("table.open_folder", "Открыть папку: {column}"),
+    ("table.open_folder_failed", "Не удалось открыть папку {path}: {error}"),
+    ("table.virustotal", "Проверить {column} на VirusTotal"),
+    ("table.copy_all", "Копировать всё"),
+    ("table.page_size", "Строк на странице:"),
+    ("table.page_all", "Все"),
+    ("table.page", "страница {page} из {pages}"),
+    ("export.csv", "Экспорт CSV"),
+    ("export.json", "Экспорт JSON"),
+    ("export.json_all", "Экспорт JSON (все категории)"),
+    ("export.sbom", "SBOM CycloneDX"),
+    ("export.ioc", "Экспорт индикаторов"),
+    ("export.ioc_csv", "Список IOC (CSV)"),
+    ("export.ioc_stix", "Пакет STIX 2.1 (JSON)"),
+    ("export.ioc_hint", "Хеши файлов, пути и сетевые адреса из текущих находок всех категорий, без повторов"),
+    ("export.no_indicators", "В текущих находках нет хешей, путей или сетевых адресов"),
+    ("export.no_data", "Нет данных для экспорта"),
+    ("export.page_only", "Только текущая страница"),
+    ("export.selected_only", "Только выбранные строки"),
+    ("export.saved", "Сохранено: {path}"),
+    ("export.failed", "Не удалось записать {path}: {error}"),
+    ("export.cancelled", "Сохранение отменено"),
+    ("severity.info", "Информация"),
+    ("severity.low", "Низкая"),
+    ("severity.medium", "Средняя"),
+    ("severity.high", "Высокая"),
+    ("severity.critical", "Критическая"),
+    ("check.device_status", "Состояние устройств"),
+    ("finding.device_status", "Устройство в состоянии {state}"),
+    ("check.problem_devices", "Проблемные устройства"),
+    ("finding.problem_device", "Код ошибки диспетчера устройств {code}"),
+    ("check.problem_device_count", "Число проблемных устройств"),
+    ("finding.problem_device_count", "Проблемных устройств: {count}"),
+    ("check.dirty_volumes", "Тома с битом загрязнения"),
+    ("finding.dirty_volume", "Установлен бит загрязнения: том не был корректно отключён или повреждён"),
+    ("check.long_uptime", "Рабочие станции без перезагрузки более 30 дней"),
+    ("check.stale_reboot", "Перезагрузка откладывается после обновлений"),
+    ("finding.long_uptime", "Рабочая станция не перезагружалась {days} дн."),
+    ("finding.long_uptime_detail", "Последняя загрузка: {boot}. Обновления, требующие перезагрузки, не действуют"),
+    ("finding.stale_reboot", "Перезагрузка ожидается {days} дн. после последнего обновления"),
+    ("check.placeholder_identity", "Заглушки вместо идентификаторов оборудования"),
+    ("finding.placeholder_identity", "{field}: значение по умолчанию производителя, идентификатор ненадёжен"),
+    ("check.never_sleeps", "Активная схема электропитания без перехода в сон"),
+    ("finding.never_sleeps", "Компьютер «никогда не засыпает»"),
+    ("check.no_wake_password", "Пароль при пробуждении в активной схеме"),
+    ("finding.no_wake_password", "«Пароль при пробуждении не требуется»"),
+    ("finding.power_plan_detail", "Активная схема: {plan}"),
+    ("check.prefetch_missing", "Нет каталога предвыборки"),
+    ("finding.prefetch_missing", "Каталог Prefetch отсутствует"),
+    ("finding.prefetch_missing_detail", "Предвыборка выключена (EnablePrefetcher = 0, политика для SSD или серверная редакция) или её следы удалены"),
+    ("check.recycle_truncated", "Корзина показана не полностью"),
+    ("finding.recycle_truncated", "Показаны только последние удалённые объекты"),
+    ("finding.recycle_truncated_detail", "Показано {shown} из {total}; более старые объекты в таблицу не вошли"),
+    ("check.alternate_streams", "Нестандартные потоки NTFS"),
+    ("finding.alternate_stream", "Дополнительный поток «{stream}»"),
+    ("check.risky_extensions", "Расширения с опасными разрешениями"),
+    ("finding.risky_extension", "Расширение «{name}» ({browser}) с опасными разрешениями"),
+    ("finding.risky_extension_detail", "{permissions}; пользователь {user}"),
+    ("check.defender_exclusions", "Широкие исключения Defender"),
+    ("finding.defender_exclusion", "Широкое исключение Defender ({kind}): {exclusion}"),
+    ("finding.defender_hidden", "Исключения Defender ({kind}) не проверены"),
+    ("check.packet_capture", "Драйверы перехвата пакетов"),
+    ("finding.packet_capture", "Адаптер доступен драйверу перехвата пакетов {driver}"),
+    ("report.section", "Отчёт"),
+    ("report.categories", "Категории:"),
+    ("report.checks", "Проверки:"),
+    ("report.format", "Формат:"),
+    ("report.generate", "Сформировать отчёт"),
+    ("report.builder", "Конструктор отчёта…"),
+    ("report.profile", "Профиль:"),
+    ("report.no_profile", "(без профиля)"),
+    ("report.profile_name", "Имя профиля:"),
+    ("report.save_profile", "Сохранить профиль"),
+    ("report.delete_profile", "Удалить профиль"),
+    ("report.all_checks", "Выбрать все проверки безопасности"),
+    ("report.move_up", "Выше"),
+    ("report.move_down", "Ниже"),
+    (
+        "report.selection",
+        "Разделов: {categories}, проверок: {checks}, формат {format}",
+    ),
+    ("report.running", "Формирование отчёта…"),
+    ("report.saved", "Отчёт сохранён: {path}"),
+    ("report.aborted", "Формирование отчёта прервано"),
+    ("report.title", "Отчёт WMI"),
+    ("report.summary", "Сводка"),
+    ("report.host", "Компьютер"),
+    ("report.os", "ОС"),
+    ("report.time", "Время"),
+    ("report.findings", "Находки"),
+    ("report.findings_count", "Находки: {severity}"),
+    ("report.severity", "Важность"),
+    ("report.category", "Категория"),
+    ("report.description", "Описание"),
+    ("report.detail", "Подробности"),
+    ("report.query_error", "Ошибка запроса:"),
+    (
+        "report.truncated",
+        "Показаны первые {shown} строк из {total}",
+    ),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("app.heading", "Lab work — WMI"),
+    ("app.language", "Language"),
+    ("app.window_title", "WMI Lab — {host}"),
+    ("remote.title", "Connect to computer"),
+    ("remote.host", "Computer:"),
+    ("remote.recent", "From list"),
+    ("remote.user", "User:"),
+    ("remote.password", "Password:"),
+    ("remote.hint", "Without a user name the current credentials are used"),
+    ("remote.connect", "Connect"),
+    ("remote.local", "Local computer"),
+    ("remote.cancel", "Cancel"),
+    ("profiles.title", "Connection profiles"),
+    ("profiles.none", "No profile"),
+    ("profiles.manage", "Profiles…"),
+    ("profiles.add", "Add"),
+    ("profiles.delete", "Delete"),
+    ("profiles.save", "Save"),
+    ("profiles.unnamed", "Unnamed"),
+    ("profiles.empty", "No profiles yet. Add the first one"),
+    ("profiles.name", "Name:"),
+    ("profiles.domain", "Domain:"),
+    ("profiles.namespace", "Namespace:"),
+    ("profiles.save_password", "Keep the password in Windows Credential Manager"),
+    (
+        "profiles.save_password_hint",
+        "Otherwise the password is asked on every connection. It is never written to the config file",
+    ),
+    ("profiles.store", "Remember password"),
+    ("profiles.stored", "Password saved"),
+    ("profiles.password_for", "Password for profile “{name}”"),
+    (
+        "remote.rpc_unavailable",
+        "RPC server unavailable: check the name {host}, the network and the \"Windows Management Instrumentation (WMI)\" firewall rule",
+    ),
+    (
+        "remote.access_denied",
+        "Access denied: the account needs administrator rights on the remote computer and DCOM/WMI access",
+    ),
+    ("remote.logon_failure", "Wrong user name or password"),
+    ("remote.invalid_namespace", "The namespace does not exist on the remote computer"),
+    ("theme.label", "Theme"),
+    ("theme.system", "System"),
+    ("theme.light", "Light"),
+    ("theme.dark", "Dark"),
+    ("settings.label", "Settings"),
+    ("perf.toggle", "CPU and memory usage graph"),
+    ("perf.cpu", "CPU: {percent} %"),
+    ("perf.memory", "Committed memory: {used} of {total} GB ({percent} %)"),
+    ("perf.waiting", "Waiting for the first sample…"),
+    ("perf.cpu_line", "CPU"),
+    ("perf.memory_line", "Memory"),
+    ("shortcuts.title", "Keyboard shortcuts"),
+    ("shortcuts.refresh", "Re-run the category query"),
+    ("shortcuts.focus_filter", "Focus the row filter"),
+    ("shortcuts.clear_filter", "Clear the row filter"),
+    ("shortcuts.export", "Export the category to CSV"),
+    ("shortcuts.copy_row", "Copy the selected row"),
+    ("shortcuts.category", "Select a category by its position in the sidebar"),
+    (
+        "shortcuts.typing_hint",
+        "While a text field has focus only Esc in the filter and regular text copying work",
+    ),
+    ("config.reload", "Reload config.toml"),
+    ("config.reloaded", "Settings reloaded: {path}"),
+    ("config.read_failed", "Failed to read {path}: {error}"),
+    ("config.parse_failed", "Error in {path}, line {line}, column {column}: {error}"),
+    ("config.write_failed", "Failed to write {path}: {error}"),
+    ("zoom.label", "Zoom:"),
+    ("zoom.reset", "Reset zoom (Ctrl+0)"),
+    ("nav.toggle", "Category list"),
+    ("tab.categories", "Categories"),
+    ("tab.console", "WQL console"),
+    ("tab.classes", "WMI classes"),
+    ("tab.history", "History"),
+    ("syslog.enabled", "Forward to syslog"),
+    (
+        "syslog.hint",
+        "Report findings and watch events are sent to a syslog server as RFC 5424",
+    ),
+    ("syslog.server", "Server:"),
+    ("syslog.test", "Test message"),
+    ("syslog.test_text", "WMI Lab connectivity test"),
+    ("syslog.test_sent", "Test message sent"),
+    ("syslog.disabled", "Forwarding is off"),
+    ("webhook.enabled", "Send important findings to a webhook"),
+    ("webhook.hint", "JSON POST to a Slack, Teams or custom endpoint; delivery errors go to the log"),
+    ("webhook.url", "URL"),
+    ("webhook.muted", "Don't send findings of checks:"),
+    ("webhook.muted_triggers", "Don't watch for changes:"),
+    ("tripwire.category", "Watchers"),
+    ("tripwire.admin_member", "New member of the Administrators group"),
+    ("tripwire.event_consumer", "New WMI event consumer"),
+    ("webhook.test", "Test"),
+    ("webhook.test_text", "WMI Lab webhook test"),
+    ("webhook.test_sent", "Delivered, status {status}"),
+    ("webhook.failed", "Not delivered: {error}"),
+    ("webhook.status", "server answered with status {status}"),
+    ("eventlog.enabled", "Write findings to the Windows Event Log"),
+    ("eventlog.hint", "Report findings are written to the Application log; the same finding is written once per session"),
+    ("eventlog.host", "Computer"),
+    ("eventlog.identity", "Key"),
+    ("eventlog.registered", "Source {source} is registered"),
+    ("eventlog.fallback", "Source {source} is not registered: events are written under the Application source"),
+    ("eventlog.register", "Register source"),
+    ("eventlog.register_hint", "Creates the event source in the registry once; requires administrator rights"),
+    ("eventlog.register_done", "Source {source} registered"),
+    ("eventlog.register_failed", "Source not registered: {error}"),
+    ("eventlog.need_admin", "administrator rights are required"),
+    ("eventlog.test", "Test event"),
+    ("eventlog.test_text", "WMI Lab test event"),
+    ("eventlog.test_done", "Test event written to the Application log"),
+    ("eventlog.test_failed", "Event not written: {error}"),
+    ("virustotal.key", "VirusTotal API key"),
+    ("virustotal.hint", "File hashes from the tables are looked up in VirusTotal, at most 4 requests a minute. Only hashes are sent, never files. The key is kept in Windows Credential Manager"),
+    ("virustotal.disabled", "Hash lookup is off until a key is set"),
+    ("virustotal.queue", "VirusTotal queue: {count}"),
+    ("virustotal.hashing", "Hashing files: {count} left"),
+    ("virustotal.retry", "Retry failed lookups ({count})"),
+    ("virustotal.queued", "VT: queued"),
+    ("virustotal.unknown", "VT: not found"),
+    ("virustotal.failed", "VT: error"),
+    ("virustotal.network", "VirusTotal is unreachable: {error}"),
+    ("virustotal.key_rejected", "VirusTotal rejected the API key"),
+    ("virustotal.quota", "VirusTotal quota exceeded"),
+    ("virustotal.status", "VirusTotal replied with code {status}"),
+    ("virustotal.bad_reply", "VirusTotal sent an unexpected reply"),
+    ("streams.local_only", "Folders are scanned on this computer, not on the connected one"),
+    ("streams.remove_dir", "Remove the folder from the scan"),
+    ("streams.add_dir", "Add folder…"),
+    ("streams.max_depth", "Depth:"),
+    ("streams.max_files", "At most files:"),
+    ("streams.start", "Find streams"),
+    ("streams.cancel", "Stop"),
+    ("streams.progress", "Checked: {count}"),
+    ("streams.summary", "Non-standard streams: {found}; files and folders checked: {scanned}; downloaded from the internet (Zone.Identifier): {marked}"),
+    ("streams.limited", "The scan hit the depth or file limit: not every file was checked"),
+    ("streams.cancelled", "The scan was stopped: not every file was checked"),
+    ("streams.errors", "Could not read: {count}"),
+    ("autoexport.enabled", "Auto export on exit"),
+    ("autoexport.hint", "When the program closes, data of every category is saved as JSON without new queries; the 10 latest files are kept"),
+    ("autoexport.dir", "Folder:"),
+    ("autoexport.open_latest", "Open latest auto export"),
+    ("autoexport.none", "No auto exports yet"),
+    ("extensions.profile_denied", "The profile cannot be read: administrator rights are required"),
+    ("extensions.bad_file", "The extension file is damaged or in an unknown format"),
+    ("defender.kind.path", "Folder or file"),
+    ("defender.kind.extension", "Extension"),
+    ("defender.kind.process", "Process"),
+    ("defender.hidden", "Values are hidden: administrator rights are required, and if they are already granted, the exclusions are hidden by the HideExclusionsFromLocalAdmins policy or tamper protection"),
+    ("defender.none", "No exclusions"),
+    ("defender.no_namespace", "The Microsoft Defender namespace is unavailable: Defender is removed or replaced by another antivirus"),
+    ("defender.broad.drive", "An entire drive is excluded"),
+    ("defender.broad.users", "Every user profile is excluded"),
+    ("defender.broad.temp", "A temporary folder any program can write to is excluded"),
+    ("defender.broad.executable", "Every file of this executable type is excluded"),
+    ("defender.broad.script_host", "A script host is excluded: nothing it opens is scanned"),
+    ("defender.broad.wildcard", "The process pattern matches any program"),
+    ("netstatus.disconnected", "Disconnected"),
+    ("netstatus.connecting", "Connecting"),
+    ("netstatus.connected", "Connected"),
+    ("netstatus.disconnecting", "Disconnecting"),
+    ("netstatus.not_present", "Hardware not present"),
+    ("netstatus.disabled", "Hardware disabled"),
+    ("netstatus.malfunction", "Hardware malfunction"),
+    ("netstatus.media_disconnected", "Media disconnected"),
+    ("netstatus.authenticating", "Authenticating"),
+    ("netstatus.authenticated", "Authentication succeeded"),
+    ("netstatus.auth_failed", "Authentication failed"),
+    ("netstatus.invalid_address", "Invalid address"),
+    ("netstatus.credentials_required", "Credentials required"),
+    ("devproblem.1", "device is not configured correctly"),
+    ("devproblem.3", "driver is corrupted or the system is low on memory"),
+    ("devproblem.10", "device cannot start"),
+    ("devproblem.12", "not enough free resources"),
+    ("devproblem.14", "restart required"),
+    ("devproblem.16", "not all resources used by the device were identified"),
+    ("devproblem.18", "drivers must be reinstalled"),
+    ("devproblem.19", "configuration information in the registry is incomplete or damaged"),
+    ("devproblem.21", "device is being removed"),
+    ("devproblem.22", "device is disabled"),
+    ("devproblem.24", "device is not present, not working properly or missing drivers"),
+    ("devproblem.28", "drivers are not installed"),
+    ("devproblem.29", "device is disabled by the firmware"),
+    ("devproblem.31", "Windows cannot load the drivers"),
+    ("devproblem.32", "driver service is disabled"),
+    ("devproblem.33", "required resources cannot be determined"),
+    ("devproblem.34", "device must be configured manually"),
+    ("devproblem.35", "firmware lacks information to configure the device"),
+    ("devproblem.37", "driver reported a failure"),
+    ("devproblem.38", "a previous instance of the driver is still in memory"),
+    ("devproblem.39", "driver is corrupted or missing"),
+    ("devproblem.40", "driver service information in the registry is missing or invalid"),
+    ("devproblem.41", "driver loaded but the hardware was not found"),
+    ("devproblem.43", "device was stopped because it reported problems"),
+    ("devproblem.45", "device is not connected"),
+    ("devproblem.48", "driver is blocked due to known problems"),
+    ("devproblem.52", "driver digital signature cannot be verified"),
+    ("devproblem.other", "{code}: Device Manager error"),
+    ("devices.no_problems", "No problem devices"),
+    ("cooling.no_instances", "The class returned no instances"),
+    ("cooling.no_reading", "The sensor reports no reading"),
+    ("cooling.implausible", "Outside the plausible range {min}…{max} °C"),
+    ("availability.other", "Other"),
+    ("availability.unknown", "Unknown"),
+    ("availability.running", "Running"),
+    ("availability.warning", "Warning"),
+    ("availability.in_test", "In test"),
+    ("availability.not_applicable", "Not applicable"),
+    ("availability.power_off", "Power off"),
+    ("availability.off_line", "Off line"),
+    ("availability.degraded", "Degraded"),
+    ("availability.not_installed", "Not installed"),
+    ("availability.install_error", "Install error"),
+    ("availability.power_save", "Power save"),
+    ("availability.code", "State {code}"),
+    ("port.parallel", "Parallel port"),
+    ("port.serial", "Serial port"),
+    ("port.joystick", "Joystick port"),
+    ("port.keyboard", "Keyboard port"),
+    ("port.mouse", "Mouse port"),
+    ("port.video", "Video port"),
+    ("port.audio", "Audio port"),
+    ("port.modem", "Modem port"),
+    ("port.network", "Network port"),
+    ("port.other", "Other"),
+    ("port.unknown", "Unknown connector"),
+    ("port.code", "code {code}"),
+    ("port.male", "male"),
+    ("port.female", "female"),
+    ("port.usb_count", "USB ports: {count}"),
+    ("port.total", "Connectors in total: {count}"),
+    ("port.no_data", "The firmware does not report connectors: Win32_PortConnector is empty"),
+    ("quota.unsupported", "quotas are not supported"),
+    ("quota.disabled", "disabled"),
+    ("quota.tracked", "tracking only"),
+    ("quota.enforced", "enforced, writes over the limit are denied"),
+    ("quota.state", "state {code}"),
+    ("uptime.role", "System type"),
+    ("uptime.workstation", "Workstation"),
+    ("uptime.domain_controller", "Domain controller"),
+    ("uptime.server", "Server"),
+    ("uptime.role_code", "Type {code}"),
+    ("uptime.last_boot", "Last boot"),
+    ("uptime.uptime", "Uptime"),
+    ("uptime.duration", "{days} d {hours} h"),
+    ("uptime.pending", "Reboot pending"),
+    ("uptime.pending_yes", "yes: {reasons}"),
+    ("uptime.registry_error", "Could not read {key} from the registry (code {code})"),
+    ("uptime.hotfix", "Latest update"),
+    ("uptime.shutdown", "Unexpected shutdown"),
+    ("uptime.no_shutdowns", "none in {days} days"),
+    ("asset.hostname", "Computer name"),
+    ("asset.uuid", "UUID"),
+    ("asset.vendor", "Vendor"),
+    ("asset.model", "Model"),
+    ("asset.identifying_number", "Serial number"),
+    ("asset.bios_serial", "BIOS serial number"),
+    ("asset.asset_tag", "Asset tag"),
+    ("asset.mac", "Primary MAC address"),
+    ("asset.os_product_id", "OS product ID"),
+    ("power.balanced", "Balanced"),
+    ("power.high_performance", "High performance"),
+    ("power.saver", "Power saver"),
+    ("power.ultimate", "Ultimate performance"),
+    ("power.from_wmi", "WMI"),
+    ("power.from_registry", "Registry"),
+    ("power.no_namespace", "The root\\cimv2\\power namespace is unavailable (happens on server editions); the active plan was read from the registry"),
+    ("asset.copy_json", "Copy as JSON"),
+    ("asset.copy_json_hint", "Summary to paste into the asset database: English keys, placeholders listed in unreliable"),
+    ("quota.ok", "within limits"),
+    ("quota.warning", "warning level exceeded"),
+    ("quota.exceeded", "limit exceeded"),
+    ("quota.no_limit", "no limit"),
+    ("quota.more", "…and {count} more"),
+    ("virt.verdict", "Conclusion"),
+    ("virt.physical", "Physical machine"),
+    ("virt.vm", "VM: {hypervisor}"),
+    ("virt.model", "Manufacturer and model"),
+    ("virt.serial", "BIOS serial number"),
+    ("virt.device", "Virtual device"),
+    ("virt.no_devices", "none found"),
+    ("virt.hypervisor_present", "Hypervisor running (HypervisorPresent)"),
+    ("virt.hyperv_feature", "Hyper-V feature"),
+    ("virt.feature.enabled", "enabled"),
+    ("virt.feature.disabled", "disabled"),
+    ("virt.feature.absent", "not installed"),
+    ("virt.vmms", "Hyper-V management service (vmms)"),
+    ("virt.no_service", "not installed"),
+    ("virt.hyperv_vm", "Running Hyper-V VM"),
+    ("syslog.no_address", "server address not found"),
+    ("syslog.failed", "Could not send to syslog: {error}"),
+    ("syslog.buffered", "Waiting to be sent: {count}, lost: {dropped}"),
+    ("history.enabled", "Keep snapshot history"),
+    (
+        "history.hint",
+        "Every report and the “Save snapshot” button write data to history.sqlite3",
+    ),
+    ("history.keep_runs", "Runs to keep:"),
+    ("history.kind_report", "report"),
+    ("history.kind_manual", "manual"),
+    ("schedule.enabled", "Scheduled report"),
+    ("schedule.hint", "While the app is open, the chosen profile's report is generated automatically, saved to the folder and recorded in history"),
+    ("schedule.daily", "Every day at"),
+    ("schedule.every", "Every"),
+    ("schedule.hours", "h"),
+    ("schedule.bad_time", "HH:MM"),
+    ("schedule.dir", "Folder:"),
+    ("schedule.next", "⏰ Next report: {time}"),
+    ("schedule.running", "Scheduled report…"),
+    ("schedule.saved", "Scheduled report saved: {path}"),
+    ("schedule.no_profile", "Report profile “{name}” for the schedule not found"),
+    ("history.kind_scheduled", "scheduled"),
+    ("history.failed", "History database error: {error}"),
+    ("history.open_failed", "Could not open history database {path}: {error}"),
+    ("history.saved", "Snapshot #{run} saved, categories: {count}"),
+    ("history.nothing", "No fresh data for the report categories"),
+    ("history.save", "Save snapshot"),
+    (
+        "history.save_hint",
+        "Save fresh data of the categories selected for the report",
+    ),
+    ("history.reload", "Reload list"),
+    ("history.disabled", "History is off. Turn it on in the ⚙ menu"),
+    ("history.empty", "No runs yet"),
+    ("history.run", "Run"),
+    ("history.host", "Computer"),
+    ("history.kind", "Source"),
+    ("history.categories", "Categories"),
+    ("history.category", "Category:"),
+    ("history.older", "Earlier:"),
+    ("history.newer", "Later:"),
+    ("history.compare", "Compare"),
+    ("history.no_changes", "No differences"),
+    ("history.trend", "Rows where"),
+    ("history.column_hint", "column"),
+    ("history.value_hint", "value"),
+    ("history.plot", "Plot"),
+    ("history.rows", "Rows"),
+    ("history.open", "Open"),
+    ("history.open_hint", "Show the run data in the category tables"),
+    ("namespace.label", "Namespace:"),
+    ("namespace.custom", "other, e.g. root\\Microsoft"),
+    ("namespace.open", "Open"),
+    ("namespace.retry", "Retry connection"),
+    ("browser.search", "Find class"),
+    ("browser.reload", "Reload class list"),
+    ("browser.count", "Classes: {count}"),
+    ("browser.instances", "{class}: {count} instances"),
+    (
+        "browser.empty",
+        "Pick a class on the left to list its instances",
+    ),
+    ("console.run", "Run (Ctrl+Enter)"),
+    ("console.history", "History"),
+    ("console.rows", "Rows: {count}"),
+    ("section.system", "System"),
+    ("section.hardware", "Hardware"),
+    ("section.accounts", "Accounts"),
+    ("section.network", "Network"),
+    ("section.security", "Security"),
+    ("section.monitoring", "Monitoring"),
+    ("category.env_vars", "Environment variables"),
+    ("category.sid_counts", "SID statistics"),
+    ("category.bus_info", "Bus information"),
+    ("category.problem_devices", "Problem devices"),
+    ("category.cooling", "Cooling"),
+    ("category.port_connectors", "Port connectors"),
+    ("category.volumes", "Volumes"),
+    ("category.disk_quotas", "Disk quotas"),
+    ("category.processes", "Processes"),
+    ("category.services", "Services"),
+    ("category.software", "Installed software"),
+    ("category.network_adapters", "Network adapters"),
+    ("category.virtualization", "Virtualization"),
+    ("category.uptime", "Uptime"),
+    ("category.asset", "Asset identity"),
+    ("category.power_plans", "Power plans"),
+    ("category.prefetch", "Prefetch (executions)"),
+    ("category.recycle_bin", "Recycle Bin"),
+    ("category.streams", "NTFS alternate streams"),
+    ("category.browser_extensions", "Browser extensions"),
+    ("category.defender_exclusions", "Defender exclusions"),
+    ("category.log", "Log"),
+    ("column.Time", "Time"),
+    ("column.Level", "Level"),
+    ("column.Target", "Source"),
+    ("column.Message", "Message"),
+    ("log.error", "errors"),
+    ("log.warn", "warnings"),
+    ("log.info", "info"),
+    ("log.debug", "debug"),
+    ("log.level_label", "Log level:"),
+    ("log.filter_label", "Show:"),
+    ("log.dir_label", "Log folder:"),
+    ("log.dir_default", "next to the program"),
+    ("log.dir_restart", "The folder applies after restart"),
+    ("column.Name", "Name"),
+    ("column.UserName", "User"),
+    ("column.VariableValue", "Value"),
+    ("column.SIDType", "SID type"),
+    ("column.Count", "Count"),
+    ("column.DeviceID", "ID"),
+    ("column.Status", "Status"),
+    ("column.PNPClass", "Class"),
+    ("column.Manufacturer", "Manufacturer"),
+    ("column.ConfigManagerErrorCode", "Error code"),
+    ("column.SystemVariable", "System"),
+    ("column.Domain", "Domain"),
+    ("column.LocalAccount", "Local"),
+    ("column.ProcessId", "PID"),
+    ("column.ExecutablePath", "Executable"),
+    ("column.SHA256", "File SHA-256"),
+    ("column.CommandLine", "Command line"),
+    ("column.ParentProcessId", "Parent PID"),
+    ("column.ParentName", "Parent"),
+    ("column.ThreadCount", "Threads"),
+    ("column.DisplayName", "Display name"),
+    ("column.State", "State"),
+    ("column.StartMode", "Start mode"),
+    ("column.PathName", "Executable"),
+    ("column.StartName", "Account"),
+    ("column.Severity", "Severity"),
+    ("column.Category", "Category"),
+    ("column.Title", "Finding"),
+    ("column.Detail", "Details"),
+    ("column.Description", "Description"),
+    ("column.Service", "Driver"),
+    ("column.HardwareID", "Hardware IDs"),
+    ("column.Present", "Present"),
+    ("column.SessionId", "Session"),
+    ("column.Priority", "Priority"),
+    ("column.HandleCount", "Handles"),
+    ("column.ServiceType", "Service type"),
+    ("column.AcceptStop", "Can stop"),
+    ("column.DelayedAutoStart", "Delayed start"),
+    ("column.Version", "Version"),
+    ("column.Vendor", "Publisher"),
+    ("column.Language", "Language"),
+    ("column.ProgramId", "Program ID"),
+    ("column.MsiProductCode", "MSI product code"),
+    ("column.Executable", "Program"),
+    ("column.FileName", "File"),
+    ("column.PathHash", "Path hash"),
+    ("column.LastModified", "Last run"),
+    ("column.CreationDate", "First run"),
+    ("column.FileSize", "Size"),
+    ("column.Copies", "Files for program"),
+    ("column.RanAgain", "Ran again"),
+    ("column.LastAccessed", "Last read"),
+    ("column.Owner", "Owner"),
+    ("column.OriginalPath", "Original path"),
+    ("column.DeletedAt", "Deleted"),
+    ("column.Size", "Size, bytes"),
+    ("column.Payload", "Contents present"),
+    ("column.SID", "SID"),
+    ("column.TotalItems", "Total in bins"),
+    ("column.FilePath", "File"),
+    ("column.Stream", "Stream"),
+    ("column.StreamSize", "Stream size, bytes"),
+    ("column.User", "User"),
+    ("column.Browser", "Browser"),
+    ("column.Profile", "Browser profile"),
+    ("column.Permissions", "Permissions"),
+    ("column.Enabled", "Enabled"),
+    ("column.Error", "Error"),
+    ("column.ExtensionId", "Extension ID"),
+    ("column.Path", "Path"),
+    ("column.ExclusionType", "Exclusion type"),
+    ("column.Exclusion", "Exclusion"),
+    ("column.Broad", "Too broad"),
+    ("column.Note", "Note"),
+    ("column.Source", "Source"),
+    ("column.Reading", "Reading"),
+    ("column.ConnectorKind", "Connector kind"),
+    ("column.Connectors", "Connectors"),
+    ("column.Designators", "Designators"),
+    ("column.MountPath", "Mount path"),
+    ("column.Label", "Label"),
+    ("column.FileSystem", "File system"),
+    ("column.DirtyBitSet", "Dirty bit"),
+    ("column.Compressed", "Compressed"),
+    ("column.BlockSize", "Block size"),
+    ("column.BootVolume", "Boot"),
+    ("column.SystemVolume", "System"),
+    ("column.Volume", "Volume"),
+    ("column.QuotaState", "Quotas"),
+    ("column.DiskSpaceUsed", "Used, bytes"),
+    ("column.Limit", "Limit"),
+    ("column.WarningLimit", "Warning level"),
+    ("column.QuotaStatus", "Quota status"),
+    ("column.MACAddress", "MAC address"),
+    ("column.AdapterType", "Adapter type"),
+    ("column.PNPDeviceID", "PnP ID"),
+    ("column.ConnectionState", "Connection"),
+    ("column.Virtual", "Virtual"),
+    ("column.IPAddress", "IP addresses"),
+    ("column.DefaultIPGateway", "Gateway"),
+    ("column.CaptureDriver", "Capture driver"),
+    ("column.Problem", "Problem"),
+    ("column.Indicator", "Indicator"),
+    ("column.Observed", "Observed"),
+    ("column.Days", "Days"),
+    ("column.Code", "Code"),
+    ("column.Placeholder", "Placeholder"),
+    ("column.Key", "Key"),
+    ("column.PowerPlan", "Plan"),
+    ("column.Active", "Active"),
+    ("column.SleepAC", "Sleep on AC, s (0 = never)"),
+    ("column.SleepDC", "Sleep on battery, s (0 = never)"),
+    ("column.WakePasswordAC", "Password on wake (AC)"),
+    ("column.WakePasswordDC", "Password on wake (battery)"),
+    ("column.PlanGuid", "Plan GUID"),
+    ("column.SettingsSource", "Settings source"),
+    ("column.Battery", "Battery"),
+    ("column.Hypervisor", "Hypervisor"),
+    ("column.NetConnectionID", "Windows connection"),
+    ("column.NetConnectionStatus", "Status code"),
+    ("column.ServiceName", "Driver"),
+    ("column.DHCPEnabled", "DHCP"),
+    ("column.DNSServerSearchOrder", "DNS servers"),
+    ("column.NetEnabled", "Enabled"),
+    ("where.title", "Query conditions (WHERE)"),
+    ("where.apply", "Apply"),
+    ("where.reset", "Reset"),
+    ("where.clear", "Remove condition"),
+    ("where.any", "any"),
+    ("value.yes", "yes"),
+    ("value.no", "no"),
+    ("value.na", "n/a"),
+    ("value.empty", "(empty)"),
+    ("value.object", "[object]"),
+    ("error.wmi_init", "WMI initialization failed: {error}"),
+    ("error.com_init", "CoInitializeEx returned error {code}"),
+    ("error.connect_local", "Could not connect to WMI"),
+    ("error.connect", "Could not connect to WMI on {host}"),
+    ("error.query", "Query to {class} failed"),
+    ("error.namespace", "Namespace {namespace} is unavailable"),
+    ("error.access_denied", "Access denied"),
+    ("error.elevate", "Run the program as administrator"),
+    ("error.hidden", "Files in {path} are not visible"),
+    ("error.hidden_hint", "Without administrator rights WMI does not return the directory contents; run the program as administrator. The directory can also be empty when prefetch is disabled"),
+    ("error.details", "Details"),
+    ("error.gone", "The {class} object no longer exists"),
+    ("error.method", "Method {class}.{method} returned code {code}"),
+    ("error.method_reason", "{class}.{method}: {reason} (code {code})"),
+    ("init.retry", "Retry connection"),
+    (
+        "init.service_stopped",
+        "The WMI service (Winmgmt) is stopped; the app will reconnect once it starts",
+    ),
+    ("results.label", "Results:"),
+    ("status.elevated", "🛡 Administrator"),
+    ("status.not_elevated", "⚠ Not elevated"),
+    ("status.not_elevated_hint", "Without administrator rights some WMI classes silently return fewer rows. Run the program as administrator"),
+    ("status.rows", "Rows: {shown} of {total}"),
+    ("status.selected", "Selected: {count}"),
+    ("status.duration", "Query: {secs} s"),
+    ("status.running", "Queries running: {count}"),
+    ("query.timeout", "Timed out ({secs} s)"),
+    ("query.timeout_label", "Query timeout:"),
+    ("cache.ttl_label", "Data stays fresh:"),
+    ("query.seconds", " s"),
+    ("results.refresh", "Refresh"),
+    ("results.fetched", "updated {time} ({ago})"),
+    ("results.fetched_hint", "Click to refresh"),
+    ("age.seconds", "{n} s ago"),
+    ("age.minutes", "{n} min ago"),
+    ("age.hours", "{n} h ago"),
+    ("view.grouped", "By class"),
+    ("devices.problems", "Problem devices ({count})"),
+    ("devices.no_class", "No class"),
+    ("env.system", "System ({count})"),
+    ("env.copy_item", "Click to copy"),
+    ("envwatch.toggle", "🔔 Events"),
+    ("envwatch.hint", "Subscribe to WMI events: every added, changed or removed variable is written to the log. Changing PATH is a known persistence technique"),
+    ("envwatch.history", "Variable changes"),
+    ("envwatch.no_process", "WMI does not report which process changed the variable"),
+    ("envwatch.system", "system"),
+    ("envwatch.created", "added {name} ({scope}): {new}"),
+    ("envwatch.modified", "changed {name} ({scope}): {old} → {new}"),
+    ("envwatch.deleted", "removed {name} ({scope}), was: {old}"),
+    ("envwatch.items_added", "; added to the list: {items}"),
+    ("envwatch.items_removed", "; removed from the list: {items}"),
+    ("columns.button", "☷ Columns"),
+    ("columns.reset", "Reset to default"),
+    ("export.all_columns", "Export all columns"),
+    ("notify.enabled", "Notify about watch events"),
+    ("notify.hint", "Desktop notification while the window is minimized or inactive; at most one per category every 10 seconds"),
+    ("notify.min_severity", "At least:"),
+    ("notify.more", "and {count} more events"),
+    ("snapshot.on_exit", "Save results on exit"),
+    ("snapshot.on_exit_hint", "The latest results are written to snapshot.json next to config.toml and shown on the next start without querying"),
+    ("snapshot.open", "Open snapshot…"),
+    ("snapshot.label", "offline data from {time}"),
+    ("snapshot.foreign_label", "offline data of {host} from {time}"),
+    ("snapshot.hint", "Data from a snapshot; \"Refresh\" queries it again"),
+    ("snapshot.read_only", "Data of another computer from a snapshot"),
+    ("snapshot.loaded", "Categories loaded from snapshot: {count}"),
+    ("snapshot.empty", "The file has no data for known categories"),
+    ("snapshot.read_failed", "Could not read snapshot {path}: {error}"),
+    ("snapshot.newer_schema", "The file was created by a newer version: schema {version}, supported up to {supported}"),
+    ("snapshot.not_export", "{path} is not a WMI Lab export: no schema version"),
+    ("menu.file", "File"),
+    ("viewer.open", "Open export…"),
+    ("viewer.close", "Back to live data"),
+    ("viewer.opened", "Export file opened, categories: {count}"),
+    ("viewer.banner", "📂 Viewing export: {host}, {time}. Queries and actions are disabled"),
+    ("viewer.window_title", "WMI Lab — export of {host} from {time} (read-only)"),
+    ("viewer.disabled", "Unavailable while viewing an export file"),
+    ("watch.toggle", "👁 Watch"),
+    ("watch.hint", "Re-run the query and highlight changes: new rows in green, removed rows in red, changed cells in yellow"),
+    ("watch.history", "Change history"),
+    ("watch.clear", "Clear"),
+    ("watch.no_changes", "No changes yet"),
+    ("watch.added", "row {row} appeared"),
+    ("watch.removed", "row {row} disappeared"),
+    ("watch.changed", "{row}: {columns} changed"),
+    ("view.table", "Table"),
+    ("view.chart", "Chart"),
+    ("chart.count", "Count"),
+    ("chart.no_path", "Path unavailable"),
+    ("sid_type.user", "User"),
+    ("sid_type.group", "Group"),
+    ("sid_type.domain", "Domain"),
+    ("sid_type.alias", "Alias"),
+    ("sid_type.well_known_group", "Well-known group"),
+    ("sid_type.deleted_account", "Deleted account"),
+    ("sid_type.invalid", "Invalid"),
+    ("sid_type.unknown", "Unknown"),
+    ("sid_type.computer", "Computer"),
+    ("detail.toggle", "Details"),
+    ("detail.title", "Object properties"),
+    ("detail.no_selection", "Select a table row"),
+    ("detail.unsupported", "Rows of this category are not WMI objects"),
+    ("detail.loading", "Querying properties…"),
+    ("detail.missing", "The object no longer exists"),
+    ("detail.copy", "Copy value"),
+    ("process.terminate", "Terminate process…"),
+    ("process.terminate_title", "Terminate process"),
+    ("process.confirm", "Terminate the process? Any unsaved data in it will be lost."),
+    ("process.terminate_button", "Terminate"),
+    ("process.terminating", "Terminating the process…"),
+    ("process.close", "Close"),
+    ("service.start", "▶ Start"),
+    ("service.stop", "■ Stop"),
+    ("service.restart", "⟳ Restart"),
+    ("service.needs_admin", "Controlling services requires administrator rights. Run the program as administrator"),
+    ("service.working", "{name}: running the command…"),
+    ("service.done", "Service {name}: {state}"),
+    ("service.dependents", "Service {service} cannot be stopped: running services {dependents} depend on it"),
+    ("service.dependents_hint", "Stop the dependent services first"),
+    ("service.code.not_supported", "the request is not supported"),
+    ("service.code.access_denied", "access denied"),
+    ("service.code.cannot_accept", "the service cannot accept this control now"),
+    ("service.code.not_active", "the service is not running"),
+    ("service.code.timeout", "the service did not respond in time"),
+    ("service.code.already_running", "the service is already running"),
+    ("service.code.database_locked", "the service database is locked"),
+    ("service.code.dependency_failed", "a service it depends on failed to start"),
+    ("service.code.disabled", "the service is disabled"),
+    ("service.code.logon_failed", "the service account could not log on"),
+    ("service.code.marked_for_deletion", "the service is marked for deletion"),
+    ("process.terminated", "Process {name} (PID {pid}) terminated"),
+    ("process.exited", "The process has already exited; the process list was refreshed"),
+    ("process.owners_loading", "Fetching owners: {count} left"),
+    (
+        "process.denied",
+        "no permission to terminate: the process is protected by the system or runs as another user",
+    ),
+    ("results.empty", "Select a category to display data"),
+    ("results.stale", "Data from {time}, the last query failed"),
+    ("results.failed", "The query failed"),
+    ("results.dismiss", "Dismiss"),
+    ("filter.label", "Filter:"),
+    ("filter.regex", "Regular expression"),
+    ("hunt.label", "Search command lines:"),
+    ("hunt.hint", "e.g. -enc or mimikatz"),
+    ("hunt.found", "Processes found: {count}"),
+    ("hunt.hidden", "Processes with a hidden command line: {count}"),
+    ("hunt.hidden_hint", "Command lines of other users' processes are visible only with administrator rights"),
+    ("filter.column", "Only column"),
+    ("table.copy_row", "Copy row"),
+    ("table.copy_selected", "Copy selected ({count})"),
+    ("table.no_details", "No other properties"),
+    ("table.copy_cell", "Copy cell"),
+    ("table.open_folder", "Open folder: {column}"),
+    ("table.open_folder_failed", "Could not open the folder of {path}: {error}"),
+    ("table.virustotal", "Look up {column} on VirusTotal"),
+    ("table.copy_all", "Copy all"),
+    ("table.page_size", "Rows per page:"),
+    ("table.page_all", "All"),
+    ("table.page", "page {page} of {pages}"),
+    ("export.csv", "Export CSV"),
+    ("export.json", "Export JSON"),
+    ("export.json_all", "Export JSON (all categories)"),
+    ("export.sbom", "CycloneDX SBOM"),
+    ("export.ioc", "Export indicators"),
+    ("export.ioc_csv", "IOC list (CSV)"),
+    ("export.ioc_stix", "STIX 2.1 bundle (JSON)"),
+    ("export.ioc_hint", "File hashes, file paths and network addresses from the current findings of all categories, without duplicates"),
+    ("export.no_indicators", "The current findings contain no hashes, paths or network addresses"),
+    ("export.no_data", "Nothing to export"),
+    ("export.page_only", "Current page only"),
+    ("export.selected_only", "Selected rows only"),
+    ("export.saved", "Saved: {path}"),
+    ("export.failed", "Could not write {path}: {error}"),
+    ("export.cancelled", "Save cancelled"),
+    ("severity.info", "Info"),
+    ("severity.low", "Low"),
+    ("severity.medium", "Medium"),
+    ("severity.high", "High"),
+    ("severity.critical", "Critical"),
+    ("check.device_status", "Device status"),
+    ("finding.device_status", "Device in state {state}"),
+    ("check.problem_devices", "Problem devices"),
+    ("finding.problem_device", "Device Manager error code {code}"),
+    ("check.problem_device_count", "Problem device count"),
+    ("finding.problem_device_count", "Problem devices: {count}"),
+    ("check.dirty_volumes", "Dirty volumes"),
+    ("finding.dirty_volume", "Dirty bit is set: the volume was not dismounted cleanly or is corrupted"),
+    ("check.long_uptime", "Workstations not rebooted for over 30 days"),
+    ("check.stale_reboot", "Reboot postponed after updates"),
+    ("finding.long_uptime", "Workstation has not been rebooted for {days} days"),
+    ("finding.long_uptime_detail", "Last boot: {boot}. Updates that need a reboot are not in effect"),
+    ("finding.stale_reboot", "Reboot pending for {days} days since the latest update"),
+    ("check.placeholder_identity", "Placeholder hardware identifiers"),
+    ("finding.placeholder_identity", "{field}: vendor default value, unreliable identifier"),
+    ("check.never_sleeps", "Active power plan without sleep"),
+    ("finding.never_sleeps", "The computer never sleeps"),
+    ("check.no_wake_password", "Password on wake in the active power plan"),
+    ("finding.no_wake_password", "Password on wakeup is not required"),
+    ("finding.power_plan_detail", "Active plan: {plan}"),
+    ("check.prefetch_missing", "Prefetch directory missing"),
+    ("finding.prefetch_missing", "The Prefetch directory is missing"),
+    ("finding.prefetch_missing_detail", "Prefetch is disabled (EnablePrefetcher = 0, SSD policy or a server edition) or its traces were removed"),
+    ("check.recycle_truncated", "Recycle Bin shown partially"),
+    ("finding.recycle_truncated", "Only the most recently deleted items are shown"),
+    ("finding.recycle_truncated_detail", "{shown} of {total} shown; older items are not listed"),
+    ("check.alternate_streams", "Non-standard NTFS streams"),
+    ("finding.alternate_stream", "Alternate stream \"{stream}\""),
+    ("check.risky_extensions", "Extensions with high-risk permissions"),
+    ("finding.risky_extension", "Extension \"{name}\" ({browser}) requests high-risk permissions"),
+    ("finding.risky_extension_detail", "{permissions}; user {user}"),
+    ("check.defender_exclusions", "Broad Defender exclusions"),
+    ("finding.defender_exclusion", "Broad Defender exclusion ({kind}): {exclusion}"),
+    ("finding.defender_hidden", "Defender exclusions ({kind}) were not checked"),
+    ("check.packet_capture", "Packet capture drivers"),
+    ("finding.packet_capture", "The adapter is exposed to the {driver} packet capture driver"),
+    ("report.section", "Report"),
+    ("report.categories", "Categories:"),
+    ("report.checks", "Checks:"),
+    ("report.format", "Format:"),
+    ("report.generate", "Generate report"),
+    ("report.builder", "Report builder…"),
+    ("report.profile", "Profile:"),
+    ("report.no_profile", "(no profile)"),
+    ("report.profile_name", "Profile name:"),
+    ("report.save_profile", "Save profile"),
+    ("report.delete_profile", "Delete profile"),
+    ("report.all_checks", "Select all security checks"),
+    ("report.move_up", "Move up"),
+    ("report.move_down", "Move down"),
+    (
+        "report.selection",
+        "Sections: {categories}, checks: {checks}, format {format}",
+    ),
+    ("report.running", "Generating report…"),
+    ("report.saved", "Report saved: {path}"),
+    ("report.aborted", "Report generation was interrupted"),
+    ("report.title", "WMI report"),
+    ("report.summary", "Summary"),
+    ("report.host", "Computer"),
+    ("report.os", "OS"),
+    ("report.time", "Time"),
+    ("report.findings", "Findings"),
+    ("report.findings_count", "Findings: {severity}"),
+    ("report.severity", "Severity"),
+    ("report.category", "Category"),
+    ("report.description", "Description"),
+    ("report.detail", "Details"),
+    ("report.query_error", "Query failed:"),
+    (
+        "report.truncated",
+        "Showing the first {shown} of {total} rows",
+    ),
+];
@@ -0,0 +1,465 @@
+use crate::export::{csv_line, UTF8_BOM};
+use crate::findings::{self, Finding};
+use crate::model::{ColumnKind, ResultSet, Value};
+use crate::ActiveData;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use regex::Regex;
+use serde_json::json;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+// Пространство имён UUIDv5 из спецификации STIX 2.1: одинаковый индикатор
+// получает одинаковый идентификатор в разных выгрузках
+const STIX_NAMESPACE: Uuid = Uuid::from_u128(0x00abedb4_aa42_466c_9c01_fed23315a9b7);
+
+/// Формат выгрузки индикаторов
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Stix, // Пакет STIX 2.1 (JSON)
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Stix => "json",
+        }
+    }
+}
+
+/// Тип индикатора компрометации
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Md5,
+    Sha1,
+    Sha256,
+    FilePath,
+    Ipv4,
+    Ipv6,
+}
+
+impl Kind {
+    /// Имя типа в CSV; совпадает с именами объектов и хешей STIX, где это возможно
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Md5 => "md5",
+            Kind::Sha1 => "sha1",
+            Kind::Sha256 => "sha256",
+            Kind::FilePath => "file-path",
+            Kind::Ipv4 => "ipv4-addr",
+            Kind::Ipv6 => "ipv6-addr",
+        }
+    }
+}
+
+/// Находка вместе с тем, где и когда получены данные, на которых она сработала
+#[derive(Debug, Clone)]
+pub struct Observed {
+    pub finding: Finding,
+    pub host: String,
+    pub seen: DateTime<Local>,
+    pub evidence: Vec<String>, // Ячейки строки, в которых ищутся индикаторы
+}
+
+/// Индикатор без повторов: все находки и компьютеры, где он встретился
+#[derive(Debug, Clone, PartialEq)]
+pub struct Indicator {
+    pub kind: Kind,
+    pub value: String,
+    pub context: Vec<String>, // «Категория: находка»
+    pub first_seen: DateTime<Local>,
+    pub hosts: Vec<String>,
+}
+
+/// Ячейки строки, где могут быть индикаторы: пути, хеши и сетевые адреса.
+/// Прочие столбцы (версии, описания) не берутся, чтобы не принять версию за адрес
+pub fn evidence(set: &ResultSet, row: usize) -> Vec<String> {
+    let Some(cells) = set.rows.get(row) else {
+        return Vec::new();
+    };
+    set.columns
+        .iter()
+        .zip(cells)
+        .filter(|(column, value)| {
+            **value != Value::Null
+                && (column.kind() != ColumnKind::Plain
+                    || column.name.ends_with("Address")
+                    || column.name.ends_with("Addresses"))
+        })
+        .map(|(_, value)| value.display().into_owned())
+        .collect()
+}
+
+/// Находки проверок категории `data` вместе с ячейками их строк, по порядку строк.
+/// `host` и `seen` — компьютер и время, с которых и когда получены данные
+pub fn observed(
+    data: ActiveData,
+    set: &ResultSet,
+    host: &str,
+    seen: DateTime<Local>,
+) -> Vec<Observed> {
+    let mut rows: Vec<_> = findings::row_findings(data, set).into_iter().collect();
+    rows.sort_unstable_by_key(|(row, _)| *row);
+    let mut observed = Vec::new();
+    for (row, found) in rows {
+        let evidence = evidence(set, row);
+        observed.extend(found.into_iter().map(|finding| Observed {
+            finding,
+            host: host.to_string(),
+            seen,
+            evidence: evidence.clone(),
+        }));
+    }
+    observed
+}
+
+fn path_pattern() -> &'static Regex {
+    static PATH: OnceLock<Regex> = OnceLock::new();
+    // Путь в кавычках может содержать пробелы; без кавычек — до первого пробела
+    PATH.get_or_init(|| {
+        Regex::new(r#"(?i)"((?:[a-z]:|\\\\[^\\\s"]+)\\[^"\r\n]*)"|\b[a-z]:\\[^\s"]*|\\\\[^\\\s"]+\\[^\s"]+"#)
+            .unwrap()
+    })
+}
+
+fn hash_pattern() -> &'static Regex {
+    static HASH: OnceLock<Regex> = OnceLock::new();
+    HASH.get_or_init(|| Regex::new(r"(?i)\b(?:[0-9a-f]{64}|[0-9a-f]{40}|[0-9a-f]{32})\b").unwrap())
+}
+
+// Индикаторы в тексте в порядке появления. Хеши и адреса ищутся вне путей,
+// чтобы часть имени файла не стала отдельным индикатором
+fn scan(text: &str) -> Vec<(Kind, String)> {
+    // Ячейка целиком — путь без кавычек и ключей (ExecutablePath): пробелы — часть имени
+    let whole = text.trim();
+    if !whole.contains('"')
+        && !whole.contains(" /")
+        && !whole.contains(" -")
+        && path_pattern().find(whole).is_some_and(|m| m.start() == 0)
+    {
+        return vec![(Kind::FilePath, whole.to_string())];
+    }
+    let mut found = Vec::new();
+    let mut rest = text.to_string();
+    for m in path_pattern().captures_iter(text) {
+        let whole = m.get(0).unwrap();
+        let path = m.get(1).unwrap_or(whole).as_str();
+        let path = path.trim().trim_end_matches([',', ';', ')', '.']);
+        if !path.is_empty() {
+            found.push((Kind::FilePath, path.to_string()));
+        }
+        rest.replace_range(whole.range(), &" ".repeat(whole.len()));
+    }
+    for m in hash_pattern().find_iter(&rest) {
+        let kind = match m.len() {
+            32 => Kind::Md5,
+            40 => Kind::Sha1,
+            _ => Kind::Sha256,
+        };
+        found.push((kind, m.as_str().to_ascii_lowercase()));
+    }
+    let separators = |c: char| c.is_whitespace() || ",;()[]{}\"'=".contains(c);
+    for token in rest.split(separators) {
+        let token = token.trim_end_matches('.');
+        let ip = token
+            .parse::<SocketAddr>()
+            .map(|a| a.ip())
+            .or_else(|_| token.parse::<IpAddr>());
+        // Локальные и «любые» адреса ничего не говорят о чужом узле
+        match ip {
+            Ok(ip) if ip.is_loopback() || ip.is_unspecified() => {}
+            Ok(IpAddr::V4(ip)) => found.push((Kind::Ipv4, ip.to_string())),
+            Ok(IpAddr::V6(ip)) => found.push((Kind::Ipv6, ip.to_string())),
+            Err(_) => {}
+        }
+    }
+    found
+}
+
+/// Индикаторы из находок без повторов. Пути сравниваются без учёта регистра,
+/// как в Windows; самое раннее время получения данных — время первого появления
+pub fn indicators(observed: &[Observed]) -> Vec<Indicator> {
+    let mut result: Vec<Indicator> = Vec::new();
+    for item in observed {
+        let finding = &item.finding;
+        let context = format!("{}: {}", finding.category, finding.title);
+        let texts = [&finding.title, &finding.detail]
+            .into_iter()
+            .chain(&item.evidence);
+        for (kind, value) in texts.flat_map(|text| scan(text)) {
+            let key = value.to_lowercase();
+            let same = |i: &Indicator| i.kind == kind && i.value.to_lowercase() == key;
+            let indicator = match result.iter().position(same) {
+                Some(index) => &mut result[index],
+                None => {
+                    result.push(Indicator {
+                        kind,
+                        value,
+                        context: Vec::new(),
+                        first_seen: item.seen,
+                        hosts: Vec::new(),
+                    });
+                    result.last_mut().unwrap()
+                }
+            };
+            indicator.first_seen = indicator.first_seen.min(item.seen);
+            if !indicator.context.contains(&context) {
+                indicator.context.push(context.clone());
+            }
+            if !indicator.hosts.contains(&item.host) {
+                indicator.hosts.push(item.host.clone());
+            }
+        }
+    }
+    result
+}
+
+fn utc(time: DateTime<Local>) -> String {
+    time.with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// CSV для обмена: имена столбцов не переводятся, время — в UTC
+pub fn to_csv(indicators: &[Indicator]) -> String {
+    let header = ["type", "value", "context", "first_seen", "host"];
+    let mut out = csv_line(header.into_iter().map(Into::into));
+    out.push_str("\r\n");
+    for i in indicators {
+        let cells = [
+            i.kind.name().to_string(),
+            i.value.clone(),
+            i.context.join("; "),
+            utc(i.first_seen),
+            i.hosts.join("; "),
+        ];
+        out.push_str(&csv_line(cells.into_iter().map(Into::into)));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+// Строка в шаблоне STIX: обратная косая черта и апостроф экранируются
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn stix_pattern(indicator: &Indicator) -> String {
+    let value = &indicator.value;
+    match indicator.kind {
+        Kind::Md5 => format!("[file:hashes.MD5 = {}]", quote(value)),
+        Kind::Sha1 => format!("[file:hashes.'SHA-1' = {}]", quote(value)),
+        Kind::Sha256 => format!("[file:hashes.'SHA-256' = {}]", quote(value)),
+        Kind::FilePath => match value.trim_end_matches('\\').rsplit_once('\\') {
+            Some((dir, name)) if !name.is_empty() => format!(
+                "[file:name = {} AND file:parent_directory_ref.path = {}]",
+                quote(name),
+                quote(dir)
+            ),
+            _ => format!("[directory:path = {}]", quote(value)),
+        },
+        Kind::Ipv4 => format!("[ipv4-addr:value = {}]", quote(value)),
+        Kind::Ipv6 => format!("[ipv6-addr:value = {}]", quote(value)),
+    }
+}
+
+/// Минимальный пакет STIX 2.1: по объекту indicator на каждый индикатор.
+/// `created` — время выгрузки
+pub fn to_stix(indicators: &[Indicator], created: DateTime<Local>) -> serde_json::Value {
+    let created = created
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+    let objects: Vec<_> = indicators
+        .iter()
+        .map(|i| {
+            let pattern = stix_pattern(i);
+            let id = Uuid::new_v5(&STIX_NAMESPACE, pattern.as_bytes());
+            json!({
+                "type": "indicator",
+                "spec_version": "2.1",
+                "id": format!("indicator--{id}"),
+                "created": created,
+                "modified": created,
+                "name": format!("{}: {}", i.kind.name(), i.value),
+                "description": i.context.join("; "),
+                "indicator_types": ["anomalous-activity"],
+                "pattern": pattern,
+                "pattern_type": "stix",
+                "valid_from": i.first_seen
+                    .with_timezone(&Utc)
+                    .to_rfc3339_opts(SecondsFormat::Millis, true),
+                "labels": i.hosts,
+            })
+        })
+        .collect();
+    let ids: Vec<&str> = objects.iter().filter_map(|o| o["id"].as_str()).collect();
+    let bundle = Uuid::new_v5(
+        &STIX_NAMESPACE,
+        format!("{created}|{}", ids.join("|")).as_bytes(),
+    );
+    json!({
+        "type": "bundle",
+        "id": format!("bundle--{bundle}"),
+        "objects": objects,
+    })
+}
+
+pub fn write(
+    path: &Path,
+    format: Format,
+    indicators: &[Indicator],
+    created: DateTime<Local>,
+) -> std::io::Result<()> {
+    let text = match format {
+        Format::Csv => format!("{UTF8_BOM}{}", to_csv(indicators)),
+        Format::Stix => serde_json::to_string_pretty(&to_stix(indicators, created))?,
+    };
+    std::fs::write(path, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categories;
+    use crate::i18n::trf;
+    use crate::source::Fixtures;
+    use chrono::TimeZone;
+    use futures::executor::block_on;
+
+    fn at(hour: u32) -> DateTime<Local> {
+        Utc.with_ymd_and_hms(2024, 5, 1, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    // Находки настоящих проверок: исключения Defender с двух компьютеров,
+    // адаптеры с драйвером перехвата и поток с расширением сценария
+    fn sample() -> Vec<Observed> {
+        let fixtures = Fixtures::new("workstation");
+        let exclusions = block_on(categories::defender_exclusions(&fixtures)).unwrap();
+        let adapters = block_on(categories::network_adapters(&fixtures, None)).unwrap();
+        let mut streams = ResultSet::new(&["FilePath", "Stream", "StreamSize"]);
+        streams.rows.push(vec![
+            r"C:\Users\user\Downloads\report.docx".into(),
+            "hidden.ps1".into(),
+            Value::Int(26),
+        ]);
+        let mut found = observed(ActiveData::DefenderExclusions, &exclusions, "WS-01", at(10));
+        found.extend(observed(
+            ActiveData::NetworkAdapters,
+            &adapters,
+            "WS-02",
+            at(9),
+        ));
+        found.extend(observed(
+            ActiveData::DefenderExclusions,
+            &exclusions,
+            "WS-02",
+            at(8),
+        ));
+        found.extend(observed(ActiveData::Streams, &streams, "WS-02", at(11)));
+        found
+    }
+
+    fn brief(found: &[Indicator]) -> Vec<(Kind, &str)> {
+        found.iter().map(|i| (i.kind, i.value.as_str())).collect()
+    }
+
+    // Контекст индикатора, как его пишет выгрузка
+    fn context(data: ActiveData, title: String) -> String {
+        format!("{}: {title}", data.title())
+    }
+
+    fn root_exclusion() -> String {
+        let fixtures = Fixtures::new("workstation");
+        let set = block_on(categories::defender_exclusions(&fixtures)).unwrap();
+        let kind = set.value(0, "ExclusionType").unwrap().to_string();
+        context(
+            ActiveData::DefenderExclusions,
+            trf(
+                "finding.defender_exclusion",
+                &[("kind", &kind), ("exclusion", &"C:\\")],
+            ),
+        )
+    }
+
+    #[test]
+    fn indicators_are_extracted_and_deduplicated() {
+        let found = indicators(&sample());
+        assert_eq!(
+            brief(&found),
+            [
+                (Kind::FilePath, "C:\\"),
+                (Kind::Ipv4, "192.168.1.20"),
+                (Kind::Ipv6, "fe80::1c2d:3e4f:5a6b:7c8d"),
+                (Kind::Ipv4, "169.254.10.5"),
+                (Kind::FilePath, r"C:\Users\user\Downloads\report.docx"),
+            ]
+        );
+        // Исключение с обоих компьютеров — один индикатор с самым ранним временем
+        let root = &found[0];
+        assert_eq!(root.first_seen, at(8));
+        assert_eq!(root.hosts, ["WS-01", "WS-02"]);
+        assert_eq!(root.context, [root_exclusion()]);
+    }
+
+    #[test]
+    fn versions_and_device_ids_are_not_indicators() {
+        assert!(scan(r"PCI\VEN_8086&DEV_A0F0&SUBSYS_00000000 10.0.19041").is_empty());
+        assert_eq!(scan(r"\\srv\share\tool.exe")[0].1, r"\\srv\share\tool.exe");
+    }
+
+    #[test]
+    fn csv_format_is_stable() {
+        let found = indicators(&sample());
+        let context = root_exclusion();
+        let cells = [
+            "file-path",
+            "C:\\",
+            &context,
+            "2024-05-01T08:00:00Z",
+            "WS-01; WS-02",
+        ];
+        assert_eq!(
+            to_csv(&found[..1]),
+            format!(
+                "type;value;context;first_seen;host\r\n{}\r\n",
+                csv_line(cells.into_iter().map(Into::into))
+            )
+        );
+    }
+
+    #[test]
+    fn stix_bundle_round_trips() {
+        let found = indicators(&sample());
+        let text = serde_json::to_string(&to_stix(&found, at(12))).unwrap();
+        let bundle: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(bundle["type"], "bundle");
+        assert!(bundle["id"].as_str().unwrap().starts_with("bundle--"));
+        let objects = bundle["objects"].as_array().unwrap();
+        let patterns: Vec<_> = objects
+            .iter()
+            .map(|o| o["pattern"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            patterns,
+            [
+                r"[directory:path = 'C:\\']",
+                "[ipv4-addr:value = '192.168.1.20']",
+                "[ipv6-addr:value = 'fe80::1c2d:3e4f:5a6b:7c8d']",
+                "[ipv4-addr:value = '169.254.10.5']",
+                r"[file:name = 'report.docx' AND file:parent_directory_ref.path = 'C:\\Users\\user\\Downloads']",
+            ]
+        );
+        let root = &objects[0];
+        assert_eq!(root["spec_version"], "2.1");
+        assert_eq!(root["valid_from"], "2024-05-01T08:00:00.000Z");
+        assert_eq!(root["created"], "2024-05-01T12:00:00.000Z");
+        assert_eq!(root["description"], root_exclusion());
+        // Идентификатор зависит только от шаблона
+        let again = to_stix(&found[..1], at(13));
+        assert_eq!(again["objects"][0]["id"], root["id"]);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::config;
+use crate::i18n::tr;
+use crate::model::{ResultSet, Value};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+// Сколько последних записей журнала хранится в памяти
+const CAPACITY: usize = 2000;
+
+const FILE_PREFIX: &str = "wmi-lab.log";
+
+/// Уровень подробности журнала
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            LogLevel::Error => tr("log.error"),
+            LogLevel::Warn => tr("log.warn"),
+            LogLevel::Info => tr("log.info"),
+            LogLevel::Debug => tr("log.debug"),
+        }
+    }
+
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+        }
+    }
+
+    // Записи уровня trace показываются вместе с debug
+    fn of(level: Level) -> Self {
+        match level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+/// Настройки журнала. Пустой каталог — рядом с исполняемым файлом
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub level: LogLevel,
+    pub dir: String,
+}
+
+impl Settings {
+    /// Каталог файлов журнала
+    pub fn dir(&self) -> PathBuf {
+        if !self.dir.trim().is_empty() {
+            return PathBuf::from(self.dir.trim());
+        }
+        config::exe_dir()
+    }
+
+    /// Поля настройки для меню параметров. Уровень меняется сразу,
+    /// каталог — после перезапуска
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("log.level_label"));
+            let previous = self.level;
+            level_combo(ui, "log_level", &mut self.level);
+            if self.level != previous {
+                set_level(self.level);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("log.dir_label"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.dir)
+                    .hint_text(tr("log.dir_default"))
+                    .desired_width(200.0),
+            );
+        });
+        ui.weak(tr("log.dir_restart"));
+    }
+}
+
+/// Выпадающий список уровней
+pub fn level_combo(ui: &mut egui::Ui, id: &str, level: &mut LogLevel) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(level.title())
+        .show_ui(ui, |ui| {
+            for option in LogLevel::ALL {
+                ui.selectable_value(level, option, option.title());
+            }
+        });
+}
+
+/// Запись журнала в памяти
+#[derive(Debug, Clone)]
+struct Entry {
+    time: DateTime<Local>,
+    level: LogLevel,
+    target: String,
+    message: String,
+}
+
+static ENTRIES: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+static REVISION: AtomicU64 = AtomicU64::new(0);
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Подключает запись в файл с ежедневной ротацией и в буфер в памяти.
+/// Файл пишется отдельным потоком; возвращённый guard дописывает очередь при удалении
+pub fn init(settings: &Settings) -> Option<WorkerGuard> {
+    let (filter, handle) = reload::Layer::new(settings.level.filter());
+    let appender = tracing_appender::rolling::daily(settings.dir(), FILE_PREFIX);
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(writer).with_ansi(false))
+        .with(RingLayer)
+        .try_init()
+        .ok()?;
+    let _ = LEVEL.set(handle);
+    Some(guard)
+}
+
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = LEVEL.get() {
+        let _ = handle.modify(|filter| *filter = level.filter());
+    }
+}
+
+/// Номер последней записи; меняется с каждой новой записью
+pub fn revision() -> u64 {
+    REVISION.load(Ordering::Relaxed)
+}
+
+/// Записи не подробнее `level` в виде таблицы
+pub fn result_set(level: LogLevel) -> ResultSet {
+    let mut set = ResultSet::new(&["Time", "Level", "Target", "Message"]);
+    let entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+    set.rows = entries
+        .iter()
+        .filter(|entry| entry.level <= level)
+        .map(|entry| {
+            vec![
+                entry.time.format("%H:%M:%S%.3f").to_string().into(),
+                Value::from(entry.level.title()),
+                entry.target.as_str().into(),
+                entry.message.as_str().into(),
+            ]
+        })
+        .collect();
+    set
+}
+
+// Слой, складывающий события в кольцевой буфер
+struct RingLayer;
+
+impl<S: Subscriber> Layer<S> for RingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = Message::default();
+        event.record(&mut message);
+        let metadata = event.metadata();
+        let entry = Entry {
+            time: Local::now(),
+            level: LogLevel::of(*metadata.level()),
+            target: metadata.target().to_string(),
+            message: message.0,
+        };
+        let mut entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+        REVISION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Текст события: сообщение, затем остальные поля как `имя=значение`
+#[derive(Default)]
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{value:?}"));
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.insert_str(0, value);
+        } else {
+            let _ = write!(self.0, " {}={value}", field.name());
+        }
+    }
+}
@@ -1,185 +1,2921 @@
-use serde::Deserialize;
-use windows::Win32::System::Com::{
-    CoInitializeEx, COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE,
-};
-use wmi::{COMLibrary, WMIConnection};
+mod adapters;
+mod apartment;
+mod asset;
+mod autoexport;
+mod browser;
+mod cache;
+mod categories;
+mod chart;
+mod cli;
+mod config;
+mod connection;
+mod connectors;
+mod console;
+mod cooling;
+mod cyclonedx;
+mod defender;
+mod detail;
+mod devices;
+mod envvars;
+mod envwatch;
+mod error;
+mod eventlog;
+mod export;
+mod extensions;
+mod filehash;
+mod findings;
+mod history;
+mod http;
+mod hunt;
+mod i18n;
+mod ioc;
+mod logging;
+mod model;
+mod namespaces;
+mod nav;
+mod notify;
+mod output;
+mod perf;
+mod power;
+mod process;
+mod profiles;
+mod queries;
+mod quota;
+mod recycle;
+mod report;
+mod save;
+mod schedule;
+mod service;
+mod shell;
+mod shortcuts;
+mod snapshot;
+mod source;
+mod status;
+mod streams;
+mod syslog;
+mod table;
+mod theme;
+mod tripwire;
+mod uptime;
+mod virtualization;
+mod virustotal;
+mod watch;
+mod webhook;
+mod worker;
+mod wql;
 
-#[derive(Default)]
+use apartment::Apartment;
+use browser::ClassBrowser;
+use cache::{Age, Cache, Thresholds, Ttl};
+use chart::Chart;
+use chrono::Local;
+use config::{Config, ConfigError};
+use connection::{Connection, DialogAction, Target};
+use console::Console;
+use detail::{DetailPanel, Selection};
+use envwatch::EnvWatch;
+use error::AppError;
+use findings::Severity;
+use history::History;
+use i18n::{tr, trf, Language};
+use logging::LogLevel;
+use model::{CategoryExport, ExportFile, ResultSet};
+use namespaces::{Connections, NamespacePicker};
+use nav::{Navigator, Section};
+use notify::Notifier;
+use perf::PerfStrip;
+use process::{Outcome, Owners, Process, TerminateDialog};
+use profiles::{EditorAction, Profile};
+use report::{Collected, ReportPanel};
+use save::{Choice, SaveDialog};
+use serde::{Deserialize, Serialize};
+use shortcuts::Action;
+use snapshot::Origin;
+use status::StatusBar;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+use table::{ColumnLayout, Highlight, MenuEvent, RowAction, TableView};
+use theme::{Theme, Zoom};
+use tracing_appender::non_blocking::WorkerGuard;
+use watch::Watch;
+use windows::Win32::System::Com::{COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE};
+use worker::{Ticket, Timeout, Worker};
+use wql::{Field, FieldKind, Identity, QueryFilter};
+
+// Между запусками сохраняются только активная категория и настройки таблиц
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct LabApp {
-    com_lib: Option<COMLibrary>,
-    wmi_con: Option<WMIConnection>,
-    env_vars: Vec<String>,
-    sid_counts: Vec<String>,
-    bus_info: Vec<String>,
+    #[serde(skip)]
+    wmi_con: Option<Connection>,
+    #[serde(skip)]
+    target: Target, // Каждый запуск начинается с локального компьютера
+    remote: Target, // Последний удалённый компьютер для окна подключения, без пароля
+    #[serde(skip)]
+    dialog: Option<connection::Dialog>,
+    #[serde(skip)]
+    profiles: Vec<Profile>, // Профили подключения из файла настроек
+    #[serde(skip)]
+    active_profile: Option<String>, // Профиль текущего подключения
+    #[serde(skip)]
+    profile_editor: Option<profiles::Editor>,
+    #[serde(skip)]
+    window_title: String,
+    #[serde(skip)]
+    init: InitState,
+    #[serde(skip)]
+    cache: Cache<ActiveData>, // Последний удачный результат каждой категории
+    #[serde(skip)]
+    offline: HashMap<ActiveData, Origin>, // Категории, показанные из снимка до нового запроса
+    snapshot_on_exit: bool, // Сохранять результаты при выходе и загружать при запуске
+    autoexport: autoexport::Settings,
+    #[serde(skip)]
+    snapshot_dialog: Option<SaveDialog>, // Выбор файла снимка для загрузки
+    #[serde(skip)]
+    viewer: Option<Viewer>, // Открытый для просмотра файл экспорта
+    #[serde(skip)]
+    viewer_dialog: Option<SaveDialog>,
+    #[serde(skip)]
+    failures: HashMap<ActiveData, AppError>, // Ошибка последнего запроса, пока её не закрыли
+    #[serde(skip)]
+    worker: Option<Worker>, // Поток запросов категорий, создаётся после подключения к WMI
+    #[serde(skip)]
+    pending: HashMap<ActiveData, Pending>,
+    #[serde(skip)]
+    durations: HashMap<ActiveData, Duration>, // Длительность последнего запроса категории
+    timeout: Timeout,
+    cache_ttl: Ttl,
     active_data: ActiveData, // Новое поле для отслеживания активных данных
+    views: HashMap<ActiveData, TableView>, // Фильтр и выделение для каждой категории
+    #[serde(skip)]
+    layouts: HashMap<ActiveData, ColumnLayout>, // Столбцы категорий; хранятся в файле настроек
+    query_filters: HashMap<ActiveData, QueryFilter>, // Условия WHERE для каждой категории
+    #[serde(skip)]
+    watches: HashMap<ActiveData, Watch>, // Наблюдение за категориями; период хранится в файле настроек
+    nav: Navigator,
+    tab: Tab,
+    console: Console,
+    browser: ClassBrowser,
+    namespace: NamespacePicker, // Пространство имён консоли и обзора классов
+    #[serde(skip)]
+    connections: Connections,
+    language: Language,
+    theme: Theme,
+    zoom: Zoom,
+    logging: logging::Settings,
+    log_filter: LogLevel, // Уровень записей, показываемых в категории журнала
+    notifications: notify::Settings,
+    history: history::Settings,
+    syslog: syslog::Settings,
+    #[serde(skip)]
+    forwarder: syslog::Forwarder,
+    eventlog: eventlog::Settings,
+    #[serde(skip)]
+    event_writer: eventlog::Writer,
+    webhook: webhook::Settings,
+    #[serde(skip)]
+    webhook_notifier: webhook::Notifier,
+    #[serde(skip)]
+    tripwire: tripwire::Tripwire, // Работает, пока веб-хуку нужен хотя бы один наблюдатель
+    #[serde(skip)]
+    virustotal: virustotal::Settings, // Ключ хранится в диспетчере учётных данных
+    #[serde(skip)]
+    virustotal_lookup: virustotal::Lookup,
+    streams: streams::Scanner, // Каталоги и пределы поиска потоков сохраняются
+    schedule: schedule::Settings,
+    schedule_clock: schedule::Clock,
+    #[serde(skip)]
+    scheduled: Option<Receiver<Result<(PathBuf, Collected), String>>>, // Отчёт по расписанию
+    #[serde(skip)]
+    next_run: Option<chrono::DateTime<Local>>,
+    #[serde(skip)]
+    history_db: Option<History>, // Открывается при первом обращении
+    #[serde(skip)]
+    history_view: history::Browser,
+    #[serde(skip)]
+    notifier: Notifier<ActiveData>,
+    #[serde(skip)]
+    log_revision: u64, // Номер записи журнала, на которой построена таблица
+    #[serde(skip)]
+    export_status: Option<Result<String, String>>, // Результат последнего экспорта или действия
+    export_dir: Option<PathBuf>, // Каталог последнего экспорта для диалога сохранения
+    #[serde(skip)]
+    save: Option<(Export, SaveDialog)>, // Экспорт ждёт выбора файла
+    #[serde(skip)]
+    report: ReportPanel,
+    #[serde(skip)]
+    status: StatusBar,
+    detail: DetailPanel,
+    perf: PerfStrip,
+    hunt: hunt::Hunt,    // Быстрый поиск по командным строкам процессов
+    env_watch: EnvWatch, // Сохраняется только переключатель
+    #[serde(skip)]
+    terminate: Option<TerminateDialog>, // Подтверждение завершения выбранного процесса
+    #[serde(skip)]
+    owners: Owners, // Владельцы процессов, запрошенные после списка
+    #[serde(skip)]
+    hashes: HashMap<ActiveData, filehash::Hashes>, // Хеши файлов, пока задан ключ VirusTotal
+    #[serde(skip)]
+    service: Option<service::Control>, // Команда выбранной службе, пока она выполняется
+    #[serde(skip)]
+    shortcuts_open: bool, // Окно справки по сочетаниям клавиш
+    #[serde(skip)]
+    restore_pending: bool, // Повторить запрос сохранённой категории после подключения к WMI
+    #[serde(skip)]
+    hosts: Vec<String>, // Удалённые компьютеры из файла настроек и подключённые в этот раз
+    #[serde(skip)]
+    config_path: PathBuf,
+    #[serde(skip)]
+    config: Config, // Содержимое файла настроек на момент последнего чтения или записи
+    #[serde(skip)]
+    config_status: Option<Result<String, ConfigError>>,
+    // Поля удаляются в порядке объявления, поэтому COM объявлен после всего остального:
+    // CoUninitialize вызывается после того, как освобождены все подключения
+    #[serde(skip)]
+    apartment: Option<Apartment>,
+    #[serde(skip)]
+    log_guard: Option<WorkerGuard>, // Последним, чтобы в файл попали записи о завершении
+}
+
+// Состояние подключения к WMI. После ошибки новая попытка делается только по кнопке
+// или когда служба Winmgmt снова запустилась
+#[derive(Default)]
+enum InitState {
+    #[default]
+    Pending,
+    Ready,
+    Failed {
+        error: AppError,
+        service_running: Option<bool>, // Состояние Winmgmt на момент ошибки
+        checked: Instant,
+    },
+}
+
+// Интервал проверки службы WMI после неудачного подключения
+const SERVICE_PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Что записать после выбора файла в диалоге сохранения
+enum Export {
+    Csv(ActiveData),
+    Json(Vec<ActiveData>),
+    Sbom,             // Установленные программы в формате CycloneDX
+    Ioc(ioc::Format), // Индикаторы из находок по всем категориям
+}
+
+// Действие над строкой категории
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowCommand {
+    Terminate,
+    Service(service::Command),
+}
+
+// Что сейчас разрешено делать со строками
+#[derive(Debug, Clone, Copy)]
+struct Availability {
+    busy: bool,             // Открыто окно завершения или выполняется команда службе
+    services_allowed: bool, // Локальными службами без прав администратора не управлять
+    read_only: bool,        // Данные другого компьютера из снимка
+}
+
+// Запрос категории, ожидающий ответа потока запросов
+struct Pending {
+    result: Ticket<Result<ResultSet, AppError>>, // Удаление отменяет запрос
+    started: Instant,
+    deadline: Instant,
 }
 
-#[derive(Default, PartialEq)]
+// Файл экспорта, открытый только для просмотра. Данные окна на это время
+// откладываются и возвращаются при выходе из просмотра
+struct Viewer {
+    path: PathBuf,
+    origin: Origin,
+    categories: Vec<ActiveData>, // Категории файла, только они видны в боковой панели
+    live: Live,
+}
+
+// Состояние окна до открытия файла
+struct Live {
+    cache: Cache<ActiveData>,
+    offline: HashMap<ActiveData, Origin>,
+    failures: HashMap<ActiveData, AppError>,
+    selected: HashMap<ActiveData, Option<usize>>,
+    active_data: ActiveData,
+    tab: Tab,
+}
+
+// Вкладки центральной панели
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Tab {
+    #[default]
+    Categories,
+    Console,
+    Classes,
+    History,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 enum ActiveData {
     #[default]
     None,
     EnvVars,
     SidCounts,
     BusInfo,
-}
-#[derive(Debug, Deserialize)]
-#[serde(rename = "Win32_Environment")]
-struct Win32Environment {
-    Name: String,
-    VariableValue: String,
+    ProblemDevices,
+    Cooling,
+    PortConnectors,
+    Volumes,
+    DiskQuotas,
+    Processes,
+    Services,
+    Software,
+    NetworkAdapters,
+    Prefetch,
+    RecycleBin,
+    Streams,
+    BrowserExtensions,
+    DefenderExclusions,
+    Virtualization,
+    Uptime,
+    Asset,
+    PowerPlans,
+    Log,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename = "Win32_Account")]
-struct Win32Account {
-    SIDType: u8,
-    Caption: String,
-}
+impl ActiveData {
+    const ALL: [ActiveData; 22] = [
+        ActiveData::EnvVars,
+        ActiveData::SidCounts,
+        ActiveData::BusInfo,
+        ActiveData::ProblemDevices,
+        ActiveData::Cooling,
+        ActiveData::PortConnectors,
+        ActiveData::Volumes,
+        ActiveData::DiskQuotas,
+        ActiveData::Processes,
+        ActiveData::Services,
+        ActiveData::Software,
+        ActiveData::NetworkAdapters,
+        ActiveData::Virtualization,
+        ActiveData::Uptime,
+        ActiveData::PowerPlans,
+        ActiveData::Asset,
+        ActiveData::Prefetch,
+        ActiveData::RecycleBin,
+        ActiveData::Streams,
+        ActiveData::BrowserExtensions,
+        ActiveData::DefenderExclusions,
+        ActiveData::Log,
+    ];
+
+    // Категория по короткому имени из файла экспорта
+    fn from_slug(slug: &str) -> Option<ActiveData> {
+        ActiveData::ALL.into_iter().find(|data| data.slug() == slug)
+    }
+
+    // Короткое имя категории для имён файлов экспорта
+    fn slug(self) -> &'static str {
+        match self {
+            ActiveData::None => "none",
+            ActiveData::EnvVars => "env_vars",
+            ActiveData::SidCounts => "sid_counts",
+            ActiveData::BusInfo => "bus_info",
+            ActiveData::ProblemDevices => "problem_devices",
+            ActiveData::Cooling => "cooling",
+            ActiveData::PortConnectors => "port_connectors",
+            ActiveData::Volumes => "volumes",
+            ActiveData::DiskQuotas => "disk_quotas",
+            ActiveData::Processes => "processes",
+            ActiveData::Services => "services",
+            ActiveData::Software => "software",
+            ActiveData::NetworkAdapters => "network_adapters",
+            ActiveData::Virtualization => "virtualization",
+            ActiveData::Uptime => "uptime",
+            ActiveData::Asset => "asset",
+            ActiveData::PowerPlans => "power_plans",
+            ActiveData::Prefetch => "prefetch",
+            ActiveData::RecycleBin => "recycle_bin",
+            ActiveData::Streams => "streams",
+            ActiveData::BrowserExtensions => "browser_extensions",
+            ActiveData::DefenderExclusions => "defender_exclusions",
+            ActiveData::Log => "log",
+        }
+    }
 
-// Исправленная структура для шин (класс может отличаться в вашей системе)
-#[derive(Debug, Deserialize)]
-#[serde(rename = "Win32_PnPEntity")] // Пример альтернативного класса
-struct Win32Bus {
-    DeviceID: String,
-    Status: String, // Пример другого поля
+    fn title(self) -> &'static str {
+        match self {
+            ActiveData::None => "",
+            ActiveData::EnvVars => tr("category.env_vars"),
+            ActiveData::SidCounts => tr("category.sid_counts"),
+            ActiveData::BusInfo => tr("category.bus_info"),
+            ActiveData::ProblemDevices => tr("category.problem_devices"),
+            ActiveData::Cooling => tr("category.cooling"),
+            ActiveData::PortConnectors => tr("category.port_connectors"),
+            ActiveData::Volumes => tr("category.volumes"),
+            ActiveData::DiskQuotas => tr("category.disk_quotas"),
+            ActiveData::Processes => tr("category.processes"),
+            ActiveData::Services => tr("category.services"),
+            ActiveData::Software => tr("category.software"),
+            ActiveData::NetworkAdapters => tr("category.network_adapters"),
+            ActiveData::Virtualization => tr("category.virtualization"),
+            ActiveData::Uptime => tr("category.uptime"),
+            ActiveData::Asset => tr("category.asset"),
+            ActiveData::PowerPlans => tr("category.power_plans"),
+            ActiveData::Prefetch => tr("category.prefetch"),
+            ActiveData::RecycleBin => tr("category.recycle_bin"),
+            ActiveData::Streams => tr("category.streams"),
+            ActiveData::BrowserExtensions => tr("category.browser_extensions"),
+            ActiveData::DefenderExclusions => tr("category.defender_exclusions"),
+            ActiveData::Log => tr("category.log"),
+        }
+    }
+
+    // Поля условия WHERE, доступные для категории
+    fn filter_fields(self) -> &'static [Field] {
+        match self {
+            // Пустая таблица предвыборки означает, что каталога нет; условие
+            // сделало бы её неотличимой от «ничего не нашлось», поэтому только фильтр строк
+            ActiveData::None | ActiveData::Prefetch | ActiveData::Log => &[],
+            // Таблица собирается из нескольких запросов к файлам корзин
+            ActiveData::RecycleBin => &[],
+            // Поиск потоков работает с файловой системой, а не с WMI
+            ActiveData::Streams => &[],
+            // Расширения читаются из файлов профилей браузеров
+            ActiveData::BrowserExtensions => &[],
+            // Объект настроек Defender один, исключения — элементы его массивов
+            ActiveData::DefenderExclusions => &[],
+            // Вывод делается по нескольким классам сразу
+            ActiveData::Virtualization => &[],
+            // Сведения из ОС, журнала System и реестра сводятся в несколько строк
+            ActiveData::Uptime => &[],
+            // Сводка по одному объекту каждого из нескольких классов
+            ActiveData::Asset => &[],
+            // Схемы и их параметры читаются из двух классов и реестра
+            ActiveData::PowerPlans => &[],
+            // Условие по коду ошибки задано самой категорией
+            ActiveData::ProblemDevices => &[],
+            // Строки собираются из трёх классов, два из них в одном пространстве имён
+            ActiveData::Cooling => &[],
+            // Строки — виды разъёмов, а не объекты класса
+            ActiveData::PortConnectors => &[],
+            // Диски, настройки и записи квот соединяются после запросов
+            ActiveData::DiskQuotas => &[],
+            ActiveData::EnvVars => &[
+                Field {
+                    property: "Name",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "UserName",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "SystemVariable",
+                    kind: FieldKind::Bool,
+                },
+            ],
+            ActiveData::SidCounts => &[
+                Field {
+                    property: "Domain",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "LocalAccount",
+                    kind: FieldKind::Bool,
+                },
+            ],
+            ActiveData::BusInfo => &[
+                Field {
+                    property: "DeviceID",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "Status",
+                    kind: FieldKind::Choice(&["OK", "Error", "Degraded", "Unknown"]),
+                },
+            ],
+            ActiveData::Processes => &[
+                Field {
+                    property: "Name",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "ExecutablePath",
+                    kind: FieldKind::Contains,
+                },
+            ],
+            ActiveData::Services => &[
+                Field {
+                    property: "Name",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "State",
+                    kind: FieldKind::Choice(&["Running", "Stopped", "Paused"]),
+                },
+                Field {
+                    property: "StartMode",
+                    kind: FieldKind::Choice(&["Auto", "Manual", "Disabled"]),
+                },
+            ],
+            ActiveData::Software => &[
+                Field {
+                    property: "Name",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "Vendor",
+                    kind: FieldKind::Contains,
+                },
+            ],
+            ActiveData::Volumes => &[
+                Field {
+                    property: "FileSystem",
+                    kind: FieldKind::Choice(&["NTFS", "ReFS", "FAT32", "exFAT"]),
+                },
+                Field {
+                    property: "DirtyBitSet",
+                    kind: FieldKind::Bool,
+                },
+            ],
+            // Условие относится к Win32_NetworkAdapter, настройки IP присоединяются после
+            ActiveData::NetworkAdapters => &[
+                Field {
+                    property: "Name",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "PNPDeviceID",
+                    kind: FieldKind::Contains,
+                },
+                Field {
+                    property: "NetEnabled",
+                    kind: FieldKind::Bool,
+                },
+            ],
+        }
+    }
+
+    // Класс и ключевые свойства строк для панели подробностей;
+    // у сводных строк и журнала своего объекта WMI нет
+    fn identity(self) -> Option<Identity> {
+        match self {
+            ActiveData::EnvVars => Some(Identity {
+                class: "Win32_Environment",
+                keys: &["Name", "UserName"],
+            }),
+            ActiveData::BusInfo | ActiveData::ProblemDevices => Some(Identity {
+                class: "Win32_PnPEntity",
+                keys: &["DeviceID"],
+            }),
+            ActiveData::Processes => Some(Identity {
+                class: "Win32_Process",
+                keys: &["ProcessId"],
+            }),
+            ActiveData::Services => Some(Identity {
+                class: "Win32_Service",
+                keys: &["Name"],
+            }),
+            ActiveData::Software => Some(Identity {
+                class: "Win32_InstalledWin32Program",
+                keys: &["ProgramId"],
+            }),
+            ActiveData::Volumes => Some(Identity {
+                class: "Win32_Volume",
+                keys: &["DeviceID"],
+            }),
+            // Имена файлов предвыборки совпадают с ключом CIM_DataFile только вместе с путём
+            // Строка объединяет адаптер и его настройки IP
+            ActiveData::NetworkAdapters => None,
+            ActiveData::Prefetch => None,
+            // Строка — пара файлов $I и $R, одного объекта WMI у неё нет
+            ActiveData::RecycleBin => None,
+            // Строка — манифест расширения в профиле браузера
+            ActiveData::BrowserExtensions => None,
+            // Строка — элемент массива MSFT_MpPreference
+            ActiveData::DefenderExclusions => None,
+            // Строка — признак из одного из нескольких классов
+            ActiveData::Virtualization => None,
+            // Строка — сведение из ОС, журнала или реестра
+            ActiveData::Uptime => None,
+            // Строка — свойство одного из нескольких классов
+            ActiveData::Asset => None,
+            // Строка — схема вместе с её параметрами из Win32_PowerSettingDataIndex
+            ActiveData::PowerPlans => None,
+            // Строка — экземпляр одного из классов или пояснение о классе
+            ActiveData::Cooling => None,
+            // Строка объединяет разъёмы одного вида
+            ActiveData::PortConnectors => None,
+            // Строка тома — объект Win32_QuotaSetting, строка пользователя — ассоциация
+            ActiveData::DiskQuotas => None,
+            ActiveData::None | ActiveData::SidCounts | ActiveData::Streams | ActiveData::Log => {
+                None
+            }
+        }
+    }
+
+    // Столбцы, по которым наблюдение сопоставляет строки соседних результатов.
+    // Журнал обновляется сам и в наблюдении не нуждается
+    fn watch_keys(self) -> Option<&'static [&'static str]> {
+        match self {
+            ActiveData::SidCounts => Some(&["SIDType"]),
+            ActiveData::Prefetch => Some(&["FileName"]),
+            ActiveData::NetworkAdapters => Some(&["PNPDeviceID"]),
+            ActiveData::RecycleBin => Some(&["Owner", "OriginalPath", "DeletedAt"]),
+            ActiveData::BrowserExtensions => Some(&["User", "Browser", "Profile", "Name"]),
+            ActiveData::DefenderExclusions => Some(&["ExclusionType", "Exclusion"]),
+            ActiveData::Virtualization => Some(&["Indicator", "Observed"]),
+            ActiveData::Uptime => Some(&["Code", "Observed"]),
+            ActiveData::Asset => Some(&["Source"]),
+            ActiveData::PowerPlans => Some(&["PlanGuid"]),
+            ActiveData::Cooling => Some(&["Source", "Name"]),
+            ActiveData::PortConnectors => Some(&["ConnectorKind"]),
+            ActiveData::DiskQuotas => Some(&["Volume", "User"]),
+            ActiveData::None | ActiveData::Log => None,
+            _ => self.identity().map(|identity| identity.keys),
+        }
+    }
+
+    // Столбцы, по которым упорядочены строки с равными значениями столбца сортировки
+    fn sort_keys(self) -> &'static [&'static str] {
+        self.watch_keys().unwrap_or_default()
+    }
+
+    // Когда данные категории помечаются устаревшими: жёлтым — после срока
+    // годности кэша, красным — после предела для быстро меняющихся категорий
+    fn thresholds(self, ttl: Ttl) -> Thresholds {
+        let stale = match self {
+            ActiveData::Processes => Some(Duration::from_secs(5 * 60)),
+            _ => None,
+        };
+        Thresholds {
+            aging: Some(ttl.duration()).filter(|ttl| !ttl.is_zero()),
+            stale,
+        }
+    }
+
+    // Устройства можно показать по классам, переменные окружения — по областям,
+    // корзину — по владельцам
+    fn grouped(self) -> bool {
+        matches!(
+            self,
+            ActiveData::BusInfo | ActiveData::EnvVars | ActiveData::RecycleBin
+        )
+    }
+
+    // Диаграмма по сводной таблице категории
+    fn chart(self) -> Option<Chart> {
+        match self {
+            ActiveData::SidCounts => Some(Chart {
+                label: "SIDType",
+                value: Some("Count"),
+                label_text: chart::sid_type,
+                stacked: false,
+            }),
+            // Доли состояний служб в одном столбике
+            ActiveData::Services => Some(Chart {
+                label: "State",
+                value: None,
+                label_text: chart::cell,
+                stacked: true,
+            }),
+            ActiveData::Processes => Some(Chart {
+                label: "ExecutablePath",
+                value: None,
+                label_text: chart::top_folder,
+                stacked: false,
+            }),
+            ActiveData::None
+            | ActiveData::EnvVars
+            | ActiveData::BusInfo
+            | ActiveData::ProblemDevices
+            | ActiveData::Cooling
+            | ActiveData::PortConnectors
+            | ActiveData::Volumes
+            | ActiveData::DiskQuotas
+            | ActiveData::Software
+            | ActiveData::NetworkAdapters
+            | ActiveData::Prefetch
+            | ActiveData::RecycleBin
+            | ActiveData::Streams
+            | ActiveData::BrowserExtensions
+            | ActiveData::DefenderExclusions
+            | ActiveData::Virtualization
+            | ActiveData::Uptime
+            | ActiveData::Asset
+            | ActiveData::PowerPlans
+            | ActiveData::Log => None,
+        }
+    }
+
+    // Действия над строкой `row` для кнопок над таблицей и контекстного меню;
+    // без выбранной строки действия видны, но недоступны
+    fn row_actions(
+        self,
+        set: &ResultSet,
+        row: Option<usize>,
+        can: Availability,
+    ) -> Vec<RowAction<RowCommand>> {
+        match self {
+            ActiveData::Processes => {
+                let process = row.and_then(|row| Process::from_row(set, row));
+                vec![RowAction {
+                    title: tr("process.terminate"),
+                    enabled: !can.busy && !can.read_only && process.is_some(),
+                    hint: can.read_only.then_some("snapshot.read_only"),
+                    action: RowCommand::Terminate,
+                }]
+            }
+            ActiveData::Services => {
+                let running = row
+                    .and_then(|row| service::from_row(set, row))
+                    .map(|(_, state)| state.as_deref() == Some("Running"));
+                let hint = if can.read_only {
+                    Some("snapshot.read_only")
+                } else {
+                    (!can.services_allowed).then_some("service.needs_admin")
+                };
+                let allowed = can.services_allowed && !can.busy && !can.read_only;
+                [
+                    (
+                        "service.start",
+                        service::Command::Start,
+                        running == Some(false),
+                    ),
+                    (
+                        "service.stop",
+                        service::Command::Stop,
+                        running == Some(true),
+                    ),
+                    (
+                        "service.restart",
+                        service::Command::Restart,
+                        running == Some(true),
+                    ),
+                ]
+                .into_iter()
+                .map(|(title, command, enabled)| RowAction {
+                    title: tr(title),
+                    enabled: allowed && enabled,
+                    hint,
+                    action: RowCommand::Service(command),
+                })
+                .collect()
+            }
+            ActiveData::None
+            | ActiveData::EnvVars
+            | ActiveData::SidCounts
+            | ActiveData::BusInfo
+            | ActiveData::ProblemDevices
+            | ActiveData::Cooling
+            | ActiveData::PortConnectors
+            | ActiveData::Volumes
+            | ActiveData::DiskQuotas
+            | ActiveData::Software
+            | ActiveData::NetworkAdapters
+            | ActiveData::Prefetch
+            | ActiveData::RecycleBin
+            | ActiveData::Streams
+            | ActiveData::BrowserExtensions
+            | ActiveData::DefenderExclusions
+            | ActiveData::Virtualization
+            | ActiveData::Uptime
+            | ActiveData::Asset
+            | ActiveData::PowerPlans
+            | ActiveData::Log => Vec::new(),
+        }
+    }
+
+    // Поиск потоков обходит каталоги и может идти минуты, поэтому запускается
+    // только кнопкой, а не при выборе категории или подключении
+    fn on_demand(self) -> bool {
+        self == ActiveData::Streams
+    }
+
+    // Данные мониторинга меняются постоянно и запрашиваются при каждом выборе
+    fn cached(self) -> bool {
+        self.section() != Section::Monitoring
+    }
+
+    fn section(self) -> Section {
+        match self {
+            ActiveData::None
+            | ActiveData::EnvVars
+            | ActiveData::Services
+            | ActiveData::Software
+            | ActiveData::Virtualization
+            | ActiveData::Uptime
+            | ActiveData::PowerPlans
+            | ActiveData::DiskQuotas => Section::System,
+            ActiveData::BusInfo
+            | ActiveData::ProblemDevices
+            | ActiveData::Cooling
+            | ActiveData::PortConnectors
+            | ActiveData::Volumes
+            | ActiveData::Asset => Section::Hardware,
+            ActiveData::SidCounts => Section::Accounts,
+            ActiveData::NetworkAdapters => Section::Network,
+            ActiveData::Prefetch
+            | ActiveData::RecycleBin
+            | ActiveData::Streams
+            | ActiveData::BrowserExtensions
+            | ActiveData::DefenderExclusions => Section::Security,
+            ActiveData::Processes | ActiveData::Log => Section::Monitoring,
+        }
+    }
 }
 
 impl LabApp {
-    fn init_wmi(&mut self) -> Result<(), wmi::WMIError> {
-        unsafe {
-            let coinit_flags = COINIT_APARTMENTTHREADED.0 | COINIT_DISABLE_OLE1DDE.0;
-            let hres = CoInitializeEx(None, std::mem::transmute(coinit_flags));
-
-            if hres.is_err() {
-                return Err(wmi::WMIError::HResultError {
-                    hres: hres.0 as i32,
-                });
+    // Состояние из хранилища eframe; повреждённые или отсутствующие данные дают значения по умолчанию.
+    // Настройки из файла `config_path` важнее сохранённых в хранилище
+    fn new(cc: &eframe::CreationContext<'_>, config_path: PathBuf) -> Self {
+        let mut app: LabApp = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+        for view in app.views.values_mut() {
+            view.filter.update();
+        }
+        let loaded = config::load(&config_path);
+        app.virustotal = virustotal::Settings::load();
+        app.virustotal_lookup = virustotal::Lookup::open(virustotal::path(&config_path));
+        app.config_path = config_path;
+        if let Ok(config) = &loaded {
+            app.apply_config(config.clone());
+        }
+        i18n::set_language(app.language);
+        app.theme.apply(&cc.egui_ctx);
+        app.zoom.apply(&cc.egui_ctx);
+        if app.snapshot_on_exit {
+            let path = snapshot::path(&app.config_path);
+            if path.exists() {
+                if let Err(e) = app.load_snapshot(&path) {
+                    tracing::warn!("{e}");
+                }
             }
-            self.com_lib = Some(COMLibrary::assume_initialized());
         }
-        self.wmi_con = Some(WMIConnection::with_namespace_path(
-            "root\\cimv2",
-            self.com_lib.as_ref().unwrap().clone(),
-        )?);
+        // Категория из снимка показывается без запроса
+        app.restore_pending =
+            app.active_data != ActiveData::None && !app.offline.contains_key(&app.active_data);
+        app.status = StatusBar::new();
+        app.log_guard = logging::init(&app.logging);
+        tracing::info!(version = env!("CARGO_PKG_VERSION"), "запуск");
+        if let Err(e) = loaded {
+            tracing::warn!("{e}");
+            app.config_status = Some(Err(e));
+        }
+        app
+    }
+
+    // Значения из файла настроек; язык и тему затем нужно применить к интерфейсу
+    fn apply_config(&mut self, config: Config) {
+        self.timeout = config.timeout;
+        self.cache_ttl = config.cache_ttl;
+        self.language = config.language;
+        self.theme = config.theme;
+        self.hosts = config.hosts.clone();
+        self.profiles = config.connections.clone();
+        self.report.set_categories(&config.report.categories);
+        self.report.set_profiles(config.report.profiles.clone());
+        self.logging = config.log.clone();
+        self.notifications = config.notifications.clone();
+        self.history = config.history.clone();
+        self.syslog = config.syslog.clone();
+        self.eventlog = config.eventlog.clone();
+        self.webhook = config.webhook.clone();
+        self.schedule = config.schedule.clone();
+        self.autoexport = config.autoexport.clone();
+        self.layouts = config
+            .columns
+            .iter()
+            .map(|entry| {
+                let layout = ColumnLayout {
+                    order: entry.order.clone(),
+                    hidden: entry.hidden.clone(),
+                };
+                (entry.category, layout)
+            })
+            .collect();
+        // Включённое наблюдение не прерывается, меняется только период
+        for (data, watch) in &mut self.watches {
+            watch.interval = config.watch.interval;
+            if let Some(entry) = config.watch.categories.iter().find(|e| e.category == *data) {
+                watch.interval = entry.interval;
+            }
+        }
+        for entry in &config.watch.categories {
+            self.watches
+                .entry(entry.category)
+                .or_insert_with(|| Watch::new(entry.interval));
+        }
+        uptime::set_lookback_days(config.audit.event_lookback_days);
+        self.config = config;
+    }
+
+    // Текущие настройки окна в виде файла настроек
+    fn current_config(&self) -> Config {
+        Config {
+            timeout: self.timeout,
+            cache_ttl: self.cache_ttl,
+            language: self.language,
+            theme: self.theme,
+            hosts: self.hosts.clone(),
+            connections: self.profiles.clone(),
+            report: config::ReportConfig {
+                categories: self.report.categories(),
+                profiles: self.report.profiles().to_vec(),
+            },
+            // Настраиваются только в файле
+            performance: self.config.performance.clone(),
+            watch: config::WatchConfig {
+                interval: self.config.watch.interval,
+                categories: ActiveData::ALL
+                    .into_iter()
+                    .filter_map(|category| {
+                        let watch = self.watches.get(&category)?;
+                        (watch.interval != self.config.watch.interval).then_some(
+                            config::CategoryWatch {
+                                category,
+                                interval: watch.interval,
+                            },
+                        )
+                    })
+                    .collect(),
+            },
+            audit: self.config.audit.clone(),
+            log: self.logging.clone(),
+            notifications: self.notifications.clone(),
+            history: self.history.clone(),
+            syslog: self.syslog.clone(),
+            eventlog: self.eventlog.clone(),
+            webhook: self.webhook.clone(),
+            schedule: self.schedule.clone(),
+            autoexport: self.autoexport.clone(),
+            columns: ActiveData::ALL
+                .into_iter()
+                .filter_map(|category| {
+                    let layout = self.layouts.get(&category).filter(|l| !l.is_default())?;
+                    Some(config::CategoryColumns {
+                        category,
+                        order: layout.order.clone(),
+                        hidden: layout.hidden.clone(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    // Повторное чтение файла настроек из меню параметров
+    fn reload_config(&mut self, ctx: &egui::Context) {
+        match config::load(&self.config_path) {
+            Ok(config) => {
+                self.apply_config(config);
+                self.apply_language();
+                self.theme.apply(ctx);
+                logging::set_level(self.logging.level);
+                let path = self.config_path.display();
+                self.config_status = Some(Ok(trf("config.reloaded", &[("path", &path)])));
+            }
+            Err(e) => {
+                tracing::warn!("{e}");
+                self.config_status = Some(Err(e));
+            }
+        }
+    }
+
+    // Изменённые в окне настройки записываются в файл. Пока файл не удалось прочитать,
+    // он не перезаписывается, чтобы не потерять ручную правку с ошибкой
+    fn sync_config(&mut self) {
+        if matches!(self.config_status, Some(Err(_))) {
+            return;
+        }
+        let current = self.current_config();
+        if current == self.config {
+            return;
+        }
+        if let Err(e) = config::save(&self.config_path, &current) {
+            tracing::warn!("{e}");
+            self.config_status = Some(Err(e));
+        }
+        self.config = current;
+    }
+
+    // Пункты меню параметров для файла настроек
+    fn config_menu(&mut self, ui: &mut egui::Ui) {
+        if ui.button(tr("config.reload")).clicked() {
+            self.reload_config(ui.ctx());
+        }
+        ui.weak(self.config_path.display().to_string());
+        match &self.config_status {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+            None => {}
+        }
+    }
+
+    fn init_wmi(&mut self) -> Result<(), AppError> {
+        // COM инициализируется один раз; повторная попытка только переподключается к WMI
+        if self.apartment.is_none() {
+            let apartment = Apartment::init(COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE)
+                .map_err(AppError::ComInit)?;
+            self.apartment = Some(apartment);
+        }
+        let com = self.apartment.as_ref().map(Apartment::com).unwrap();
+        self.wmi_con = Some(connection::connect(com, &self.target, "root\\cimv2")?);
+        self.worker = Some(Worker::new(self.target.clone()));
 
         Ok(())
     }
-}
 
-impl eframe::App for LabApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Лабораторная работа — WMI");
+    // Попытка подключения: при первом кадре, по кнопке повтора или после запуска службы
+    fn try_init(&mut self) {
+        self.init = match self.init_wmi() {
+            Ok(()) => {
+                tracing::info!(host = %self.target.host_name(), "подключение к WMI");
+                InitState::Ready
+            }
+            Err(error) => {
+                tracing::warn!("{}", error.detailed());
+                InitState::Failed {
+                    error,
+                    service_running: connection::wmi_service_running(),
+                    checked: Instant::now(),
+                }
+            }
+        };
+    }
+
+    // Ошибка подключения с кнопкой повтора. Пока служба WMI остановлена, её состояние
+    // проверяется раз в несколько секунд, и после запуска подключение повторяется само
+    fn init_failed(&mut self, ui: &mut egui::Ui) {
+        let InitState::Failed {
+            error,
+            service_running,
+            checked,
+        } = &mut self.init
+        else {
+            return;
+        };
+        error.show(ui);
+        let mut retry = ui.button(tr("init.retry")).clicked();
+        if *service_running == Some(false) {
+            ui.weak(tr("init.service_stopped"));
+            if checked.elapsed() >= SERVICE_PROBE_INTERVAL {
+                *checked = Instant::now();
+                retry |= connection::wmi_service_running() == Some(true);
+            }
+            ui.ctx().request_repaint_after(SERVICE_PROBE_INTERVAL);
+        }
+        if retry {
+            self.try_init();
+        }
+    }
+
+    // Подключение к другому компьютеру. Прежнее подключение остаётся, если новое не удалось
+    fn switch_target(&mut self, target: Target) -> Result<(), AppError> {
+        let Some(com) = self.apartment.as_ref().map(Apartment::com) else {
+            return Ok(());
+        };
+        let con = connection::connect(com, &target, "root\\cimv2")?;
+        self.wmi_con = Some(con);
+        self.worker = Some(Worker::new(target.clone()));
+        self.pending.clear();
+        if !target.is_local() {
+            self.remote = Target {
+                password: String::new(),
+                ..target.clone()
+            };
+            let host = target.host.trim().to_string();
+            if !self.hosts.contains(&host) {
+                self.hosts.push(host);
+            }
+        }
+        self.target = target;
+        self.active_profile = None;
+        self.cache.clear();
+        self.owners.clear();
+        self.hashes.clear();
+        self.offline.clear();
+        self.failures.clear();
+        self.connections.clear();
+        self.browser.reset();
+        if self.active_data != ActiveData::None && !self.active_data.on_demand() {
+            self.refresh(self.active_data);
+        }
+        Ok(())
+    }
+
+    // Окно подключения открывается кнопкой с именем текущего компьютера
+    fn connection_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.dialog else {
+            return;
+        };
+        match dialog.show(ctx) {
+            Some(DialogAction::Connect(target)) => match self.switch_target(target.clone()) {
+                Ok(()) => {
+                    // Профиль относится к подключению, только если компьютер в окне не меняли
+                    let profile = self.dialog.take().and_then(|d| d.profile);
+                    if let Some(profile) = profile.filter(|p| p.host.trim() == target.host.trim()) {
+                        self.profile_connected(profile, &target.password);
+                    }
+                }
+                Err(e) => {
+                    if let Some(dialog) = &mut self.dialog {
+                        dialog.error = Some(e);
+                    }
+                }
+            },
+            Some(DialogAction::Cancel) => self.dialog = None,
+            None => {}
+        }
+    }
+
+    // Подключение по профилю. Пароль берётся из диспетчера учётных данных, а если его
+    // там нет — запрашивается в окне подключения. Прежнее подключение остаётся до удачного
+    fn use_profile(&mut self, profile: Profile) {
+        let password = if profile.current_account() {
+            Some(String::new())
+        } else if profile.save_password {
+            profile.stored_password()
+        } else {
+            None
+        };
+        let Some(password) = password else {
+            let mut dialog =
+                connection::Dialog::new(profile.target(String::new()), self.hosts.clone());
+            dialog.profile = Some(profile);
+            self.dialog = Some(dialog);
+            return;
+        };
+        match self.switch_target(profile.target(password.clone())) {
+            Ok(()) => self.profile_connected(profile, &password),
+            Err(e) => {
+                let mut dialog =
+                    connection::Dialog::new(profile.target(String::new()), self.hosts.clone());
+                dialog.error = Some(e);
+                dialog.profile = Some(profile);
+                self.dialog = Some(dialog);
+            }
+        }
+    }
+
+    // Пароль, введённый для профиля с хранением пароля, запоминается после удачного подключения
+    fn profile_connected(&mut self, profile: Profile, password: &str) {
+        if profile.save_password && !password.is_empty() {
+            if let Err(e) = profile.store_password(password) {
+                tracing::warn!(profile = profile.name, %e, "пароль не сохранён");
+            }
+        }
+        if !profile.namespace.trim().is_empty() {
+            self.namespace.current = profile.namespace.trim().to_string();
+        }
+        self.active_profile = Some(profile.name);
+    }
+
+    // Выбор профиля рядом с кнопкой подключения; последний пункт открывает окно профилей
+    fn profile_combo(&mut self, ui: &mut egui::Ui) {
+        let mut chosen = None;
+        let text = self
+            .active_profile
+            .as_deref()
+            .unwrap_or(tr("profiles.none"));
+        egui::ComboBox::from_id_salt("connection_profile")
+            .selected_text(text)
+            .show_ui(ui, |ui| {
+                for profile in &self.profiles {
+                    let active = self.active_profile.as_ref() == Some(&profile.name);
+                    let label = format!("{} — {}", profile.name, profile.host);
+                    if ui.selectable_label(active, label).clicked() {
+                        chosen = Some(profile.clone());
+                    }
+                }
+                if !self.profiles.is_empty() {
+                    ui.separator();
+                }
+                if ui.button(tr("profiles.manage")).clicked() {
+                    self.profile_editor = Some(profiles::Editor::new(self.profiles.clone()));
+                }
+            });
+        if let Some(profile) = chosen {
+            self.use_profile(profile);
+        }
+    }
+
+    fn profile_editor(&mut self, ctx: &egui::Context) {
+        let Some(editor) = &mut self.profile_editor else {
+            return;
+        };
+        match editor.show(ctx) {
+            Some(EditorAction::Save(profiles)) => {
+                self.profiles = profiles;
+                if !self
+                    .profiles
+                    .iter()
+                    .any(|p| self.active_profile.as_ref() == Some(&p.name))
+                {
+                    self.active_profile = None;
+                }
+                self.profile_editor = None;
+            }
+            Some(EditorAction::Cancel) => self.profile_editor = None,
+            None => {}
+        }
+    }
+
+    // Заголовок окна показывает, с какого компьютера получены данные
+    fn update_title(&mut self, ctx: &egui::Context) {
+        let title = match &self.viewer {
+            Some(viewer) => trf(
+                "viewer.window_title",
+                &[
+                    ("host", &viewer.origin.host),
+                    ("time", &viewer.origin.time.format("%d.%m.%Y %H:%M")),
+                ],
+            ),
+            None => trf("app.window_title", &[("host", &self.target.host_name())]),
+        };
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    // Выбор языка; заголовки уже загруженных таблиц переводятся сразу
+    fn language_selector(&mut self, ui: &mut egui::Ui) {
+        let previous = self.language;
+        egui::ComboBox::from_id_salt("language")
+            .selected_text(self.language.title())
+            .show_ui(ui, |ui| {
+                for language in Language::ALL {
+                    ui.selectable_value(&mut self.language, language, language.title());
+                }
+            })
+            .response
+            .on_hover_text(tr("app.language"));
+        if self.language != previous {
+            self.apply_language();
+        }
+    }
+
+    // Заголовки уже загруженных таблиц переводятся на текущий язык
+    fn apply_language(&mut self) {
+        i18n::set_language(self.language);
+        for set in self.cache.sets_mut() {
+            set.retitle();
+        }
+        for view in self.views.values_mut() {
+            view.clear_tips();
+        }
+        self.console.retitle();
+        self.browser.retitle();
+    }
+
+    // Тема применяется сразу, без перезапуска
+    fn theme_selector(&mut self, ui: &mut egui::Ui) {
+        let previous = self.theme;
+        egui::ComboBox::from_id_salt("theme")
+            .selected_text(self.theme.title())
+            .show_ui(ui, |ui| {
+                for theme in Theme::ALL {
+                    ui.selectable_value(&mut self.theme, theme, theme.title());
+                }
+            })
+            .response
+            .on_hover_text(tr("theme.label"));
+        if self.theme != previous {
+            self.theme.apply(ui.ctx());
+        }
+    }
+
+    // Консоль и обзор классов работают в выбранном пространстве имён.
+    // Ошибка подключения показывается, не затрагивая открытые подключения
+    fn namespace_tab(&mut self, ui: &mut egui::Ui) {
+        if self.namespace.show(ui) {
+            self.connections.retry(&self.namespace.current);
+            self.browser.reset();
+        }
+        let Some(com) = self.apartment.as_ref().map(Apartment::com) else {
+            return;
+        };
+        let con = match self
+            .connections
+            .get(com, &self.target, &self.namespace.current)
+        {
+            Ok(con) => con,
+            Err(e) => {
+                e.show(ui);
+                if ui.button(tr("namespace.retry")).clicked() {
+                    self.connections.retry(&self.namespace.current);
+                }
+                return;
+            }
+        };
+        match self.tab {
+            Tab::Console => self.console.show(ui, con),
+            Tab::Classes => self.browser.show(ui, &self.namespace.current, con),
+            Tab::Categories | Tab::History => {}
+        }
+    }
+
+    // База истории, пока запись истории включена; открывается при первом обращении
+    fn history_db(&mut self) -> Result<Option<&mut History>, String> {
+        if !self.history.enabled {
+            self.history_db = None;
+            return Ok(None);
+        }
+        if self.history_db.is_none() {
+            self.history_db = Some(History::open(&history::path(&self.config_path))?);
+        }
+        Ok(self.history_db.as_mut())
+    }
+
+    // Находки готового отчёта уходят в syslog, журнал событий и на веб-хук, данные — в историю
+    fn report_collected(&mut self, collected: Collected, kind: history::Kind) {
+        for finding in &collected.findings {
+            self.forwarder.send(&syslog::Message::finding(
+                collected.data.hostname.clone(),
+                finding,
+            ));
+            if self.eventlog.wants(finding) {
+                self.event_writer.finding(&collected.data.hostname, finding);
+            }
+            if self.webhook.wants(finding) {
+                let payload = webhook::Payload::finding(collected.data.hostname.clone(), finding);
+                self.webhook_notifier.send(&self.webhook, &payload);
+            }
+        }
+        self.record_history(collected.data, kind);
+    }
 
-            if self.wmi_con.is_none() {
-                if let Err(e) = self.init_wmi() {
-                    ui.label(format!("Ошибка инициализации WMI: {e}"));
+    // Отчёт по расписанию формируется в фоне без диалогов и не отнимает фокус;
+    // пока он идёт, очередной запуск не начинается
+    fn poll_schedule(&mut self, ctx: &egui::Context) {
+        if let Some(job) = &self.scheduled {
+            let result = match job.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(Duration::from_secs(1));
                     return;
                 }
+                Err(TryRecvError::Disconnected) => Err(tr("report.aborted").to_string()),
+            };
+            self.scheduled = None;
+            self.export_status = Some(match result {
+                Ok((path, collected)) => {
+                    tracing::info!(path = %path.display(), "отчёт по расписанию сохранён");
+                    self.report_collected(collected, history::Kind::Scheduled);
+                    Ok(trf("schedule.saved", &[("path", &path.display())]))
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "отчёт по расписанию не сформирован");
+                    Err(e)
+                }
+            });
+        }
+        let now = Local::now();
+        if self.schedule_clock.due(&self.schedule, now) {
+            self.run_scheduled();
+        }
+        self.next_run = self.schedule_clock.next_run(&self.schedule, now);
+        if let Some(wait) = self.next_run.and_then(|at| (at - now).to_std().ok()) {
+            ctx.request_repaint_after(wait);
+        }
+    }
+
+    fn run_scheduled(&mut self) {
+        let name = &self.schedule.profile;
+        let Some(profile) = self.report.profiles().iter().find(|p| &p.name == name) else {
+            tracing::warn!(profile = %name, "профиль отчёта для расписания не найден");
+            self.export_status = Some(Err(trf("schedule.no_profile", &[("name", name)])));
+            return;
+        };
+        let dir = match self.schedule.dir.trim() {
+            "" => config::exe_dir(),
+            dir => PathBuf::from(dir),
+        };
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            tracing::warn!(dir = %dir.display(), error = %e, "каталог отчётов по расписанию");
+        }
+        let extension = profile.format.extension();
+        let file_name = export::default_file_name(&self.target.host_name(), "report", extension);
+        let request = report::Request {
+            target: self.target.clone(),
+            timeout: self.timeout,
+            categories: profile.categories.clone(),
+            checks: profile.checks.clone(),
+            format: profile.format,
+            path: dir.join(file_name),
+            known: self.fresh_results(),
+        };
+        tracing::info!(profile = %profile.name, "отчёт по расписанию запущен");
+        self.scheduled = Some(report::spawn(request));
+    }
+
+    // Запись данных категорий в историю как нового запуска
+    fn record_history(&mut self, file: ExportFile, kind: history::Kind) {
+        let keep = self.history.keep_runs;
+        let keys = |slug: &str| {
+            ActiveData::from_slug(slug)
+                .and_then(ActiveData::watch_keys)
+                .unwrap_or(&[])
+        };
+        let result = match self.history_db() {
+            Ok(Some(db)) => db.record(&file, kind, keys, keep),
+            Ok(None) => return,
+            Err(e) => Err(e),
+        };
+        let status = result.map(|run| {
+            tracing::info!(
+                run,
+                categories = file.categories.len(),
+                "снимок записан в историю"
+            );
+            trf(
+                "history.saved",
+                &[("run", &run), ("count", &file.categories.len())],
+            )
+        });
+        if let Err(e) = &status {
+            tracing::warn!("{e}");
+        }
+        self.history_view.set_status(status);
+        self.history_view.reload();
+    }
+
+    // Вкладка истории не требует подключения к WMI. «Сохранить снимок» записывает
+    // свежие данные категорий, выбранных для отчёта
+    fn history_tab(&mut self, ui: &mut egui::Ui) {
+        if let Err(e) = self.history_db() {
+            self.history_view.set_status(Err(e));
+        }
+        let categories: Vec<_> = ActiveData::ALL
+            .into_iter()
+            .filter(|data| *data != ActiveData::Log)
+            .map(|data| (data.slug(), data.title()))
+            .collect();
+        let action = self
+            .history_view
+            .show(ui, self.history_db.as_ref(), &categories);
+        if let Some(history::Action::Open(run)) = &action {
+            let file = match &self.history_db {
+                Some(db) => db.export(run),
+                None => return,
+            };
+            let status = file.map(|file| match self.show_offline(&file) {
+                0 => tr("snapshot.empty").to_string(),
+                count => {
+                    self.tab = Tab::Categories;
+                    trf("snapshot.loaded", &[("count", &count)])
+                }
+            });
+            self.history_view.set_status(status);
+        }
+        if let Some(history::Action::Save) = action {
+            let known = self.fresh_results();
+            let chosen: Vec<_> = self
+                .report
+                .categories()
+                .into_iter()
+                .filter(|data| *data != ActiveData::Log)
+                .filter_map(|data| {
+                    let set = known.get(&data)?;
+                    let rows: Vec<usize> = (0..set.rows.len()).collect();
+                    Some(CategoryExport::new(data.slug(), set, &rows))
+                })
+                .collect();
+            if chosen.is_empty() {
+                self.history_view
+                    .set_status(Err(tr("history.nothing").to_string()));
+            } else {
+                let file = ExportFile::new(self.target.host_name(), chosen);
+                self.record_history(file, history::Kind::Manual);
             }
+        }
+    }
 
-            // Группа кнопок
-            ui.horizontal(|ui| {
-                if ui.button("Переменные окружения").clicked() {
-                    self.active_data = ActiveData::EnvVars;
-                    self.env_vars.clear();
-                    if let Some(wmi_con) = &self.wmi_con {
-                        match wmi_con.query::<Win32Environment>() {
-                            Ok(envs) => {
-                                self.env_vars = envs
-                                    .iter()
-                                    .map(|env| format!("{}: {}", env.Name, env.VariableValue))
-                                    .collect();
+    // Переключение категории. Данные моложе `cache_ttl` показываются без запроса,
+    // устаревшие и отсутствующие запрашиваются заново
+    fn select_category(&mut self, data: ActiveData) {
+        self.active_data = data;
+        let now = Instant::now();
+        if !self.pending.contains_key(&data)
+            && !data.on_demand()
+            && self
+                .cache
+                .needs_query(&data, data.cached(), self.cache_ttl, now)
+        {
+            self.refresh(data);
+        }
+    }
+
+    // Повторный запрос категории в потоке запросов. Прежние данные остаются на экране,
+    // пока не придёт новый результат. Выделение сбрасывается, так как строки меняются
+    fn refresh(&mut self, data: ActiveData) {
+        if let Some(view) = self.views.get_mut(&data) {
+            view.select(None);
+        }
+        self.submit_query(data);
+    }
+
+    // Запрос категории без сброса выделения: при наблюдении выделение
+    // переносится на ту же строку нового результата
+    fn submit_query(&mut self, data: ActiveData) {
+        // При просмотре файла экспорта запросы не выполняются
+        if self.viewer.is_some() {
+            return;
+        }
+        // Журнал собирается в памяти, без запроса к WMI
+        if data == ActiveData::Log {
+            self.log_revision = logging::revision();
+            self.cache
+                .insert(data, logging::result_set(self.log_filter));
+            return;
+        }
+        if data == ActiveData::Streams {
+            self.streams.start();
+            return;
+        }
+        let Some(worker) = &mut self.worker else {
+            return;
+        };
+        let filter = self
+            .query_filters
+            .get(&data)
+            .and_then(QueryFilter::applied)
+            .map(str::to_string);
+        let result = worker.submit(move |session| async move {
+            queries::run(session.connection()?, data, filter.as_deref()).await
+        });
+        let started = Instant::now();
+        let deadline = started + self.timeout.duration();
+        let pending = Pending {
+            result,
+            started,
+            deadline,
+        };
+        self.pending.insert(data, pending);
+    }
+
+    // Приём ответов потока запросов. Данные заменяются только удачным результатом,
+    // ошибка и просрочка запоминаются отдельно. После просрочки поток заменяется
+    // новым, а остальные ожидающие запросы отправляются заново
+    fn poll_queries(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        let mut expired = false;
+        let background = ctx.input(|input| {
+            let viewport = input.viewport();
+            viewport.minimized == Some(true) || viewport.focused == Some(false)
+        });
+        self.pending
+            .retain(|&data, pending| match pending.result.try_recv() {
+                Ok(result) => {
+                    self.durations.insert(data, pending.started.elapsed());
+                    match result {
+                        Ok(mut set) => {
+                            // Известные владельцы попадают в список до сравнения с прежним
+                            if data == ActiveData::Processes {
+                                self.owners.fill(&mut set);
+                            }
+                            if filehash::path_column(data).is_some() && self.virustotal.enabled() {
+                                let host = (!self.target.is_local())
+                                    .then(|| self.target.host.trim().to_string());
+                                self.hashes
+                                    .entry(data)
+                                    .or_insert_with(|| filehash::Hashes::new(data, host))
+                                    .fill(ctx, &mut set);
                             }
-                            Err(e) => {
-                                self.env_vars.push(format!("Ошибка: {e}"));
+                            let keys = data.watch_keys().unwrap_or_default();
+                            let old = self.cache.get(&data);
+                            if let Some(watch) = self.watches.get_mut(&data) {
+                                let events = watch.apply(old, &set, keys);
+                                let flagged = findings::row_findings(data, &set);
+                                for event in events {
+                                    let found = event.index.and_then(|row| flagged.get(&row));
+                                    let severity = found
+                                        .and_then(|found| found.iter().map(|f| f.severity).max())
+                                        .unwrap_or(Severity::Info);
+                                    // Веб-хук получает только события на строках
+                                    // с незаглушёнными находками выше порога
+                                    if let Some(hooked) =
+                                        found.and_then(|found| self.webhook.severity(found))
+                                    {
+                                        let payload = webhook::Payload::new(
+                                            self.target.host_name(),
+                                            event.at,
+                                            hooked,
+                                            data.title().to_string(),
+                                            event.text(),
+                                            event.row.clone(),
+                                        );
+                                        self.webhook_notifier.send(&self.webhook, &payload);
+                                    }
+                                    self.forwarder.send(&syslog::Message {
+                                        time: event.at,
+                                        severity,
+                                        kind: "watch",
+                                        category: data.slug().to_string(),
+                                        host: self.target.host_name(),
+                                        identity: event.row.clone(),
+                                        text: event.text(),
+                                    });
+                                    if background
+                                        && self.notifications.enabled
+                                        && severity >= self.notifications.min_severity
+                                    {
+                                        self.notifier.push(data, severity, event.text());
+                                    }
+                                }
                             }
+                            // Выбор нескольких строк сбрасывается, выбранная строка
+                            // переносится на ту же строку нового результата
+                            if let Some(view) = self.views.get_mut(&data) {
+                                let selected = view
+                                    .selected
+                                    .zip(old)
+                                    .and_then(|(row, old)| watch::find_row(old, &set, keys, row));
+                                view.select(selected);
+                                view.clear_tips();
+                            }
+                            self.virustotal_lookup.request(ctx, &self.virustotal, &set);
+                            self.cache.insert(data, set);
+                            self.offline.remove(&data);
+                            self.failures.remove(&data);
+                        }
+                        Err(error) => {
+                            self.failures.insert(data, error);
                         }
                     }
+                    false
                 }
+                Err(TryRecvError::Empty) if now < pending.deadline => true,
+                Err(TryRecvError::Empty) => {
+                    self.failures.insert(data, self.timeout.error());
+                    expired = true;
+                    false
+                }
+                Err(TryRecvError::Disconnected) => false,
+            });
+        if expired {
+            if let Some(worker) = &mut self.worker {
+                worker.recycle();
+            }
+            let waiting: Vec<_> = self.pending.keys().copied().collect();
+            for data in waiting {
+                self.submit_query(data);
+            }
+        }
+        if !self.pending.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
 
-                if ui.button("Статистика SID").clicked() {
-                    self.active_data = ActiveData::SidCounts;
-                    self.sid_counts.clear();
-                    if let Some(wmi_con) = &self.wmi_con {
-                        match wmi_con.query::<Win32Account>() {
-                            Ok(accounts) => {
-                                let mut counts = std::collections::HashMap::new();
-                                accounts.iter().for_each(|acc| {
-                                    *counts.entry(acc.SIDType).or_insert(0) += 1;
-                                });
-                                self.sid_counts = counts
-                                    .iter()
-                                    .map(|(k, v)| format!("Тип {}: {}", k, v))
-                                    .collect();
-                            }
-                            Err(e) => {
-                                self.sid_counts.push(format!("Ошибка: {e}"));
-                            }
-                        }
+    // Повторные запросы наблюдаемых категорий, в том числе не открытых сейчас.
+    // Пока прежний запрос не вернулся, новый не отправляется
+    fn poll_watches(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        let due: Vec<ActiveData> = self
+            .watches
+            .iter_mut()
+            .filter(|(data, _)| !self.pending.contains_key(data))
+            .filter_map(|(&data, watch)| watch.due(now).then_some(data))
+            .collect();
+        for data in due {
+            self.submit_query(data);
+        }
+        if let Some(next) = self
+            .watches
+            .values()
+            .filter_map(|w| w.until_next(now))
+            .min()
+        {
+            ctx.request_repaint_after(next);
+        }
+    }
+
+    // Всплывающие уведомления о событиях наблюдения. Щелчок по уведомлению
+    // разворачивает окно на категории, к которой оно относится
+    fn poll_notifications(&mut self, ctx: &egui::Context) {
+        self.notifier.show(ctx, ActiveData::title);
+        if let Some(data) = self.notifier.clicked() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            self.tab = Tab::Categories;
+            self.select_category(data);
+        }
+    }
+
+    // Уровень показываемых записей журнала. Таблица перестраивается при смене уровня
+    // и при появлении новых записей
+    fn log_controls(&mut self, ui: &mut egui::Ui) {
+        let previous = self.log_filter;
+        ui.horizontal(|ui| {
+            ui.label(tr("log.filter_label"));
+            logging::level_combo(ui, "log_filter", &mut self.log_filter);
+        });
+        if self.log_filter != previous || logging::revision() != self.log_revision {
+            self.refresh(ActiveData::Log);
+        }
+        ui.ctx().request_repaint_after(Duration::from_secs(1));
+    }
+
+    // Итог поиска потоков становится данными категории, как ответ на запрос
+    fn poll_streams(&mut self, ctx: &egui::Context) {
+        if self.streams.running() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        let Some((set, elapsed)) = self.streams.poll() else {
+            return;
+        };
+        let data = ActiveData::Streams;
+        self.durations.insert(data, elapsed);
+        if let Some(view) = self.views.get_mut(&data) {
+            view.select(None);
+        }
+        self.cache.insert(data, set);
+        self.offline.remove(&data);
+        self.failures.remove(&data);
+    }
+
+    // Ошибка последнего запроса над таблицей. Если данные уже были, они остаются
+    // на экране, а в сообщении указано, когда они получены
+    fn failure_banner(&mut self, ui: &mut egui::Ui) {
+        let data = self.active_data;
+        let Some(error) = self.failures.get(&data) else {
+            return;
+        };
+        let mut dismissed = false;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let message = match self.cache.fetched(&data) {
+                    Some(at) => trf("results.stale", &[("time", &at.format("%H:%M"))]),
+                    None => tr("results.failed").to_string(),
+                };
+                ui.colored_label(ui.visuals().warn_fg_color, message);
+                dismissed = ui
+                    .small_button("✖")
+                    .on_hover_text(tr("results.dismiss"))
+                    .clicked();
+            });
+            error.show(ui);
+        });
+        if dismissed {
+            self.failures.remove(&data);
+        }
+    }
+
+    // Объект выбранной строки активной категории
+    fn selection(&self) -> Selection {
+        let data = self.active_data;
+        let Some(row) = self.views.get(&data).and_then(|view| view.selected) else {
+            return Selection::None;
+        };
+        let Some(identity) = data.identity() else {
+            return Selection::Unsupported;
+        };
+        match self
+            .cache
+            .get(&data)
+            .and_then(|set| identity.query(set, row))
+        {
+            Some(query) => Selection::Object(query),
+            None => Selection::None,
+        }
+    }
+
+    // Процесс выбранной строки категории процессов
+    fn selected_process(&self) -> Option<Process> {
+        let data = ActiveData::Processes;
+        let row = self.views.get(&data)?.selected?;
+        Process::from_row(self.cache.get(&data)?, row)
+    }
+
+    // Окно завершения процесса. После завершения список процессов обновляется,
+    // в том числе если процесс успел завершиться сам
+    fn terminate_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(worker)) = (&mut self.terminate, &mut self.worker) else {
+            return;
+        };
+        match dialog.show(ctx, worker, self.timeout) {
+            Some(Outcome::Terminated(process)) => {
+                tracing::info!(pid = process.pid, name = %process.name, "процесс завершён");
+                self.export_status = Some(Ok(process::terminated_message(&process)));
+                self.terminate = None;
+                self.refresh(ActiveData::Processes);
+            }
+            Some(Outcome::Exited) => self.refresh(ActiveData::Processes),
+            Some(Outcome::Closed) => self.terminate = None,
+            None => {}
+        }
+    }
+
+    // Что сейчас разрешено делать со строками категории
+    fn availability(&self, data: ActiveData) -> Availability {
+        Availability {
+            busy: self.terminate.is_some() || self.service.is_some(),
+            services_allowed: !self.target.is_local() || self.status.elevated() != Some(false),
+            read_only: self.viewer.is_some()
+                || self.offline.get(&data).is_some_and(|origin| origin.foreign),
+        }
+    }
+
+    // Действие над выбранной строкой из кнопки над таблицей или контекстного меню
+    fn run_row_command(&mut self, command: RowCommand) {
+        match command {
+            RowCommand::Terminate => {
+                self.terminate = self.selected_process().map(TerminateDialog::new);
+            }
+            RowCommand::Service(command) => {
+                let data = ActiveData::Services;
+                let selected = self
+                    .views
+                    .get(&data)
+                    .and_then(|view| view.selected)
+                    .zip(self.cache.get(&data))
+                    .and_then(|(row, set)| service::from_row(set, row));
+                if let (Some((name, _)), Some(worker)) = (selected, &mut self.worker) {
+                    let control = service::Control::start(worker, name, command, self.timeout);
+                    self.service = Some(control);
+                }
+            }
+        }
+    }
+
+    // Владельцы запрашиваются, только пока список процессов на экране
+    fn poll_owners(&mut self, ctx: &egui::Context) {
+        let data = ActiveData::Processes;
+        if self.tab != Tab::Categories || self.active_data != data {
+            return;
+        }
+        let Some(worker) = &mut self.worker else {
+            return;
+        };
+        let owners = &mut self.owners;
+        self.cache.update(&data, |set| owners.poll(set, worker));
+        if self
+            .cache
+            .get(&data)
+            .is_some_and(|set| self.owners.remaining(set) > 0)
+        {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+
+    // Готовые хеши вписываются в списки и сразу уходят на проверку в VirusTotal
+    fn poll_hashes(&mut self, ctx: &egui::Context) {
+        for (data, hashes) in &mut self.hashes {
+            if self.cache.update(data, |set| hashes.poll(set)) {
+                if let Some(set) = self.cache.get(data) {
+                    self.virustotal_lookup.request(ctx, &self.virustotal, set);
+                }
+            }
+        }
+    }
+
+    // Ход команды службе: каждое новое состояние сразу попадает в строку таблицы
+    fn poll_service(&mut self, ctx: &egui::Context) {
+        let (Some(control), Some(worker)) = (&mut self.service, &mut self.worker) else {
+            return;
+        };
+        let Some(progress) = control.poll(ctx, worker, self.timeout) else {
+            return;
+        };
+        let name = control.name.clone();
+        let state = match progress {
+            service::Progress::State(state) => state,
+            service::Progress::Done(Ok(state)) => {
+                tracing::info!(service = %name, command = ?control.command, %state, "команда службе выполнена");
+                self.service = None;
+                let message = trf("service.done", &[("name", &name), ("state", &state)]);
+                self.export_status = Some(Ok(message));
+                state
+            }
+            service::Progress::Done(Err(error)) => {
+                tracing::warn!(service = %name, "{}", error.detailed());
+                self.service = None;
+                let message = match error.hint() {
+                    Some(hint) => format!("{error}. {hint}"),
+                    None => error.to_string(),
+                };
+                self.export_status = Some(Err(message));
+                return;
+            }
+        };
+        self.cache.update(&ActiveData::Services, |set| {
+            service::update_state(set, &name, &state)
+        });
+    }
+
+    // Панель подробностей запрашивает объект, только пока она открыта
+    fn detail_panel(&mut self, ctx: &egui::Context) {
+        let on_categories = self.tab == Tab::Categories;
+        if self.detail.open && on_categories {
+            let selection = self.selection();
+            if let Some(worker) = &mut self.worker {
+                self.detail.select(selection, worker, self.timeout);
+            }
+        }
+        self.detail.poll(ctx, self.timeout);
+        let hunt = self
+            .hunt
+            .pattern()
+            .filter(|_| self.active_data == ActiveData::Processes);
+        self.detail.show(ctx, on_categories, hunt);
+    }
+
+    // Сочетания клавиш. Переключение категорий работает на любой вкладке,
+    // остальные действия — только над таблицей категории
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let data = self.active_data;
+        let on_categories = self.tab == Tab::Categories && data != ActiveData::None;
+        let row_selected = on_categories
+            && self
+                .views
+                .get(&data)
+                .is_some_and(|view| view.selected.is_some());
+        for action in shortcuts::read(ctx, row_selected) {
+            match action {
+                Action::Category(index) => {
+                    if let Some(&data) = nav::ordered().get(index) {
+                        self.tab = Tab::Categories;
+                        self.select_category(data);
+                    }
+                }
+                _ if !on_categories => {}
+                Action::Refresh => self.refresh(data),
+                Action::FocusFilter => {
+                    ctx.memory_mut(|memory| memory.request_focus(table::filter_id()))
+                }
+                Action::Export => {
+                    if self.save.is_none() && self.cache.get(&data).is_some() {
+                        self.start_export(ctx, Export::Csv(data));
                     }
                 }
+                Action::ClearFilter => {
+                    if let Some(view) = self.views.get_mut(&data) {
+                        view.filter.clear();
+                        view.page = 0;
+                    }
+                }
+                Action::CopyRow => {
+                    if let (Some(set), Some(view)) = (self.cache.get(&data), self.views.get(&data))
+                    {
+                        view.copy_selected(ctx, set);
+                    }
+                }
+            }
+        }
+    }
 
-                if ui.button("Информация о шинах").clicked() {
-                    self.active_data = ActiveData::BusInfo;
-                    self.bus_info.clear();
-                    if let Some(wmi_con) = &self.wmi_con {
-                        match wmi_con.query::<Win32Bus>() {
-                            Ok(buses) => {
-                                self.bus_info = buses
-                                    .iter()
-                                    .map(|bus| {
-                                        format!("ID: {}, Статус: {}", bus.DeviceID, bus.Status)
-                                    })
-                                    .collect();
-                            }
-                            Err(e) => {
-                                self.bus_info.push(format!("Ошибка: {e}"));
-                            }
-                        }
+    // Компьютер, пространство имён, права процесса и сведения об активной категории
+    fn status_bar(&self, ui: &mut egui::Ui) {
+        let host = self.target.host_name();
+        let data = self.active_data;
+        let on_categories = self.tab == Tab::Categories;
+        let rows = self.cache.get(&data).filter(|_| on_categories).map(|set| {
+            let shown = self.views.get(&data).map_or(set.rows.len(), |view| {
+                view.visible_count(set, data.sort_keys(), self.cache.revision(&data))
+            });
+            (shown, set.rows.len())
+        });
+        let selected = self
+            .views
+            .get(&data)
+            .filter(|_| on_categories && rows.is_some())
+            .map_or(0, TableView::chosen_count);
+        let info = status::Info {
+            host: &host,
+            namespace: if on_categories {
+                "root\\cimv2"
+            } else {
+                &self.namespace.current
+            },
+            rows,
+            selected,
+            duration: self.durations.get(&data).copied().filter(|_| on_categories),
+            running: self.pending.len(),
+            message: self.export_status.as_ref(),
+            next_run: self.next_run,
+            scheduled: self.scheduled.is_some(),
+        };
+        self.status.show(ui, info);
+    }
+
+    // Данные категории в порядке отображения; пустые категории пропускаются.
+    // `scoped` учитывает выбор «только текущая страница»
+    fn export_category(&self, data: ActiveData, scoped: bool) -> Option<CategoryExport> {
+        let set = self.cache.get(&data).filter(|set| !set.rows.is_empty())?;
+        let revision = self.cache.revision(&data);
+        let rows = match self.views.get(&data) {
+            Some(view) if scoped => {
+                view.export_rows(&view.visible_rows(set, data.sort_keys(), revision))
+            }
+            Some(view) => view.visible_rows(set, data.sort_keys(), revision),
+            None => (0..set.rows.len()).collect(),
+        };
+        Some(CategoryExport::new(
+            data.slug(),
+            &self.export_columns(data, set),
+            &rows,
+        ))
+    }
+
+    // Видимые столбцы в порядке таблицы либо все, если выбрано «все столбцы».
+    // Номера строк не меняются
+    fn export_columns<'a>(&self, data: ActiveData, set: &'a ResultSet) -> Cow<'a, ResultSet> {
+        let all = self.views.get(&data).is_some_and(|v| v.export_all_columns);
+        match self.layouts.get(&data).filter(|l| !all && !l.is_default()) {
+            Some(layout) => Cow::Owned(set.select_columns(&layout.visible(set))),
+            None => Cow::Borrowed(set),
+        }
+    }
+
+    fn write_json(&self, categories: &[ActiveData], path: &Path) -> Result<String, String> {
+        let exports: Vec<_> = categories
+            .iter()
+            .filter_map(|&data| self.export_category(data, categories.len() == 1))
+            .collect();
+        if exports.is_empty() {
+            return Err(tr("export.no_data").to_string());
+        }
+        let file = ExportFile::new(self.target.host_name(), exports);
+        export::outcome(path, export::write_json(path, &file))
+    }
+
+    // Все установленные программы, без учёта фильтра таблицы. Компьютер — тот,
+    // с которого получены данные, в том числе из открытого файла экспорта
+    fn write_sbom(&self, path: &Path) -> Result<String, String> {
+        let data = ActiveData::Software;
+        let Some(set) = self.cache.get(&data) else {
+            return Err(tr("export.no_data").to_string());
+        };
+        let (host, time) = match self.offline.get(&data) {
+            Some(origin) => (origin.host.clone(), origin.time),
+            None => (
+                self.target.host_name(),
+                self.cache.fetched(&data).unwrap_or_else(Local::now),
+            ),
+        };
+        let bom = cyclonedx::Bom::new(&host, time, set);
+        export::outcome(path, bom.write(path))
+    }
+
+    // Индикаторы из находок по данным всех категорий, в том числе офлайн-данным:
+    // компьютер и время — те, с которых и когда данные получены
+    fn write_ioc(&self, format: ioc::Format, path: &Path) -> Result<String, String> {
+        let mut observed = Vec::new();
+        for data in ActiveData::ALL {
+            let Some(set) = self.cache.get(&data) else {
+                continue;
+            };
+            let (host, seen) = match self.offline.get(&data) {
+                Some(origin) => (origin.host.clone(), origin.time),
+                None => (
+                    self.target.host_name(),
+                    self.cache.fetched(&data).unwrap_or_else(Local::now),
+                ),
+            };
+            observed.extend(ioc::observed(data, set, &host, seen));
+        }
+        let indicators = ioc::indicators(&observed);
+        if indicators.is_empty() {
+            return Err(tr("export.no_indicators").to_string());
+        }
+        let result = ioc::write(path, format, &indicators, Local::now());
+        export::outcome(path, result)
+    }
+
+    // Данные категорий, которые не устарели: отчёт не запрашивает их повторно.
+    // Офлайн-данные в отчёт не попадают
+    fn fresh_results(&self) -> HashMap<ActiveData, ResultSet> {
+        let now = Instant::now();
+        ActiveData::ALL
+            .into_iter()
+            .filter(|data| {
+                !self.offline.contains_key(data)
+                    && !self
+                        .cache
+                        .needs_query(data, data.cached(), self.cache_ttl, now)
+            })
+            .filter_map(|data| Some((data, self.cache.get(&data)?.clone())))
+            .collect()
+    }
+
+    // Строки CSV те же, что видны в таблице, с учётом «только текущая страница»
+    fn write_csv(&self, data: ActiveData, path: &Path) -> Result<String, String> {
+        let Some(set) = self.cache.get(&data) else {
+            return Err(tr("export.no_data").to_string());
+        };
+        let rows = match self.views.get(&data) {
+            Some(view) => {
+                let revision = self.cache.revision(&data);
+                view.export_rows(&view.visible_rows(set, data.sort_keys(), revision))
+            }
+            None => (0..set.rows.len()).collect(),
+        };
+        let set = self.export_columns(data, set);
+        export::outcome(path, export::write_csv(path, &set, &rows))
+    }
+
+    // Диалог сохранения с именем файла по умолчанию; файл пишется после выбора
+    fn start_export(&mut self, ctx: &egui::Context, job: Export) {
+        let (name, extension) = match &job {
+            Export::Csv(data) => (data.slug(), "csv"),
+            Export::Json(categories) => match categories.as_slice() {
+                [data] => (data.slug(), "json"),
+                _ => ("all", "json"),
+            },
+            Export::Sbom => ("sbom", "json"),
+            Export::Ioc(format) => ("ioc", format.extension()),
+        };
+        let file_name = export::default_file_name(&self.target.host_name(), name, extension);
+        let dialog = SaveDialog::open(ctx, file_name, extension, self.export_dir.as_deref());
+        self.save = Some((job, dialog));
+        self.export_status = None;
+    }
+
+    // Все полученные в этот раз результаты без новых запросов. Журнал собирается заново
+    // при каждом запуске, а офлайн-данные уже лежат в своём файле; `None`, если новых
+    // данных нет
+    fn session_export(&self) -> Option<ExportFile> {
+        let categories: Vec<_> = ActiveData::ALL
+            .into_iter()
+            .filter(|data| *data != ActiveData::Log && !self.offline.contains_key(data))
+            .filter_map(|data| {
+                let set = self.cache.get(&data)?;
+                let rows: Vec<usize> = (0..set.rows.len()).collect();
+                Some(CategoryExport::new(data.slug(), set, &rows))
+            })
+            .collect();
+        (!categories.is_empty()).then(|| ExportFile::new(self.target.host_name(), categories))
+    }
+
+    // Результаты в файл снимка; если новых данных нет, прежний снимок остаётся
+    fn write_snapshot(&self) {
+        let Some(file) = self.session_export() else {
+            return;
+        };
+        let path = snapshot::path(&self.config_path);
+        match export::write_json(&path, &file) {
+            Ok(()) => tracing::info!(path = %path.display(), "снимок результатов сохранён"),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "не удалось сохранить снимок")
+            }
+        }
+    }
+
+    // Результаты из снимка или файла экспорта JSON вместо текущих данных категорий.
+    // Возвращает число загруженных категорий
+    fn load_snapshot(&mut self, path: &Path) -> Result<usize, String> {
+        let file = snapshot::read(path)?;
+        let loaded = self.show_offline(&file);
+        tracing::info!(path = %path.display(), host = %file.hostname, loaded, "загружен снимок результатов");
+        Ok(loaded)
+    }
+
+    // Категории файла показываются вместо текущих данных до нового запроса
+    fn show_offline(&mut self, file: &ExportFile) -> usize {
+        let origin = Origin::new(file, &self.target.host_name());
+        let mut loaded = 0;
+        for category in &file.categories {
+            let Some(data) = ActiveData::from_slug(&category.category) else {
+                continue;
+            };
+            if data == ActiveData::Log {
+                continue;
+            }
+            self.cache.restore(
+                data,
+                category.to_result_set(),
+                origin.time.with_timezone(&Local),
+            );
+            self.offline.insert(data, origin.clone());
+            self.failures.remove(&data);
+            if let Some(view) = self.views.get_mut(&data) {
+                view.select(None);
+                view.clear_tips();
+            }
+            loaded += 1;
+        }
+        loaded
+    }
+
+    // Просмотр файла экспорта вместо данных окна. Повторное открытие заменяет
+    // просматриваемый файл, а отложенные данные окна остаются прежними
+    fn open_viewer(&mut self, path: PathBuf) -> Result<usize, String> {
+        let file = snapshot::read(&path)?;
+        let categories: Vec<ActiveData> = nav::ordered()
+            .into_iter()
+            .filter(|&data| data != ActiveData::Log)
+            .filter(|data| {
+                file.categories
+                    .iter()
+                    .any(|c| ActiveData::from_slug(&c.category) == Some(*data))
+            })
+            .collect();
+        let Some(&first) = categories.first() else {
+            return Err(tr("snapshot.empty").to_string());
+        };
+        let live = match self.viewer.take() {
+            Some(viewer) => viewer.live,
+            None => Live {
+                cache: std::mem::take(&mut self.cache),
+                offline: std::mem::take(&mut self.offline),
+                failures: std::mem::take(&mut self.failures),
+                selected: self
+                    .views
+                    .iter()
+                    .map(|(&data, view)| (data, view.selected))
+                    .collect(),
+                active_data: self.active_data,
+                tab: self.tab,
+            },
+        };
+        self.cache = Cache::default();
+        self.offline.clear();
+        self.failures.clear();
+        let loaded = self.show_offline(&file);
+        if !categories.contains(&self.active_data) {
+            self.active_data = first;
+        }
+        self.tab = Tab::Categories;
+        let origin = Origin::new(&file, &self.target.host_name());
+        tracing::info!(path = %path.display(), host = %origin.host, loaded, "открыт файл экспорта для просмотра");
+        self.viewer = Some(Viewer {
+            path,
+            origin,
+            categories,
+            live,
+        });
+        Ok(loaded)
+    }
+
+    // Возврат к данным окна в том виде, в каком они были до просмотра
+    fn close_viewer(&mut self) {
+        let Some(Viewer { live, .. }) = self.viewer.take() else {
+            return;
+        };
+        self.cache = live.cache;
+        self.offline = live.offline;
+        self.failures = live.failures;
+        for (data, view) in &mut self.views {
+            view.select(live.selected.get(data).copied().flatten());
+            view.clear_tips();
+        }
+        self.active_data = live.active_data;
+        self.tab = live.tab;
+        tracing::info!("просмотр файла экспорта закрыт");
+    }
+
+    // Файл для просмотра выбирается в меню «Файл»
+    fn poll_viewer(&mut self) {
+        let Some(dialog) = &self.viewer_dialog else {
+            return;
+        };
+        match dialog.poll() {
+            Choice::Waiting => {}
+            Choice::Cancelled => self.viewer_dialog = None,
+            Choice::Chosen(path) => {
+                self.viewer_dialog = None;
+                self.export_dir = path.parent().map(PathBuf::from);
+                self.export_status = Some(
+                    self.open_viewer(path)
+                        .map(|count| trf("viewer.opened", &[("count", &count)])),
+                );
+            }
+        }
+    }
+
+    fn file_menu(&mut self, ui: &mut egui::Ui) {
+        let idle = self.viewer_dialog.is_none();
+        if ui
+            .add_enabled(idle, egui::Button::new(tr("viewer.open")))
+            .clicked()
+        {
+            let dir = self.export_dir.as_deref();
+            self.viewer_dialog = Some(SaveDialog::pick(ui.ctx(), "json", dir));
+            ui.close_menu();
+        }
+        // Файлы автоэкспорта открываются так же, как выбранный файл экспорта
+        let latest = autoexport::latest(&self.autoexport.dir());
+        if ui
+            .add_enabled(
+                latest.is_some(),
+                egui::Button::new(tr("autoexport.open_latest")),
+            )
+            .on_disabled_hover_text(tr("autoexport.none"))
+            .clicked()
+        {
+            if let Some(path) = latest {
+                self.export_status = Some(
+                    self.open_viewer(path)
+                        .map(|count| trf("viewer.opened", &[("count", &count)])),
+                );
+            }
+            ui.close_menu();
+        }
+        if ui
+            .add_enabled(self.viewer.is_some(), egui::Button::new(tr("viewer.close")))
+            .clicked()
+        {
+            self.close_viewer();
+            ui.close_menu();
+        }
+    }
+
+    // Полоса над вкладками, пока открыт файл экспорта
+    fn viewer_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(viewer) = &self.viewer else {
+            return;
+        };
+        let mut close = false;
+        egui::Frame::new()
+            .fill(ui.visuals().warn_fg_color.gamma_multiply(0.15))
+            .inner_margin(6.0)
+            .corner_radius(4.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        trf(
+                            "viewer.banner",
+                            &[
+                                ("host", &viewer.origin.host),
+                                ("time", &viewer.origin.time.format("%d.%m.%Y %H:%M")),
+                            ],
+                        ),
+                    )
+                    .on_hover_text(viewer.path.display().to_string());
+                    close = ui.button(tr("viewer.close")).clicked();
+                });
+            });
+        if close {
+            self.close_viewer();
+        }
+    }
+
+    // Снимок для загрузки выбирается в диалоге из меню параметров
+    fn poll_snapshot(&mut self) {
+        let Some(dialog) = &self.snapshot_dialog else {
+            return;
+        };
+        match dialog.poll() {
+            Choice::Waiting => {}
+            Choice::Cancelled => self.snapshot_dialog = None,
+            Choice::Chosen(path) => {
+                self.snapshot_dialog = None;
+                self.export_status = Some(match self.load_snapshot(&path) {
+                    Ok(0) => Err(tr("snapshot.empty").to_string()),
+                    Ok(count) => Ok(trf("snapshot.loaded", &[("count", &count)])),
+                    Err(e) => Err(e),
+                });
+            }
+        }
+    }
+
+    // Результат диалога сохранения; выбранный каталог запоминается
+    fn poll_export(&mut self) {
+        let Some((_, dialog)) = &self.save else {
+            return;
+        };
+        match dialog.poll() {
+            Choice::Waiting => {}
+            Choice::Cancelled => {
+                self.save = None;
+                self.export_status = Some(Ok(tr("export.cancelled").to_string()));
+            }
+            Choice::Chosen(path) => {
+                self.export_dir = path.parent().map(PathBuf::from);
+                self.export_status = match self.save.take() {
+                    Some((Export::Csv(data), _)) => Some(self.write_csv(data, &path)),
+                    Some((Export::Json(categories), _)) => {
+                        Some(self.write_json(&categories, &path))
+                    }
+                    Some((Export::Sbom, _)) => Some(self.write_sbom(&path)),
+                    Some((Export::Ioc(format), _)) => Some(self.write_ioc(format, &path)),
+                    None => None,
+                };
+            }
+        }
+    }
+}
+
+impl eframe::App for LabApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.close_viewer();
+        if self.snapshot_on_exit {
+            self.write_snapshot();
+        }
+        // Окна уже нет, поэтому ошибка записи остаётся только в файле журнала
+        if self.autoexport.enabled {
+            if let Some(file) = self.session_export() {
+                let dir = self.autoexport.dir();
+                match autoexport::write(&dir, &file, Local::now()) {
+                    Ok(path) => tracing::info!(path = %path.display(), "автоэкспорт сохранён"),
+                    Err(e) => {
+                        tracing::error!(dir = %dir.display(), error = %e, "автоэкспорт не сохранён")
                     }
                 }
+            }
+        }
+        tracing::info!("завершение работы");
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.sync_config();
+        self.forwarder.configure(&self.syslog);
+        self.zoom.handle_keys(ctx);
+        self.update_title(ctx);
+        // Ответы на запросы ждут конца просмотра файла, чтобы попасть в данные окна
+        if self.viewer.is_none() {
+            self.poll_queries(ctx);
+            self.poll_watches(ctx);
+            self.poll_owners(ctx);
+            self.poll_hashes(ctx);
+        }
+        self.poll_notifications(ctx);
+        self.virustotal_lookup.poll();
+        self.poll_streams(ctx);
+        self.poll_export();
+        self.poll_snapshot();
+        self.poll_viewer();
+        self.poll_schedule(ctx);
+        self.handle_shortcuts(ctx);
+        self.connection_dialog(ctx);
+        self.profile_editor(ctx);
+        self.terminate_dialog(ctx);
+        self.poll_service(ctx);
+        shortcuts::help(ctx, &mut self.shortcuts_open);
+
+        let cache = &self.cache;
+        let only = self.viewer.as_ref().map(|v| v.categories.as_slice());
+        let chosen = self.nav.show(ctx, self.active_data, only, |data| {
+            cache.get(&data).map(|set| set.rows.len())
+        });
+        if let Some(data) = chosen {
+            self.tab = Tab::Categories;
+            self.select_category(data);
+        }
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| self.status_bar(ui));
+        let perf = &self.config.performance;
+        self.perf
+            .show(ctx, &self.target, perf.pause_when_hidden, perf.interval);
+        self.env_watch.poll(ctx, &self.target);
+        let hooked = tripwire::Trigger::ALL
+            .into_iter()
+            .any(|trigger| self.webhook.wants_trigger(trigger));
+        for alert in self.tripwire.poll(ctx, &self.target, hooked) {
+            if self.webhook.wants_trigger(alert.trigger) {
+                let payload = webhook::Payload::alert(self.target.host_name(), &alert);
+                self.webhook_notifier.send(&self.webhook, &payload);
+            }
+        }
+        self.detail_panel(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("☰").on_hover_text(tr("nav.toggle")).clicked() {
+                    self.nav.open = !self.nav.open;
+                }
+                ui.menu_button(tr("menu.file"), |ui| self.file_menu(ui));
+                ui.heading(tr("app.heading"));
+                self.language_selector(ui);
+                self.theme_selector(ui);
+                // Пока открыт файл экспорта, подключение не меняется
+                let live = self.viewer.is_none();
+                ui.add_enabled_ui(live, |ui| {
+                    let host = format!("🖧 {}", self.target.host_name());
+                    if ui.button(host).on_hover_text(tr("remote.title")).clicked() {
+                        let dialog =
+                            connection::Dialog::new(self.remote.clone(), self.hosts.clone());
+                        self.dialog = Some(dialog);
+                    }
+                    self.profile_combo(ui);
+                });
+                ui.menu_button("⚙", |ui| {
+                    self.zoom.show(ui);
+                    self.timeout.show(ui);
+                    self.cache_ttl.show(ui);
+                    ui.separator();
+                    self.logging.show(ui);
+                    ui.separator();
+                    self.notifications.show(ui);
+                    ui.separator();
+                    self.history.show(ui);
+                    ui.separator();
+                    self.syslog.show(ui, &mut self.forwarder);
+                    ui.separator();
+                    self.eventlog.show(ui, &mut self.event_writer);
+                    ui.separator();
+                    self.webhook.show(ui, &mut self.webhook_notifier);
+                    ui.separator();
+                    self.virustotal.show(ui, &mut self.virustotal_lookup);
+                    ui.separator();
+                    let names: Vec<String> = self
+                        .report
+                        .profiles()
+                        .iter()
+                        .map(|p| p.name.clone())
+                        .collect();
+                    self.schedule.show(ui, &names);
+                    ui.separator();
+                    ui.checkbox(&mut self.snapshot_on_exit, tr("snapshot.on_exit"))
+                        .on_hover_text(tr("snapshot.on_exit_hint"));
+                    self.autoexport.show(ui);
+                    let idle = self.snapshot_dialog.is_none();
+                    if ui
+                        .add_enabled(idle, egui::Button::new(tr("snapshot.open")))
+                        .clicked()
+                    {
+                        let dir = self.export_dir.as_deref();
+                        self.snapshot_dialog = Some(SaveDialog::pick(ui.ctx(), "json", dir));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    self.config_menu(ui);
+                })
+                .response
+                .on_hover_text(tr("settings.label"));
+                ui.toggle_value(&mut self.perf.open, "📈")
+                    .on_hover_text(tr("perf.toggle"));
+                if ui
+                    .button("?")
+                    .on_hover_text(tr("shortcuts.title"))
+                    .clicked()
+                {
+                    self.shortcuts_open = !self.shortcuts_open;
+                }
+            });
+            if let Some(Err(e)) = &self.config_status {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+
+            if matches!(self.init, InitState::Pending) {
+                self.try_init();
+            }
+            if !matches!(self.init, InitState::Ready) {
+                self.init_failed(ui);
+                return;
+            }
+
+            self.viewer_banner(ui);
+            let live = self.viewer.is_none();
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.tab, Tab::Categories, tr("tab.categories"));
+                ui.add_enabled_ui(live, |ui| {
+                    ui.selectable_value(&mut self.tab, Tab::Console, tr("tab.console"));
+                    ui.selectable_value(&mut self.tab, Tab::Classes, tr("tab.classes"));
+                    ui.selectable_value(&mut self.tab, Tab::History, tr("tab.history"));
+                })
+                .response
+                .on_disabled_hover_text(tr("viewer.disabled"));
             });
+            if self.tab == Tab::History {
+                self.history_tab(ui);
+                return;
+            }
+            if self.tab != Tab::Categories {
+                self.namespace_tab(ui);
+                return;
+            }
+
+            if self.restore_pending {
+                self.restore_pending = false;
+                if !self.active_data.on_demand() {
+                    self.refresh(self.active_data);
+                }
+            }
+
+            if let Some(status) = self.report.poll(ctx, &mut self.export_dir) {
+                self.export_status = Some(status);
+            }
+            if let Some(collected) = self.report.take_collected() {
+                self.report_collected(collected, history::Kind::Report);
+            }
+            let generate = live
+                && ui
+                    .collapsing(tr("report.section"), |ui| self.report.show(ui))
+                    .body_returned
+                    == Some(true);
+            if generate {
+                let known = self.fresh_results();
+                let dir = self.export_dir.as_deref();
+                self.report
+                    .choose_file(ctx, &self.target, self.timeout, dir, known);
+            }
 
             // Отображение результатов
             ui.separator();
-            ui.label("Результаты:");
-
-            egui::ScrollArea::vertical()
-                .id_salt("results_scroll")
-                .show(ui, |ui| match self.active_data {
-                    ActiveData::EnvVars => {
-                        for env in &self.env_vars {
-                            ui.label(env);
-                        }
-                    }
-                    ActiveData::SidCounts => {
-                        for sid in &self.sid_counts {
-                            ui.label(sid);
-                        }
+            let data = self.active_data;
+            let can = self.availability(data);
+            let selected = self.views.get(&data).and_then(|view| view.selected);
+            let actions = self
+                .cache
+                .get(&data)
+                .map(|set| data.row_actions(set, selected, can))
+                .unwrap_or_default();
+            let mut command = None;
+            ui.horizontal(|ui| {
+                ui.label(tr("results.label"));
+                if live
+                    && self.active_data != ActiveData::None
+                    && ui.button(tr("results.refresh")).clicked()
+                {
+                    self.refresh(self.active_data);
+                }
+                if let Some(origin) = self.offline.get(&self.active_data) {
+                    ui.colored_label(ui.visuals().warn_fg_color, origin.label())
+                        .on_hover_text(tr("snapshot.hint"));
+                } else if let Some(at) = self.cache.fetched(&data) {
+                    // Метка давности обновляется каждую секунду и сама служит кнопкой обновления
+                    let elapsed = self.cache.age(&data, Local::now()).unwrap_or_default();
+                    let text = trf(
+                        "results.fetched",
+                        &[
+                            ("time", &at.format("%H:%M:%S")),
+                            ("ago", &cache::ago(elapsed)),
+                        ],
+                    );
+                    let visuals = ui.visuals();
+                    let color = match data.thresholds(self.cache_ttl).age(elapsed) {
+                        Age::Fresh => visuals.weak_text_color(),
+                        Age::Aging => visuals.warn_fg_color,
+                        Age::Stale => visuals.error_fg_color,
+                    };
+                    let label =
+                        egui::Button::new(egui::RichText::new(text).color(color)).frame(false);
+                    if ui
+                        .add(label)
+                        .on_hover_text(tr("results.fetched_hint"))
+                        .clicked()
+                    {
+                        self.refresh(data);
                     }
-                    ActiveData::BusInfo => {
-                        for bus in &self.bus_info {
-                            ui.label(bus);
-                        }
+                    ui.ctx().request_repaint_after(Duration::from_secs(1));
+                }
+                if live && data.watch_keys().is_some() {
+                    let interval = self.config.watch.interval;
+                    self.watches
+                        .entry(data)
+                        .or_insert_with(|| Watch::new(interval))
+                        .controls(ui);
+                }
+                if live && data == ActiveData::EnvVars {
+                    self.env_watch.controls(ui);
+                }
+                ui.toggle_value(&mut self.detail.open, tr("detail.toggle"));
+                let owners = match self.cache.get(&data) {
+                    Some(set) if data == ActiveData::Processes => self.owners.remaining(set),
+                    _ => 0,
+                };
+                if owners > 0 && live {
+                    ui.spinner();
+                    ui.label(trf("process.owners_loading", &[("count", &owners)]));
+                }
+                let hashing = self
+                    .hashes
+                    .get(&data)
+                    .map_or(0, filehash::Hashes::remaining);
+                if hashing > 0 && live {
+                    ui.spinner();
+                    ui.label(trf("virustotal.hashing", &[("count", &hashing)]));
+                }
+                if let Some(control) = &self.service {
+                    ui.spinner();
+                    ui.label(trf("service.working", &[("name", &control.name)]));
+                }
+                for action in &actions {
+                    let mut response =
+                        ui.add_enabled(action.enabled, egui::Button::new(action.title));
+                    if let Some(hint) = action.hint {
+                        response = response.on_disabled_hover_text(tr(hint));
                     }
-                    ActiveData::None => {
-                        ui.label("Выберите категорию для отображения данных");
+                    if response.clicked() {
+                        command = Some(action.action);
                     }
+                }
+            });
+            if let Some(command) = command.take() {
+                self.run_row_command(command);
+            }
+
+            if self.active_data == ActiveData::Log {
+                self.log_controls(ui);
+            }
+            if self.active_data == ActiveData::Streams {
+                self.streams.controls(ui, self.target.is_local());
+            }
+            let fields = self.active_data.filter_fields();
+            if live && !fields.is_empty() {
+                let filter = self.query_filters.entry(self.active_data).or_default();
+                if filter.show(ui, fields) {
+                    self.refresh(self.active_data);
+                }
+            }
+
+            self.failure_banner(ui);
+            let Some(result) = self.cache.get(&self.active_data) else {
+                if !self.pending.contains_key(&self.active_data)
+                    && !self.failures.contains_key(&self.active_data)
+                {
+                    ui.label(tr("results.empty"));
+                }
+                return;
+            };
+            if self.active_data == ActiveData::Asset
+                && ui
+                    .button(tr("asset.copy_json"))
+                    .on_hover_text(tr("asset.copy_json_hint"))
+                    .clicked()
+            {
+                ui.ctx().copy_text(asset::to_json(result));
+            }
+
+            let view = self.views.entry(self.active_data).or_default();
+            if view.filter.show(ui, &result.columns) {
+                view.page = 0;
+            }
+            let revision = self.cache.revision(&self.active_data);
+            let mut rows = view.visible_rows(result, self.active_data.sort_keys(), revision);
+            if self.active_data == ActiveData::Processes {
+                self.hunt.show(ui, result, &mut rows);
+            }
+            let chart = self.active_data.chart();
+            let show_chart = chart.is_some() && view.show_chart;
+            let grouped = self.active_data.grouped() && view.grouped;
+            if let Some(watch) = self
+                .watches
+                .get_mut(&self.active_data)
+                .filter(|w| w.enabled)
+            {
+                egui::TopBottomPanel::bottom("watch_history")
+                    .resizable(true)
+                    .default_height(120.0)
+                    .show_inside(ui, |ui| watch.history(ui));
+            }
+            if self.active_data == ActiveData::EnvVars && self.env_watch.enabled {
+                egui::TopBottomPanel::bottom("env_watch")
+                    .resizable(true)
+                    .default_height(120.0)
+                    .show_inside(ui, |ui| self.env_watch.history(ui));
+            }
+            if !show_chart && !grouped {
+                egui::TopBottomPanel::bottom("pagination")
+                    .show_inside(ui, |ui| view.pagination(ui, rows.len()));
+            }
+            let export_rows = view.export_rows(&rows);
+            let highlight = Highlight {
+                flags: view.row_flags(revision, || findings::row_flags(self.active_data, result)),
+                marks: self.watches.get(&self.active_data).and_then(Watch::marks),
+                hashes: self.virustotal.enabled().then_some(&self.virustotal_lookup),
+                hunt: self
+                    .hunt
+                    .pattern()
+                    .filter(|_| self.active_data == ActiveData::Processes),
+            };
+            let mut export = None;
+            let mut menu = None;
+            let idle = self.save.is_none();
+            let layout = self.layouts.entry(self.active_data).or_default();
+            ui.horizontal(|ui| {
+                view.copy_buttons(ui, result, &export_rows);
+                if view.page_size.is_some() {
+                    ui.checkbox(&mut view.export_page, tr("export.page_only"));
+                }
+                if view.chosen_count() > 1 {
+                    ui.checkbox(&mut view.export_selected, tr("export.selected_only"));
+                }
+                ui.menu_button(tr("columns.button"), |ui| layout.show(ui, result));
+                if !layout.is_default() {
+                    ui.checkbox(&mut view.export_all_columns, tr("export.all_columns"));
+                }
+                let mut button = |title| ui.add_enabled(idle, egui::Button::new(title)).clicked();
+                if button(tr("export.csv")) {
+                    export = Some(Export::Csv(self.active_data));
+                }
+                if button(tr("export.json")) {
+                    export = Some(Export::Json(vec![self.active_data]));
+                }
+                if button(tr("export.json_all")) {
+                    export = Some(Export::Json(ActiveData::ALL.to_vec()));
+                }
+                if self.active_data == ActiveData::Software && button(tr("export.sbom")) {
+                    export = Some(Export::Sbom);
+                }
+                ui.add_enabled_ui(idle, |ui| {
+                    ui.menu_button(tr("export.ioc"), |ui| {
+                        if ui.button(tr("export.ioc_csv")).clicked() {
+                            export = Some(Export::Ioc(ioc::Format::Csv));
+                            ui.close_menu();
+                        }
+                        if ui.button(tr("export.ioc_stix")).clicked() {
+                            export = Some(Export::Ioc(ioc::Format::Stix));
+                            ui.close_menu();
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("export.ioc_hint"));
                 });
+                if chart.is_some() {
+                    ui.separator();
+                    ui.selectable_value(&mut view.show_chart, false, tr("view.table"));
+                    ui.selectable_value(&mut view.show_chart, true, tr("view.chart"));
+                }
+                if self.active_data.grouped() {
+                    ui.separator();
+                    ui.selectable_value(&mut view.grouped, false, tr("view.table"));
+                    ui.selectable_value(&mut view.grouped, true, tr("view.grouped"));
+                }
+            });
+
+            // Диаграмма строится по тем же строкам, что видны в таблице
+            match chart.filter(|_| show_chart) {
+                Some(chart) => chart.show(ui, result, &rows),
+                // Группы строятся по тем же строкам, без разбиения на страницы
+                None if grouped => {
+                    egui::ScrollArea::vertical()
+                        .id_salt("grouped_rows")
+                        .show(ui, |ui| {
+                            let mut selected = view.selected;
+                            match self.active_data {
+                                ActiveData::EnvVars => {
+                                    envvars::group(result, &rows).show(ui, result, &mut selected)
+                                }
+                                ActiveData::RecycleBin => {
+                                    recycle::group(result, &rows).show(ui, result, &mut selected)
+                                }
+                                _ => devices::group(result, &rows).show(ui, result, &mut selected),
+                            }
+                            if selected != view.selected {
+                                view.select(selected);
+                            }
+                        });
+                }
+                // Вертикальную прокрутку ведёт таблица, у каждой категории свои ширины столбцов
+                None => {
+                    egui::ScrollArea::horizontal()
+                        .id_salt("results_scroll")
+                        .show(ui, |ui| {
+                            let data = self.active_data;
+                            let page = view.page_rows(&rows);
+                            let columns = layout.visible(result);
+                            let actions = |row| data.row_actions(result, Some(row), can);
+                            menu = ui
+                                .push_id(data, |ui| {
+                                    view.show_table(ui, result, page, &columns, &highlight, actions)
+                                })
+                                .inner;
+                        });
+                }
+            }
+
+            if let Some(job) = export {
+                self.start_export(ctx, job);
+            }
+            match menu {
+                Some(MenuEvent::Action(command)) => self.run_row_command(command),
+                Some(MenuEvent::Failed(message)) => self.export_status = Some(Err(message)),
+                None => {}
+            }
         });
     }
 }
 
-fn main() -> eframe::Result<()> {
+// С аргументами программа работает без окна, см. `cli`
+fn main() -> ExitCode {
+    if std::env::args_os().len() > 1 {
+        return cli::run();
+    }
+    run_window(config::path(None))
+}
+
+/// Окно приложения с настройками из файла `config`
+fn run_window(config: PathBuf) -> ExitCode {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([600.0, 400.0]),
         ..Default::default()
     };
 
-    eframe::run_native(
+    let result = eframe::run_native(
         "WMI Lab",
         options,
-        Box::new(|_cc| Ok(Box::new(LabApp::default()))),
-    )
+        Box::new(|cc| Ok(Box::new(LabApp::new(cc, config)))),
+    );
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
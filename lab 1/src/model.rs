@@ -0,0 +1,507 @@
+use crate::i18n::{self, tr};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Версия формата файла экспорта
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Значение ячейки с сохранением типа WMI-свойства
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl Value {
+    /// Текст для отображения; для строк без копирования
+    pub fn display(&self) -> Cow<'_, str> {
+        match self {
+            Value::Text(text) => Cow::Borrowed(text),
+            other => Cow::Owned(other.to_string()),
+        }
+    }
+
+    /// Порядок ячеек одного столбца: числа по значению, даты WMI по времени,
+    /// строки без учёта регистра. Пустые ячейки идут после остальных.
+    /// Для сортировки многих строк ключи удобнее посчитать заранее, см. [`SortKey`]
+    pub fn compare(&self, other: &Value) -> Ordering {
+        SortKey::new(self).compare(&SortKey::new(other))
+    }
+}
+
+/// Ячейка, подготовленная к сравнению: дата WMI разобрана, а строка приведена
+/// к нижнему регистру один раз, а не при каждом сравнении
+pub struct SortKey<'a> {
+    value: &'a Value,
+    time: Option<DateTime<FixedOffset>>,
+    folded: String, // Только у строк
+}
+
+impl<'a> SortKey<'a> {
+    pub fn new(value: &'a Value) -> Self {
+        match value {
+            Value::Text(text) => Self {
+                value,
+                time: wmi_datetime(text),
+                folded: fold(text),
+            },
+            _ => Self {
+                value,
+                time: None,
+                folded: String::new(),
+            },
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        *self.value == Value::Null
+    }
+
+    /// Порядок как у [`Value::compare`]
+    pub fn compare(&self, other: &SortKey) -> Ordering {
+        match (self.value, other.value) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Null, _) => Ordering::Greater,
+            (_, Value::Null) => Ordering::Less,
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.total_cmp(&(*b as f64)),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Text(a), Value::Text(b)) => match (self.time, other.time) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                _ => self.folded.cmp(&other.folded).then_with(|| a.cmp(b)),
+            },
+            (a, b) => compare_text(&a.display(), &b.display()),
+        }
+    }
+}
+
+/// Дата CIM_DATETIME: `yyyymmddHHMMSS.mmmmmm±UUU`, где UUU — смещение в минутах
+pub fn wmi_datetime(text: &str) -> Option<DateTime<FixedOffset>> {
+    if text.len() != 25 || !text.is_ascii() {
+        return None;
+    }
+    let (time, offset) = text.split_at(21);
+    let sign = match &offset[..1] {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let minutes: i32 = offset[1..].parse().ok()?;
+    let offset = FixedOffset::east_opt(sign * minutes * 60)?;
+    let naive = chrono::NaiveDateTime::parse_from_str(time, "%Y%m%d%H%M%S%.6f").ok()?;
+    naive.and_local_timezone(offset).single()
+}
+
+// Строка для сравнения без учёта регистра; «ё» стоит рядом с «е»
+fn fold(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == 'ё' { 'е' } else { c })
+        .collect()
+}
+
+// При равенстве без учёта регистра решает точное сравнение, чтобы порядок
+// не зависел от исходного
+fn compare_text(a: &str, b: &str) -> Ordering {
+    fold(a).cmp(&fold(b)).then_with(|| a.cmp(b))
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Bool(value) => f.write_str(tr(if *value { "value.yes" } else { "value.no" })),
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value}"),
+            Value::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+/// Столбец: исходное имя свойства WMI и заголовок для интерфейса
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub title: String,
+}
+
+/// Смысл значений столбца для контекстного меню строк; определяется по имени
+/// свойства, поэтому новые категории получают пункты меню без настройки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Plain,
+    Path, // Путь к файлу или командная строка с ним
+    Hash, // Хеш содержимого файла
+}
+
+impl Column {
+    /// Столбец свойства `name` с заголовком из перевода
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            title: i18n::column(name),
+        }
+    }
+
+    pub fn kind(&self) -> ColumnKind {
+        let name = self.name.as_str();
+        // Системные свойства вроде __PATH — пути объектов WMI, а не файлов
+        if name.starts_with("__") {
+            ColumnKind::Plain
+        } else if name.ends_with("Path") || name == "PathName" {
+            ColumnKind::Path
+        } else if ["SHA256", "SHA1", "MD5"].contains(&name) || name.ends_with("Hash") {
+            ColumnKind::Hash
+        } else {
+            ColumnKind::Plain
+        }
+    }
+}
+
+/// Табличный результат запроса: столбцы и строки типизированных ячеек
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResultSet {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+    pub extra: Extra,
+}
+
+/// Свойства для всплывающей подсказки над строкой. Запрашиваются вместе с таблицей,
+/// но в ней не показываются; строки идут в том же порядке, что и строки таблицы
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Extra {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+fn columns(names: &[&str]) -> Vec<Column> {
+    names.iter().map(|name| Column::new(name)).collect()
+}
+
+impl ResultSet {
+    /// Столбцы по именам свойств WMI; заголовки берутся из перевода
+    pub fn new(names: &[&str]) -> Self {
+        Self {
+            columns: columns(names),
+            ..Self::default()
+        }
+    }
+
+    /// Задаёт свойства подсказки; строки заполняются вместе со строками таблицы
+    pub fn with_extra(mut self, names: &[&str]) -> Self {
+        self.extra.columns = columns(names);
+        self
+    }
+
+    /// Свойства подсказки строки `row`, у которых есть значение
+    pub fn tip(&self, row: usize) -> Vec<(String, String)> {
+        let Some(cells) = self.extra.rows.get(row) else {
+            return Vec::new();
+        };
+        self.extra
+            .columns
+            .iter()
+            .zip(cells)
+            .filter(|(_, cell)| **cell != Value::Null)
+            .map(|(column, cell)| (column.title.clone(), cell.to_string()))
+            .collect()
+    }
+
+    /// Обновляет заголовки после смены языка
+    pub fn retitle(&mut self) {
+        for column in self.columns.iter_mut().chain(&mut self.extra.columns) {
+            column.title = i18n::column(&column.name);
+        }
+    }
+
+    /// Ячейка строки `row` в столбце свойства `name`
+    pub fn value(&self, row: usize, name: &str) -> Option<&Value> {
+        let index = self.columns.iter().position(|c| c.name == name)?;
+        self.rows.get(row)?.get(index)
+    }
+
+    /// Свойство подсказки строки `row` по имени
+    pub fn extra_value(&self, row: usize, name: &str) -> Option<&Value> {
+        let index = self.extra.columns.iter().position(|c| c.name == name)?;
+        self.extra.rows.get(row)?.get(index)
+    }
+
+    /// Копия результата только со столбцами `columns`, в их порядке
+    pub fn select_columns(&self, columns: &[usize]) -> ResultSet {
+        ResultSet {
+            columns: columns.iter().map(|&i| self.columns[i].clone()).collect(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| columns.iter().map(|&i| row[i].clone()).collect())
+                .collect(),
+            extra: self.extra.clone(),
+        }
+    }
+}
+
+/// Значение свойства одного объекта для панели подробностей; элементы массива
+/// хранятся по отдельности, а не склеиваются в одну строку
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Property {
+    Scalar(Value),
+    Array(Vec<Value>),
+}
+
+impl Property {
+    /// Текст для буфера обмена: элементы массива по одному в строке
+    pub fn text(&self) -> String {
+        match self {
+            Property::Scalar(value) => value.to_string(),
+            Property::Array(items) => items
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// Данные одной категории в файле экспорта; строки хранятся как объекты
+/// с исходными именами свойств WMI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryExport {
+    pub category: String,
+    pub columns: Vec<Column>,
+    pub rows: Vec<BTreeMap<String, Value>>,
+}
+
+impl CategoryExport {
+    pub fn new(category: &str, set: &ResultSet, rows: &[usize]) -> Self {
+        Self {
+            category: category.to_string(),
+            columns: set.columns.clone(),
+            rows: rows
+                .iter()
+                .map(|&i| {
+                    set.columns
+                        .iter()
+                        .zip(&set.rows[i])
+                        .map(|(column, value)| (column.name.clone(), value.clone()))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Таблица из файла экспорта; отсутствующие в строке свойства пусты,
+    /// заголовки берутся на текущем языке
+    pub fn to_result_set(&self) -> ResultSet {
+        let mut set = ResultSet {
+            columns: self.columns.clone(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    self.columns
+                        .iter()
+                        .map(|column| row.get(&column.name).cloned().unwrap_or(Value::Null))
+                        .collect()
+                })
+                .collect(),
+            ..ResultSet::default()
+        };
+        set.retitle();
+        set
+    }
+}
+
+/// Файл экспорта: метаданные и данные одной или нескольких категорий
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportFile {
+    pub schema_version: u32,
+    pub hostname: String,
+    pub timestamp: DateTime<FixedOffset>,
+    pub app_version: String,
+    pub categories: Vec<CategoryExport>,
+}
+
+impl ExportFile {
+    pub fn new(hostname: String, categories: Vec<CategoryExport>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            hostname,
+            timestamp: chrono::Local::now().fixed_offset(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            categories,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ResultSet {
+        let mut set = ResultSet::new(&["Name", "SIDType", "Disabled", "Load", "Status"]);
+        set.rows.push(vec![
+            "Администратор".into(),
+            Value::Int(1),
+            Value::Bool(false),
+            Value::Float(0.5),
+            Value::Null,
+        ]);
+        set.rows.push(vec![
+            "Гость".into(),
+            Value::Int(1),
+            Value::Bool(true),
+            Value::Float(12.25),
+            "OK".into(),
+        ]);
+        set
+    }
+
+    #[test]
+    fn values_compare_by_type() {
+        let order = |a: Value, b: Value| a.compare(&b);
+        assert_eq!(order(Value::Int(2), Value::Int(10)), Ordering::Less);
+        assert_eq!(order(Value::Float(2.5), Value::Int(2)), Ordering::Greater);
+        assert_eq!(order(Value::Null, Value::Int(-1)), Ordering::Greater);
+        assert_eq!(order("яблоко".into(), "Ёж".into()), Ordering::Greater);
+        assert_eq!(order("ёж".into(), "Жук".into()), Ordering::Less);
+        assert_eq!(order("b".into(), "A".into()), Ordering::Greater);
+        // 10:00 по Москве раньше 08:00 UTC
+        assert_eq!(
+            order(
+                "20240101100000.000000+180".into(),
+                "20240101080000.000000+000".into()
+            ),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn column_kind_by_property_name() {
+        let kind = |name: &str| ResultSet::new(&[name]).columns[0].kind();
+        assert_eq!(kind("ExecutablePath"), ColumnKind::Path);
+        assert_eq!(kind("PathName"), ColumnKind::Path);
+        assert_eq!(kind("__PATH"), ColumnKind::Plain);
+        assert_eq!(kind("SHA256"), ColumnKind::Hash);
+        assert_eq!(kind("FileHash"), ColumnKind::Hash);
+        assert_eq!(kind("Name"), ColumnKind::Plain);
+    }
+
+    #[test]
+    fn value_by_column_name() {
+        let set = sample();
+        assert_eq!(set.value(1, "Status"), Some(&Value::from("OK")));
+        assert_eq!(set.value(0, "Missing"), None);
+        assert_eq!(set.value(5, "Name"), None);
+    }
+
+    #[test]
+    fn tip_skips_missing_values() {
+        let mut set = ResultSet::new(&["Name"]).with_extra(&["Description", "Status"]);
+        set.rows.push(vec!["Spooler".into()]);
+        set.extra
+            .rows
+            .push(vec!["Диспетчер печати".into(), Value::Null]);
+        assert_eq!(set.tip(0).len(), 1);
+        assert_eq!(set.tip(0)[0].1, "Диспетчер печати");
+        assert!(set.tip(1).is_empty());
+    }
+
+    #[test]
+    fn selected_columns_keep_given_order() {
+        let set = sample().select_columns(&[4, 0]);
+        let names: Vec<_> = set.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Status", "Name"]);
+        assert_eq!(set.rows[1], vec![Value::from("OK"), Value::from("Гость")]);
+    }
+
+    #[test]
+    fn category_export_restores_result_set() {
+        let set = sample();
+        let mut export = CategoryExport::new("sid_counts", &set, &[0, 1]);
+        export.rows[1].remove("Status");
+        let restored = export.to_result_set();
+        assert_eq!(restored.rows[0], set.rows[0]);
+        assert_eq!(restored.value(1, "Status"), Some(&Value::Null));
+        assert_eq!(restored.value(1, "Name"), Some(&Value::from("Гость")));
+    }
+
+    #[test]
+    fn export_round_trips_through_json() {
+        let set = sample();
+        let file = ExportFile::new(
+            "WS-01".to_string(),
+            vec![CategoryExport::new("sid_counts", &set, &[0, 1])],
+        );
+        let json = serde_json::to_string(&file).unwrap();
+        let parsed: ExportFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, file);
+    }
+
+    #[test]
+    fn numbers_and_booleans_stay_typed() {
+        let set = sample();
+        let export = CategoryExport::new("sid_counts", &set, &[1]);
+        let json = serde_json::to_value(&export).unwrap();
+        let row = &json["rows"][0];
+        assert_eq!(row["SIDType"], serde_json::json!(1));
+        assert_eq!(row["Disabled"], serde_json::json!(true));
+        assert_eq!(row["Load"], serde_json::json!(12.25));
+        assert_eq!(row["Name"], serde_json::json!("Гость"));
+    }
+
+    #[test]
+    fn null_is_preserved() {
+        let json = r#"{
+            "category": "bus_info",
+            "columns": [
+                {"name": "DeviceID", "title": "ID"},
+                {"name": "Status", "title": "Статус"}
+            ],
+            "rows": [{"DeviceID": "ROOT\\1", "Status": null}]
+        }"#;
+        let export: CategoryExport = serde_json::from_str(json).unwrap();
+        assert_eq!(export.rows[0]["Status"], Value::Null);
+        assert_eq!(export.rows[0]["DeviceID"], Value::from("ROOT\\1"));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::connection::{self, Connection, Target};
+use crate::error::AppError;
+use crate::i18n::tr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wmi::COMLibrary;
+
+/// Часто используемые пространства имён WMI
+const NAMESPACES: [&str; 5] = [
+    "root\\cimv2",
+    "root\\StandardCimv2",
+    "root\\WMI",
+    "root\\SecurityCenter2",
+    "root\\subscription",
+];
+
+/// Подключения к пространствам имён, создаются при первом обращении.
+/// Неудачная попытка запоминается, чтобы не повторять её каждый кадр
+#[derive(Default)]
+pub struct Connections {
+    open: HashMap<String, Connection>,
+    errors: HashMap<String, AppError>,
+}
+
+impl Connections {
+    pub fn get(
+        &mut self,
+        com: COMLibrary,
+        target: &Target,
+        namespace: &str,
+    ) -> Result<&Connection, AppError> {
+        if let Some(error) = self.errors.get(namespace) {
+            return Err(error.clone());
+        }
+        if !self.open.contains_key(namespace) {
+            match connection::connect(com, target, namespace) {
+                Ok(con) => {
+                    self.open.insert(namespace.to_string(), con);
+                }
+                Err(e) => {
+                    self.errors.insert(namespace.to_string(), e.clone());
+                    return Err(e);
+                }
+            }
+        }
+        Ok(&self.open[namespace])
+    }
+
+    /// Разрешает новую попытку подключения после ошибки
+    pub fn retry(&mut self, namespace: &str) {
+        self.errors.remove(namespace);
+    }
+
+    /// Закрывает все подключения, например при смене компьютера
+    pub fn clear(&mut self) {
+        self.open.clear();
+        self.errors.clear();
+    }
+}
+
+/// Выбор пространства имён: список известных и произвольный ввод
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct NamespacePicker {
+    pub current: String,
+    custom: String,
+}
+
+impl Default for NamespacePicker {
+    fn default() -> Self {
+        Self {
+            current: NAMESPACES[0].to_string(),
+            custom: String::new(),
+        }
+    }
+}
+
+impl NamespacePicker {
+    /// Возвращает `true`, если пространство имён сменилось
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let previous = self.current.clone();
+        ui.horizontal(|ui| {
+            ui.label(tr("namespace.label"));
+            egui::ComboBox::from_id_salt("namespace")
+                .selected_text(&self.current)
+                .show_ui(ui, |ui| {
+                    for namespace in NAMESPACES {
+                        ui.selectable_value(&mut self.current, namespace.to_string(), namespace);
+                    }
+                });
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.custom)
+                    .hint_text(tr("namespace.custom"))
+                    .desired_width(180.0),
+            );
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (entered || ui.button(tr("namespace.open")).clicked())
+                && !self.custom.trim().is_empty()
+            {
+                self.current = self.custom.trim().to_string();
+            }
+        });
+        self.current != previous
+    }
+}
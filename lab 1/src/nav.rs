@@ -0,0 +1,132 @@
+use crate::i18n::tr;
+use crate::ActiveData;
+use egui::{Key, Stroke, StrokeKind};
+use serde::{Deserialize, Serialize};
+
+/// Раздел боковой панели
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    System,
+    Hardware,
+    Accounts,
+    Network,
+    Security,
+    Monitoring,
+}
+
+impl Section {
+    pub const ALL: [Section; 6] = [
+        Section::System,
+        Section::Hardware,
+        Section::Accounts,
+        Section::Network,
+        Section::Security,
+        Section::Monitoring,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::System => tr("section.system"),
+            Section::Hardware => tr("section.hardware"),
+            Section::Accounts => tr("section.accounts"),
+            Section::Network => tr("section.network"),
+            Section::Security => tr("section.security"),
+            Section::Monitoring => tr("section.monitoring"),
+        }
+    }
+}
+
+/// Боковая панель со списком категорий по разделам
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Navigator {
+    pub open: bool,
+    #[serde(skip)]
+    cursor: usize,
+}
+
+impl Default for Navigator {
+    fn default() -> Self {
+        Self {
+            open: true,
+            cursor: 0,
+        }
+    }
+}
+
+/// Категории в порядке отображения на панели
+pub fn ordered() -> Vec<ActiveData> {
+    Section::ALL
+        .iter()
+        .flat_map(|&section| {
+            ActiveData::ALL
+                .into_iter()
+                .filter(move |d| d.section() == section)
+        })
+        .collect()
+}
+
+impl Navigator {
+    /// Рисует панель и возвращает категорию, выбранную щелчком или клавишей Enter.
+    /// `row_count` — число строк в уже загруженных данных категории
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        active: ActiveData,
+        only: Option<&[ActiveData]>, // Показать только эти категории
+        row_count: impl Fn(ActiveData) -> Option<usize>,
+    ) -> Option<ActiveData> {
+        let mut order = ordered();
+        if let Some(only) = only {
+            order.retain(|data| only.contains(data));
+        }
+        let mut chosen = None;
+
+        // Стрелки и Enter не перехватываются, пока фокус в поле ввода
+        if self.open && !ctx.wants_keyboard_input() {
+            ctx.input(|input| {
+                if input.key_pressed(Key::ArrowDown) {
+                    self.cursor = (self.cursor + 1).min(order.len() - 1);
+                }
+                if input.key_pressed(Key::ArrowUp) {
+                    self.cursor = self.cursor.saturating_sub(1);
+                }
+                if input.key_pressed(Key::Enter) {
+                    chosen = order.get(self.cursor).copied();
+                }
+            });
+        }
+
+        egui::SidePanel::left("categories").show_animated(ctx, self.open, |ui| {
+            for section in Section::ALL {
+                let entries: Vec<_> = order.iter().filter(|d| d.section() == section).collect();
+                if entries.is_empty() {
+                    continue;
+                }
+                ui.add_space(4.0);
+                ui.weak(section.title());
+                for &data in entries {
+                    let index = order.iter().position(|&d| d == data).unwrap_or_default();
+                    let text = match row_count(data) {
+                        Some(count) => format!("{} ({count})", data.title()),
+                        None => data.title().to_string(),
+                    };
+                    let response = ui.selectable_label(data == active, text);
+                    if index == self.cursor {
+                        ui.painter().rect_stroke(
+                            response.rect,
+                            2.0,
+                            Stroke::new(1.0, ui.visuals().weak_text_color()),
+                            StrokeKind::Inside,
+                        );
+                    }
+                    if response.clicked() {
+                        self.cursor = index;
+                        chosen = Some(data);
+                    }
+                }
+            }
+        });
+        chosen
+    }
+}
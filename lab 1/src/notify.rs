@@ -0,0 +1,353 @@
+use crate::findings::Severity;
+use crate::i18n::{tr, trf};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use windows::core::w;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{
+    Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_MESSAGE, NIF_TIP, NIIF_INFO, NIIF_WARNING, NIM_ADD,
+    NIM_DELETE, NIM_MODIFY, NIN_BALLOONUSERCLICK, NOTIFYICONDATAW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, LoadIconW, RegisterClassW, HWND_MESSAGE,
+    IDI_APPLICATION, WINDOW_EX_STYLE, WINDOW_STYLE, WM_APP, WM_LBUTTONUP, WNDCLASSW,
+};
+
+/// Не чаще одного уведомления от одного наблюдения за этот интервал
+pub const INTERVAL: Duration = Duration::from_secs(10);
+
+/// Раздел `[notifications]`: всплывающие уведомления о событиях наблюдения,
+/// пока окно свёрнуто или неактивно
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub min_severity: Severity, // События ниже этой важности не показываются
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_severity: Severity::Info,
+        }
+    }
+}
+
+impl Settings {
+    /// Переключатель и порог важности для меню настроек
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, tr("notify.enabled"))
+            .on_hover_text(tr("notify.hint"));
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("notify.min_severity"));
+                egui::ComboBox::from_id_salt("notify_severity")
+                    .selected_text(self.min_severity.title())
+                    .show_ui(ui, |ui| {
+                        for severity in Severity::ALL {
+                            ui.selectable_value(&mut self.min_severity, severity, severity.title());
+                        }
+                    });
+            });
+        });
+    }
+}
+
+/// Уведомление к показу: первое событие и число остальных, накопившихся за интервал
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note<K> {
+    pub key: K,
+    pub severity: Severity, // Наибольшая среди событий
+    pub text: String,
+    pub more: usize,
+}
+
+impl<K> Note<K> {
+    pub fn body(&self) -> String {
+        match self.more {
+            0 => self.text.clone(),
+            more => format!("{}\n{}", self.text, trf("notify.more", &[("count", &more)])),
+        }
+    }
+}
+
+/// Ограничение частоты: события копятся по ключу и выдаются одним уведомлением
+/// не чаще раза в `INTERVAL`
+pub struct Throttle<K> {
+    last: HashMap<K, Instant>,
+    held: HashMap<K, Note<K>>,
+}
+
+impl<K> Default for Throttle<K> {
+    fn default() -> Self {
+        Self {
+            last: HashMap::new(),
+            held: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> Throttle<K> {
+    pub fn push(&mut self, key: K, severity: Severity, text: String) {
+        match self.held.get_mut(&key) {
+            Some(note) => {
+                note.severity = note.severity.max(severity);
+                note.more += 1;
+            }
+            None => {
+                let note = Note {
+                    key,
+                    severity,
+                    text,
+                    more: 0,
+                };
+                self.held.insert(key, note);
+            }
+        }
+    }
+
+    /// Накопленные уведомления, для которых интервал уже прошёл
+    pub fn take(&mut self, now: Instant) -> Vec<Note<K>> {
+        let ready: Vec<K> = self
+            .held
+            .keys()
+            .filter(|key| self.last.get(key).is_none_or(|&at| now >= at + INTERVAL))
+            .copied()
+            .collect();
+        ready
+            .into_iter()
+            .filter_map(|key| {
+                self.last.insert(key, now);
+                self.held.remove(&key)
+            })
+            .collect()
+    }
+
+    /// Через сколько можно будет выдать следующее накопленное уведомление
+    pub fn next_due(&self, now: Instant) -> Option<Duration> {
+        self.held
+            .keys()
+            .filter_map(|key| self.last.get(key))
+            .map(|&at| (at + INTERVAL).saturating_duration_since(now))
+            .min()
+    }
+}
+
+/// Уведомления о событиях наблюдения. Щелчок по уведомлению возвращает окно
+/// на категорию последнего показанного уведомления
+pub struct Notifier<K> {
+    throttle: Throttle<K>,
+    tray: Option<Tray>,
+    unavailable: bool, // Значок не удалось добавить; больше не пытаться
+    shown: Option<K>,
+}
+
+impl<K> Default for Notifier<K> {
+    fn default() -> Self {
+        Self {
+            throttle: Throttle::default(),
+            tray: None,
+            unavailable: false,
+            shown: None,
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> Notifier<K> {
+    pub fn push(&mut self, key: K, severity: Severity, text: String) {
+        self.throttle.push(key, severity, text);
+    }
+
+    /// Показ накопленных уведомлений; вызывается каждый кадр
+    pub fn show(&mut self, ctx: &egui::Context, title: impl Fn(K) -> &'static str) {
+        let now = Instant::now();
+        for note in self.throttle.take(now) {
+            if self.tray.is_none() && !self.unavailable {
+                match Tray::create(ctx) {
+                    Ok(tray) => self.tray = Some(tray),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "не удалось добавить значок уведомлений");
+                        self.unavailable = true;
+                    }
+                }
+            }
+            let Some(tray) = &self.tray else {
+                continue;
+            };
+            let warning = note.severity >= Severity::Medium;
+            if tray.balloon(title(note.key), &note.body(), warning) {
+                self.shown = Some(note.key);
+            }
+        }
+        if let Some(next) = self.throttle.next_due(now) {
+            ctx.request_repaint_after(next);
+        }
+    }
+
+    /// Категория уведомления, по которому щёлкнули после прошлого кадра
+    pub fn clicked(&mut self) -> Option<K> {
+        if CLICKED.swap(false, Ordering::Relaxed) {
+            self.shown
+        } else {
+            None
+        }
+    }
+}
+
+// Сообщение, которое оболочка присылает окну значка
+const CALLBACK: u32 = WM_APP + 1;
+const ICON_ID: u32 = 1;
+
+static CLICKED: AtomicBool = AtomicBool::new(false);
+static REPAINT: OnceLock<egui::Context> = OnceLock::new();
+
+// Окно значка создаётся в потоке интерфейса, поэтому его сообщения разбирает
+// тот же цикл сообщений, что и сообщения главного окна
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if message != CALLBACK {
+        return unsafe { DefWindowProcW(hwnd, message, wparam, lparam) };
+    }
+    let event = lparam.0 as u32;
+    if event == NIN_BALLOONUSERCLICK || event == WM_LBUTTONUP {
+        CLICKED.store(true, Ordering::Relaxed);
+        if let Some(ctx) = REPAINT.get() {
+            ctx.request_repaint();
+        }
+    }
+    LRESULT(0)
+}
+
+// Значок в области уведомлений с невидимым окном для его сообщений
+struct Tray {
+    hwnd: HWND,
+}
+
+impl Tray {
+    fn create(ctx: &egui::Context) -> windows::core::Result<Self> {
+        let _ = REPAINT.set(ctx.clone());
+        let class_name = w!("WmiLabNotify");
+        unsafe {
+            let instance = GetModuleHandleW(None)?;
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance.into(),
+                lpszClassName: class_name,
+                ..Default::default()
+            };
+            // Повторная регистрация класса не удаётся, но прежний класс годится
+            RegisterClassW(&class);
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                class_name,
+                w!("WMI Lab"),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                Some(instance.into()),
+                None,
+            )?;
+            let mut data = Self::data(hwnd);
+            data.uFlags = NIF_MESSAGE | NIF_ICON | NIF_TIP;
+            data.uCallbackMessage = CALLBACK;
+            data.hIcon = LoadIconW(None, IDI_APPLICATION)?;
+            copy(&mut data.szTip, "WMI Lab");
+            if !Shell_NotifyIconW(NIM_ADD, &data).as_bool() {
+                let _ = DestroyWindow(hwnd);
+                return Err(windows::core::Error::from_win32());
+            }
+            Ok(Self { hwnd })
+        }
+    }
+
+    fn data(hwnd: HWND) -> NOTIFYICONDATAW {
+        NOTIFYICONDATAW {
+            cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+            hWnd: hwnd,
+            uID: ICON_ID,
+            ..Default::default()
+        }
+    }
+
+    fn balloon(&self, title: &str, text: &str, warning: bool) -> bool {
+        let mut data = Self::data(self.hwnd);
+        data.uFlags = NIF_INFO;
+        data.dwInfoFlags = if warning { NIIF_WARNING } else { NIIF_INFO };
+        copy(&mut data.szInfoTitle, title);
+        copy(&mut data.szInfo, text);
+        unsafe { Shell_NotifyIconW(NIM_MODIFY, &data) }.as_bool()
+    }
+}
+
+impl Drop for Tray {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = Shell_NotifyIconW(NIM_DELETE, &Self::data(self.hwnd));
+            let _ = DestroyWindow(self.hwnd);
+        }
+    }
+}
+
+// Строка в буфер фиксированной длины; не поместившееся обрезается
+fn copy(buffer: &mut [u16], text: &str) {
+    let limit = buffer.len() - 1;
+    let mut len = 0;
+    for unit in text.encode_utf16().take(limit) {
+        buffer[len] = unit;
+        len += 1;
+    }
+    buffer[len] = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_within_interval_are_summarized() {
+        let mut throttle = Throttle::default();
+        let start = Instant::now();
+        throttle.push(1, Severity::Info, "первое".to_string());
+        let notes = throttle.take(start);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].more, 0);
+
+        throttle.push(1, Severity::Info, "второе".to_string());
+        throttle.push(1, Severity::High, "третье".to_string());
+        throttle.push(2, Severity::Info, "другое наблюдение".to_string());
+        let notes = throttle.take(start + Duration::from_secs(1));
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].key, 2);
+        assert_eq!(
+            throttle.next_due(start + Duration::from_secs(1)),
+            Some(Duration::from_secs(9))
+        );
+
+        let notes = throttle.take(start + INTERVAL);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].text, "второе");
+        assert_eq!(notes[0].more, 1);
+        assert_eq!(notes[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn long_text_is_truncated() {
+        let mut buffer = [1u16; 4];
+        copy(&mut buffer, "abcdef");
+        assert_eq!(buffer, [97, 98, 99, 0]);
+    }
+}
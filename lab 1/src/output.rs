@@ -0,0 +1,142 @@
+use crate::export::{self, UTF8_BOM};
+use crate::model::{CategoryExport, ExportFile, ResultSet};
+use std::path::Path;
+
+/// Формат вывода командной строки
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Выровненные столбцы для чтения человеком
+    #[default]
+    Table,
+    /// Один документ в схеме JSON-экспорта окна
+    Json,
+    /// CSV с тем же экранированием, что и экспорт окна
+    Csv,
+}
+
+/// Таблица с выровненными столбцами. Переводы строк внутри ячеек заменяются пробелами
+pub fn table(set: &ResultSet) -> String {
+    let header: Vec<String> = set.columns.iter().map(|c| c.title.clone()).collect();
+    let rows: Vec<Vec<String>> = set
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.display().replace(['\r', '\n'], " "))
+                .collect()
+        })
+        .collect();
+    let mut widths: Vec<usize> = header.iter().map(|t| t.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = line(&header, &widths);
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    out.push_str(&line(&rule, &widths));
+    for row in &rows {
+        out.push_str(&line(row, &widths));
+    }
+    out
+}
+
+// Ячейки через два пробела, без хвостовых пробелов в конце строки
+fn line(cells: &[String], widths: &[usize]) -> String {
+    let mut out = String::new();
+    for (cell, &width) in cells.iter().zip(widths) {
+        out.push_str(cell);
+        out.push_str(&" ".repeat(width - cell.chars().count() + 2));
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Документ JSON с метаданными
+pub fn json(file: &ExportFile) -> String {
+    let mut out = serde_json::to_string_pretty(file).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// Данные одной категории в выбранном формате
+pub fn render(format: Format, host: &str, category: &str, set: &ResultSet) -> String {
+    let rows: Vec<usize> = (0..set.rows.len()).collect();
+    match format {
+        Format::Table => table(set),
+        Format::Json => json(&ExportFile::new(
+            host.to_string(),
+            vec![CategoryExport::new(category, set, &rows)],
+        )),
+        Format::Csv => export::to_csv(set, &rows),
+    }
+}
+
+/// Запись в файл в UTF-8; CSV получает BOM, как при экспорте из окна
+pub fn write(path: &Path, format: Format, text: &str) -> std::io::Result<()> {
+    match format {
+        Format::Csv => std::fs::write(path, format!("{UTF8_BOM}{text}")),
+        Format::Table | Format::Json => std::fs::write(path, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Value;
+
+    fn sample() -> ResultSet {
+        let mut set = ResultSet::new(&["Name", "VariableValue", "UserName"]);
+        set.rows.push(vec![
+            "PATH".into(),
+            "C:\\Windows;C:\\Tools".into(),
+            "<SYSTEM>".into(),
+        ]);
+        set.rows.push(vec![
+            "Сообщение".into(),
+            "скажи \"привет\"".into(),
+            Value::Null,
+        ]);
+        set.rows.push(vec![
+            "Описание".into(),
+            "строка 1\nстрока 2".into(),
+            "WS-01\\Гость".into(),
+        ]);
+        set
+    }
+
+    #[test]
+    fn table_matches_golden() {
+        assert_eq!(table(&sample()), include_str!("../tests/golden/env.table"));
+    }
+
+    #[test]
+    fn csv_matches_golden() {
+        let csv = render(Format::Csv, "WS-01", "env_vars", &sample());
+        assert_eq!(csv, include_str!("../tests/golden/env.csv"));
+    }
+
+    #[test]
+    fn json_matches_golden() {
+        let set = sample();
+        let file = ExportFile {
+            schema_version: crate::model::SCHEMA_VERSION,
+            hostname: "WS-01".to_string(),
+            timestamp: "2026-01-15T14:32:00+03:00".parse().unwrap(),
+            app_version: "0.1.0".to_string(),
+            categories: vec![CategoryExport::new("env_vars", &set, &[0, 1, 2])],
+        };
+        assert_eq!(json(&file), include_str!("../tests/golden/env.json"));
+    }
+
+    #[test]
+    fn csv_file_starts_with_bom() {
+        let path = std::env::temp_dir().join("lab1_output_test.csv");
+        write(&path, Format::Csv, "a;b\r\n").unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(bytes, b"\xEF\xBB\xBFa;b\r\n");
+    }
+}
@@ -0,0 +1,348 @@
+use crate::apartment::Apartment;
+use crate::connection::{self, Connection, Target};
+use crate::error::AppError;
+use crate::i18n::{tr, trf};
+use egui_plot::{Legend, Line, Plot, PlotBounds, PlotPoints};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use windows::Win32::System::Com::COINIT_MULTITHREADED;
+use wmi::Variant;
+
+/// Интервал между замерами по умолчанию, в секундах
+pub const INTERVAL_SECS: u64 = 2;
+
+/// Сколько секунд истории показывает график
+const HISTORY_SECS: u64 = 300;
+
+const CPU_QUERY: &str =
+    "SELECT PercentProcessorTime FROM Win32_PerfFormattedData_PerfOS_Processor WHERE Name = '_Total'";
+const MEMORY_QUERY: &str =
+    "SELECT FreeVirtualMemory, TotalVirtualMemorySize FROM Win32_OperatingSystem";
+
+/// Один замер: загрузка процессора и выделенная память
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub at: Instant,
+    pub cpu: f64,          // Проценты
+    pub committed_kb: u64, // Выделено виртуальной памяти
+    pub limit_kb: u64,     // Предел выделения: физическая память и файл подкачки
+}
+
+impl Sample {
+    pub fn memory_percent(&self) -> f64 {
+        if self.limit_kb == 0 {
+            return 0.0;
+        }
+        self.committed_kb as f64 * 100.0 / self.limit_kb as f64
+    }
+}
+
+/// Последние замеры; при заполнении самые старые вытесняются
+pub struct Ring {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl Default for Ring {
+    fn default() -> Self {
+        Self::covering(Duration::from_secs(INTERVAL_SECS))
+    }
+}
+
+impl Ring {
+    /// Ёмкость на `HISTORY_SECS` секунд при замерах через `interval`
+    pub fn covering(interval: Duration) -> Self {
+        Self::new((HISTORY_SECS / interval.as_secs().max(1)) as usize)
+    }
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    pub fn last(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+}
+
+// Общее состояние потока замеров и окна
+struct Shared {
+    ring: Mutex<Ring>,
+    error: Mutex<Option<AppError>>,
+    paused: AtomicBool,
+    stop: AtomicBool,
+}
+
+/// Фоновый поток замеров со своим подключением к WMI. Медленный замер задерживает
+/// только этот поток. Удаление останавливает поток после текущего замера
+pub struct Sampler {
+    target: Target,
+    interval: Duration,
+    shared: Arc<Shared>,
+}
+
+impl Sampler {
+    pub fn start(ctx: &egui::Context, target: Target, interval: Duration) -> Self {
+        let shared = Arc::new(Shared {
+            ring: Mutex::new(Ring::covering(interval)),
+            error: Mutex::new(None),
+            paused: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+        });
+        let thread_shared = Arc::clone(&shared);
+        let thread_target = target.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || run(&ctx, &thread_target, interval, &thread_shared));
+        Self {
+            target,
+            interval,
+            shared,
+        }
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.shared.paused.store(paused, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Цикл потока замеров. Подключение повторяется на каждом такте, пока не удастся
+fn run(ctx: &egui::Context, target: &Target, interval: Duration, shared: &Shared) {
+    // Подключение объявлено после COM и удаляется раньше него
+    let apartment = Apartment::init(COINIT_MULTITHREADED).map_err(AppError::ComInit);
+    let mut con: Option<Connection> = None;
+    let mut next = Instant::now();
+    while !shared.stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        if now < next || shared.paused.load(Ordering::Relaxed) {
+            std::thread::sleep((next - now).min(Duration::from_millis(200)));
+            continue;
+        }
+        next = now + interval;
+        let result = match &con {
+            Some(con) => sample(con),
+            None => apartment
+                .as_ref()
+                .map_err(AppError::clone)
+                .and_then(|apartment| connection::connect(apartment.com(), target, "root\\cimv2"))
+                .and_then(|connected| sample(con.insert(connected))),
+        };
+        let mut error = shared.error.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(sample) => {
+                let mut ring = shared.ring.lock().unwrap_or_else(|e| e.into_inner());
+                ring.push(sample);
+                *error = None;
+            }
+            Err(e) => {
+                // Ошибка пишется в журнал один раз, а не на каждом такте
+                if error.as_ref().map(ToString::to_string) != Some(e.to_string()) {
+                    tracing::warn!(error = %e, "замер производительности не удался");
+                }
+                *error = Some(e);
+            }
+        }
+        ctx.request_repaint();
+    }
+}
+
+fn sample(con: &Connection) -> Result<Sample, AppError> {
+    let query = |wql: &str| -> Result<Vec<HashMap<String, Variant>>, AppError> {
+        con.poll_query(wql)
+            .map_err(|e| AppError::query(con.host(), connection::class_of(wql), e))
+    };
+    let cpu = query(CPU_QUERY)?;
+    let os = query(MEMORY_QUERY)?;
+    let value = |rows: &[HashMap<String, Variant>], name: &str| {
+        rows.first()
+            .and_then(|row| row.get(name))
+            .and_then(number)
+            .unwrap_or_default()
+    };
+    let limit_kb = value(&os, "TotalVirtualMemorySize") as u64;
+    let free_kb = value(&os, "FreeVirtualMemory") as u64;
+    Ok(Sample {
+        at: Instant::now(),
+        cpu: value(&cpu, "PercentProcessorTime"),
+        committed_kb: limit_kb.saturating_sub(free_kb),
+        limit_kb,
+    })
+}
+
+// Свойства uint64 WMI передаёт строкой
+fn number(value: &Variant) -> Option<f64> {
+    match value {
+        Variant::String(text) => text.parse().ok(),
+        Variant::UI8(n) => Some(*n as f64),
+        Variant::I8(n) => Some(*n as f64),
+        Variant::UI4(n) => Some(*n as f64),
+        Variant::I4(n) => Some(*n as f64),
+        _ => None,
+    }
+}
+
+/// Полоса с графиком загрузки процессора и памяти над строкой состояния
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerfStrip {
+    pub open: bool,
+    #[serde(skip)]
+    sampler: Option<Sampler>,
+}
+
+impl PerfStrip {
+    /// `pause_hidden` — не делать замеров, пока полоса скрыта; `interval` — секунд
+    /// между замерами
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        target: &Target,
+        pause_hidden: bool,
+        interval: u64,
+    ) {
+        if self.open || !pause_hidden {
+            // Замеры идут с компьютера, к которому подключено окно; новый период
+            // из файла настроек перезапускает поток
+            let interval = Duration::from_secs(interval.max(1));
+            let current = |s: &Sampler| s.target == *target && s.interval == interval;
+            if !self.sampler.as_ref().is_some_and(current) {
+                self.sampler = Some(Sampler::start(ctx, target.clone(), interval));
+            }
+        }
+        if let Some(sampler) = &self.sampler {
+            sampler.set_paused(!self.open && pause_hidden);
+        }
+        let Some(sampler) = self.sampler.as_ref().filter(|_| self.open) else {
+            return;
+        };
+        egui::TopBottomPanel::bottom("perf_strip")
+            .resizable(true)
+            .default_height(110.0)
+            .show(ctx, |ui| strip(ui, &sampler.shared));
+    }
+}
+
+fn strip(ui: &mut egui::Ui, shared: &Shared) {
+    let ring = shared.ring.lock().unwrap_or_else(|e| e.into_inner());
+    ui.horizontal(|ui| {
+        match ring.last() {
+            Some(last) => {
+                let gb = |kb: u64| format!("{:.1}", kb as f64 / (1024.0 * 1024.0));
+                ui.label(trf("perf.cpu", &[("percent", &format!("{:.0}", last.cpu))]));
+                ui.separator();
+                ui.label(trf(
+                    "perf.memory",
+                    &[
+                        ("used", &gb(last.committed_kb)),
+                        ("total", &gb(last.limit_kb)),
+                        ("percent", &format!("{:.0}", last.memory_percent())),
+                    ],
+                ));
+            }
+            None => {
+                ui.spinner();
+                ui.label(tr("perf.waiting"));
+            }
+        }
+        let error = shared.error.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(error) = error.as_ref() {
+            ui.separator();
+            ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+        }
+    });
+
+    // По оси X — секунды до текущего момента
+    let now = Instant::now();
+    let points = |value: fn(&Sample) -> f64| -> PlotPoints {
+        ring.samples()
+            .map(|s| [-now.duration_since(s.at).as_secs_f64(), value(s)])
+            .collect()
+    };
+    let cpu = Line::new(points(|s| s.cpu)).name(tr("perf.cpu_line"));
+    let memory = Line::new(points(Sample::memory_percent)).name(tr("perf.memory_line"));
+    Plot::new("perf_plot")
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .allow_double_click_reset(false)
+        .legend(Legend::default())
+        .x_axis_formatter(|mark, _| format!("{:.0} {}", mark.value, tr("query.seconds").trim()))
+        .y_axis_formatter(|mark, _| format!("{:.0} %", mark.value))
+        .show(ui, |plot| {
+            plot.set_plot_bounds(PlotBounds::from_min_max(
+                [-(HISTORY_SECS as f64), 0.0],
+                [0.0, 100.0],
+            ));
+            plot.line(cpu);
+            plot.line(memory);
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu: f64) -> Sample {
+        Sample {
+            at: Instant::now(),
+            cpu,
+            committed_kb: 4,
+            limit_kb: 16,
+        }
+    }
+
+    #[test]
+    fn ring_drops_oldest_when_full() {
+        let mut ring = Ring::new(3);
+        for cpu in [1.0, 2.0, 3.0, 4.0] {
+            ring.push(sample(cpu));
+        }
+        let cpu: Vec<f64> = ring.samples().map(|s| s.cpu).collect();
+        assert_eq!(cpu, [2.0, 3.0, 4.0]);
+        assert_eq!(ring.last().map(|s| s.cpu), Some(4.0));
+    }
+
+    #[test]
+    fn default_ring_holds_five_minutes() {
+        assert_eq!(Ring::default().capacity, 150);
+    }
+
+    #[test]
+    fn memory_percent_of_commit_limit() {
+        assert_eq!(sample(0.0).memory_percent(), 25.0);
+        let empty = Sample {
+            limit_kb: 0,
+            ..sample(0.0)
+        };
+        assert_eq!(empty.memory_percent(), 0.0);
+    }
+
+    #[test]
+    fn uint64_arrives_as_text() {
+        assert_eq!(number(&Variant::String("8388608".into())), Some(8388608.0));
+        assert_eq!(number(&Variant::UI4(12)), Some(12.0));
+        assert_eq!(number(&Variant::Null), None);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::i18n::tr;
+use crate::model::{ResultSet, Value};
+use crate::source::Row;
+
+/// Пространство имён схем электропитания; на некоторых серверных редакциях его нет
+pub const NAMESPACE: &str = "root\\cimv2\\power";
+
+pub const PLAN_CLASS: &str = "Win32_PowerPlan";
+
+/// Значения параметров схем от сети (AC) и от батареи (DC)
+pub const INDEX_CLASS: &str = "Win32_PowerSettingDataIndex";
+
+/// Раздел HKEY_LOCAL_MACHINE со схемами и значением ActivePowerScheme
+pub const SCHEMES_KEY: &str = "SYSTEM\\CurrentControlSet\\Control\\Power\\User\\PowerSchemes";
+
+// Значения по умолчанию параметров: PowerSettings\<группа>\<параметр>\DefaultPowerSchemeValues\<схема>
+const SETTINGS_KEY: &str = "SYSTEM\\CurrentControlSet\\Control\\Power\\PowerSettings";
+
+// Встроенные схемы Windows; у своих схем берётся имя из ElementName
+const PLANS: [(&str, &str); 4] = [
+    ("381b4222-f694-41f0-9685-ff5bb260df2e", "power.balanced"),
+    (
+        "8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c",
+        "power.high_performance",
+    ),
+    ("a1841308-3541-4fab-bc81-f71556f20b4a", "power.saver"),
+    ("e9a42b02-d5df-448d-aa00-03f14749eb61", "power.ultimate"),
+];
+
+/// Параметр схемы, который проверяет аудит
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Sleep,        // Переход в сон через, секунд; 0 — никогда
+    WakePassword, // Пароль при пробуждении: 1 — требуется
+}
+
+impl Setting {
+    pub const ALL: [Setting; 2] = [Setting::Sleep, Setting::WakePassword];
+
+    /// GUID параметра (STANDBYIDLE, CONSOLELOCK)
+    pub fn guid(self) -> &'static str {
+        match self {
+            Setting::Sleep => "29f6c1db-86da-48c5-9fdb-f2b67b1f44da",
+            Setting::WakePassword => "0e796bdb-100d-47d6-a2d5-f7d2daa51f51",
+        }
+    }
+
+    // Группа параметров в реестре (SUB_SLEEP, SUB_NONE)
+    fn subgroup(self) -> &'static str {
+        match self {
+            Setting::Sleep => "238c9fa8-0aad-41ed-83f4-97be242c8f20",
+            Setting::WakePassword => "fea3413e-7e05-4911-9a71-700331f1c294",
+        }
+    }
+
+    fn of_guid(guid: &str) -> Option<Setting> {
+        Setting::ALL.into_iter().find(|s| s.guid() == guid)
+    }
+
+    /// Разделы реестра со значениями параметра схемы `plan`: изменённое
+    /// пользователем, затем значение по умолчанию
+    pub fn registry_keys(self, plan: &str) -> [String; 2] {
+        let (subgroup, setting) = (self.subgroup(), self.guid());
+        [
+            format!("{SCHEMES_KEY}\\{{{plan}}}\\{{{subgroup}}}\\{{{setting}}}"),
+            format!("{SETTINGS_KEY}\\{{{subgroup}}}\\{{{setting}}}\\DefaultPowerSchemeValues\\{{{plan}}}"),
+        ]
+    }
+}
+
+/// GUID без фигурных скобок в нижнем регистре
+pub fn guid(text: &str) -> String {
+    text.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .to_lowercase()
+}
+
+/// Схема, от сети ли значение и параметр по Win32_PowerSettingDataIndex.InstanceID:
+/// `Microsoft:PowerSettingDataIndex\{схема}\AC\{параметр}`
+pub fn data_index(instance_id: &str) -> Option<(String, bool, Setting)> {
+    let parts: Vec<&str> = instance_id.split('\\').collect();
+    let [_, plan, power, setting] = parts.as_slice() else {
+        return None;
+    };
+    let ac = match *power {
+        "AC" => true,
+        "DC" => false,
+        _ => return None,
+    };
+    Some((guid(plan), ac, Setting::of_guid(&guid(setting))?))
+}
+
+/// Имя схемы: встроенные переводятся, свои — по ElementName, иначе GUID
+pub fn plan_name(plan: &str, element_name: Option<&str>) -> String {
+    match PLANS.iter().find(|(known, _)| *known == plan) {
+        Some((_, key)) => tr(key).to_string(),
+        None => element_name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(plan)
+            .to_string(),
+    }
+}
+
+/// Значение параметра от сети и от батареи
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Values {
+    pub ac: Option<i64>,
+    pub dc: Option<i64>,
+}
+
+impl Values {
+    pub fn get(&self, ac: bool) -> Option<i64> {
+        if ac {
+            self.ac
+        } else {
+            self.dc
+        }
+    }
+
+    fn set(&mut self, ac: bool, value: i64) {
+        if ac {
+            self.ac = Some(value);
+        } else {
+            self.dc = Some(value);
+        }
+    }
+}
+
+/// Схема электропитания с проверяемыми параметрами
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Plan {
+    pub guid: String,
+    pub name: Option<String>, // ElementName; из реестра имени нет
+    pub active: bool,
+    pub sleep: Values,
+    pub wake_password: Values,
+    pub registry: bool, // Хотя бы одно значение прочитано из реестра
+}
+
+impl Plan {
+    /// Схема по объекту Win32_PowerPlan: InstanceID `Microsoft:PowerPlan\{GUID}`
+    pub fn from_row(row: &Row) -> Option<Plan> {
+        let Some(Value::Text(id)) = row.get("InstanceID") else {
+            return None;
+        };
+        let name = match row.get("ElementName") {
+            Some(Value::Text(name)) => Some(name.clone()),
+            _ => None,
+        };
+        Some(Plan {
+            guid: guid(id.rsplit('\\').next()?),
+            name,
+            active: row.get("IsActive") == Some(&Value::Bool(true)),
+            ..Plan::default()
+        })
+    }
+
+    pub fn get(&self, setting: Setting, ac: bool) -> Option<i64> {
+        match setting {
+            Setting::Sleep => self.sleep.get(ac),
+            Setting::WakePassword => self.wake_password.get(ac),
+        }
+    }
+
+    pub fn set(&mut self, setting: Setting, ac: bool, value: i64) {
+        match setting {
+            Setting::Sleep => self.sleep.set(ac, value),
+            Setting::WakePassword => self.wake_password.set(ac, value),
+        }
+    }
+
+    /// Известны ли все проверяемые значения
+    pub fn complete(&self) -> bool {
+        Setting::ALL
+            .iter()
+            .all(|&setting| self.get(setting, true).is_some() && self.get(setting, false).is_some())
+    }
+}
+
+/// Схемы электропитания: активная первой, затем остальные по имени. `note` —
+/// первая строка с пояснением, например о недоступном пространстве имён.
+/// `battery` — есть ли у компьютера батарея: без неё значения DC не действуют.
+/// Время до сна — в секундах, как в WMI: 0 означает «никогда», и по числу
+/// проверка находит его в данных на любом языке
+pub fn result_set(plans: &[Plan], battery: bool, note: Option<&str>) -> ResultSet {
+    let mut set = ResultSet::new(&[
+        "PowerPlan",
+        "Active",
+        "SleepAC",
+        "SleepDC",
+        "WakePasswordAC",
+        "WakePasswordDC",
+        "Battery",
+    ])
+    .with_extra(&["PlanGuid", "SettingsSource"]);
+    if let Some(note) = note {
+        let mut row = vec![Value::Null; set.columns.len()];
+        row[0] = note.into();
+        set.rows.push(row);
+        set.extra
+            .rows
+            .push(vec![Value::Null; set.extra.columns.len()]);
+    }
+    let mut plans: Vec<(String, &Plan)> = plans
+        .iter()
+        .map(|plan| (plan_name(&plan.guid, plan.name.as_deref()), plan))
+        .collect();
+    plans.sort_by(|a, b| b.1.active.cmp(&a.1.active).then_with(|| a.0.cmp(&b.0)));
+    for (name, plan) in plans {
+        let sleep = |value: Option<i64>| value.map_or(Value::Null, Value::Int);
+        let password = |value: Option<i64>| Value::from(value.map(|value| value != 0));
+        set.rows.push(vec![
+            name.into(),
+            plan.active.into(),
+            sleep(plan.sleep.ac),
+            sleep(plan.sleep.dc),
+            password(plan.wake_password.ac),
+            password(plan.wake_password.dc),
+            battery.into(),
+        ]);
+        let known = Setting::ALL.iter().any(|&setting| {
+            plan.get(setting, true).is_some() || plan.get(setting, false).is_some()
+        });
+        let source = match (plan.registry, known) {
+            (true, _) => Some(tr("power.from_registry")),
+            (false, true) => Some(tr("power.from_wmi")),
+            (false, false) => None,
+        };
+        set.extra
+            .rows
+            .push(vec![plan.guid.as_str().into(), Value::from(source)]);
+    }
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_index_ids_are_parsed() {
+        let id = "Microsoft:PowerSettingDataIndex\\{381b4222-f694-41f0-9685-ff5bb260df2e}\\DC\\{29F6C1DB-86DA-48C5-9FDB-F2B67B1F44DA}";
+        assert_eq!(
+            data_index(id),
+            Some((
+                "381b4222-f694-41f0-9685-ff5bb260df2e".to_string(),
+                false,
+                Setting::Sleep
+            ))
+        );
+        // Параметры, которые аудит не проверяет, пропускаются
+        let other = "Microsoft:PowerSettingDataIndex\\{381b4222-f694-41f0-9685-ff5bb260df2e}\\AC\\{3c0bc021-c8a8-4e07-a973-6b14cbcb2b7e}";
+        assert_eq!(data_index(other), None);
+        assert_eq!(
+            plan_name(
+                "8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c",
+                Some("High performance")
+            ),
+            tr("power.high_performance")
+        );
+        assert_eq!(plan_name("0000", Some("Киоск")), "Киоск");
+    }
+
+    #[test]
+    fn active_plan_comes_first() {
+        let mut kiosk = Plan {
+            guid: "1b2c".to_string(),
+            name: Some("Киоск".to_string()),
+            active: true,
+            ..Plan::default()
+        };
+        kiosk.set(Setting::Sleep, true, 0);
+        kiosk.set(Setting::WakePassword, true, 1);
+        let balanced = Plan {
+            guid: PLANS[0].0.to_string(),
+            ..Plan::default()
+        };
+        let set = result_set(&[balanced, kiosk], false, None);
+        assert_eq!(set.value(0, "PowerPlan"), Some(&"Киоск".into()));
+        assert_eq!(set.value(0, "SleepAC"), Some(&Value::Int(0)));
+        assert_eq!(set.value(0, "WakePasswordAC"), Some(&Value::Bool(true)));
+        assert_eq!(set.value(0, "SleepDC"), Some(&Value::Null));
+        assert_eq!(set.value(1, "Battery"), Some(&Value::Bool(false)));
+    }
+}
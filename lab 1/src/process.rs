@@ -0,0 +1,405 @@
+use crate::connection::Connection;
+use crate::error::AppError;
+use crate::i18n::{tr, trf};
+use crate::model::{Column, ResultSet, Value};
+use crate::worker::{Ticket, Timeout, Worker};
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+use wmi::Variant;
+
+const CLASS: &str = "Win32_Process";
+
+/// Путь объекта процесса; ключ класса — Handle, совпадающий с PID
+pub fn path(pid: i64) -> String {
+    format!("{CLASS}.Handle=\"{pid}\"")
+}
+
+/// Завершает процесс методом Terminate. Уже завершённый процесс даёт `AppError::Gone`
+pub fn terminate(con: &Connection, pid: i64) -> Result<(), AppError> {
+    let code = con
+        .exec_method(&path(pid), "Terminate", HashMap::new())
+        .map_err(|e| AppError::method(con.host(), CLASS, e))?;
+    match code {
+        0 => Ok(()),
+        code => Err(AppError::MethodFailed {
+            class: CLASS.to_string(),
+            method: "Terminate".to_string(),
+            code,
+        }),
+    }
+}
+
+/// Учётная запись процесса `ДОМЕН\имя` методом GetOwner. `None`, если владельца
+/// узнать нельзя: у процессов вроде System его нет, к чужим нужен доступ
+pub fn owner(con: &Connection, pid: i64) -> Result<Option<String>, AppError> {
+    let output = con
+        .invoke(&path(pid), "GetOwner", HashMap::new())
+        .map_err(|e| AppError::method(con.host(), CLASS, e))?;
+    let text = |name: &str| match output.get(name) {
+        Some(Variant::String(text)) if !text.is_empty() => Some(text.as_str()),
+        _ => None,
+    };
+    // Отказ в доступе и другие ошибки метода приходят в ReturnValue, а не ошибкой вызова
+    if !matches!(
+        output.get("ReturnValue"),
+        Some(Variant::UI4(0) | Variant::I4(0))
+    ) {
+        return Ok(None);
+    }
+    Ok(text("User").map(|user| match text("Domain") {
+        Some(domain) => format!("{domain}\\{user}"),
+        None => user.to_string(),
+    }))
+}
+
+/// Столбец владельца; его добавляет окно после получения списка процессов
+pub const OWNER: &str = "Owner";
+
+// Вызовов GetOwner в одном задании. Поток запросов на это время занят,
+// и запросы других категорий ждут не дольше одной пачки
+const BATCH: usize = 25;
+
+// PID, PID родителя и имя: PID завершившегося процесса достаётся новому,
+// а у него почти всегда другие родитель или имя
+type Key = (i64, i64, String);
+
+fn key(set: &ResultSet, row: usize) -> Option<Key> {
+    let Some(Value::Int(pid)) = set.value(row, "ProcessId") else {
+        return None;
+    };
+    let parent = match set.value(row, "ParentProcessId") {
+        Some(Value::Int(parent)) => *parent,
+        _ => 0,
+    };
+    let name = set.value(row, "Name").map(Value::to_string);
+    Some((*pid, parent, name.unwrap_or_default()))
+}
+
+// Ответы пачки: владелец или `None`, если узнать его не удалось
+type Answers = Vec<(Key, Option<String>)>;
+
+/// Владельцы процессов. Вызов GetOwner для каждого процесса медленный, поэтому
+/// столбец заполняется пачками уже после того, как список показан. Ответы хранятся,
+/// пока процесс есть в списке: повторный запрос категории заполняет столбец сразу
+#[derive(Default)]
+pub struct Owners {
+    known: HashMap<Key, Option<String>>, // `None` — владельца узнать не удалось
+    pending: Option<Ticket<Answers>>,
+}
+
+impl Owners {
+    /// Забывает ответы, например при подключении к другому компьютеру
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Добавляет столбец владельца в новый список процессов и заполняет его известными
+    /// ответами; ответы о процессах, которых больше нет, забываются
+    pub fn fill(&mut self, set: &mut ResultSet) {
+        if !set.columns.iter().any(|c| c.name == OWNER) {
+            let index = set
+                .columns
+                .iter()
+                .position(|c| c.name == "Name")
+                .map_or(set.columns.len(), |i| i + 1);
+            set.columns.insert(index, Column::new(OWNER));
+            for row in &mut set.rows {
+                row.insert(index, Value::Null);
+            }
+        }
+        let present: HashSet<Key> = (0..set.rows.len())
+            .filter_map(|row| key(set, row))
+            .collect();
+        self.known.retain(|key, _| present.contains(key));
+        self.apply(set);
+    }
+
+    /// Сколько процессов списка ещё ждут ответа
+    pub fn remaining(&self, set: &ResultSet) -> usize {
+        (0..set.rows.len())
+            .filter_map(|row| key(set, row))
+            .filter(|key| !self.known.contains_key(key))
+            .count()
+    }
+
+    /// Принимает ответ на пачку, заполняя столбец на месте, и отправляет следующую.
+    /// `true`, если столбец изменился
+    pub fn poll(&mut self, set: &mut ResultSet, worker: &mut Worker) -> bool {
+        let mut changed = false;
+        if let Some(ticket) = &self.pending {
+            match ticket.try_recv() {
+                Ok(answers) => {
+                    self.known.extend(answers);
+                    self.apply(set);
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => return false,
+                // Поток запросов заменён; пачка отправится заново
+                Err(TryRecvError::Disconnected) => {}
+            }
+            self.pending = None;
+        }
+        let batch: Vec<Key> = (0..set.rows.len())
+            .filter_map(|row| key(set, row))
+            .filter(|key| !self.known.contains_key(key))
+            .take(BATCH)
+            .collect();
+        if !batch.is_empty() {
+            // Завершившийся процесс, отказ в доступе и ошибка подключения дают «н/д»
+            self.pending = Some(worker.submit(move |session| async move {
+                let con = session.connection().ok();
+                batch
+                    .into_iter()
+                    .map(|key| {
+                        let found = con.and_then(|con| owner(con, key.0).ok().flatten());
+                        (key, found)
+                    })
+                    .collect()
+            }));
+        }
+        changed
+    }
+
+    fn apply(&self, set: &mut ResultSet) {
+        let Some(index) = set.columns.iter().position(|c| c.name == OWNER) else {
+            return;
+        };
+        for row in 0..set.rows.len() {
+            let Some(found) = key(set, row).and_then(|key| self.known.get(&key)) else {
+                continue;
+            };
+            set.rows[row][index] = match found {
+                Some(owner) => owner.as_str().into(),
+                None => tr("value.na").into(),
+            };
+        }
+    }
+}
+
+/// Процесс из строки категории процессов
+#[derive(Debug, Clone, PartialEq)]
+pub struct Process {
+    pub pid: i64,
+    pub name: String,
+    pub executable: Option<String>, // Скрыт для чужих процессов без прав администратора
+}
+
+impl Process {
+    pub fn from_row(set: &ResultSet, row: usize) -> Option<Self> {
+        let Some(Value::Int(pid)) = set.value(row, "ProcessId") else {
+            return None;
+        };
+        let text = |name| match set.value(row, name) {
+            Some(Value::Text(text)) => Some(text.clone()),
+            _ => None,
+        };
+        Some(Self {
+            pid: *pid,
+            name: text("Name").unwrap_or_default(),
+            executable: text("ExecutablePath"),
+        })
+    }
+}
+
+enum State {
+    Confirm,
+    Running {
+        ticket: Ticket<Result<(), AppError>>,
+        deadline: Instant,
+    },
+    Failed(AppError),
+}
+
+/// Что произошло в окне завершения процесса в этом кадре
+pub enum Outcome {
+    Terminated(Process),
+    Exited, // Процесс завершился сам; окно остаётся открытым с сообщением
+    Closed,
+}
+
+/// Окно подтверждения завершения процесса
+pub struct TerminateDialog {
+    process: Process,
+    state: State,
+}
+
+impl TerminateDialog {
+    pub fn new(process: Process) -> Self {
+        Self {
+            process,
+            state: State::Confirm,
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        worker: &mut Worker,
+        timeout: Timeout,
+    ) -> Option<Outcome> {
+        let mut outcome = self.poll(ctx, timeout);
+        let mut start = false;
+        egui::Window::new(tr("process.terminate_title"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr("process.confirm"));
+                egui::Grid::new("terminate_process")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr("column.Name"));
+                        ui.strong(&self.process.name);
+                        ui.end_row();
+                        ui.label(tr("column.ProcessId"));
+                        ui.strong(self.process.pid.to_string());
+                        ui.end_row();
+                        ui.label(tr("column.ExecutablePath"));
+                        match &self.process.executable {
+                            Some(path) => ui.monospace(path),
+                            None => ui.weak(tr("value.na")),
+                        };
+                        ui.end_row();
+                    });
+                ui.separator();
+                match &self.state {
+                    State::Confirm => {
+                        ui.horizontal(|ui| {
+                            start = ui.button(tr("process.terminate_button")).clicked();
+                            if ui.button(tr("remote.cancel")).clicked() {
+                                outcome = Some(Outcome::Closed);
+                            }
+                        });
+                    }
+                    State::Running { .. } => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(tr("process.terminating"));
+                        });
+                    }
+                    State::Failed(error) => {
+                        match error {
+                            AppError::Gone { .. } => {
+                                ui.colored_label(ui.visuals().warn_fg_color, tr("process.exited"));
+                            }
+                            error => error.show(ui),
+                        }
+                        if ui.button(tr("process.close")).clicked() {
+                            outcome = Some(Outcome::Closed);
+                        }
+                    }
+                }
+            });
+        if start {
+            let pid = self.process.pid;
+            self.state = State::Running {
+                ticket: worker
+                    .submit(move |session| async move { terminate(session.connection()?, pid) }),
+                deadline: Instant::now() + timeout.duration(),
+            };
+        }
+        outcome
+    }
+
+    // Приём ответа потока запросов
+    fn poll(&mut self, ctx: &egui::Context, timeout: Timeout) -> Option<Outcome> {
+        let State::Running { ticket, deadline } = &self.state else {
+            return None;
+        };
+        let result = match ticket.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) if Instant::now() < *deadline => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                return None;
+            }
+            Err(TryRecvError::Empty) => Err(timeout.error()),
+            Err(TryRecvError::Disconnected) => {
+                self.state = State::Confirm;
+                return None;
+            }
+        };
+        match result {
+            Ok(()) => Some(Outcome::Terminated(self.process.clone())),
+            Err(error) => {
+                let exited = matches!(error, AppError::Gone { .. });
+                if exited {
+                    tracing::info!(pid = self.process.pid, "процесс уже завершился");
+                } else {
+                    tracing::warn!(pid = self.process.pid, "{}", error.detailed());
+                }
+                self.state = State::Failed(error);
+                exited.then_some(Outcome::Exited)
+            }
+        }
+    }
+}
+
+/// Сообщение в строке состояния после завершения
+pub fn terminated_message(process: &Process) -> String {
+    trf(
+        "process.terminated",
+        &[("name", &process.name), ("pid", &process.pid)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::class_of_path;
+
+    #[test]
+    fn path_addresses_instance_by_handle() {
+        assert_eq!(path(4242), "Win32_Process.Handle=\"4242\"");
+        assert_eq!(class_of_path(&path(4242)), CLASS);
+        assert_eq!(
+            class_of_path("\\\\srv.corp\\root\\cimv2:Win32_Directory.Name=\"C:\\\\x.y\""),
+            "Win32_Directory"
+        );
+    }
+
+    #[test]
+    fn process_from_row_without_path() {
+        let mut set = ResultSet::new(&["ProcessId", "Name", "ExecutablePath"]);
+        set.rows
+            .push(vec![Value::Int(4), "System".into(), Value::Null]);
+        set.rows.push(vec![Value::Null, "?".into(), Value::Null]);
+        assert_eq!(
+            Process::from_row(&set, 0),
+            Some(Process {
+                pid: 4,
+                name: "System".to_string(),
+                executable: None,
+            })
+        );
+        assert_eq!(Process::from_row(&set, 1), None);
+    }
+
+    #[test]
+    fn owners_fill_column_after_name() {
+        let list = |rows: &[(i64, &str)]| {
+            let mut set = ResultSet::new(&["ProcessId", "Name", "ParentProcessId"]);
+            for &(pid, name) in rows {
+                set.rows
+                    .push(vec![Value::Int(pid), name.into(), Value::Int(4)]);
+            }
+            set
+        };
+        let mut owners = Owners::default();
+        owners
+            .known
+            .insert((900, 4, "explorer.exe".into()), Some("WS-01\\user".into()));
+        owners.known.insert((700, 4, "lsass.exe".into()), None);
+        let mut set = list(&[(900, "explorer.exe"), (700, "lsass.exe"), (800, "cmd.exe")]);
+        owners.fill(&mut set);
+        assert_eq!(set.columns[2].name, OWNER);
+        assert_eq!(set.value(0, OWNER), Some(&"WS-01\\user".into()));
+        assert_eq!(set.value(1, OWNER), Some(&tr("value.na").into()));
+        assert_eq!(set.value(2, OWNER), Some(&Value::Null));
+        assert_eq!(owners.remaining(&set), 1);
+        // PID достался другому процессу: прежний ответ не подходит и забывается
+        let mut set = list(&[(900, "notepad.exe")]);
+        owners.fill(&mut set);
+        assert_eq!(set.value(0, OWNER), Some(&Value::Null));
+        assert!(owners.known.is_empty());
+    }
+}
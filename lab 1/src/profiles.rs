@@ -0,0 +1,278 @@
+use crate::connection::Target;
+use crate::i18n::tr;
+use serde::{Deserialize, Serialize};
+use windows::core::{HSTRING, PWSTR};
+use windows::Win32::Security::Credentials::{
+    CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+    CRED_TYPE_GENERIC,
+};
+
+/// Таблица `[[connections]]`: сохранённое подключение к компьютеру. Пароля в файле нет:
+/// он запрашивается при подключении или хранится в диспетчере учётных данных Windows
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub host: String,
+    pub user: String, // Пусто — текущие учётные данные
+    pub domain: String,
+    pub namespace: String, // Пространство имён консоли и обзора классов; пусто — не менять
+    pub save_password: bool,
+}
+
+impl Profile {
+    /// Учётная запись для подключения: «ДОМЕН\пользователь» или просто имя
+    pub fn account(&self) -> String {
+        let (domain, user) = (self.domain.trim(), self.user.trim());
+        if domain.is_empty() || user.is_empty() {
+            user.to_string()
+        } else {
+            format!("{domain}\\{user}")
+        }
+    }
+
+    pub fn target(&self, password: String) -> Target {
+        Target {
+            host: self.host.trim().to_string(),
+            user: self.account(),
+            password,
+        }
+    }
+
+    /// Пароль не нужен: подключение с текущими учётными данными
+    pub fn current_account(&self) -> bool {
+        self.user.trim().is_empty()
+    }
+
+    // Имя записи в диспетчере учётных данных
+    fn credential(&self) -> String {
+        format!("WMI Lab/{}@{}", self.account(), self.host.trim())
+    }
+
+    /// Пароль из диспетчера учётных данных, если он там есть
+    pub fn stored_password(&self) -> Option<String> {
+        read_secret(&self.credential())
+    }
+
+    /// Запоминает пароль в диспетчере учётных данных
+    pub fn store_password(&self, password: &str) -> windows::core::Result<()> {
+        write_secret(&self.credential(), &self.account(), password)
+    }
+
+    /// Удаляет сохранённый пароль; если его не было, ничего не происходит
+    pub fn forget_password(&self) {
+        delete_secret(&self.credential());
+    }
+}
+
+/// Секрет записи `name` диспетчера учётных данных Windows, если он там есть
+pub fn read_secret(name: &str) -> Option<String> {
+    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+    unsafe {
+        CredReadW(
+            &HSTRING::from(name),
+            CRED_TYPE_GENERIC,
+            None,
+            &mut credential,
+        )
+        .ok()?;
+        let blob = std::slice::from_raw_parts(
+            (*credential).CredentialBlob as *const u16,
+            (*credential).CredentialBlobSize as usize / 2,
+        );
+        let secret = String::from_utf16_lossy(blob);
+        CredFree(credential as *const _);
+        Some(secret)
+    }
+}
+
+/// Записывает секрет под именем `name`; прежний секрет заменяется
+pub fn write_secret(name: &str, user: &str, secret: &str) -> windows::core::Result<()> {
+    let mut name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+    let mut user: Vec<u16> = user.encode_utf16().chain([0]).collect();
+    let mut blob: Vec<u16> = secret.encode_utf16().collect();
+    let credential = CREDENTIALW {
+        Type: CRED_TYPE_GENERIC,
+        TargetName: PWSTR(name.as_mut_ptr()),
+        UserName: PWSTR(user.as_mut_ptr()),
+        CredentialBlobSize: (blob.len() * 2) as u32,
+        CredentialBlob: blob.as_mut_ptr() as *mut u8,
+        Persist: CRED_PERSIST_LOCAL_MACHINE,
+        ..Default::default()
+    };
+    unsafe { CredWriteW(&credential, 0) }
+}
+
+/// Удаляет запись `name`; если её не было, ничего не происходит
+pub fn delete_secret(name: &str) {
+    let _ = unsafe { CredDeleteW(&HSTRING::from(name), CRED_TYPE_GENERIC, None) };
+}
+
+/// Решение, принятое в окне профилей
+pub enum EditorAction {
+    Save(Vec<Profile>),
+    Cancel,
+}
+
+/// Окно добавления, правки и удаления профилей подключения
+pub struct Editor {
+    original: Vec<Profile>,
+    profiles: Vec<Profile>,
+    selected: Option<usize>,
+    password: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Editor {
+    pub fn new(profiles: Vec<Profile>) -> Self {
+        Self {
+            selected: (!profiles.is_empty()).then_some(0),
+            original: profiles.clone(),
+            profiles,
+            password: String::new(),
+            status: None,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<EditorAction> {
+        let mut action = None;
+        egui::Window::new(tr("profiles.title"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| self.list(ui));
+                    ui.separator();
+                    ui.vertical(|ui| self.fields(ui));
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let named = self.profiles.iter().all(|p| !p.name.trim().is_empty());
+                    if ui
+                        .add_enabled(named, egui::Button::new(tr("profiles.save")))
+                        .on_disabled_hover_text(tr("profiles.unnamed"))
+                        .clicked()
+                    {
+                        action = Some(EditorAction::Save(self.save()));
+                    }
+                    if ui.button(tr("remote.cancel")).clicked() {
+                        action = Some(EditorAction::Cancel);
+                    }
+                });
+            });
+        action
+    }
+
+    fn list(&mut self, ui: &mut egui::Ui) {
+        for (index, profile) in self.profiles.iter().enumerate() {
+            let name = if profile.name.trim().is_empty() {
+                tr("profiles.unnamed")
+            } else {
+                profile.name.as_str()
+            };
+            if ui
+                .selectable_label(self.selected == Some(index), name)
+                .clicked()
+            {
+                self.selected = Some(index);
+                self.password.clear();
+                self.status = None;
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui.button(tr("profiles.add")).clicked() {
+                self.profiles.push(Profile::default());
+                self.selected = Some(self.profiles.len() - 1);
+                self.password.clear();
+            }
+            if ui
+                .add_enabled(
+                    self.selected.is_some(),
+                    egui::Button::new(tr("profiles.delete")),
+                )
+                .clicked()
+            {
+                if let Some(index) = self.selected.take() {
+                    self.profiles.remove(index);
+                    self.selected = index
+                        .checked_sub(1)
+                        .or((!self.profiles.is_empty()).then_some(0));
+                }
+            }
+        });
+    }
+
+    fn fields(&mut self, ui: &mut egui::Ui) {
+        let Some(profile) = self.selected.and_then(|i| self.profiles.get_mut(i)) else {
+            ui.weak(tr("profiles.empty"));
+            return;
+        };
+        egui::Grid::new("profile_fields")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr("profiles.name"));
+                ui.text_edit_singleline(&mut profile.name);
+                ui.end_row();
+                ui.label(tr("remote.host"));
+                ui.text_edit_singleline(&mut profile.host);
+                ui.end_row();
+                ui.label(tr("remote.user"));
+                ui.text_edit_singleline(&mut profile.user);
+                ui.end_row();
+                ui.label(tr("profiles.domain"));
+                ui.text_edit_singleline(&mut profile.domain);
+                ui.end_row();
+                ui.label(tr("profiles.namespace"));
+                ui.add(egui::TextEdit::singleline(&mut profile.namespace).hint_text("root\\cimv2"));
+                ui.end_row();
+            });
+        ui.add_enabled(
+            !profile.current_account(),
+            egui::Checkbox::new(&mut profile.save_password, tr("profiles.save_password")),
+        )
+        .on_hover_text(tr("profiles.save_password_hint"));
+        if profile.save_password && !profile.current_account() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.password).password(true));
+                if ui
+                    .add_enabled(
+                        !self.password.is_empty(),
+                        egui::Button::new(tr("profiles.store")),
+                    )
+                    .clicked()
+                {
+                    self.status = Some(
+                        profile
+                            .store_password(&self.password)
+                            .map(|()| tr("profiles.stored").to_string())
+                            .map_err(|e| e.message()),
+                    );
+                    self.password.clear();
+                }
+            });
+        }
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            None => {}
+        }
+    }
+
+    // Пароли удалённых профилей и профилей, где хранение выключено, стираются
+    fn save(&self) -> Vec<Profile> {
+        for old in &self.original {
+            let kept = self
+                .profiles
+                .iter()
+                .any(|p| p.save_password && p.credential() == old.credential());
+            if old.save_password && !kept {
+                old.forget_password();
+            }
+        }
+        self.profiles.clone()
+    }
+}
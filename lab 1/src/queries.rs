@@ -0,0 +1,258 @@
+// Имена полей совпадают с именами свойств WMI
+#![allow(non_snake_case)]
+
+use crate::categories;
+use crate::connection::{class_of, class_of_path, Connection};
+use crate::error::AppError;
+use crate::i18n::tr;
+use crate::logging::{self, LogLevel};
+use crate::model::{Property, ResultSet, Value};
+use crate::source::{DataSource, Object, Row};
+use crate::ActiveData;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use wmi::Variant;
+
+// Системное свойство __CLASS есть у каждого класса в meta_class
+#[derive(Debug, Deserialize)]
+struct MetaClass {
+    __CLASS: String,
+}
+
+/// Выполняет запрос категории и возвращает таблицу результата.
+/// `filter` — условие WHERE, уже собранное с экранированием
+pub async fn run(
+    source: &impl DataSource,
+    data: ActiveData,
+    filter: Option<&str>,
+) -> Result<ResultSet, AppError> {
+    match data {
+        ActiveData::EnvVars => categories::environment(source, filter).await,
+        ActiveData::SidCounts => categories::sid_counts(source, filter).await,
+        ActiveData::BusInfo => categories::buses(source, filter).await,
+        ActiveData::ProblemDevices => categories::problem_devices(source).await,
+        ActiveData::Cooling => categories::cooling(source).await,
+        ActiveData::PortConnectors => categories::port_connectors(source).await,
+        ActiveData::Volumes => categories::volumes(source, filter).await,
+        ActiveData::DiskQuotas => categories::disk_quotas(source).await,
+        ActiveData::Processes => categories::processes(source, filter).await,
+        ActiveData::Services => categories::services(source, filter).await,
+        ActiveData::Software => categories::software(source, filter).await,
+        ActiveData::NetworkAdapters => categories::network_adapters(source, filter).await,
+        ActiveData::Prefetch => categories::prefetch(source).await,
+        ActiveData::RecycleBin => categories::recycle_bin(source).await,
+        ActiveData::BrowserExtensions => categories::browser_extensions(source).await,
+        ActiveData::DefenderExclusions => categories::defender_exclusions(source).await,
+        ActiveData::Virtualization => categories::virtualization(source).await,
+        ActiveData::Uptime => categories::uptime(source).await,
+        ActiveData::Asset => categories::asset(source).await,
+        ActiveData::PowerPlans => categories::power_plans(source).await,
+        ActiveData::Log => Ok(logging::result_set(LogLevel::Debug)),
+        // Потоки ищутся в файловой системе этого компьютера по кнопке окна,
+        // отчёт берёт последний результат окна
+        ActiveData::Streams => Ok(ResultSet::default()),
+        ActiveData::None => Ok(ResultSet::default()),
+    }
+}
+
+// Объекты запроса в пространстве имён `namespace`; подключение к другому
+// пространству имён того же компьютера открывается при первом запросе к нему
+async fn variants(
+    con: &Connection,
+    class: &str,
+    namespace: &str,
+    wql: &str,
+) -> Result<Vec<HashMap<String, Variant>>, AppError> {
+    let sibling = con.sibling(namespace)?;
+    let con = sibling.as_deref().unwrap_or(con);
+    con.raw_query_async(wql)
+        .await
+        .map_err(|e| AppError::query(con.host(), class, e))
+}
+
+/// Путь для чтения файла компьютера `host` отсюда. Удалённый файл читается через
+/// административный ресурс диска (\\host\C$) с правами текущего пользователя
+/// Windows, а не учётной записи подключения
+pub fn file_path(host: Option<&str>, path: &str) -> String {
+    match (host, path.split_once(':')) {
+        (Some(host), Some((drive, rest))) => format!("\\\\{host}\\{drive}${rest}"),
+        _ => path.to_string(),
+    }
+}
+
+// Категории запрашивают в основном root\cimv2, к которому подключён поток запросов
+impl DataSource for Connection {
+    async fn query_rows(
+        &self,
+        class: &str,
+        namespace: &str,
+        wql: &str,
+    ) -> Result<Vec<Row>, AppError> {
+        let objects = variants(self, class, namespace, wql).await?;
+        Ok(objects
+            .into_iter()
+            .map(|object| {
+                object
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = variant(&value);
+                        (name, value)
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    async fn query_objects(
+        &self,
+        class: &str,
+        namespace: &str,
+        wql: &str,
+    ) -> Result<Vec<Object>, AppError> {
+        let objects = variants(self, class, namespace, wql).await?;
+        Ok(objects
+            .into_iter()
+            .map(|object| {
+                object
+                    .into_iter()
+                    .map(|(name, value)| (name, property(&value)))
+                    .collect()
+            })
+            .collect())
+    }
+
+    fn read_file(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        std::fs::read(file_path(self.host(), path))
+    }
+
+    fn call(
+        &self,
+        namespace: &str,
+        path: &str,
+        method: &str,
+        params: &[(&str, Value)],
+    ) -> Result<Object, AppError> {
+        let sibling = self.sibling(namespace)?;
+        let con = sibling.as_deref().unwrap_or(self);
+        let params = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), argument(value)))
+            .collect();
+        let output = con
+            .invoke(path, method, params)
+            .map_err(|e| AppError::method(con.host(), class_of_path(path), e))?;
+        Ok(output
+            .into_iter()
+            .map(|(name, value)| (name, property(&value)))
+            .collect())
+    }
+}
+
+// Входной параметр метода. uint32 WMI принимает как VT_I4, поэтому значения
+// до u32::MAX (например, HKEY_LOCAL_MACHINE) передаются с переносом знака,
+// а 64-разрядные числа — текстом, как WMI передаёт их сам
+fn argument(value: &Value) -> Variant {
+    match value {
+        Value::Null => Variant::Null,
+        Value::Bool(b) => Variant::Bool(*b),
+        Value::Int(n) => i32::try_from(*n)
+            .or_else(|_| u32::try_from(*n).map(|n| n as i32))
+            .map(Variant::I4)
+            .unwrap_or_else(|_| Variant::String(n.to_string())),
+        Value::Float(n) => Variant::R8(*n),
+        Value::Text(text) => Variant::String(text.clone()),
+    }
+}
+
+/// Произвольный запрос WQL. Столбцы — объединение свойств всех строк по алфавиту,
+/// отсутствующие у строки свойства остаются пустыми
+pub fn raw(con: &Connection, wql: &str) -> Result<ResultSet, AppError> {
+    let objects: Vec<HashMap<String, Variant>> = con
+        .raw_query(wql)
+        .map_err(|e| AppError::query(con.host(), class_of(wql), e))?;
+    let names: BTreeSet<&str> = objects
+        .iter()
+        .flat_map(|object| object.keys().map(String::as_str))
+        .collect();
+    let names: Vec<&str> = names.into_iter().collect();
+    let mut set = ResultSet::new(&names);
+    set.rows = objects
+        .iter()
+        .map(|object| {
+            names
+                .iter()
+                .map(|&name| object.get(name).map(variant).unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    Ok(set)
+}
+
+/// Все свойства первого объекта запроса по алфавиту; `None`, если объект не найден
+pub async fn object(
+    con: &Connection,
+    wql: &str,
+) -> Result<Option<Vec<(String, Property)>>, AppError> {
+    let objects: Vec<HashMap<String, Variant>> = con
+        .raw_query_async(wql)
+        .await
+        .map_err(|e| AppError::query(con.host(), class_of(wql), e))?;
+    let Some(object) = objects.into_iter().next() else {
+        return Ok(None);
+    };
+    let mut properties: Vec<_> = object
+        .into_iter()
+        .map(|(name, value)| (name, property(&value)))
+        .collect();
+    properties.sort_by_key(|(name, _)| name.to_lowercase());
+    Ok(Some(properties))
+}
+
+/// Имена классов пространства имён по алфавиту, без системных классов `__*`
+pub fn classes(con: &Connection) -> Result<Vec<String>, AppError> {
+    let mut classes: Vec<String> = con
+        .raw_query::<MetaClass>("SELECT * FROM meta_class")
+        .map_err(|e| AppError::query(con.host(), "meta_class", e))?
+        .into_iter()
+        .map(|meta| meta.__CLASS)
+        .filter(|class| !class.starts_with("__"))
+        .collect();
+    classes.sort_unstable_by_key(|class| class.to_lowercase());
+    Ok(classes)
+}
+
+fn property(value: &Variant) -> Property {
+    match value {
+        Variant::Array(items) => Property::Array(items.iter().map(variant).collect()),
+        other => Property::Scalar(variant(other)),
+    }
+}
+
+fn variant(value: &Variant) -> Value {
+    match value {
+        Variant::Empty | Variant::Null => Value::Null,
+        Variant::String(s) => Value::Text(s.clone()),
+        Variant::Bool(b) => Value::Bool(*b),
+        Variant::I1(n) => Value::Int(*n as i64),
+        Variant::I2(n) => Value::Int(*n as i64),
+        Variant::I4(n) => Value::Int(*n as i64),
+        Variant::I8(n) => Value::Int(*n),
+        Variant::UI1(n) => Value::Int(*n as i64),
+        Variant::UI2(n) => Value::Int(*n as i64),
+        Variant::UI4(n) => Value::Int(*n as i64),
+        // Значения выше i64::MAX сохраняются текстом без потери точности
+        Variant::UI8(n) => i64::try_from(*n)
+            .map(Value::Int)
+            .unwrap_or_else(|_| Value::Text(n.to_string())),
+        Variant::R4(n) => Value::Float(*n as f64),
+        Variant::R8(n) => Value::Float(*n),
+        Variant::Array(items) => Value::Text(
+            items
+                .iter()
+                .map(|item| variant(item).to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Variant::Unknown(_) | Variant::Object(_) => Value::Text(tr("value.object").to_string()),
+    }
+}
@@ -0,0 +1,136 @@
+use crate::i18n::{tr, trf};
+use crate::model::Value;
+use std::collections::HashMap;
+
+/// Сколько записей пользователей показывается для тома
+pub const TOP: usize = 50;
+
+/// Путь к объекту WMI из свойства-ссылки: класс и значения ключей. Путь бывает
+/// полным `\\HOST\root\cimv2:Win32_Account.Domain="WS-01",Name="user"` или
+/// только с классом; в кавычках экранированы `\"` и `\\`
+pub fn reference(path: &str) -> Option<(String, HashMap<String, String>)> {
+    let path = path.trim();
+    let relative = match path.split_once(':') {
+        // Двоеточие до точки отделяет пространство имён: в имени класса его не бывает,
+        // а в значении ключа (DeviceID="C:") оно стоит после точки
+        Some((namespace, rest))
+            if path.starts_with("\\\\")
+                || !(namespace.contains('.') || namespace.contains('=')) =>
+        {
+            rest
+        }
+        _ => path,
+    };
+    let (class, keys) = relative.split_once('.')?;
+    let mut values = HashMap::new();
+    let mut chars = keys.chars().peekable();
+    loop {
+        let name: String = chars.by_ref().take_while(|&c| c != '=').collect();
+        if name.is_empty() {
+            break;
+        }
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+            // Запятая перед следующим ключом
+            chars.next();
+        } else {
+            value = chars.by_ref().take_while(|&c| c != ',').collect();
+        }
+        values.insert(name.trim().to_string(), value);
+    }
+    Some((class.to_string(), values))
+}
+
+/// Учётная запись `ДОМЕН\имя` из ссылки на Win32_Account
+pub fn account(path: &str) -> Option<String> {
+    let (_, keys) = reference(path)?;
+    let name = keys.get("Name")?;
+    Some(match keys.get("Domain") {
+        Some(domain) if !domain.is_empty() => format!("{domain}\\{name}"),
+        _ => name.clone(),
+    })
+}
+
+/// Буква диска из ссылки на Win32_LogicalDisk, например `C:`
+pub fn volume(path: &str) -> Option<String> {
+    reference(path)?.1.remove("DeviceID")
+}
+
+/// Число байтов; WMI передаёт uint64 текстом
+pub fn bytes(value: Option<&Value>) -> Option<u64> {
+    match value? {
+        Value::Int(n) => u64::try_from(*n).ok(),
+        Value::Text(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Значение предела для таблицы: наибольшее uint64 означает «без ограничения»
+pub fn limit(value: Option<&Value>) -> Value {
+    match bytes(value) {
+        Some(u64::MAX) => tr("quota.no_limit").into(),
+        Some(n) => i64::try_from(n).map_or(Value::Null, Value::Int),
+        None => Value::Null,
+    }
+}
+
+/// Режим квот тома по Win32_QuotaSetting.State
+pub fn state(code: i64) -> String {
+    match code {
+        0 => tr("quota.disabled").to_string(),
+        1 => tr("quota.tracked").to_string(),
+        2 => tr("quota.enforced").to_string(),
+        _ => trf("quota.state", &[("code", &code)]),
+    }
+}
+
+/// Состояние записи пользователя по Win32_DiskQuota.Status
+pub fn status(code: i64) -> String {
+    match code {
+        0 => tr("quota.ok").to_string(),
+        1 => tr("quota.warning").to_string(),
+        2 => tr("quota.exceeded").to_string(),
+        _ => trf("quota.state", &[("code", &code)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_are_parsed() {
+        let user = r#"\\WS-01.corp.local\root\cimv2:Win32_Account.Domain="CORP",Name="o\"brien""#;
+        assert_eq!(account(user).as_deref(), Some("CORP\\o\"brien"));
+        assert_eq!(
+            volume(r#"Win32_LogicalDisk.DeviceID="C:""#).as_deref(),
+            Some("C:")
+        );
+        assert_eq!(
+            volume(r#"root\cimv2:Win32_LogicalDisk.DeviceID="D:""#).as_deref(),
+            Some("D:")
+        );
+        let (class, keys) = reference("Win32_Process.Handle=42").unwrap();
+        assert_eq!(class, "Win32_Process");
+        assert_eq!(keys["Handle"], "42");
+        assert_eq!(reference("plain"), None);
+    }
+
+    #[test]
+    fn limits_come_as_text() {
+        assert_eq!(
+            limit(Some(&"18446744073709551615".into())),
+            tr("quota.no_limit").into()
+        );
+        assert_eq!(limit(Some(&"1073741824".into())), Value::Int(1073741824));
+        assert_eq!(bytes(Some(&Value::Int(-1))), None);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::i18n::tr;
+use crate::model::{wmi_datetime, ResultSet, Value};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::BTreeMap;
+
+/// Сколько последних удалённых объектов показывается; остальные только считаются
+pub const LIMIT: usize = 1000;
+
+// Заголовок $I: версия, размер и время удаления по 8 байт
+const HEADER: usize = 24;
+// В версии 1 путь занимает MAX_PATH символов UTF-16 с завершающим нулём
+const V1_PATH: usize = 260;
+
+/// Сведения об удалённом объекте из файла `$I`. Парный файл `$R` с тем же
+/// окончанием имени хранит само содержимое
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deleted {
+    pub version: u64,
+    pub size: u64,
+    pub deleted: Option<DateTime<Utc>>,
+    pub path: String,
+}
+
+// Время FILETIME: интервалы по 100 нс от 1 января 1601 года UTC; ноль — не задано
+fn filetime(ticks: u64) -> Option<DateTime<Utc>> {
+    if ticks == 0 {
+        return None;
+    }
+    let epoch = DateTime::parse_from_rfc3339("1601-01-01T00:00:00Z")
+        .ok()?
+        .with_timezone(&Utc);
+    epoch.checked_add_signed(TimeDelta::microseconds((ticks / 10) as i64))
+}
+
+fn u64_at(data: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?))
+}
+
+// Строка UTF-16LE до первого нуля
+fn utf16(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Разбирает файл `$I`. Версия 1 (Vista–8.1) хранит путь в поле постоянной длины,
+/// версия 2 (Windows 10 и новее) — длину пути в символах и сам путь.
+/// Обрезанный файл или неизвестная версия — `None`
+pub fn parse(data: &[u8]) -> Option<Deleted> {
+    let version = u64_at(data, 0)?;
+    let size = u64_at(data, 8)?;
+    let deleted = filetime(u64_at(data, 16)?);
+    let path = match version {
+        1 => data.get(HEADER..HEADER + V1_PATH * 2)?,
+        2 => {
+            let len = u32::from_le_bytes(data.get(HEADER..HEADER + 4)?.try_into().ok()?);
+            data.get(HEADER + 4..HEADER + 4 + len as usize * 2)?
+        }
+        _ => return None,
+    };
+    Some(Deleted {
+        version,
+        size,
+        deleted,
+        path: utf16(path),
+    })
+}
+
+/// Время в формате CIM_DATETIME, как у дат WMI в остальных таблицах
+pub fn cim_datetime(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%d%H%M%S%.6f+000").to_string()
+}
+
+/// Удалённые объекты по владельцам корзин
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Grouping {
+    pub owners: BTreeMap<String, Vec<usize>>,
+}
+
+fn text(set: &ResultSet, row: usize, name: &str) -> String {
+    set.value(row, name)
+        .map(|value| value.display().into_owned())
+        .unwrap_or_default()
+}
+
+/// Группирует строки `rows` в их порядке
+pub fn group(set: &ResultSet, rows: &[usize]) -> Grouping {
+    let mut grouping = Grouping::default();
+    for &row in rows {
+        let owner = text(set, row, "Owner");
+        grouping.owners.entry(owner).or_default().push(row);
+    }
+    grouping
+}
+
+impl Grouping {
+    /// Разделы владельцев с числом объектов; щелчок по объекту выбирает его строку
+    pub fn show(&self, ui: &mut egui::Ui, set: &ResultSet, selected: &mut Option<usize>) {
+        for (owner, rows) in &self.owners {
+            let title = format!("{owner} ({})", rows.len());
+            egui::CollapsingHeader::new(title)
+                .id_salt(("recycle_owner", owner))
+                .default_open(self.owners.len() == 1)
+                .show(ui, |ui| {
+                    for &row in rows {
+                        let path = match set.value(row, "OriginalPath") {
+                            Some(Value::Text(path)) => path.clone(),
+                            _ => tr("value.na").to_string(),
+                        };
+                        let when = match set.value(row, "DeletedAt") {
+                            Some(Value::Text(time)) => wmi_datetime(time)
+                                .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_default(),
+                            _ => String::new(),
+                        };
+                        ui.horizontal(|ui| {
+                            if ui.selectable_label(*selected == Some(row), path).clicked() {
+                                *selected = Some(row);
+                            }
+                            ui.weak(when);
+                        });
+                    }
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn sample(name: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join("recycle_bin")
+            .join("files")
+            .join(name);
+        std::fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn version_2_header() {
+        let item = parse(&sample("$IA1B2C3.docx")).unwrap();
+        assert_eq!(item.version, 2);
+        assert_eq!(item.size, 48213);
+        assert_eq!(item.path, "C:\\Users\\user\\Documents\\Отчёт.docx");
+        assert_eq!(
+            cim_datetime(item.deleted.unwrap()),
+            "20240501101530.250000+000"
+        );
+    }
+
+    #[test]
+    fn version_1_header() {
+        let item = parse(&sample("$I0K1L2M.exe")).unwrap();
+        assert_eq!(item.version, 1);
+        assert_eq!(item.size, 734208);
+        assert_eq!(item.path, "C:\\Users\\Administrator\\Desktop\\setup.exe");
+        assert_eq!(
+            cim_datetime(item.deleted.unwrap()),
+            "20230914081200.000000+000"
+        );
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        assert_eq!(parse(&sample("$IBROKEN.txt")), None);
+        let mut unknown = sample("$IA1B2C3.docx");
+        unknown[0] = 3;
+        assert_eq!(parse(&unknown), None);
+    }
+
+    #[test]
+    fn items_are_grouped_by_owner() {
+        let mut set = ResultSet::new(&["Owner", "OriginalPath"]);
+        for owner in ["WS-01\\user", "WS-01\\Administrator", "WS-01\\user"] {
+            set.rows.push(vec![owner.into(), Value::Null]);
+        }
+        let grouping = group(&set, &[0, 1, 2]);
+        assert_eq!(grouping.owners["WS-01\\user"], [0, 2]);
+        assert_eq!(grouping.owners["WS-01\\Administrator"], [1]);
+    }
+}
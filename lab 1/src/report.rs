@@ -0,0 +1,965 @@
+use crate::config::Profile;
+use crate::connection::Target;
+use crate::error::AppError;
+use crate::findings::{Check, Finding, Severity};
+use crate::i18n::{self, tr, trf};
+use crate::model::{CategoryExport, ExportFile, ResultSet, SCHEMA_VERSION};
+use crate::save::{Choice, SaveDialog};
+use crate::worker::{Ticket, Timeout, Worker};
+use crate::{categories, export, nav, queries, ActiveData};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+/// Сколько строк таблицы попадает в отчёт
+const MAX_ROWS: usize = 500;
+
+/// С какого числа столбцов Markdown-таблица заменяется блоком кода
+const MARKDOWN_TABLE_MAX_COLUMNS: usize = 4;
+
+/// Категории, строки «признак — значение» которых повторяются в сводке отчёта
+const SUMMARY_SECTIONS: [ActiveData; 2] = [ActiveData::Asset, ActiveData::Uptime];
+
+/// Формат файла отчёта
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    #[default]
+    Html,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    const ALL: [Format; 3] = [Format::Html, Format::Json, Format::Markdown];
+
+    fn title(self) -> &'static str {
+        match self {
+            Format::Html => "HTML",
+            Format::Json => "JSON",
+            Format::Markdown => "Markdown",
+        }
+    }
+
+    /// Формат по расширению файла; по умолчанию HTML
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("md") => Format::Markdown,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Format::Json,
+            _ => Format::Html,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Json => "json",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// Раздел отчёта: данные категории или ошибка её запроса
+pub struct Section {
+    pub category: ActiveData,
+    pub title: String,
+    pub data: Result<ResultSet, String>,
+}
+
+/// Отчёт в JSON: метаданные как в файле экспорта, находки и разделы целиком
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema_version: u32,
+    hostname: &'a str,
+    os: &'a str,
+    timestamp: DateTime<FixedOffset>,
+    app_version: &'static str,
+    findings: Vec<&'a Finding>,
+    sections: Vec<JsonSection<'a>>,
+}
+
+/// Раздел JSON-отчёта: данные в схеме экспорта или текст ошибки запроса
+#[derive(Serialize)]
+#[serde(untagged)]
+enum JsonSection<'a> {
+    Data(CategoryExport),
+    Error {
+        category: &'static str,
+        error: &'a str,
+    },
+}
+
+/// Собранный отчёт до преобразования в конкретный формат
+pub struct Report {
+    pub hostname: String,
+    pub os: String,
+    pub timestamp: chrono::DateTime<chrono::Local>,
+    pub findings: Vec<Finding>,
+    pub sections: Vec<Section>,
+}
+
+impl Report {
+    fn severity_counts(&self) -> Vec<(Severity, usize)> {
+        Severity::ALL
+            .iter()
+            .map(|&s| (s, self.findings.iter().filter(|f| f.severity == s).count()))
+            .collect()
+    }
+
+    // Находки от самых важных к менее важным
+    fn sorted_findings(&self) -> Vec<&Finding> {
+        let mut findings: Vec<_> = self.findings.iter().collect();
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        findings
+    }
+
+    // Категории по разделам боковой панели в их порядке; внутри раздела
+    // категории идут в выбранном порядке, пустые разделы пропускаются
+    fn grouped_sections(&self) -> Vec<(nav::Section, Vec<&Section>)> {
+        nav::Section::ALL
+            .into_iter()
+            .map(|group| {
+                let sections: Vec<_> = self
+                    .sections
+                    .iter()
+                    .filter(|section| section.category.section() == group)
+                    .collect();
+                (group, sections)
+            })
+            .filter(|(_, sections)| !sections.is_empty())
+            .collect()
+    }
+
+    // Подписи и значения сводки из разделов SUMMARY_SECTIONS
+    fn summary_lines(&self) -> Vec<(String, String)> {
+        self.sections
+            .iter()
+            .filter(|section| SUMMARY_SECTIONS.contains(&section.category))
+            .filter_map(|section| section.data.as_ref().ok())
+            .flat_map(|set| &set.rows)
+            .filter_map(|row| match row.as_slice() {
+                [label, value, ..] => Some((label.to_string(), value.to_string())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn timestamp_text(&self) -> String {
+        self.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Html => self.to_html(),
+            Format::Json => self.to_json(),
+            Format::Markdown => self.to_markdown(),
+        }
+    }
+
+    /// Полученные данные разделов для истории снимков; журнал в неё не попадает
+    pub fn export(&self) -> ExportFile {
+        let categories = self
+            .sections
+            .iter()
+            .filter(|section| section.category != ActiveData::Log)
+            .filter_map(|section| {
+                let set = section.data.as_ref().ok()?;
+                let rows: Vec<usize> = (0..set.rows.len()).collect();
+                Some(CategoryExport::new(section.category.slug(), set, &rows))
+            })
+            .collect();
+        ExportFile {
+            timestamp: self.timestamp.fixed_offset(),
+            ..ExportFile::new(self.hostname.clone(), categories)
+        }
+    }
+
+    /// JSON без ограничения числа строк: он читается программами, а не людьми
+    fn to_json(&self) -> String {
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| match &section.data {
+                Ok(set) => {
+                    let rows: Vec<usize> = (0..set.rows.len()).collect();
+                    JsonSection::Data(CategoryExport::new(section.category.slug(), set, &rows))
+                }
+                Err(error) => JsonSection::Error {
+                    category: section.category.slug(),
+                    error,
+                },
+            })
+            .collect();
+        let report = JsonReport {
+            schema_version: SCHEMA_VERSION,
+            hostname: &self.hostname,
+            os: &self.os,
+            timestamp: self.timestamp.fixed_offset(),
+            app_version: env!("CARGO_PKG_VERSION"),
+            findings: self.sorted_findings(),
+            sections,
+        };
+        let mut json = serde_json::to_string_pretty(&report).unwrap_or_default();
+        json.push('\n');
+        json
+    }
+
+    /// Самодостаточный HTML: стили встроены, внешних ресурсов нет
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+        let lang = match i18n::language() {
+            i18n::Language::Ru => "ru",
+            i18n::Language::En => "en",
+        };
+        html.push_str(&format!(
+            "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"utf-8\">\n"
+        ));
+        html.push_str(&format!(
+            "<title>{} — {}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n",
+            tr("report.title"),
+            escape(&self.hostname)
+        ));
+        html.push_str(&format!(
+            "<h1>{}</h1>\n<h2>{}</h2>\n<table class=\"summary\">\n",
+            tr("report.title"),
+            tr("report.summary")
+        ));
+        for (name, value) in [
+            (tr("report.host").to_string(), self.hostname.clone()),
+            (tr("report.os").to_string(), self.os.clone()),
+            (tr("report.time").to_string(), self.timestamp_text()),
+        ]
+        .into_iter()
+        .chain(self.summary_lines())
+        {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                escape(&name),
+                escape(&value)
+            ));
+        }
+        for (severity, count) in self.severity_counts() {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{count}</td></tr>\n",
+                trf("report.findings_count", &[("severity", &severity.title())])
+            ));
+        }
+        html.push_str("</table>\n");
+
+        if !self.findings.is_empty() {
+            html.push_str(&format!(
+                "<h2>{}</h2>\n<table>\n<tr>",
+                tr("report.findings")
+            ));
+            for key in FINDING_COLUMNS {
+                html.push_str(&format!("<th>{}</th>", tr(key)));
+            }
+            html.push_str("</tr>\n");
+            for finding in self.sorted_findings() {
+                html.push_str(&format!(
+                    "<tr class=\"{:?}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    finding.severity,
+                    finding.severity.title(),
+                    escape(&finding.category),
+                    escape(&finding.title),
+                    escape(&finding.detail)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        for (group, sections) in self.grouped_sections() {
+            html.push_str(&format!("<h2>{}</h2>\n", escape(group.title())));
+            for section in sections {
+                html.push_str(&format!("<h3>{}</h3>\n", escape(&section.title)));
+                match &section.data {
+                    Ok(set) => html.push_str(&html_table(set)),
+                    Err(error) => html.push_str(&format!(
+                        "<p class=\"error\">{} {}</p>\n",
+                        tr("report.query_error"),
+                        escape(error)
+                    )),
+                }
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Markdown для вики: сначала находки, затем категории по разделам
+    fn to_markdown(&self) -> String {
+        let mut md = format!("# {} — {}\n\n", tr("report.title"), self.hostname);
+        md.push_str(&format!("- {}: {}\n", tr("report.host"), self.hostname));
+        md.push_str(&format!("- {}: {}\n", tr("report.os"), self.os));
+        md.push_str(&format!(
+            "- {}: {}\n",
+            tr("report.time"),
+            self.timestamp_text()
+        ));
+        for (label, value) in self.summary_lines() {
+            md.push_str(&format!("- {label}: {value}\n"));
+        }
+        md.push('\n');
+
+        md.push_str(&format!("## {}\n\n", tr("report.findings")));
+        let counts: Vec<_> = self
+            .severity_counts()
+            .iter()
+            .map(|(severity, count)| format!("{}: {count}", severity.title()))
+            .collect();
+        md.push_str(&format!("{}\n\n", counts.join(", ")));
+        if !self.findings.is_empty() {
+            let titles: Vec<_> = FINDING_COLUMNS.iter().map(|&key| tr(key)).collect();
+            md.push_str(&format!("| {} |\n", titles.join(" | ")));
+            md.push_str("| --- | --- | --- | --- |\n");
+            for finding in self.sorted_findings() {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    finding.severity.title(),
+                    markdown_cell(&finding.category),
+                    markdown_cell(&finding.title),
+                    markdown_cell(&finding.detail)
+                ));
+            }
+            md.push('\n');
+        }
+
+        for (group, sections) in self.grouped_sections() {
+            md.push_str(&format!("## {}\n\n", group.title()));
+            for section in sections {
+                md.push_str(&format!("### {}\n\n", section.title));
+                match &section.data {
+                    Ok(set) if set.columns.len() > MARKDOWN_TABLE_MAX_COLUMNS => {
+                        md.push_str(&markdown_code_block(set))
+                    }
+                    Ok(set) => md.push_str(&markdown_table(set)),
+                    Err(error) => md.push_str(&format!(
+                        "**{}** {}\n",
+                        tr("report.query_error"),
+                        markdown_cell(error)
+                    )),
+                }
+                if let Ok(set) = &section.data {
+                    if set.rows.len() > MAX_ROWS {
+                        md.push_str(&format!("\n_{}_\n", truncated_note(set)));
+                    }
+                }
+                md.push('\n');
+            }
+        }
+        md
+    }
+}
+
+const FINDING_COLUMNS: [&str; 4] = [
+    "report.severity",
+    "report.category",
+    "report.description",
+    "report.detail",
+];
+
+fn truncated_note(set: &ResultSet) -> String {
+    trf(
+        "report.truncated",
+        &[("shown", &MAX_ROWS), ("total", &set.rows.len())],
+    )
+}
+
+const STYLE: &str = "body{font-family:Segoe UI,Arial,sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f0f0f0}\
+.note{color:#666;font-style:italic}\
+.error{color:#b00020}\
+.Critical td,.High td{background:#fde2e2}\
+.Medium td{background:#fff4d6}";
+
+fn html_table(set: &ResultSet) -> String {
+    let mut html = String::from("<table>\n<tr>");
+    for column in &set.columns {
+        html.push_str(&format!("<th>{}</th>", escape(&column.title)));
+    }
+    html.push_str("</tr>\n");
+    for row in set.rows.iter().take(MAX_ROWS) {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", escape(&cell.display())));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    if set.rows.len() > MAX_ROWS {
+        html.push_str(&format!("<p class=\"note\">{}</p>\n", truncated_note(set)));
+    }
+    html
+}
+
+// Ячейка таблицы Markdown: экранирование и перевод строк в <br>
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('`', "\\`")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn markdown_table(set: &ResultSet) -> String {
+    let titles: Vec<_> = set
+        .columns
+        .iter()
+        .map(|c| markdown_cell(&c.title))
+        .collect();
+    let mut md = format!("| {} |\n", titles.join(" | "));
+    md.push_str(&format!("|{}\n", " --- |".repeat(set.columns.len())));
+    for row in set.rows.iter().take(MAX_ROWS) {
+        let cells: Vec<_> = row.iter().map(|c| markdown_cell(&c.display())).collect();
+        md.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    md
+}
+
+// Широкие таблицы выводятся выровненным текстом в блоке кода
+fn markdown_code_block(set: &ResultSet) -> String {
+    let rows: Vec<Vec<String>> =
+        std::iter::once(set.columns.iter().map(|c| c.title.clone()).collect())
+            .chain(set.rows.iter().take(MAX_ROWS).map(|row| {
+                row.iter()
+                    .map(|c| c.display().replace(['\r', '\n'], " "))
+                    .collect()
+            }))
+            .collect();
+    let mut widths = vec![0; set.columns.len()];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    // Ограждение длиннее любой последовательности обратных кавычек внутри данных
+    let longest_run = rows
+        .iter()
+        .flatten()
+        .flat_map(|cell| cell.split(|c| c != '`').map(str::len))
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    let mut md = format!("{fence}text\n");
+    for row in &rows {
+        let line: Vec<_> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell:<width$}"))
+            .collect();
+        md.push_str(line.join("  ").trim_end());
+        md.push('\n');
+    }
+    md.push_str(&format!("{fence}\n"));
+    md
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Что включить в отчёт
+pub struct Request {
+    pub target: Target,
+    pub timeout: Timeout,
+    pub categories: Vec<ActiveData>, // В порядке разделов отчёта
+    pub checks: Vec<Check>,
+    pub format: Format,
+    pub path: PathBuf,
+    pub known: HashMap<ActiveData, ResultSet>, // Свежие данные окна; эти категории не запрашиваются
+}
+
+/// Выполняет запросы и проверки отчёта. Запросы идут через свой поток запросов,
+/// чтобы зависший запрос прерывался по времени ожидания, а не останавливал отчёт
+pub fn collect(request: &Request) -> Report {
+    let timeout = request.timeout;
+    let mut worker = Worker::new(request.target.clone());
+    let os =
+        worker.submit(|session| async move { categories::os_caption(session.connection()?).await });
+
+    // Сведения для учёта оборудования открывают каждый отчёт, даже если их не выбрали
+    let mut categories = request.categories.clone();
+    categories.retain(|&data| data != ActiveData::Asset);
+    categories.insert(0, ActiveData::Asset);
+
+    let mut needed = categories.clone();
+    for check in &request.checks {
+        if !needed.contains(&check.source()) {
+            needed.push(check.source());
+        }
+    }
+    needed.retain(|data| !request.known.contains_key(data));
+    // Запросы отправляются сразу все и выполняются параллельно;
+    // время ожидания отсчитывается для каждого отдельно
+    let tickets: Vec<_> = needed
+        .into_iter()
+        .map(|data| {
+            let ticket = worker.submit(move |session| async move {
+                queries::run(session.connection()?, data, None).await
+            });
+            (data, ticket)
+        })
+        .collect();
+    let os = wait(&os, timeout).unwrap_or_else(|e| format!("{} ({e})", tr("value.na")));
+    // Ошибка одной категории становится разделом с текстом ошибки, а не прерывает отчёт
+    let mut results: HashMap<_, Result<ResultSet, String>> = request
+        .known
+        .iter()
+        .map(|(&data, set)| (data, Ok(set.clone())))
+        .collect();
+    for (data, ticket) in &tickets {
+        results.insert(*data, wait(ticket, timeout).map_err(|e| e.detailed()));
+    }
+    let sections = categories
+        .iter()
+        .filter_map(|data| {
+            Some(Section {
+                category: *data,
+                title: data.title().to_string(),
+                data: results.get(data)?.clone(),
+            })
+        })
+        .collect();
+    let results: HashMap<_, _> = results
+        .into_iter()
+        .filter_map(|(data, result)| Some((data, result.ok()?)))
+        .collect();
+
+    Report {
+        hostname: request.target.host_name(),
+        os,
+        timestamp: chrono::Local::now(),
+        findings: request
+            .checks
+            .iter()
+            .flat_map(|c| c.run(&results))
+            .collect(),
+        sections,
+    }
+}
+
+// Результат запроса раздела; не дождавшийся ответа считается ошибкой времени ожидания
+fn wait<T>(ticket: &Ticket<Result<T, AppError>>, timeout: Timeout) -> Result<T, AppError> {
+    ticket
+        .wait(timeout.duration())
+        .unwrap_or_else(|_| Err(timeout.error()))
+}
+
+/// Записывает готовый отчёт в файл запроса
+pub fn write(request: &Request, report: &Report) -> Result<(), String> {
+    let written = std::fs::write(&request.path, report.render(request.format));
+    export::outcome(&request.path, written).map(|_| ())
+}
+
+/// Данные готового отчёта для истории снимков и пересылки находок
+pub struct Collected {
+    pub data: ExportFile,
+    pub findings: Vec<Finding>,
+}
+
+// Выполняется в отдельном потоке панели отчёта
+fn generate(request: Request) -> Result<(PathBuf, Collected), String> {
+    let report = collect(&request);
+    write(&request, &report)?;
+    let collected = Collected {
+        data: report.export(),
+        findings: report.findings,
+    };
+    Ok((request.path, collected))
+}
+
+/// Конструктор отчёта, сохранённые профили и состояние фоновой генерации
+pub struct ReportPanel {
+    order: Vec<ActiveData>, // Все категории в порядке разделов отчёта
+    categories: HashSet<ActiveData>,
+    checks: HashSet<Check>,
+    format: Format,
+    profiles: Vec<Profile>,
+    profile_name: String, // Имя профиля в конструкторе
+    builder_open: bool,
+    dialog: Option<(SaveDialog, Request)>, // Запрос ждёт выбора файла
+    job: Option<Receiver<Result<(PathBuf, Collected), String>>>,
+    collected: Option<Collected>,
+}
+
+impl Default for ReportPanel {
+    fn default() -> Self {
+        Self {
+            order: ActiveData::ALL.to_vec(),
+            categories: ActiveData::ALL.into_iter().collect(),
+            checks: Check::ALL.into_iter().collect(),
+            format: Format::Html,
+            profiles: Vec::new(),
+            profile_name: String::new(),
+            builder_open: false,
+            dialog: None,
+            job: None,
+            collected: None,
+        }
+    }
+}
+
+impl ReportPanel {
+    /// Выбранные категории в порядке разделов отчёта
+    pub fn categories(&self) -> Vec<ActiveData> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|d| self.categories.contains(d))
+            .collect()
+    }
+
+    /// Выбранные категории идут первыми в заданном порядке, остальные — за ними
+    pub fn set_categories(&mut self, categories: &[ActiveData]) {
+        self.categories = categories.iter().copied().collect();
+        self.order = categories.to_vec();
+        for data in ActiveData::ALL {
+            if !self.order.contains(&data) {
+                self.order.push(data);
+            }
+        }
+    }
+
+    fn checks(&self) -> Vec<Check> {
+        Check::ALL
+            .into_iter()
+            .filter(|c| self.checks.contains(c))
+            .collect()
+    }
+
+    /// Данные готового отчёта; забираются один раз
+    pub fn take_collected(&mut self) -> Option<Collected> {
+        self.collected.take()
+    }
+
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    pub fn set_profiles(&mut self, profiles: Vec<Profile>) {
+        self.profiles = profiles;
+    }
+
+    fn apply(&mut self, profile: Profile) {
+        self.set_categories(&profile.categories);
+        self.checks = profile.checks.into_iter().collect();
+        self.format = profile.format;
+        self.profile_name = profile.name;
+    }
+
+    // Совпадает ли текущий выбор с профилем
+    fn matches(&self, profile: &Profile) -> bool {
+        profile.categories == self.categories()
+            && profile.checks == self.checks()
+            && profile.format == self.format
+    }
+
+    // Профиль с тем же именем заменяется
+    fn save_profile(&mut self) {
+        let profile = Profile {
+            name: self.profile_name.trim().to_string(),
+            categories: self.categories(),
+            checks: self.checks(),
+            format: self.format,
+        };
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Проверка диалога сохранения и фоновой генерации; вызывается каждый кадр.
+    /// Возвращает сообщение для строки состояния, когда генерация закончилась или
+    /// сохранение отменено. `dir` запоминает каталог выбранного файла
+    pub fn poll(
+        &mut self,
+        ctx: &egui::Context,
+        dir: &mut Option<PathBuf>,
+    ) -> Option<Result<String, String>> {
+        if let Some((dialog, _)) = &self.dialog {
+            match dialog.poll() {
+                Choice::Waiting => {}
+                Choice::Cancelled => {
+                    self.dialog = None;
+                    return Some(Ok(tr("export.cancelled").to_string()));
+                }
+                Choice::Chosen(path) => {
+                    *dir = path.parent().map(PathBuf::from);
+                    if let Some((_, request)) = self.dialog.take() {
+                        self.start(Request { path, ..request });
+                    }
+                }
+            }
+        }
+        let job = self.job.as_ref()?;
+        let status = match job.try_recv() {
+            Ok(result) => result.map(|(path, data)| {
+                self.collected = Some(data);
+                trf("report.saved", &[("path", &path.display())])
+            }),
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                return None;
+            }
+            Err(TryRecvError::Disconnected) => Err(tr("report.aborted").to_string()),
+        };
+        self.job = None;
+        Some(status)
+    }
+
+    /// Выбор профиля, кнопки конструктора и генерации. Возвращает `true`, когда
+    /// нажата «Сформировать отчёт»; тогда вызывающий передаёт данные в `choose_file`
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut generate = false;
+        ui.horizontal(|ui| {
+            ui.label(tr("report.profile"));
+            self.profile_combo(ui);
+            if ui.button(tr("report.builder")).clicked() {
+                self.builder_open = true;
+            }
+            generate = self.generate_button(ui);
+        });
+        ui.label(trf(
+            "report.selection",
+            &[
+                ("categories", &self.categories.len()),
+                ("checks", &self.checks.len()),
+                ("format", &self.format.title()),
+            ],
+        ));
+
+        let mut open = self.builder_open;
+        egui::Window::new(tr("report.builder"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ui.ctx(), |ui| generate |= self.builder(ui));
+        self.builder_open = open;
+        generate
+    }
+
+    fn profile_combo(&mut self, ui: &mut egui::Ui) {
+        let selected = self
+            .profiles
+            .iter()
+            .position(|p| p.name == self.profile_name && self.matches(p));
+        let text = selected.map_or(tr("report.no_profile"), |i| &self.profiles[i].name);
+        let mut chosen = None;
+        egui::ComboBox::from_id_salt("report_profile")
+            .selected_text(text)
+            .show_ui(ui, |ui| {
+                for (index, profile) in self.profiles.iter().enumerate() {
+                    if ui
+                        .selectable_label(selected == Some(index), &profile.name)
+                        .clicked()
+                    {
+                        chosen = Some(index);
+                    }
+                }
+            });
+        if let Some(index) = chosen {
+            self.apply(self.profiles[index].clone());
+        }
+    }
+
+    fn generate_button(&self, ui: &mut egui::Ui) -> bool {
+        let idle = self.job.is_none() && self.dialog.is_none();
+        let clicked = ui
+            .add_enabled(idle, egui::Button::new(tr("report.generate")))
+            .clicked();
+        if self.job.is_some() {
+            ui.spinner();
+            ui.label(tr("report.running"));
+        }
+        clicked
+    }
+
+    // Содержимое окна конструктора: разделы с порядком, проверки, формат и профиль
+    fn builder(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.label(tr("report.categories"));
+        let mut swap = None;
+        let last = self.order.len() - 1;
+        for (index, &data) in self.order.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let up = ui
+                    .add_enabled(index > 0, egui::Button::new("⏶").small())
+                    .on_hover_text(tr("report.move_up"));
+                if up.clicked() {
+                    swap = Some((index, index - 1));
+                }
+                let down = ui
+                    .add_enabled(index < last, egui::Button::new("⏷").small())
+                    .on_hover_text(tr("report.move_down"));
+                if down.clicked() {
+                    swap = Some((index, index + 1));
+                }
+                toggle(ui, &mut self.categories, data, data.title());
+            });
+        }
+        if let Some((a, b)) = swap {
+            self.order.swap(a, b);
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("report.checks"));
+            if ui.button(tr("report.all_checks")).clicked() {
+                self.checks = Check::ALL.into_iter().collect();
+            }
+        });
+        for check in Check::ALL {
+            toggle(ui, &mut self.checks, check, check.title());
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("report.format"));
+            for format in Format::ALL {
+                ui.radio_value(&mut self.format, format, format.title());
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr("report.profile_name"));
+            ui.text_edit_singleline(&mut self.profile_name);
+            let name = self.profile_name.trim().to_string();
+            if ui
+                .add_enabled(
+                    !name.is_empty(),
+                    egui::Button::new(tr("report.save_profile")),
+                )
+                .clicked()
+            {
+                self.save_profile();
+            }
+            let exists = self.profiles.iter().any(|p| p.name == name);
+            if ui
+                .add_enabled(exists, egui::Button::new(tr("report.delete_profile")))
+                .clicked()
+            {
+                self.profiles.retain(|p| p.name != name);
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| self.generate_button(ui)).inner
+    }
+
+    /// Открывает диалог сохранения. Разделы запоминаются сейчас, путь
+    /// подставляется после выбора файла. `known` — свежие данные окна
+    pub fn choose_file(
+        &mut self,
+        ctx: &egui::Context,
+        target: &Target,
+        timeout: Timeout,
+        dir: Option<&Path>,
+        known: HashMap<ActiveData, ResultSet>,
+    ) {
+        let extension = self.format.extension();
+        let file_name = export::default_file_name(&target.host_name(), "report", extension);
+        let request = Request {
+            categories: self.categories(),
+            checks: self.checks(),
+            format: self.format,
+            path: PathBuf::from(&file_name),
+            target: target.clone(),
+            timeout,
+            known,
+        };
+        let dialog = SaveDialog::open(ctx, file_name, extension, dir);
+        self.dialog = Some((dialog, request));
+    }
+
+    fn start(&mut self, request: Request) {
+        self.job = Some(spawn(request));
+    }
+}
+
+/// Формирует отчёт в отдельном потоке; ответ приходит один раз
+pub fn spawn(request: Request) -> Receiver<Result<(PathBuf, Collected), String>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(generate(request));
+    });
+    rx
+}
+
+fn toggle<T: Eq + std::hash::Hash>(ui: &mut egui::Ui, set: &mut HashSet<T>, item: T, title: &str) {
+    let mut checked = set.contains(&item);
+    if ui.checkbox(&mut checked, title).changed() {
+        if checked {
+            set.insert(item);
+        } else {
+            set.remove(&item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(categories: &[ActiveData]) -> Report {
+        Report {
+            hostname: "WS-01".into(),
+            os: String::new(),
+            timestamp: chrono::Local::now(),
+            findings: Vec::new(),
+            sections: categories
+                .iter()
+                .map(|&category| Section {
+                    category,
+                    title: category.title().to_string(),
+                    data: Ok(ResultSet::default()),
+                })
+                .collect(),
+        }
+    }
+
+    // Заголовки идут в порядке `expected`
+    fn assert_in_order(text: &str, expected: &[String]) {
+        let mut from = 0;
+        for heading in expected {
+            let at = text[from..]
+                .find(heading.as_str())
+                .unwrap_or_else(|| panic!("нет {heading} после позиции {from}"));
+            from += at + heading.len();
+        }
+    }
+
+    #[test]
+    fn categories_are_grouped_by_section() {
+        // Выбранный порядок смешивает разделы
+        let report = report(&[ActiveData::DefenderExclusions, ActiveData::Cooling]);
+        let hardware = tr("section.hardware");
+        let security = tr("section.security");
+        let cooling = ActiveData::Cooling.title();
+        let exclusions = ActiveData::DefenderExclusions.title();
+        assert_in_order(
+            &report.to_html(),
+            &[
+                format!("<h2>{hardware}</h2>\n<h3>{cooling}</h3>"),
+                format!("<h2>{security}</h2>\n<h3>{exclusions}</h3>"),
+            ],
+        );
+        assert_in_order(
+            &report.to_markdown(),
+            &[
+                format!("## {hardware}\n\n### {cooling}\n"),
+                format!("## {security}\n\n### {exclusions}\n"),
+            ],
+        );
+        // Пустые разделы не выводятся
+        assert!(!report.to_html().contains(tr("section.network")));
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// Чем закончился диалог сохранения
+pub enum Choice {
+    Waiting,
+    Cancelled,
+    Chosen(PathBuf),
+}
+
+/// Системный диалог «Сохранить как» или «Открыть» в отдельном потоке, чтобы окно
+/// продолжало перерисовываться. О перезаписи существующего файла диалог спрашивает сам
+pub struct SaveDialog(Receiver<Option<PathBuf>>);
+
+impl SaveDialog {
+    /// `extension` — расширение без точки для фильтра; `dir` — последний каталог экспорта
+    pub fn open(
+        ctx: &egui::Context,
+        file_name: String,
+        extension: &'static str,
+        dir: Option<&Path>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        let dir = dir.map(Path::to_path_buf);
+        std::thread::spawn(move || {
+            let mut dialog = rfd::FileDialog::new()
+                .set_file_name(file_name)
+                .add_filter(extension.to_uppercase(), &[extension]);
+            if let Some(dir) = dir {
+                dialog = dialog.set_directory(dir);
+            }
+            let _ = tx.send(dialog.save_file());
+            ctx.request_repaint();
+        });
+        SaveDialog(rx)
+    }
+
+    /// Системный диалог «Открыть» для файла с расширением `extension`
+    pub fn pick(ctx: &egui::Context, extension: &'static str, dir: Option<&Path>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        let dir = dir.map(Path::to_path_buf);
+        std::thread::spawn(move || {
+            let mut dialog =
+                rfd::FileDialog::new().add_filter(extension.to_uppercase(), &[extension]);
+            if let Some(dir) = dir {
+                dialog = dialog.set_directory(dir);
+            }
+            let _ = tx.send(dialog.pick_file());
+            ctx.request_repaint();
+        });
+        SaveDialog(rx)
+    }
+
+    /// Системный диалог выбора каталога
+    pub fn pick_folder(ctx: &egui::Context, dir: Option<&Path>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        let dir = dir.map(Path::to_path_buf);
+        std::thread::spawn(move || {
+            let mut dialog = rfd::FileDialog::new();
+            if let Some(dir) = dir {
+                dialog = dialog.set_directory(dir);
+            }
+            let _ = tx.send(dialog.pick_folder());
+            ctx.request_repaint();
+        });
+        SaveDialog(rx)
+    }
+
+    /// Проверка каждый кадр; после `Cancelled` или `Chosen` диалог больше не нужен
+    pub fn poll(&self) -> Choice {
+        match self.0.try_recv() {
+            Ok(Some(path)) => Choice::Chosen(path),
+            Ok(None) | Err(TryRecvError::Disconnected) => Choice::Cancelled,
+            Err(TryRecvError::Empty) => Choice::Waiting,
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::i18n::tr;
+use chrono::{DateTime, Local, NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+/// Как повторяется запуск
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Repeat {
+    #[default]
+    Daily, // Каждый день в `at`
+    Hours, // Каждые `hours` часов после предыдущего запуска
+}
+
+/// Раздел `[schedule]`: отчёт по профилю, который формируется сам, пока программа открыта
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub profile: String, // Имя профиля отчёта
+    pub repeat: Repeat,
+    pub at: String, // «ЧЧ:ММ»
+    pub hours: u32,
+    pub dir: String, // Каталог отчётов; пусто — рядом с программой
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            profile: String::new(),
+            repeat: Repeat::Daily,
+            at: "09:00".to_string(),
+            hours: 24,
+            dir: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    fn time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.at.trim(), "%H:%M").ok()
+    }
+
+    /// Первый запуск строго после `after`; `None`, если время записано неверно
+    pub fn next(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self.repeat {
+            Repeat::Hours => Some(after + TimeDelta::hours(self.hours.max(1) as i64)),
+            Repeat::Daily => {
+                let time = self.time()?;
+                let mut day = after.date_naive();
+                // Следующие сутки, если время уже прошло; при переводе часов
+                // несуществующее время сдвигается на час вперёд
+                for _ in 0..3 {
+                    let naive = day.and_time(time);
+                    let local = naive.and_local_timezone(Local).earliest().or_else(|| {
+                        (naive + TimeDelta::hours(1))
+                            .and_local_timezone(Local)
+                            .earliest()
+                    });
+                    if let Some(local) = local.filter(|&t| t > after) {
+                        return Some(local);
+                    }
+                    day = day.succ_opt()?;
+                }
+                None
+            }
+        }
+    }
+
+    /// Поля для меню настроек; `profiles` — имена сохранённых профилей отчёта
+    pub fn show(&mut self, ui: &mut egui::Ui, profiles: &[String]) {
+        ui.checkbox(&mut self.enabled, tr("schedule.enabled"))
+            .on_hover_text(tr("schedule.hint"));
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("report.profile"));
+                let text = if self.profile.is_empty() {
+                    tr("report.no_profile")
+                } else {
+                    self.profile.as_str()
+                };
+                egui::ComboBox::from_id_salt("schedule_profile")
+                    .selected_text(text)
+                    .show_ui(ui, |ui| {
+                        for name in profiles {
+                            ui.selectable_value(&mut self.profile, name.clone(), name);
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.repeat, Repeat::Daily, tr("schedule.daily"));
+                ui.add_enabled(
+                    self.repeat == Repeat::Daily,
+                    egui::TextEdit::singleline(&mut self.at).desired_width(50.0),
+                );
+                if self.repeat == Repeat::Daily && self.time().is_none() {
+                    ui.colored_label(ui.visuals().error_fg_color, tr("schedule.bad_time"));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.repeat, Repeat::Hours, tr("schedule.every"));
+                ui.add_enabled(
+                    self.repeat == Repeat::Hours,
+                    egui::DragValue::new(&mut self.hours).range(1..=720),
+                );
+                ui.label(tr("schedule.hours"));
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("schedule.dir"));
+                ui.add(egui::TextEdit::singleline(&mut self.dir).desired_width(180.0));
+            });
+        });
+    }
+}
+
+/// Время запусков. Последний запуск сохраняется между сеансами, поэтому
+/// пропущенный (компьютер спал или программа была закрыта) выполняется
+/// один раз при первой возможности, а не столько раз, сколько пропущено
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Clock {
+    last: Option<DateTime<Local>>,
+    #[serde(skip)]
+    since: Option<DateTime<Local>>, // Запуск программы или последняя смена расписания
+    #[serde(skip)]
+    changed: bool, // Расписание менялось в этом сеансе
+    #[serde(skip)]
+    settings: Option<Settings>,
+}
+
+impl Clock {
+    fn track(&mut self, settings: &Settings, now: DateTime<Local>) {
+        if self.settings.as_ref() == Some(settings) {
+            return;
+        }
+        self.changed |= self.settings.is_some();
+        self.since = Some(now);
+        self.settings = Some(settings.clone());
+    }
+
+    /// Время следующего запуска; `None`, если расписание выключено
+    pub fn next_run(
+        &mut self,
+        settings: &Settings,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        self.track(settings, now);
+        if !settings.enabled || settings.profile.is_empty() {
+            return None;
+        }
+        // Изменённое расписание отсчитывается от момента изменения, без немедленного
+        // запуска; при старте программы — от последнего запуска прошлых сеансов
+        let base = if self.changed {
+            self.last.max(self.since)
+        } else {
+            self.last.or(self.since)
+        };
+        settings.next(base?)
+    }
+
+    /// Пора ли запускать; если да, запуск считается выполненным
+    pub fn due(&mut self, settings: &Settings, now: DateTime<Local>) -> bool {
+        let due = self.next_run(settings, now).is_some_and(|at| at <= now);
+        if due {
+            self.last = Some(now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 5, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn daily() -> Settings {
+        Settings {
+            enabled: true,
+            profile: "Ежемесячный аудит".to_string(),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn next_run_is_strictly_after() {
+        let settings = daily();
+        assert_eq!(settings.next(at(1, 8, 0)), Some(at(1, 9, 0)));
+        assert_eq!(settings.next(at(1, 9, 0)), Some(at(2, 9, 0)));
+        let hourly = Settings {
+            repeat: Repeat::Hours,
+            hours: 6,
+            ..daily()
+        };
+        assert_eq!(hourly.next(at(1, 9, 30)), Some(at(1, 15, 30)));
+        let broken = Settings {
+            at: "25:00".to_string(),
+            ..daily()
+        };
+        assert_eq!(broken.next(at(1, 8, 0)), None);
+    }
+
+    #[test]
+    fn missed_runs_collapse_into_one() {
+        let settings = daily();
+        let mut clock = Clock {
+            last: Some(at(1, 9, 0)),
+            ..Clock::default()
+        };
+        // Программа запущена через три дня: один запуск сразу, следующий — завтра в 9:00
+        assert!(clock.due(&settings, at(4, 12, 0)));
+        assert!(!clock.due(&settings, at(4, 12, 1)));
+        assert_eq!(clock.next_run(&settings, at(4, 12, 1)), Some(at(5, 9, 0)));
+    }
+
+    #[test]
+    fn changed_schedule_waits_for_next_slot() {
+        let mut clock = Clock {
+            last: Some(at(1, 9, 0)),
+            ..Clock::default()
+        };
+        assert!(!clock.due(&daily(), at(1, 10, 0)));
+        // Время сдвинуто на уже прошедшее сегодня: запуск завтра, а не сейчас
+        let earlier = Settings {
+            at: "08:00".to_string(),
+            ..daily()
+        };
+        assert!(!clock.due(&earlier, at(2, 8, 30)));
+        assert_eq!(clock.next_run(&earlier, at(2, 8, 30)), Some(at(3, 8, 0)));
+    }
+}
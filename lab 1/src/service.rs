@@ -0,0 +1,290 @@
+// Имена полей совпадают с именами свойств WMI
+#![allow(non_snake_case)]
+
+use crate::connection::Connection;
+use crate::error::AppError;
+use crate::model::{ResultSet, Value};
+use crate::worker::{Ticket, Timeout, Worker};
+use crate::wql;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+
+const CLASS: &str = "Win32_Service";
+
+/// Сколько раз после команды опрашивается состояние службы и с каким интервалом
+const POLLS: u32 = 10;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Код StopService, когда у службы есть запущенные зависимые службы
+const DEPENDENT_SERVICES_RUNNING: u32 = 3;
+
+#[derive(Debug, Deserialize)]
+struct ServiceState {
+    State: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dependent {
+    Name: String,
+    DisplayName: Option<String>,
+    State: Option<String>,
+}
+
+/// Команда службе
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Start,
+    Stop,
+    Restart,
+}
+
+/// Путь объекта службы; в значении ключа экранируются кавычки и обратная косая черта
+pub fn path(name: &str) -> String {
+    let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("{CLASS}.Name=\"{name}\"")
+}
+
+// StartService или StopService. При отказе из-за зависимых служб возвращается
+// их список вместо кода
+fn call(con: &Connection, name: &str, method: &'static str) -> Result<(), AppError> {
+    let code = con
+        .exec_method(&path(name), method, HashMap::new())
+        .map_err(|e| AppError::method(con.host(), CLASS, e))?;
+    match code {
+        0 => Ok(()),
+        DEPENDENT_SERVICES_RUNNING if method == "StopService" => Err(AppError::DependentServices {
+            service: name.to_string(),
+            dependents: running_dependents(con, name)?,
+        }),
+        code => Err(AppError::MethodFailed {
+            class: CLASS.to_string(),
+            method: method.to_string(),
+            code,
+        }),
+    }
+}
+
+// Запущенные службы, которые зависят от службы `name`
+fn running_dependents(con: &Connection, name: &str) -> Result<Vec<String>, AppError> {
+    let wql = format!(
+        "ASSOCIATORS OF {{{}}} WHERE AssocClass = Win32_DependentService Role = Antecedent",
+        path(name)
+    );
+    let dependents: Vec<Dependent> = con
+        .raw_query(&wql)
+        .map_err(|e| AppError::query(con.host(), "Win32_DependentService", e))?;
+    Ok(dependents
+        .into_iter()
+        .filter(|d| d.State.as_deref() == Some("Running"))
+        .map(|d| d.DisplayName.unwrap_or(d.Name))
+        .collect())
+}
+
+// Текущее состояние службы; `None`, если службы больше нет
+fn state(con: &Connection, name: &str) -> Result<Option<String>, AppError> {
+    let wql = format!(
+        "SELECT State FROM {CLASS} WHERE Name = {}",
+        wql::quote(name)
+    );
+    let rows: Vec<ServiceState> = con
+        .raw_query(&wql)
+        .map_err(|e| AppError::query(con.host(), CLASS, e))?;
+    Ok(rows.into_iter().next().and_then(|row| row.State))
+}
+
+/// Записывает состояние в строку службы `name`; `false`, если строки нет
+pub fn update_state(set: &mut ResultSet, name: &str, state: &str) -> bool {
+    let position = |column| set.columns.iter().position(|c| c.name == column);
+    let (Some(name_column), Some(state_column)) = (position("Name"), position("State")) else {
+        return false;
+    };
+    let Some(row) = set
+        .rows
+        .iter_mut()
+        .find(|row| matches!(row.get(name_column), Some(Value::Text(text)) if text == name))
+    else {
+        return false;
+    };
+    row[state_column] = Value::Text(state.to_string());
+    true
+}
+
+/// Служба выбранной строки: имя и состояние
+pub fn from_row(set: &ResultSet, row: usize) -> Option<(String, Option<String>)> {
+    let text = |name| match set.value(row, name) {
+        Some(Value::Text(text)) => Some(text.clone()),
+        _ => None,
+    };
+    Some((text("Name")?, text("State")))
+}
+
+// Этапы выполнения команды. Перезапуск — остановка, затем запуск
+enum Step {
+    Method {
+        ticket: Ticket<Result<(), AppError>>,
+        wanted: &'static str, // Состояние, которого ждать после вызова
+    },
+    Wait {
+        wanted: &'static str,
+        next: Instant,
+        left: u32,
+    },
+    Poll {
+        ticket: Ticket<Result<Option<String>, AppError>>,
+        wanted: &'static str,
+        left: u32,
+    },
+    Finished,
+}
+
+/// Что изменилось за кадр
+pub enum Progress {
+    State(String),                  // Новое состояние для строки таблицы
+    Done(Result<String, AppError>), // Команда завершена; Ok — последнее состояние
+}
+
+/// Выполняемая команда службе: вызов метода и несколько опросов состояния
+pub struct Control {
+    pub name: String,
+    pub command: Command,
+    step: Step,
+    then_start: bool, // После остановки запустить службу снова
+    deadline: Instant,
+}
+
+impl Control {
+    pub fn start(worker: &mut Worker, name: String, command: Command, timeout: Timeout) -> Self {
+        let (method, wanted) = match command {
+            Command::Start => ("StartService", "Running"),
+            Command::Stop | Command::Restart => ("StopService", "Stopped"),
+        };
+        Self {
+            step: method_step(worker, &name, method, wanted),
+            then_start: command == Command::Restart,
+            deadline: Instant::now() + timeout.duration(),
+            name,
+            command,
+        }
+    }
+
+    /// Приём ответов и очередной опрос состояния; вызывается каждый кадр.
+    /// Служба может так и остаться в промежуточном состоянии, это не ошибка
+    pub fn poll(
+        &mut self,
+        ctx: &egui::Context,
+        worker: &mut Worker,
+        timeout: Timeout,
+    ) -> Option<Progress> {
+        ctx.request_repaint_after(Duration::from_millis(100));
+        let now = Instant::now();
+        match &self.step {
+            Step::Method { ticket, wanted } => {
+                let result = match ticket.try_recv() {
+                    Ok(result) => result,
+                    Err(TryRecvError::Empty) if now < self.deadline => return None,
+                    Err(_) => Err(timeout.error()),
+                };
+                let wanted = *wanted;
+                if let Err(error) = result {
+                    self.step = Step::Finished;
+                    return Some(Progress::Done(Err(error)));
+                }
+                self.step = Step::Wait {
+                    wanted,
+                    next: now,
+                    left: POLLS,
+                };
+                None
+            }
+            Step::Wait { wanted, next, left } => {
+                if now < *next {
+                    return None;
+                }
+                let (wanted, left) = (*wanted, *left - 1);
+                let name = self.name.clone();
+                self.step = Step::Poll {
+                    ticket: worker
+                        .submit(move |session| async move { state(session.connection()?, &name) }),
+                    wanted,
+                    left,
+                };
+                self.deadline = now + timeout.duration();
+                None
+            }
+            Step::Poll {
+                ticket,
+                wanted,
+                left,
+            } => {
+                let state = match ticket.try_recv() {
+                    Ok(Ok(state)) => state.unwrap_or_default(),
+                    Ok(Err(error)) => {
+                        self.step = Step::Finished;
+                        return Some(Progress::Done(Err(error)));
+                    }
+                    Err(TryRecvError::Empty) if now < self.deadline => return None,
+                    Err(_) => {
+                        self.step = Step::Finished;
+                        return Some(Progress::Done(Err(timeout.error())));
+                    }
+                };
+                let (wanted, left) = (*wanted, *left);
+                let reached = state == wanted;
+                if reached && self.then_start {
+                    self.then_start = false;
+                    self.deadline = now + timeout.duration();
+                    self.step = method_step(worker, &self.name, "StartService", "Running");
+                } else if reached || left == 0 {
+                    self.step = Step::Finished;
+                    return Some(Progress::Done(Ok(state)));
+                } else {
+                    self.step = Step::Wait {
+                        wanted,
+                        next: now + POLL_INTERVAL,
+                        left,
+                    };
+                }
+                Some(Progress::State(state))
+            }
+            Step::Finished => None,
+        }
+    }
+}
+
+fn method_step(
+    worker: &mut Worker,
+    name: &str,
+    method: &'static str,
+    wanted: &'static str,
+) -> Step {
+    let name = name.to_string();
+    Step::Method {
+        ticket: worker
+            .submit(move |session| async move { call(session.connection()?, &name, method) }),
+        wanted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_escapes_key_value() {
+        assert_eq!(path("Spooler"), "Win32_Service.Name=\"Spooler\"");
+        assert_eq!(path("a\\b\"c"), "Win32_Service.Name=\"a\\\\b\\\"c\"");
+    }
+
+    #[test]
+    fn state_written_to_matching_row() {
+        let mut set = ResultSet::new(&["Name", "State"]);
+        set.rows.push(vec!["Spooler".into(), "Running".into()]);
+        set.rows.push(vec!["W32Time".into(), "Running".into()]);
+        assert!(update_state(&mut set, "W32Time", "Stopped"));
+        assert_eq!(set.value(1, "State"), Some(&Value::from("Stopped")));
+        assert_eq!(set.value(0, "State"), Some(&Value::from("Running")));
+        assert!(!update_state(&mut set, "Missing", "Stopped"));
+    }
+}
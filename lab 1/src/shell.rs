@@ -0,0 +1,141 @@
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Файл из значения свойства WMI. Командная строка службы даёт путь без кавычек
+/// и аргументов, пути драйверов `\SystemRoot\…` и `\??\…` разворачиваются,
+/// переменные `%name%` подставляются из окружения
+pub fn file_path(text: &str) -> Option<PathBuf> {
+    let text = text.trim();
+    let path = match text.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or(quoted),
+        None => unquoted_executable(text),
+    };
+    let path = expand(path.trim());
+    let path = match path.strip_prefix("\\??\\") {
+        Some(rest) => rest.to_string(),
+        None => path,
+    };
+    let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let path = match strip_prefix_ignore_case(&path, "\\SystemRoot\\") {
+        Some(rest) => format!("{root}\\{rest}"),
+        // Путь драйвера относительно каталога Windows
+        None if strip_prefix_ignore_case(&path, "System32\\").is_some() => {
+            format!("{root}\\{path}")
+        }
+        None => path,
+    };
+    // Только абсолютные пути: буква диска или сетевой ресурс
+    let bytes = path.as_bytes();
+    let absolute =
+        (bytes.len() > 2 && bytes[1] == b':' && bytes[2] == b'\\') || path.starts_with("\\\\");
+    absolute.then(|| PathBuf::from(path))
+}
+
+// Без кавычек путь с пробелами неотличим от аргументов, поэтому он заканчивается
+// на первом «.exe», за которым идёт пробел
+fn unquoted_executable(text: &str) -> &str {
+    let lower = text.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(".exe") {
+        let end = from + found + ".exe".len();
+        if lower[end..].is_empty() || lower[end..].starts_with(' ') {
+            return &text[..end];
+        }
+        from = end;
+    }
+    text
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+// Подстановка %name%; неизвестные переменные остаются как есть
+fn expand(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        out.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => out.push_str(&value),
+            _ => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Открывает папку в проводнике и выделяет в ней файл. Кавычки в путях Windows
+/// недопустимы, поэтому путь целиком берётся в кавычки без экранирования
+pub fn reveal(path: &Path) -> std::io::Result<()> {
+    let path = path.display().to_string().replace('"', "");
+    // Проводник разбирает «/select,» сам и не понимает стандартного экранирования
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{path}\""))
+        .spawn()
+        .map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_service_command_line() {
+        assert_eq!(
+            file_path("\"C:\\Program Files\\Vendor\\svc.exe\" -k run"),
+            Some(PathBuf::from("C:\\Program Files\\Vendor\\svc.exe"))
+        );
+    }
+
+    #[test]
+    fn unquoted_path_ends_at_exe() {
+        assert_eq!(
+            file_path("C:\\Windows\\system32\\svchost.exe -k netsvcs -p"),
+            Some(PathBuf::from("C:\\Windows\\system32\\svchost.exe"))
+        );
+        assert_eq!(
+            file_path("C:\\Program Files\\My App\\app.exe"),
+            Some(PathBuf::from("C:\\Program Files\\My App\\app.exe"))
+        );
+    }
+
+    #[test]
+    fn driver_paths_are_expanded() {
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        assert_eq!(
+            file_path("\\SystemRoot\\System32\\drivers\\tcpip.sys"),
+            Some(PathBuf::from(format!(
+                "{root}\\System32\\drivers\\tcpip.sys"
+            )))
+        );
+        assert_eq!(
+            file_path("System32\\drivers\\acpi.sys"),
+            Some(PathBuf::from(format!(
+                "{root}\\System32\\drivers\\acpi.sys"
+            )))
+        );
+        assert_eq!(
+            file_path("\\??\\C:\\Drivers\\x.sys"),
+            Some(PathBuf::from("C:\\Drivers\\x.sys"))
+        );
+    }
+
+    #[test]
+    fn relative_and_empty_values_have_no_file() {
+        assert_eq!(file_path(""), None);
+        assert_eq!(file_path("svchost.exe"), None);
+        assert_eq!(
+            expand("%LAB1_NO_SUCH_VARIABLE%\\x"),
+            "%LAB1_NO_SUCH_VARIABLE%\\x"
+        );
+    }
+}
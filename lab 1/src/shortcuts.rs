@@ -0,0 +1,110 @@
+use crate::i18n::tr;
+use crate::table;
+use egui::{Event, Key, KeyboardShortcut, Modifiers};
+
+const REFRESH: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::R);
+const FOCUS_FILTER: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+const EXPORT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::E);
+const CLEAR_FILTER: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::Escape);
+const COPY_ROW: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::C);
+
+/// Ctrl+1…9 выбирают категорию по порядку на боковой панели
+const CATEGORY_KEYS: [Key; 9] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+];
+
+/// Действие, вызванное сочетанием клавиш
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Refresh,
+    FocusFilter,
+    Export,
+    ClearFilter,
+    CopyRow,
+    Category(usize), // Индекс с нуля в порядке боковой панели
+}
+
+/// Нажатые в этом кадре сочетания. Сработавшие сочетания поглощаются, чтобы не
+/// попасть в виджеты. Пока фокус в поле ввода, работает только Esc в поле фильтра,
+/// а Ctrl+C копирует выделенный текст поля. `row_selected` — есть ли строка для Ctrl+C
+pub fn read(ctx: &egui::Context, row_selected: bool) -> Vec<Action> {
+    let typing = ctx.wants_keyboard_input();
+    let in_filter = ctx.memory(|memory| memory.has_focus(table::filter_id()));
+    ctx.input_mut(|input| {
+        let mut actions = Vec::new();
+        if typing {
+            // Esc не поглощается: поле фильтра само снимает с себя фокус
+            if in_filter && input.key_pressed(Key::Escape) {
+                actions.push(Action::ClearFilter);
+            }
+            return actions;
+        }
+        for (shortcut, action) in [
+            (REFRESH, Action::Refresh),
+            (FOCUS_FILTER, Action::FocusFilter),
+            (EXPORT, Action::Export),
+            (CLEAR_FILTER, Action::ClearFilter),
+        ] {
+            if input.consume_shortcut(&shortcut) {
+                actions.push(action);
+            }
+        }
+        for (index, key) in CATEGORY_KEYS.into_iter().enumerate() {
+            if input.consume_key(Modifiers::COMMAND, key) {
+                actions.push(Action::Category(index));
+            }
+        }
+        // Ctrl+C приходит событием копирования, а не нажатием клавиши.
+        // Без выбранной строки событие остаётся выделенному тексту надписей
+        let copy = |event: &Event| matches!(event, Event::Copy);
+        if row_selected && input.events.iter().any(copy) {
+            input.events.retain(|event| !copy(event));
+            actions.push(Action::CopyRow);
+        }
+        actions
+    })
+}
+
+/// Окно со списком сочетаний клавиш
+pub fn help(ctx: &egui::Context, open: &mut bool) {
+    let categories = format!(
+        "{}…9",
+        ctx.format_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Num1))
+    );
+    let rows = [
+        (ctx.format_shortcut(&REFRESH), tr("shortcuts.refresh")),
+        (
+            ctx.format_shortcut(&FOCUS_FILTER),
+            tr("shortcuts.focus_filter"),
+        ),
+        (
+            ctx.format_shortcut(&CLEAR_FILTER),
+            tr("shortcuts.clear_filter"),
+        ),
+        (ctx.format_shortcut(&EXPORT), tr("shortcuts.export")),
+        (ctx.format_shortcut(&COPY_ROW), tr("shortcuts.copy_row")),
+        (categories, tr("shortcuts.category")),
+    ];
+    egui::Window::new(tr("shortcuts.title"))
+        .open(open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts").striped(true).show(ui, |ui| {
+                for (keys, description) in rows {
+                    ui.monospace(keys);
+                    ui.label(description);
+                    ui.end_row();
+                }
+            });
+            ui.weak(tr("shortcuts.typing_hint"));
+        });
+}
@@ -0,0 +1,103 @@
+use crate::i18n::trf;
+use crate::model::{ExportFile, SCHEMA_VERSION};
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "snapshot.json";
+
+/// Снимок последних результатов лежит рядом с файлом настроек
+pub fn path(config: &Path) -> PathBuf {
+    config.with_file_name(FILE_NAME)
+}
+
+/// Читает снимок или файл экспорта JSON; оба в одной схеме
+pub fn read(path: &Path) -> Result<ExportFile, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        trf(
+            "snapshot.read_failed",
+            &[("path", &path.display()), ("error", &e)],
+        )
+    })?;
+    parse(&text).map_err(|e| match e {
+        Parse::Invalid(e) => trf(
+            "snapshot.read_failed",
+            &[("path", &path.display()), ("error", &e)],
+        ),
+        Parse::NotExport => trf("snapshot.not_export", &[("path", &path.display())]),
+        Parse::Schema(version) => trf(
+            "snapshot.newer_schema",
+            &[("version", &version), ("supported", &SCHEMA_VERSION)],
+        ),
+    })
+}
+
+/// Почему текст не читается как файл экспорта
+#[derive(Debug, PartialEq)]
+pub enum Parse {
+    Invalid(String), // Не JSON или поля не той формы
+    NotExport,       // JSON без версии схемы: чужой файл
+    Schema(u64),     // Схема новее поддерживаемой
+}
+
+/// Версия схемы проверяется до разбора полей, чтобы файл более новой версии
+/// давал понятное сообщение, а не ошибку о незнакомом поле
+pub fn parse(text: &str) -> Result<ExportFile, Parse> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| Parse::Invalid(e.to_string()))?;
+    let version = value
+        .get("schema_version")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or(Parse::NotExport)?;
+    if version > SCHEMA_VERSION as u64 {
+        return Err(Parse::Schema(version));
+    }
+    serde_json::from_value(value).map_err(|e| Parse::Invalid(e.to_string()))
+}
+
+/// Откуда взяты офлайн-данные категории. Данные другого компьютера только
+/// просматриваются: действия над их строками недоступны
+#[derive(Debug, Clone, PartialEq)]
+pub struct Origin {
+    pub host: String,
+    pub time: DateTime<Local>,
+    pub foreign: bool,
+}
+
+impl Origin {
+    pub fn new(file: &ExportFile, local_host: &str) -> Self {
+        Self {
+            host: file.hostname.clone(),
+            time: file.timestamp.with_timezone(&Local),
+            foreign: !file.hostname.eq_ignore_ascii_case(local_host),
+        }
+    }
+
+    /// Пометка над таблицей
+    pub fn label(&self) -> String {
+        let time = self.time.format("%d.%m.%Y %H:%M");
+        if self.foreign {
+            trf(
+                "snapshot.foreign_label",
+                &[("host", &self.host), ("time", &time)],
+            )
+        } else {
+            trf("snapshot.label", &[("time", &time)])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_version_is_checked_before_fields() {
+        let file = ExportFile::new("WS-01".to_string(), Vec::new());
+        let text = serde_json::to_string(&file).unwrap();
+        assert_eq!(parse(&text).unwrap().hostname, "WS-01");
+        let newer = text.replace("\"schema_version\":1", "\"schema_version\":7,\"extra\":[1]");
+        assert_eq!(parse(&newer), Err(Parse::Schema(7)));
+        assert_eq!(parse("{\"rows\": []}"), Err(Parse::NotExport));
+        assert!(matches!(parse("[1, 2"), Err(Parse::Invalid(_))));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::AppError;
+use crate::model::{Property, Value};
+use std::collections::HashMap;
+
+/// Пространство имён таблиц категорий
+pub const CIMV2: &str = "root\\cimv2";
+
+/// Пространство имён поставщика реестра StdRegProv
+pub const REGISTRY: &str = "root\\default";
+
+/// Объект WMI: значения свойств по именам
+pub type Row = HashMap<String, Value>;
+
+/// Объект WMI, у которого элементы свойств-массивов хранятся по отдельности
+pub type Object = HashMap<String, Property>;
+
+/// Откуда берутся строки WMI. Таблицы категорий строятся только через него,
+/// поэтому их и проверки над ними можно испытать на записанных ответах, без WMI
+pub trait DataSource {
+    /// Строки запроса `wql` к классу `class` пространства имён `namespace`
+    async fn query_rows(
+        &self,
+        class: &str,
+        namespace: &str,
+        wql: &str,
+    ) -> Result<Vec<Row>, AppError>;
+
+    /// Как [`DataSource::query_rows`], но массивы не склеиваются в одну строку
+    async fn query_objects(
+        &self,
+        class: &str,
+        namespace: &str,
+        wql: &str,
+    ) -> Result<Vec<Object>, AppError>;
+
+    /// Содержимое файла по пути на этом компьютере. WMI содержимое файлов
+    /// не возвращает, поэтому файл читается напрямую
+    fn read_file(&self, path: &str) -> std::io::Result<Vec<u8>>;
+
+    /// Вызов метода `method` класса или объекта по пути `path`, например чтение
+    /// реестра через StdRegProv. Возвращает выходные параметры вместе с ReturnValue
+    fn call(
+        &self,
+        namespace: &str,
+        path: &str,
+        method: &str,
+        params: &[(&str, Value)],
+    ) -> Result<Object, AppError>;
+}
+
+/// Запрос всех свойств класса с необязательным условием WHERE
+pub fn select(class: &str, filter: Option<&str>) -> String {
+    match filter {
+        Some(filter) => format!("SELECT * FROM {class} WHERE {filter}"),
+        None => format!("SELECT * FROM {class}"),
+    }
+}
+
+/// Записанные ответы WMI из `fixtures/<снимок>/<класс>.json`: массив объектов
+/// со значениями свойств; свойства-массивы читает только `query_objects`.
+/// Условие WHERE и пространство имён не учитываются.
+/// Содержимое файлов — в `fixtures/<снимок>/files/`, ответы методов — в
+/// `<класс>.<метод>.json`: массив пар входных параметров и выходных
+#[cfg(test)]
+pub struct Fixtures {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(test)]
+impl Fixtures {
+    pub fn new(capture: &str) -> Self {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join(capture);
+        Self { dir }
+    }
+
+    fn load<T: serde::de::DeserializeOwned>(&self, class: &str) -> Vec<T> {
+        let path = self.dir.join(format!("{class}.json"));
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("нет записанного ответа {}: {e}", path.display()));
+        serde_json::from_str(&text)
+            .unwrap_or_else(|e| panic!("повреждён записанный ответ {}: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+impl DataSource for Fixtures {
+    async fn query_rows(
+        &self,
+        class: &str,
+        _namespace: &str,
+        _wql: &str,
+    ) -> Result<Vec<Row>, AppError> {
+        Ok(self.load(class))
+    }
+
+    async fn query_objects(
+        &self,
+        class: &str,
+        _namespace: &str,
+        _wql: &str,
+    ) -> Result<Vec<Object>, AppError> {
+        Ok(self.load(class))
+    }
+
+    // Файлы лежат в `files/` снимка под своими именами, без каталогов. Файлы с
+    // одинаковыми именами — по полному пути без буквы диска
+    fn read_file(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        let files = self.dir.join("files");
+        let relative = path.split_once(":\\").map_or(path, |(_, rest)| rest);
+        let nested = files.join(relative.replace('\\', "/"));
+        if nested.is_file() {
+            return std::fs::read(nested);
+        }
+        let name = path.rsplit('\\').next().unwrap_or(path);
+        std::fs::read(files.join(name))
+    }
+
+    fn call(
+        &self,
+        _namespace: &str,
+        path: &str,
+        method: &str,
+        params: &[(&str, Value)],
+    ) -> Result<Object, AppError> {
+        #[derive(serde::Deserialize)]
+        struct Answer {
+            params: Row,
+            output: Object,
+        }
+        let class = path.split('.').next().unwrap_or(path);
+        let params: Row = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        let answers: Vec<Answer> = self.load(&format!("{class}.{method}"));
+        let answer = answers
+            .into_iter()
+            .find(|answer| answer.params == params)
+            .unwrap_or_else(|| panic!("нет записанного ответа {class}.{method} для {params:?}"));
+        Ok(answer.output)
+    }
+}
@@ -0,0 +1,121 @@
+use crate::i18n::{tr, trf};
+use chrono::{DateTime, Local};
+use std::time::Duration;
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+/// Запущен ли процесс с правами администратора; `None`, если токен не удалось прочитать
+pub fn is_elevated() -> Option<bool> {
+    let mut token = HANDLE::default();
+    unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) }.ok()?;
+    let mut elevation = TOKEN_ELEVATION::default();
+    let mut size = 0;
+    let read = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        )
+    };
+    let _ = unsafe { CloseHandle(token) };
+    read.ok()?;
+    Some(elevation.TokenIsElevated != 0)
+}
+
+/// Что показать в строке состояния в этом кадре
+pub struct Info<'a> {
+    pub host: &'a str,
+    pub namespace: &'a str,
+    pub rows: Option<(usize, usize)>, // Строк после фильтра и всего
+    pub selected: usize,              // Сколько строк выбрано
+    pub duration: Option<Duration>,   // Длительность последнего запроса категории
+    pub running: usize,               // Сколько запросов ещё выполняется
+    pub message: Option<&'a Result<String, String>>, // Итог последнего экспорта или отчёта
+    pub next_run: Option<DateTime<Local>>, // Следующий отчёт по расписанию
+    pub scheduled: bool,              // Отчёт по расписанию формируется
+}
+
+/// Строка состояния внизу окна. Права процесса проверяются один раз при создании
+#[derive(Default)]
+pub struct StatusBar {
+    elevated: Option<bool>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self {
+            elevated: is_elevated(),
+        }
+    }
+
+    /// Права процесса, прочитанные при создании
+    pub fn elevated(&self) -> Option<bool> {
+        self.elevated
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui, info: Info) {
+        ui.horizontal(|ui| {
+            ui.label(format!("🖧 {} · {}", info.host, info.namespace));
+            // Без прав администратора часть классов молча возвращает меньше строк
+            match self.elevated {
+                Some(true) => {
+                    ui.separator();
+                    ui.label(tr("status.elevated"));
+                }
+                Some(false) => {
+                    ui.separator();
+                    ui.colored_label(ui.visuals().warn_fg_color, tr("status.not_elevated"))
+                        .on_hover_text(tr("status.not_elevated_hint"));
+                }
+                None => {}
+            }
+            if let Some((shown, total)) = info.rows {
+                ui.separator();
+                ui.label(trf("status.rows", &[("shown", &shown), ("total", &total)]));
+            }
+            if info.selected > 0 {
+                ui.separator();
+                ui.label(trf("status.selected", &[("count", &info.selected)]));
+            }
+            if let Some(duration) = info.duration {
+                ui.separator();
+                let secs = format!("{:.2}", duration.as_secs_f64());
+                ui.label(trf("status.duration", &[("secs", &secs)]));
+            }
+            if info.running > 0 {
+                ui.separator();
+                ui.spinner();
+                ui.label(trf("status.running", &[("count", &info.running)]));
+            }
+            if info.scheduled {
+                ui.separator();
+                ui.spinner();
+                ui.label(tr("schedule.running"));
+            } else if let Some(at) = info.next_run {
+                ui.separator();
+                let format = if at.date_naive() == Local::now().date_naive() {
+                    "%H:%M"
+                } else {
+                    "%d.%m %H:%M"
+                };
+                let time = at.format(format).to_string();
+                ui.label(trf("schedule.next", &[("time", &time)]))
+                    .on_hover_text(tr("schedule.hint"));
+            }
+            match info.message {
+                Some(Ok(message)) => {
+                    ui.separator();
+                    ui.label(message);
+                }
+                Some(Err(message)) => {
+                    ui.separator();
+                    ui.colored_label(ui.visuals().error_fg_color, message);
+                }
+                None => {}
+            }
+        });
+    }
+}
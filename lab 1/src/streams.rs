@@ -0,0 +1,432 @@
+use crate::i18n::{tr, trf};
+use crate::model::{ResultSet, Value};
+use crate::save::{Choice, SaveDialog};
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::os::windows::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::ERROR_HANDLE_EOF;
+use windows::Win32::Storage::FileSystem::{
+    FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard, WIN32_FIND_STREAM_DATA,
+};
+
+/// Поток с отметкой о происхождении файла
+pub const ZONE_IDENTIFIER: &str = "Zone.Identifier";
+
+// Атрибуты FILE_ATTRIBUTE_DIRECTORY и FILE_ATTRIBUTE_REPARSE_POINT
+const DIRECTORY: u32 = 0x10;
+const REPARSE_POINT: u32 = 0x400;
+
+/// Имя потока из ответа FindFirstStreamW: «:имя:$DATA» → «имя».
+/// У основного потока «::$DATA» имя пустое
+pub fn stream_name(raw: &str) -> &str {
+    let name = raw.strip_prefix(':').unwrap_or(raw);
+    name.strip_suffix(":$DATA").unwrap_or(name)
+}
+
+/// Зона из содержимого Zone.Identifier: 3 — интернет, 4 — ограниченные узлы
+pub fn zone_id(text: &str) -> Option<u32> {
+    text.lines()
+        .find_map(|line| line.trim().strip_prefix("ZoneId=")?.trim().parse().ok())
+}
+
+/// Файл или каталог с дополнительным потоком
+#[derive(Debug, Clone, PartialEq)]
+pub struct Found {
+    pub path: PathBuf,
+    pub stream: String,
+    pub size: i64,
+}
+
+/// Итог обхода каталогов
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Report {
+    pub found: Vec<Found>,
+    pub scanned: usize,
+    pub marked: usize, // Помечены Zone.Identifier как загруженные из интернета
+    pub errors: usize, // Каталоги и файлы, которые не удалось прочитать
+    pub limited: bool, // Обход остановлен пределом глубины или числа файлов
+    pub cancelled: bool,
+}
+
+impl Report {
+    /// Таблица категории: файлы с потоками, кроме основного и Zone.Identifier
+    pub fn result_set(&self) -> ResultSet {
+        let mut set = ResultSet::new(&["FilePath", "Stream", "StreamSize"]);
+        set.rows = self
+            .found
+            .iter()
+            .map(|found| {
+                vec![
+                    found.path.display().to_string().into(),
+                    found.stream.as_str().into(),
+                    Value::Int(found.size),
+                ]
+            })
+            .collect();
+        set
+    }
+
+    /// Учитывает потоки одного файла. `zone` читает Zone.Identifier, только если он есть
+    fn add(&mut self, path: &Path, streams: Vec<(String, i64)>, zone: impl Fn() -> Option<u32>) {
+        for (raw, size) in streams {
+            match stream_name(&raw) {
+                "" => {}
+                ZONE_IDENTIFIER => {
+                    if zone().is_some_and(|zone| zone >= 3) {
+                        self.marked += 1;
+                    }
+                }
+                name => self.found.push(Found {
+                    path: path.to_path_buf(),
+                    stream: name.to_string(),
+                    size,
+                }),
+            }
+        }
+    }
+}
+
+// Потоки файла или каталога с размерами. У каталога без именованных потоков
+// FindFirstStreamW сразу отвечает ERROR_HANDLE_EOF
+fn file_streams(path: &Path) -> windows::core::Result<Vec<(String, i64)>> {
+    let mut data = WIN32_FIND_STREAM_DATA::default();
+    let pointer = &mut data as *mut WIN32_FIND_STREAM_DATA as *mut c_void;
+    let handle = match unsafe {
+        FindFirstStreamW(&HSTRING::from(path), FindStreamInfoStandard, pointer, None)
+    } {
+        Ok(handle) => handle,
+        Err(e) if e.code() == ERROR_HANDLE_EOF.to_hresult() => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut streams = Vec::new();
+    loop {
+        let len = data.cStreamName.iter().position(|&c| c == 0).unwrap_or(0);
+        streams.push((
+            String::from_utf16_lossy(&data.cStreamName[..len]),
+            data.StreamSize,
+        ));
+        if unsafe { FindNextStreamW(handle, pointer) }.is_err() {
+            break;
+        }
+    }
+    let _ = unsafe { FindClose(handle) };
+    Ok(streams)
+}
+
+fn read_zone(path: &Path) -> Option<u32> {
+    let mut stream = path.as_os_str().to_owned();
+    stream.push(":");
+    stream.push(ZONE_IDENTIFIER);
+    zone_id(&std::fs::read_to_string(stream).ok()?)
+}
+
+/// Пределы обхода: глубина вложенности каталогов и число проверенных файлов
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    depth: usize,
+    files: usize,
+}
+
+// Обход в ширину от каждого каталога. Символьные ссылки и точки соединения
+// не открываются: они могут вести в уже пройденный каталог
+fn walk(dirs: &[PathBuf], limits: Limits, progress: &AtomicUsize, cancel: &AtomicBool) -> Report {
+    let mut report = Report::default();
+    let mut queue: std::collections::VecDeque<(PathBuf, usize)> =
+        dirs.iter().map(|dir| (dir.clone(), 0)).collect();
+    'walk: while let Some((dir, depth)) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            report.errors += 1;
+            continue;
+        };
+        for entry in entries.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                report.cancelled = true;
+                break 'walk;
+            }
+            if report.scanned >= limits.files {
+                report.limited = true;
+                break 'walk;
+            }
+            // Сведения записи каталога не проходят по ссылкам
+            let Ok(meta) = entry.metadata() else {
+                report.errors += 1;
+                continue;
+            };
+            let attributes = meta.file_attributes();
+            let is_dir = attributes & DIRECTORY != 0;
+            if is_dir && attributes & REPARSE_POINT != 0 {
+                continue;
+            }
+            let path = entry.path();
+            if is_dir {
+                if depth < limits.depth {
+                    queue.push_back((path.clone(), depth + 1));
+                } else {
+                    report.limited = true;
+                }
+            }
+            report.scanned += 1;
+            progress.store(report.scanned, Ordering::Relaxed);
+            match file_streams(&path) {
+                Ok(streams) => report.add(&path, streams, || read_zone(&path)),
+                Err(_) => report.errors += 1,
+            }
+        }
+    }
+    report
+}
+
+// Идущий поиск: счётчик проверенных файлов, флаг отмены и итог
+struct Scan {
+    progress: Arc<AtomicUsize>,
+    cancel: Arc<AtomicBool>,
+    done: Receiver<Report>,
+    started: Instant,
+}
+
+/// Поиск дополнительных потоков NTFS в выбранных каталогах этого компьютера.
+/// Каталоги и пределы сохраняются между запусками
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scanner {
+    pub dirs: Vec<PathBuf>,
+    pub max_depth: usize,
+    pub max_files: usize,
+    #[serde(skip)]
+    scan: Option<Scan>,
+    #[serde(skip)]
+    dialog: Option<SaveDialog>,
+    #[serde(skip)]
+    last: Option<Report>,
+}
+
+// Загрузки и временные файлы — каталоги, куда первыми попадают скачанные файлы
+fn default_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(profile) = std::env::var_os("USERPROFILE") {
+        dirs.push(PathBuf::from(profile).join("Downloads"));
+    }
+    dirs.push(std::env::temp_dir());
+    dirs
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Self {
+            dirs: default_dirs(),
+            max_depth: 8,
+            max_files: 100_000,
+            scan: None,
+            dialog: None,
+            last: None,
+        }
+    }
+}
+
+impl Scanner {
+    pub fn running(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// Запускает поиск в отдельном потоке; идущий поиск не перезапускается
+    pub fn start(&mut self) {
+        if self.scan.is_some() || self.dirs.is_empty() {
+            return;
+        }
+        let progress = Arc::new(AtomicUsize::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, done) = mpsc::channel();
+        let dirs = self.dirs.clone();
+        let limits = Limits {
+            depth: self.max_depth,
+            files: self.max_files,
+        };
+        let (thread_progress, thread_cancel) = (progress.clone(), cancel.clone());
+        std::thread::spawn(move || {
+            let report = walk(&dirs, limits, &thread_progress, &thread_cancel);
+            tracing::info!(
+                files = report.scanned,
+                streams = report.found.len(),
+                "поиск потоков завершён"
+            );
+            let _ = tx.send(report);
+        });
+        self.scan = Some(Scan {
+            progress,
+            cancel,
+            done,
+            started: Instant::now(),
+        });
+    }
+
+    /// Таблица и длительность, когда поиск закончился
+    pub fn poll(&mut self) -> Option<(ResultSet, Duration)> {
+        let scan = self.scan.as_ref()?;
+        let report = match scan.done.try_recv() {
+            Ok(report) => report,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Report::default(),
+        };
+        let elapsed = scan.started.elapsed();
+        self.scan = None;
+        let set = report.result_set();
+        self.last = Some(report);
+        Some((set, elapsed))
+    }
+
+    /// Каталоги, пределы, запуск и ход поиска над таблицей
+    pub fn controls(&mut self, ui: &mut egui::Ui, local: bool) {
+        if !local {
+            ui.colored_label(ui.visuals().warn_fg_color, tr("streams.local_only"));
+        }
+        let mut removed = None;
+        for (index, dir) in self.dirs.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(self.scan.is_none(), egui::Button::new("✖").small())
+                    .on_hover_text(tr("streams.remove_dir"))
+                    .clicked()
+                {
+                    removed = Some(index);
+                }
+                ui.label(dir.display().to_string());
+            });
+        }
+        if let Some(index) = removed {
+            self.dirs.remove(index);
+        }
+        if let Some(dialog) = &self.dialog {
+            match dialog.poll() {
+                Choice::Waiting => {}
+                Choice::Cancelled => self.dialog = None,
+                Choice::Chosen(dir) => {
+                    if !self.dirs.contains(&dir) {
+                        self.dirs.push(dir);
+                    }
+                    self.dialog = None;
+                }
+            }
+        }
+        ui.horizontal(|ui| {
+            let idle = self.scan.is_none();
+            if ui
+                .add_enabled(
+                    idle && self.dialog.is_none(),
+                    egui::Button::new(tr("streams.add_dir")),
+                )
+                .clicked()
+            {
+                self.dialog = Some(SaveDialog::pick_folder(
+                    ui.ctx(),
+                    self.dirs.last().map(PathBuf::as_path),
+                ));
+            }
+            ui.label(tr("streams.max_depth"));
+            ui.add_enabled(
+                idle,
+                egui::DragValue::new(&mut self.max_depth).range(0..=64),
+            );
+            ui.label(tr("streams.max_files"));
+            ui.add_enabled(
+                idle,
+                egui::DragValue::new(&mut self.max_files)
+                    .range(100..=10_000_000)
+                    .speed(100),
+            );
+            match &self.scan {
+                Some(scan) => {
+                    ui.spinner();
+                    ui.label(trf(
+                        "streams.progress",
+                        &[("count", &scan.progress.load(Ordering::Relaxed))],
+                    ));
+                    if ui.button(tr("streams.cancel")).clicked() {
+                        scan.cancel.store(true, Ordering::Relaxed);
+                    }
+                    ui.ctx().request_repaint_after(Duration::from_millis(200));
+                }
+                None => {
+                    if ui
+                        .add_enabled(
+                            !self.dirs.is_empty(),
+                            egui::Button::new(tr("streams.start")),
+                        )
+                        .clicked()
+                    {
+                        self.start();
+                    }
+                }
+            }
+        });
+        if let Some(report) = &self.last {
+            ui.label(trf(
+                "streams.summary",
+                &[
+                    ("found", &report.found.len()),
+                    ("scanned", &report.scanned),
+                    ("marked", &report.marked),
+                ],
+            ));
+            let visuals = ui.visuals();
+            if report.cancelled {
+                ui.colored_label(visuals.warn_fg_color, tr("streams.cancelled"));
+            } else if report.limited {
+                ui.colored_label(visuals.warn_fg_color, tr("streams.limited"));
+            }
+            if report.errors > 0 {
+                ui.weak(trf("streams.errors", &[("count", &report.errors)]));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::{self, Severity};
+    use crate::ActiveData;
+
+    #[test]
+    fn stream_names_and_zones() {
+        assert_eq!(stream_name("::$DATA"), "");
+        assert_eq!(stream_name(":Zone.Identifier:$DATA"), ZONE_IDENTIFIER);
+        assert_eq!(
+            zone_id("[ZoneTransfer]\r\nZoneId=3\r\nHostUrl=https://example.com/a.zip\r\n"),
+            Some(3)
+        );
+        assert_eq!(zone_id("[ZoneTransfer]\r\n"), None);
+    }
+
+    #[test]
+    fn zone_identifier_is_counted_not_listed() {
+        let mut report = Report::default();
+        let streams = |names: &[&str]| names.iter().map(|n| (n.to_string(), 26)).collect();
+        report.add(
+            Path::new("C:\\Users\\user\\Downloads\\setup.zip"),
+            streams(&["::$DATA", ":Zone.Identifier:$DATA"]),
+            || Some(3),
+        );
+        report.add(
+            Path::new("C:\\Users\\user\\Downloads\\local.txt"),
+            streams(&["::$DATA", ":Zone.Identifier:$DATA"]),
+            || Some(2),
+        );
+        report.add(
+            Path::new("C:\\Users\\user\\Downloads\\report.docx"),
+            streams(&["::$DATA", ":hidden.ps1:$DATA"]),
+            || panic!("Zone.Identifier нет"),
+        );
+        assert_eq!(report.marked, 1);
+        let set = report.result_set();
+        assert_eq!(set.rows.len(), 1);
+        assert_eq!(set.value(0, "Stream"), Some(&"hidden.ps1".into()));
+        let flagged = findings::row_findings(ActiveData::Streams, &set);
+        assert_eq!(flagged[&0][0].severity, Severity::High);
+    }
+}
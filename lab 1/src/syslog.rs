@@ -0,0 +1,504 @@
+use crate::export;
+use crate::findings::{Finding, Severity};
+use crate::i18n::{tr, trf};
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Наибольшая длина сообщения в байтах, без префикса длины TCP
+pub const MAX_LEN: usize = 2048;
+
+/// Сколько сообщений ждут повторной отправки, пока сервер недоступен
+pub const BUFFER: usize = 500;
+
+/// Пауза перед повторной отправкой после ошибки сети
+const RETRY: Duration = Duration::from_secs(5);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+// local0: сообщения приложения, а не системы
+const FACILITY: u8 = 16;
+
+// Идентификатор предприятия из примеров RFC 5612
+const SD_ID: &str = "wmilab@32473";
+
+const TRUNCATED: &str = "…[truncated]";
+
+// Наибольшая длина значения параметра структурированных данных в байтах; с ней
+// заголовок занимает меньше `MAX_LEN` и всегда оставляет место тексту
+const PARAM_LEN: usize = 256;
+
+/// Протокол доставки
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transport {
+    #[default]
+    Udp,
+    Tcp, // Кадры с префиксом длины по RFC 6587
+}
+
+/// Раздел `[syslog]`: пересылка находок и событий наблюдения на сервер syslog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub server: String,
+    pub port: u16,
+    pub transport: Transport,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: String::new(),
+            port: 514,
+            transport: Transport::Udp,
+        }
+    }
+}
+
+impl Settings {
+    /// Поля для меню настроек; кнопка проверки отправляет тестовое сообщение
+    pub fn show(&mut self, ui: &mut egui::Ui, forwarder: &mut Forwarder) {
+        ui.checkbox(&mut self.enabled, tr("syslog.enabled"))
+            .on_hover_text(tr("syslog.hint"));
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("syslog.server"));
+                ui.add(egui::TextEdit::singleline(&mut self.server).desired_width(140.0));
+                ui.add(egui::DragValue::new(&mut self.port).range(1..=65535));
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.transport, Transport::Udp, "UDP");
+                ui.radio_value(&mut self.transport, Transport::Tcp, "TCP");
+            });
+            forwarder.controls(ui, self);
+        });
+    }
+}
+
+/// Сообщение для сервера syslog
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub time: DateTime<Local>,
+    pub severity: Severity,
+    pub kind: &'static str, // MSGID: finding, watch или test
+    pub category: String,   // Имя категории, как в файлах экспорта; не переводится
+    pub host: String,       // Компьютер, с которого получены данные
+    pub identity: String,   // Ключ строки, к которой относится сообщение
+    pub text: String,
+}
+
+// Уровни syslog: 2 — crit, 3 — err, 4 — warning, 5 — notice, 6 — info
+fn level(severity: Severity) -> u8 {
+    match severity {
+        Severity::Critical => 2,
+        Severity::High => 3,
+        Severity::Medium => 4,
+        Severity::Low => 5,
+        Severity::Info => 6,
+    }
+}
+
+// Поля заголовка — печатные ASCII без пробелов; пустое поле пишется как «-»
+fn header_field(text: &str, max: usize) -> String {
+    let field: String = text
+        .chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '-' })
+        .take(max)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+// В значениях структурированных данных экранируются `"`, `\` и `]`; значение
+// обрезается по `PARAM_LEN` байт вместе с экранированием
+fn param(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len().min(PARAM_LEN));
+    for c in value.chars() {
+        let escape = matches!(c, '"' | '\\' | ']');
+        if escaped.len() + c.len_utf8() + usize::from(escape) > PARAM_LEN {
+            break;
+        }
+        if escape {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Message {
+    /// Сообщение о находке отчёта, полученного с компьютера `host`
+    pub fn finding(host: String, finding: &Finding) -> Self {
+        Self {
+            time: Local::now(),
+            severity: finding.severity,
+            kind: "finding",
+            category: finding.slug().to_string(),
+            host,
+            identity: finding.identity.clone(),
+            text: finding.title.clone(),
+        }
+    }
+
+    /// Сообщение RFC 5424 не длиннее `MAX_LEN` байт: заголовок ограничен, текст
+    /// обрезается по оставшемуся месту, обрезанный текст помечается
+    pub fn format(&self, local_host: &str) -> String {
+        let head = format!(
+            "<{}>1 {} {} wmilab {} {} [{SD_ID} category=\"{}\" severity=\"{:?}\" host=\"{}\" key=\"{}\"] \u{FEFF}",
+            FACILITY * 8 + level(self.severity),
+            self.time.to_rfc3339_opts(SecondsFormat::Millis, false),
+            header_field(local_host, 255),
+            std::process::id(),
+            header_field(self.kind, 32),
+            param(&self.category),
+            self.severity,
+            param(&self.host),
+            param(&self.identity),
+        );
+        let mut message = head;
+        if message.len() + self.text.len() <= MAX_LEN {
+            message.push_str(&self.text);
+            return message;
+        }
+        let room = MAX_LEN.saturating_sub(message.len() + TRUNCATED.len());
+        let mut cut = room.min(self.text.len());
+        while !self.text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        message.push_str(&self.text[..cut]);
+        message.push_str(TRUNCATED);
+        message
+    }
+}
+
+/// Очередь сообщений, ждущих отправки. Переполненная очередь теряет самые старые
+#[derive(Debug, Default)]
+pub struct Buffer {
+    messages: VecDeque<String>,
+    pub dropped: usize,
+}
+
+impl Buffer {
+    pub fn push(&mut self, message: String) {
+        if self.messages.len() == BUFFER {
+            self.messages.pop_front();
+            self.dropped += 1;
+        }
+        self.messages.push_back(message);
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Отправляет по порядку, пока `send` удаётся; неотправленные остаются
+    pub fn flush(
+        &mut self,
+        mut send: impl FnMut(&str) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        while let Some(message) = self.messages.front() {
+            send(message)?;
+            self.messages.pop_front();
+        }
+        Ok(())
+    }
+}
+
+// Подключение к серверу; TCP-соединение держится, пока не случится ошибка
+struct Link {
+    settings: Settings,
+    udp: Option<UdpSocket>,
+    tcp: Option<TcpStream>,
+}
+
+impl Link {
+    fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            udp: None,
+            tcp: None,
+        }
+    }
+
+    fn send(&mut self, message: &str) -> std::io::Result<()> {
+        let address = (self.settings.server.trim(), self.settings.port);
+        match self.settings.transport {
+            Transport::Udp => {
+                if self.udp.is_none() {
+                    self.udp = Some(UdpSocket::bind("0.0.0.0:0")?);
+                }
+                let socket = self.udp.as_ref().expect("сокет создан выше");
+                socket.send_to(message.as_bytes(), address).map(|_| ())
+            }
+            Transport::Tcp => {
+                if self.tcp.is_none() {
+                    let target = address.to_socket_addrs()?.next().ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::NotFound, tr("syslog.no_address"))
+                    })?;
+                    self.tcp = Some(TcpStream::connect_timeout(&target, CONNECT_TIMEOUT)?);
+                }
+                let stream = self.tcp.as_mut().expect("соединение открыто выше");
+                let framed = format!("{} {message}", message.len());
+                let written = stream.write_all(framed.as_bytes());
+                if written.is_err() {
+                    self.tcp = None;
+                }
+                written
+            }
+        }
+    }
+}
+
+enum Command {
+    Send(String),
+    Configure(Settings),
+    Test(Sender<Result<(), String>>),
+}
+
+/// Состояние пересылки для меню настроек
+#[derive(Debug, Default, Clone)]
+struct Status {
+    buffered: usize,
+    dropped: usize,
+    error: Option<String>,
+}
+
+/// Пересылка в отдельном потоке: интерфейс не ждёт сети, а сообщения,
+/// не отправленные из-за ошибки, ждут в очереди и отправляются повторно
+#[derive(Default)]
+pub struct Forwarder {
+    commands: Option<Sender<Command>>,
+    settings: Option<Settings>, // Последние переданные потоку
+    status: Arc<Mutex<Status>>,
+    test: Option<Receiver<Result<(), String>>>,
+    test_result: Option<Result<String, String>>,
+}
+
+impl Forwarder {
+    /// Передаёт потоку изменившиеся настройки; поток создаётся при первом включении
+    pub fn configure(&mut self, settings: &Settings) {
+        if self.settings.as_ref() == Some(settings) {
+            return;
+        }
+        self.settings = Some(settings.clone());
+        if self.commands.is_none() && settings.enabled {
+            let (tx, rx) = mpsc::channel();
+            let status = Arc::clone(&self.status);
+            std::thread::spawn(move || run(rx, status));
+            self.commands = Some(tx);
+        }
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(Command::Configure(settings.clone()));
+        }
+    }
+
+    /// Ставит сообщение в очередь, если пересылка включена
+    pub fn send(&self, message: &Message) {
+        let enabled = self.settings.as_ref().is_some_and(|s| s.enabled);
+        if let Some(commands) = self.commands.as_ref().filter(|_| enabled) {
+            let _ = commands.send(Command::Send(message.format(&export::hostname())));
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui, settings: &Settings) {
+        if let Some(test) = &self.test {
+            match test.try_recv() {
+                Ok(result) => {
+                    self.test_result = Some(result.map(|()| tr("syslog.test_sent").to_string()));
+                    self.test = None;
+                }
+                Err(TryRecvError::Empty) => {
+                    ui.ctx().request_repaint_after(Duration::from_millis(100))
+                }
+                Err(TryRecvError::Disconnected) => self.test = None,
+            }
+        }
+        ui.horizontal(|ui| {
+            let idle = self.test.is_none() && !settings.server.trim().is_empty();
+            if ui
+                .add_enabled(idle, egui::Button::new(tr("syslog.test")))
+                .clicked()
+            {
+                self.configure(settings);
+                if let Some(commands) = &self.commands {
+                    let (tx, rx) = mpsc::channel();
+                    let _ = commands.send(Command::Test(tx));
+                    self.test = Some(rx);
+                    self.test_result = None;
+                }
+            }
+            if self.test.is_some() {
+                ui.spinner();
+            }
+            match &self.test_result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                None => {}
+            }
+        });
+        let status = self.status.lock().map(|s| s.clone()).unwrap_or_default();
+        if status.buffered > 0 || status.dropped > 0 {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                trf(
+                    "syslog.buffered",
+                    &[("count", &status.buffered), ("dropped", &status.dropped)],
+                ),
+            );
+        }
+        if let Some(error) = &status.error {
+            ui.weak(error);
+        }
+    }
+}
+
+// Поток пересылки: принимает команды и повторяет отправку очереди после ошибок
+fn run(commands: Receiver<Command>, status: Arc<Mutex<Status>>) {
+    let mut link: Option<Link> = None;
+    let mut buffer = Buffer::default();
+    let mut retry_at: Option<Instant> = None;
+    loop {
+        let wait = match retry_at {
+            Some(at) => at.saturating_duration_since(Instant::now()),
+            None => Duration::from_secs(3600),
+        };
+        match commands.recv_timeout(wait) {
+            Ok(Command::Send(message)) => buffer.push(message),
+            Ok(Command::Configure(settings)) => {
+                if settings.enabled {
+                    link = Some(Link::new(settings));
+                    retry_at = None;
+                } else {
+                    link = None;
+                    buffer = Buffer::default();
+                }
+            }
+            Ok(Command::Test(reply)) => {
+                let result = match &mut link {
+                    Some(link) => {
+                        let message = Message {
+                            time: Local::now(),
+                            severity: Severity::Info,
+                            kind: "test",
+                            category: String::new(),
+                            host: export::hostname(),
+                            identity: String::new(),
+                            text: tr("syslog.test_text").to_string(),
+                        };
+                        link.send(&message.format(&export::hostname()))
+                            .map_err(|e| trf("syslog.failed", &[("error", &e)]))
+                    }
+                    None => Err(tr("syslog.disabled").to_string()),
+                };
+                let _ = reply.send(result);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        let waiting = retry_at.is_some_and(|at| Instant::now() < at);
+        let mut error = None;
+        if let (Some(link), false) = (&mut link, waiting) {
+            match buffer.flush(|message| link.send(message)) {
+                Ok(()) => retry_at = None,
+                Err(e) => {
+                    if retry_at.is_none() {
+                        tracing::warn!(error = %e, buffered = buffer.len(), "syslog недоступен, сообщения ждут повтора");
+                    }
+                    retry_at = Some(Instant::now() + RETRY);
+                    error = Some(trf("syslog.failed", &[("error", &e)]));
+                }
+            }
+        }
+        if let Ok(mut status) = status.lock() {
+            status.buffered = buffer.len();
+            status.dropped = buffer.dropped;
+            if error.is_some() || buffer.len() == 0 {
+                status.error = error;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::{self, Check};
+    use chrono::TimeZone;
+
+    fn message(text: &str) -> Message {
+        let finding =
+            findings::sample(Check::ProblemDevices, Severity::High, "ROOT\\UNKNOWN\\0000");
+        Message {
+            time: Local.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap(),
+            text: text.to_string(),
+            ..Message::finding("SRV \"01\"".to_string(), &finding)
+        }
+    }
+
+    #[test]
+    fn message_has_rfc5424_header_and_structured_data() {
+        let text = message("Код ошибки 28").format("WS 01");
+        // local0 (16) * 8 + err (3)
+        assert!(text.starts_with("<131>1 2024-05-01T12:30:00.000"));
+        assert!(text.contains(" WS-01 wmilab "));
+        assert!(text.contains(" finding [wmilab@32473 category=\"bus_info\" severity=\"High\""));
+        assert!(text.contains("host=\"SRV \\\"01\\\"\" key=\"ROOT\\\\UNKNOWN\\\\0000\"]"));
+        assert!(text.ends_with("\u{FEFF}Код ошибки 28"));
+    }
+
+    #[test]
+    fn long_message_is_truncated_and_marked() {
+        let text = message(&"ж".repeat(3000)).format("WS-01");
+        assert!(text.len() <= MAX_LEN);
+        assert!(text.ends_with(TRUNCATED));
+    }
+
+    #[test]
+    fn long_structured_data_leaves_room_for_text() {
+        let message = Message {
+            category: "]".repeat(1000),
+            host: "ж".repeat(1000),
+            identity: "\\".repeat(1000),
+            ..message(&"ж".repeat(3000))
+        };
+        let text = message.format(&"h".repeat(1000));
+        assert!(text.len() <= MAX_LEN);
+        assert!(text.ends_with(&format!("ж{TRUNCATED}")));
+        // Экранирование не разрывается на границе обрезки: 128 символов по 2 байта
+        assert!(text.contains(&format!("key=\"{}\"]", "\\".repeat(PARAM_LEN))));
+    }
+
+    #[test]
+    fn buffer_keeps_unsent_and_drops_oldest() {
+        let mut buffer = Buffer::default();
+        for i in 0..BUFFER + 2 {
+            buffer.push(i.to_string());
+        }
+        assert_eq!((buffer.len(), buffer.dropped), (BUFFER, 2));
+        let mut sent = Vec::new();
+        let result = buffer.flush(|message| {
+            if sent.len() == 3 {
+                return Err(std::io::ErrorKind::ConnectionRefused.into());
+            }
+            sent.push(message.to_string());
+            Ok(())
+        });
+        assert!(result.is_err());
+        assert_eq!(sent, ["2", "3", "4"]);
+        assert_eq!(buffer.len(), BUFFER - 3);
+    }
+}
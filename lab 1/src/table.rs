@@ -0,0 +1,982 @@
+use crate::findings::Severity;
+use crate::hunt;
+use crate::i18n::{tr, trf};
+use crate::model::{Column, ColumnKind, ResultSet, SortKey, Value};
+use crate::shell;
+use crate::theme;
+use crate::virustotal::{Lookup, Note};
+use crate::watch::{Change, Marks};
+use egui::{Align, Color32, Key, Layout, Sense, Stroke, StrokeKind, TextStyle};
+use egui_extras::{Column as TableColumn, TableBuilder};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeSet, HashMap};
+
+/// Наибольшая ширина подсказки над строкой
+const TIP_WIDTH: f32 = 360.0;
+
+/// Поле ввода фильтра; на экране одновременно только одна таблица с фильтром
+pub fn filter_id() -> egui::Id {
+    egui::Id::new("row_filter")
+}
+
+/// Фильтр строк: подстрока или регулярное выражение, по всем столбцам или по одному
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RowFilter {
+    pub text: String,
+    pub use_regex: bool,
+    pub column: Option<usize>,
+    #[serde(skip)]
+    needle: String,
+    #[serde(skip)]
+    regex: Option<Regex>,
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl RowFilter {
+    /// Пересобирает шаблон. Вызывается только при изменении ввода, а не для каждой строки
+    pub fn update(&mut self) {
+        self.needle = self.text.to_lowercase();
+        self.regex = None;
+        self.error = None;
+        if self.use_regex && !self.text.is_empty() {
+            match Regex::new(&self.text) {
+                Ok(re) => self.regex = Some(re),
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.update();
+    }
+
+    fn cell_matches(&self, cell: &str) -> bool {
+        match &self.regex {
+            Some(re) => re.is_match(cell),
+            None => cell.to_lowercase().contains(&self.needle),
+        }
+    }
+
+    pub fn matches(&self, row: &[Value]) -> bool {
+        // Пустой ввод или некорректный шаблон не скрывают строки
+        if self.text.is_empty() || self.error.is_some() {
+            return true;
+        }
+        match self.column {
+            Some(index) => row
+                .get(index)
+                .is_some_and(|cell| self.cell_matches(&cell.display())),
+            None => row.iter().any(|cell| self.cell_matches(&cell.display())),
+        }
+    }
+
+    /// Индексы строк, прошедших фильтр
+    pub fn apply(&self, set: &ResultSet) -> Vec<usize> {
+        (0..set.rows.len())
+            .filter(|&i| self.matches(&set.rows[i]))
+            .collect()
+    }
+
+    /// Строка ввода фильтра с переключателями режима и выбором столбца.
+    /// Возвращает `true`, если условие фильтра изменилось
+    pub fn show(&mut self, ui: &mut egui::Ui, columns: &[Column]) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(tr("filter.label"));
+            let response = ui.add(egui::TextEdit::singleline(&mut self.text).id(filter_id()));
+            changed |= response.changed();
+            if self.error.is_some() {
+                ui.painter().rect_stroke(
+                    response.rect,
+                    2.0,
+                    Stroke::new(1.5, Color32::RED),
+                    StrokeKind::Outside,
+                );
+            }
+
+            changed |= ui
+                .checkbox(&mut self.use_regex, tr("filter.regex"))
+                .changed();
+
+            let mut by_column = self.column.is_some();
+            if ui.checkbox(&mut by_column, tr("filter.column")).changed() {
+                self.column = by_column.then_some(0);
+                changed = true;
+            }
+            if let Some(index) = &mut self.column {
+                let selected = columns
+                    .get(*index)
+                    .map(|c| c.title.as_str())
+                    .unwrap_or_default();
+                egui::ComboBox::from_id_salt("filter_column")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (i, column) in columns.iter().enumerate() {
+                            changed |= ui.selectable_value(index, i, &column.title).changed();
+                        }
+                    });
+            }
+        });
+
+        if changed {
+            self.update();
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::RED, error);
+        }
+        changed
+    }
+}
+
+/// Порядок и видимость столбцов категории по именам свойств. Столбцы, которых
+/// нет в `order`, идут следом в порядке запроса
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnLayout {
+    pub order: Vec<String>,
+    pub hidden: Vec<String>,
+}
+
+impl ColumnLayout {
+    pub fn is_default(&self) -> bool {
+        self.order.is_empty() && self.hidden.is_empty()
+    }
+
+    /// Все столбцы результата в порядке отображения
+    pub fn ordered(&self, set: &ResultSet) -> Vec<usize> {
+        let position = |name: &str| self.order.iter().position(|n| n == name);
+        let mut columns: Vec<usize> = (0..set.columns.len()).collect();
+        // Устойчивая сортировка оставляет неупомянутые столбцы в исходном порядке
+        columns.sort_by_key(|&i| position(&set.columns[i].name).unwrap_or(usize::MAX));
+        columns
+    }
+
+    /// Видимые столбцы в порядке отображения
+    pub fn visible(&self, set: &ResultSet) -> Vec<usize> {
+        self.ordered(set)
+            .into_iter()
+            .filter(|&i| !self.hidden.contains(&set.columns[i].name))
+            .collect()
+    }
+
+    /// Список столбцов с флажками видимости и кнопками порядка.
+    /// Последний видимый столбец скрыть нельзя
+    pub fn show(&mut self, ui: &mut egui::Ui, set: &ResultSet) {
+        let ordered = self.ordered(set);
+        let visible = self.visible(set).len();
+        let mut swap = None;
+        egui::Grid::new("column_layout")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (position, &i) in ordered.iter().enumerate() {
+                    let column = &set.columns[i];
+                    let mut shown = !self.hidden.contains(&column.name);
+                    let enabled = !shown || visible > 1;
+                    if ui
+                        .add_enabled(enabled, egui::Checkbox::new(&mut shown, &column.title))
+                        .changed()
+                    {
+                        self.hidden.retain(|name| *name != column.name);
+                        if !shown {
+                            self.hidden.push(column.name.clone());
+                        }
+                    }
+                    if ui
+                        .add_enabled(position > 0, egui::Button::new("⬆").small())
+                        .clicked()
+                    {
+                        swap = Some((position - 1, position));
+                    }
+                    if ui
+                        .add_enabled(position + 1 < ordered.len(), egui::Button::new("⬇").small())
+                        .clicked()
+                    {
+                        swap = Some((position, position + 1));
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some((a, b)) = swap {
+            let mut names: Vec<String> = ordered
+                .iter()
+                .map(|&i| set.columns[i].name.clone())
+                .collect();
+            names.swap(a, b);
+            self.order = names;
+        }
+        ui.separator();
+        if ui
+            .add_enabled(!self.is_default(), egui::Button::new(tr("columns.reset")))
+            .clicked()
+        {
+            *self = ColumnLayout::default();
+        }
+    }
+}
+
+/// Варианты размера страницы; `None` — все строки с прокруткой
+const PAGE_SIZES: [Option<usize>; 4] = [None, Some(100), Some(500), Some(1000)];
+
+/// Действие над строкой, которое выполняет окно категории: кнопка над таблицей
+/// и пункт контекстного меню
+pub struct RowAction<A> {
+    pub title: &'static str,
+    pub enabled: bool,
+    pub hint: Option<&'static str>, // Почему действие недоступно
+    pub action: A,
+}
+
+/// Что выбрано в контекстном меню строки
+pub enum MenuEvent<A> {
+    Action(A),
+    Failed(String), // Не удалось открыть папку
+}
+
+/// Подсветка строк таблицы: находки проверок и изменения при наблюдении
+#[derive(Default)]
+pub struct Highlight<'a> {
+    pub flags: HashMap<usize, Severity>, // Наибольшая важность находки строки
+    pub marks: Option<&'a Marks>,
+    pub hashes: Option<&'a Lookup>, // Ответы VirusTotal для ячеек хешей
+    pub hunt: Option<&'a Regex>,    // Совпадения быстрого поиска в командной строке
+}
+
+// Условия, при которых посчитан порядок строк: версия данных, фильтр и сортировка
+#[derive(PartialEq)]
+struct Order {
+    revision: u64,
+    filter: (String, bool, Option<usize>),
+    sort: Option<(usize, bool)>,
+}
+
+/// Состояние отображения таблицы одной категории
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableView {
+    pub filter: RowFilter,
+    #[serde(skip)]
+    pub selected: Option<usize>,
+    pub sort_column: Option<usize>,
+    pub sort_descending: bool,
+    pub page_size: Option<usize>,
+    #[serde(skip)]
+    pub page: usize, // С нуля; сбрасывается при изменении фильтра
+    pub export_page: bool,        // Экспортировать только текущую страницу
+    pub export_all_columns: bool, // Экспортировать и скрытые столбцы
+    pub show_chart: bool,         // Диаграмма вместо таблицы, если категория её поддерживает
+    pub grouped: bool,            // Группы вместо таблицы, если категория их поддерживает
+    pub export_selected: bool,    // Экспортировать только выбранные строки
+    // Выбранные строки вместе с `selected`. Это номера строк результата, а не места
+    // на экране, поэтому сортировка и фильтр выбор не сбивают
+    #[serde(skip)]
+    marked: BTreeSet<usize>,
+    #[serde(skip)]
+    anchor: Option<usize>, // Начало диапазона для Shift+щелчка
+    #[serde(skip)]
+    menu_cell: Option<usize>, // Столбец ячейки, по которой открыто контекстное меню
+    #[serde(skip)]
+    tips: HashMap<usize, Vec<(String, String)>>, // Подсказки уже наведённых строк
+    // Порядок строк и подсветка находок считаются заново, только когда меняются
+    // данные, фильтр или сортировка, а не на каждом кадре
+    #[serde(skip)]
+    rows: RefCell<(Option<Order>, Vec<usize>)>,
+    #[serde(skip)]
+    flags: RefCell<Option<(u64, HashMap<usize, Severity>)>>,
+}
+
+impl TableView {
+    /// Выбор одной строки или снятие выбора; прежний выбор сбрасывается
+    pub fn select(&mut self, row: Option<usize>) {
+        self.selected = row;
+        self.anchor = row;
+        self.marked = row.into_iter().collect();
+    }
+
+    /// Сколько строк выбрано
+    pub fn chosen_count(&self) -> usize {
+        self.marked.len().max(usize::from(self.selected.is_some()))
+    }
+
+    fn is_chosen(&self, row: usize) -> bool {
+        self.selected == Some(row) || self.marked.contains(&row)
+    }
+
+    /// Выбранные строки в порядке `rows`
+    pub fn chosen(&self, rows: &[usize]) -> Vec<usize> {
+        rows.iter()
+            .copied()
+            .filter(|&row| self.is_chosen(row))
+            .collect()
+    }
+
+    // Щелчок по строке: Ctrl добавляет её к выбору или убирает из него, Shift выбирает
+    // диапазон от прошлого щелчка. `rows` — строки в порядке отображения
+    fn click(&mut self, row: usize, rows: &[usize], modifiers: egui::Modifiers) {
+        if modifiers.shift {
+            let anchor = self.anchor.unwrap_or(row);
+            let position = |row| rows.iter().position(|&r| r == row);
+            let (Some(from), Some(to)) = (position(anchor), position(row)) else {
+                return self.select(Some(row));
+            };
+            if !modifiers.command {
+                self.marked.clear();
+            }
+            self.marked.extend(&rows[from.min(to)..=from.max(to)]);
+            self.selected = Some(row);
+        } else if modifiers.command {
+            if let Some(selected) = self.selected {
+                self.marked.insert(selected);
+            }
+            if !self.marked.remove(&row) {
+                self.marked.insert(row);
+                self.selected = Some(row);
+            } else if self.selected == Some(row) {
+                self.selected = self.marked.first().copied();
+            }
+            self.anchor = Some(row);
+        } else {
+            self.select(Some(row));
+        }
+    }
+
+    /// Сбрасывает подсказки строк; вызывается при новом результате и смене языка
+    pub fn clear_tips(&mut self) {
+        self.tips.clear();
+    }
+
+    // Запомненный порядок строк; пересчитывается при новой версии данных
+    // `revision`, другом фильтре или другой сортировке
+    fn ordered_rows(&self, set: &ResultSet, keys: &[&str], revision: u64) -> Ref<'_, [usize]> {
+        let order = Order {
+            revision,
+            filter: (
+                self.filter.text.clone(),
+                self.filter.use_regex,
+                self.filter.column,
+            ),
+            sort: self
+                .sort_column
+                .map(|column| (column, self.sort_descending)),
+        };
+        if self.rows.borrow().0.as_ref() != Some(&order) {
+            let rows = self.sorted_rows(set, keys);
+            *self.rows.borrow_mut() = (Some(order), rows);
+        }
+        Ref::map(self.rows.borrow(), |(_, rows)| rows.as_slice())
+    }
+
+    /// Индексы строк в порядке отображения: после фильтра и сортировки.
+    /// Равные строки упорядочены по ключевым столбцам `keys`, а без них
+    /// остаются в порядке запроса. `revision` — версия данных `set`,
+    /// см. [`Cache::revision`](crate::cache::Cache::revision)
+    pub fn visible_rows(&self, set: &ResultSet, keys: &[&str], revision: u64) -> Vec<usize> {
+        self.ordered_rows(set, keys, revision).to_vec()
+    }
+
+    /// Наибольшая важность находки каждой строки. `flags` вызывается только
+    /// при новой версии данных
+    pub fn row_flags(
+        &self,
+        revision: u64,
+        flags: impl FnOnce() -> HashMap<usize, Severity>,
+    ) -> HashMap<usize, Severity> {
+        let mut cached = self.flags.borrow_mut();
+        match &*cached {
+            Some((known, flags)) if *known == revision => flags.clone(),
+            _ => cached.insert((revision, flags())).1.clone(),
+        }
+    }
+
+    // Пустые ячейки в любом направлении идут последними. Ключи сравнения
+    // считаются один раз на строку, а не при каждом сравнении
+    fn sorted_rows(&self, set: &ResultSet, keys: &[&str]) -> Vec<usize> {
+        let rows = self.filter.apply(set);
+        let Some(column) = self.sort_column else {
+            return rows;
+        };
+        let keys: Vec<usize> = keys
+            .iter()
+            .filter_map(|key| set.columns.iter().position(|c| c.name == *key))
+            .collect();
+        let cell =
+            |i: usize, column: usize| SortKey::new(set.rows[i].get(column).unwrap_or(&Value::Null));
+        let mut keyed: Vec<(usize, SortKey, Vec<SortKey>)> = rows
+            .into_iter()
+            .map(|i| {
+                (
+                    i,
+                    cell(i, column),
+                    keys.iter().map(|&key| cell(i, key)).collect(),
+                )
+            })
+            .collect();
+        keyed.sort_by(|(_, x, a), (_, y, b)| {
+            let order = if x.is_null() || y.is_null() || !self.sort_descending {
+                x.compare(y)
+            } else {
+                y.compare(x)
+            };
+            a.iter()
+                .zip(b)
+                .fold(order, |order, (a, b)| order.then_with(|| a.compare(b)))
+        });
+        keyed.into_iter().map(|(i, _, _)| i).collect()
+    }
+
+    /// Число строк после фильтра
+    pub fn visible_count(&self, set: &ResultSet, keys: &[&str], revision: u64) -> usize {
+        self.ordered_rows(set, keys, revision).len()
+    }
+
+    fn page_count(&self, total: usize) -> usize {
+        match self.page_size {
+            Some(size) => total.div_ceil(size).max(1),
+            None => 1,
+        }
+    }
+
+    /// Строки текущей страницы из уже отфильтрованных и отсортированных
+    pub fn page_rows<'a>(&self, rows: &'a [usize]) -> &'a [usize] {
+        let Some(size) = self.page_size else {
+            return rows;
+        };
+        let page = self.page.min(self.page_count(rows.len()) - 1);
+        let start = (page * size).min(rows.len());
+        &rows[start..(start + size).min(rows.len())]
+    }
+
+    /// Строки для копирования и экспорта с учётом выбранного охвата
+    pub fn export_rows(&self, rows: &[usize]) -> Vec<usize> {
+        if self.export_selected && self.chosen_count() > 0 {
+            self.chosen(rows)
+        } else if self.export_page {
+            self.page_rows(rows).to_vec()
+        } else {
+            rows.to_vec()
+        }
+    }
+
+    /// Панель страниц: размер страницы, переход назад и вперёд, номер страницы
+    pub fn pagination(&mut self, ui: &mut egui::Ui, total: usize) {
+        let pages = self.page_count(total);
+        self.page = self.page.min(pages - 1);
+        ui.horizontal(|ui| {
+            ui.label(tr("table.page_size"));
+            let title = |size: Option<usize>| match size {
+                Some(size) => size.to_string(),
+                None => tr("table.page_all").to_string(),
+            };
+            egui::ComboBox::from_id_salt("page_size")
+                .selected_text(title(self.page_size))
+                .show_ui(ui, |ui| {
+                    for size in PAGE_SIZES {
+                        if ui
+                            .selectable_value(&mut self.page_size, size, title(size))
+                            .changed()
+                        {
+                            self.page = 0;
+                        }
+                    }
+                });
+            if self.page_size.is_none() {
+                return;
+            }
+            if ui
+                .add_enabled(self.page > 0, egui::Button::new("◀"))
+                .clicked()
+            {
+                self.page -= 1;
+            }
+            ui.label(trf(
+                "table.page",
+                &[("page", &(self.page + 1)), ("pages", &pages)],
+            ));
+            if ui
+                .add_enabled(self.page + 1 < pages, egui::Button::new("▶"))
+                .clicked()
+            {
+                self.page += 1;
+            }
+        });
+    }
+
+    /// Полный вид произвольного результата без подсветки находок:
+    /// фильтр, копирование, таблица и панель страниц. `revision` — версия `set`
+    pub fn show_result(&mut self, ui: &mut egui::Ui, set: &ResultSet, revision: u64, id: &str) {
+        if self.filter.show(ui, &set.columns) {
+            self.page = 0;
+        }
+        let rows = self.visible_rows(set, &[], revision);
+        egui::TopBottomPanel::bottom(egui::Id::new((id, "pagination")))
+            .show_inside(ui, |ui| self.pagination(ui, rows.len()));
+        ui.horizontal(|ui| self.copy_buttons(ui, set, &self.export_rows(&rows)));
+        let columns: Vec<usize> = (0..set.columns.len()).collect();
+        egui::ScrollArea::horizontal().id_salt(id).show(ui, |ui| {
+            self.show_table(
+                ui,
+                set,
+                self.page_rows(&rows),
+                &columns,
+                &Highlight::default(),
+                |_| Vec::<RowAction<()>>::new(),
+            )
+        });
+    }
+
+    /// Копирует выбранную строку в буфер обмена, несколько выбранных — таблицей
+    /// TSV с заголовком в порядке последней отрисовки; без выбора ничего не делает
+    pub fn copy_selected(&self, ctx: &egui::Context, set: &ResultSet) {
+        if self.chosen_count() > 1 {
+            ctx.copy_text(to_tsv(set, &self.chosen(&self.rows.borrow().1)));
+        } else if let Some(row) = self.selected.and_then(|i| set.rows.get(i)) {
+            ctx.copy_text(tsv_line(row));
+        }
+    }
+
+    /// Кнопки копирования выбранных строк и всех видимых строк
+    pub fn copy_buttons(&self, ui: &mut egui::Ui, set: &ResultSet, rows: &[usize]) {
+        let selected = self.selected.and_then(|i| set.rows.get(i));
+        let title = match self.chosen_count() {
+            count if count > 1 => trf("table.copy_selected", &[("count", &count)]),
+            _ => tr("table.copy_row").to_string(),
+        };
+        if ui
+            .add_enabled(selected.is_some(), egui::Button::new(title))
+            .clicked()
+        {
+            self.copy_selected(ui.ctx(), set);
+        }
+        if ui.button(tr("table.copy_all")).clicked() {
+            ui.ctx().copy_text(to_tsv(set, rows));
+        }
+    }
+
+    // Щелчок по заголовку сортирует по столбцу, повторный щелчок меняет направление
+    fn header(&mut self, ui: &mut egui::Ui, index: usize, column: &str) {
+        let arrow = match (self.sort_column == Some(index), self.sort_descending) {
+            (true, false) => " ⬆",
+            (true, true) => " ⬇",
+            _ => "",
+        };
+        let text = egui::RichText::new(format!("{column}{arrow}")).strong();
+        if ui.add(egui::Button::new(text).frame(false)).clicked() {
+            if self.sort_column == Some(index) {
+                self.sort_descending = !self.sort_descending;
+            } else {
+                self.sort_column = Some(index);
+                self.sort_descending = false;
+            }
+        }
+    }
+
+    /// Отрисовка строк результата с заголовком. Размечаются только видимые строки,
+    /// поэтому прокрутка остаётся плавной и на десятках тысяч записей.
+    /// Щелчок выбирает строку, правая кнопка открывает контекстное меню,
+    /// Home/End переходят к началу и концу. `columns` — видимые столбцы в порядке
+    /// отображения. Строки с находками подсвечиваются цветом важности, изменения
+    /// при наблюдении — цветом изменения; исчезнувшие строки зачёркнуты и идут
+    /// после строк страницы. Над строкой с дополнительными свойствами
+    /// результата появляется подсказка. `actions` — действия категории над строкой
+    pub fn show_table<A: Copy>(
+        &mut self,
+        ui: &mut egui::Ui,
+        set: &ResultSet,
+        rows: &[usize],
+        columns: &[usize],
+        highlight: &Highlight,
+        actions: impl Fn(usize) -> Vec<RowAction<A>>,
+    ) -> Option<MenuEvent<A>> {
+        let mut event = None;
+        let jump = if ui.ctx().wants_keyboard_input() {
+            None
+        } else {
+            ui.input(|input| {
+                if input.key_pressed(Key::Home) {
+                    Some(0)
+                } else if input.key_pressed(Key::End) {
+                    Some(rows.len().saturating_sub(1))
+                } else {
+                    None
+                }
+            })
+        };
+        // Высота строки следует за размером шрифта
+        let row_height = ui.text_style_height(&TextStyle::Body) + ui.spacing().item_spacing.y;
+        let visuals = ui.visuals().clone();
+        let (flags, marks) = (&highlight.flags, highlight.marks);
+        let hash_column = |column: usize| {
+            highlight
+                .hashes
+                .filter(|_| set.columns[column].kind() == ColumnKind::Hash)
+        };
+        let removed = marks.map_or(&[][..], |marks| marks.removed.as_slice());
+        let hunt_column = |column: usize| {
+            highlight
+                .hunt
+                .filter(|_| set.columns[column].name == hunt::COLUMN)
+        };
+
+        let mut table = TableBuilder::new(ui)
+            .striped(true)
+            .sense(Sense::click())
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .columns(
+                TableColumn::auto().resizable(true).clip(true),
+                columns.len(),
+            )
+            .min_scrolled_height(0.0);
+        if let Some(row) = jump {
+            table = table.scroll_to_row(row, Some(Align::Center));
+        }
+
+        table
+            .header(row_height, |mut header| {
+                for &index in columns {
+                    header.col(|ui| self.header(ui, index, &set.columns[index].title));
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, rows.len() + removed.len(), |mut table_row| {
+                    let Some(&i) = rows.get(table_row.index()) else {
+                        let ghost = &removed[table_row.index() - rows.len()];
+                        let tint = theme::change_tint(Change::Removed, &visuals);
+                        for cell in columns.iter().filter_map(|&column| ghost.get(column)) {
+                            table_row.col(|ui| {
+                                ui.painter().rect_filled(ui.max_rect(), 0.0, tint);
+                                ui.label(egui::RichText::new(cell.display()).strikethrough());
+                            });
+                        }
+                        return;
+                    };
+                    let row = &set.rows[i];
+                    let added = marks.is_some_and(|marks| marks.added.contains(&i));
+                    let changed = marks.and_then(|marks| marks.changed.get(&i));
+                    let tint = if added {
+                        Some(theme::change_tint(Change::Added, &visuals))
+                    } else {
+                        flags
+                            .get(&i)
+                            .and_then(|&severity| theme::severity_tint(severity, &visuals))
+                    };
+                    table_row.set_selected(self.is_chosen(i));
+                    for &column in columns {
+                        let cell = &row[column];
+                        let tint = match changed {
+                            Some(columns) if columns.contains(&column) => {
+                                Some(theme::change_tint(Change::Changed, &visuals))
+                            }
+                            _ => tint,
+                        };
+                        let (_, cell_response) = table_row.col(|ui| {
+                            if let Some(color) = tint {
+                                ui.painter().rect_filled(ui.max_rect(), 0.0, color);
+                            }
+                            if *cell == Value::Null {
+                                ui.weak(tr("value.na"));
+                            } else {
+                                let text = cell.display();
+                                match hunt_column(column) {
+                                    Some(pattern) => {
+                                        ui.label(hunt::highlighted(ui, &text, pattern))
+                                    }
+                                    None => ui.label(text.as_ref()),
+                                };
+                                let note = hash_column(column).and_then(|l| l.note(&text));
+                                if let Some(note) = note {
+                                    hash_note(ui, note);
+                                }
+                            }
+                        });
+                        if cell_response.secondary_clicked() {
+                            self.menu_cell = Some(column);
+                        }
+                    }
+                    let response = table_row.response();
+                    if response.clicked() {
+                        let modifiers = response.ctx.input(|input| input.modifiers);
+                        self.click(i, rows, modifiers);
+                    }
+                    // Меню над выбранной строкой относится ко всему выбору
+                    if response.secondary_clicked() && !self.is_chosen(i) {
+                        self.select(Some(i));
+                    }
+                    let response = if set.extra.columns.is_empty() {
+                        response
+                    } else {
+                        response.on_hover_ui(|ui| {
+                            let tip = self.tips.entry(i).or_insert_with(|| set.tip(i));
+                            row_tip(ui, tip);
+                        })
+                    };
+                    response.context_menu(|ui| {
+                        self.selected = Some(i);
+                        let count = self.chosen_count();
+                        if count > 1 {
+                            let title = trf("table.copy_selected", &[("count", &count)]);
+                            if ui.button(title).clicked() {
+                                self.copy_selected(ui.ctx(), set);
+                                ui.close_menu();
+                            }
+                        }
+                        let chosen = row_menu(ui, set, row, self.menu_cell, &actions(i));
+                        event = event.take().or(chosen);
+                    });
+                });
+            });
+        event
+    }
+}
+
+// Ответ VirusTotal рядом с хешем; обнаружения выделяются цветом ошибки
+fn hash_note(ui: &mut egui::Ui, note: Note) {
+    let response = if note.alarm {
+        ui.colored_label(ui.visuals().error_fg_color, note.text)
+    } else {
+        ui.weak(note.text)
+    };
+    if let Some(hint) = note.hint {
+        response.on_hover_text(hint);
+    }
+}
+
+// Свойства строки в две колонки; длинные значения переносятся
+fn row_tip(ui: &mut egui::Ui, tip: &[(String, String)]) {
+    ui.set_max_width(TIP_WIDTH);
+    if tip.is_empty() {
+        ui.weak(tr("table.no_details"));
+        return;
+    }
+    egui::Grid::new("row_tip")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (title, value) in tip {
+                ui.strong(title);
+                ui.add(egui::Label::new(value).wrap());
+                ui.end_row();
+            }
+        });
+}
+
+// Пункты меню строки: копирование, затем пункты по смыслу столбцов, затем действия
+// категории. На пункты для путей и хешей влияют только имена столбцов
+fn row_menu<A: Copy>(
+    ui: &mut egui::Ui,
+    set: &ResultSet,
+    row: &[Value],
+    cell: Option<usize>,
+    actions: &[RowAction<A>],
+) -> Option<MenuEvent<A>> {
+    let mut event = None;
+    if ui.button(tr("table.copy_row")).clicked() {
+        ui.ctx().copy_text(tsv_line(row));
+        ui.close_menu();
+    }
+    if let Some(value) = cell.and_then(|cell| row.get(cell)) {
+        if ui.button(tr("table.copy_cell")).clicked() {
+            ui.ctx().copy_text(value.display().into_owned());
+            ui.close_menu();
+        }
+    }
+    for (column, value) in set.columns.iter().zip(row) {
+        let Value::Text(text) = value else {
+            continue;
+        };
+        match column.kind() {
+            ColumnKind::Path => {
+                let Some(path) = shell::file_path(text) else {
+                    continue;
+                };
+                let title = trf("table.open_folder", &[("column", &column.title)]);
+                if ui
+                    .button(title)
+                    .on_hover_text(path.display().to_string())
+                    .clicked()
+                {
+                    if let Err(e) = shell::reveal(&path) {
+                        tracing::warn!(path = %path.display(), error = %e, "не удалось открыть папку");
+                        let path = path.display();
+                        event = Some(MenuEvent::Failed(trf(
+                            "table.open_folder_failed",
+                            &[("path", &path), ("error", &e)],
+                        )));
+                    }
+                    ui.close_menu();
+                }
+            }
+            ColumnKind::Hash if is_file_hash(text) => {
+                let title = trf("table.virustotal", &[("column", &column.title)]);
+                if ui.button(title).clicked() {
+                    let url = format!("https://www.virustotal.com/gui/file/{}", text.trim());
+                    ui.ctx().open_url(egui::OpenUrl::new_tab(url));
+                    ui.close_menu();
+                }
+            }
+            ColumnKind::Hash | ColumnKind::Plain => {}
+        }
+    }
+    if !actions.is_empty() {
+        ui.separator();
+    }
+    for action in actions {
+        let mut response = ui.add_enabled(action.enabled, egui::Button::new(action.title));
+        if let Some(hint) = action.hint {
+            response = response.on_disabled_hover_text(tr(hint));
+        }
+        if response.clicked() {
+            event = Some(MenuEvent::Action(action.action));
+            ui.close_menu();
+        }
+    }
+    event
+}
+
+/// MD5, SHA-1 или SHA-256 в шестнадцатеричной записи
+pub fn is_file_hash(text: &str) -> bool {
+    let text = text.trim();
+    [32, 40, 64].contains(&text.len()) && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Экранирует переводы строк и табуляции, чтобы строка таблицы оставалась одной строкой TSV
+fn tsv_escape(cell: &str) -> String {
+    cell.replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+        .replace('\t', "\\t")
+}
+
+fn tsv_line(cells: &[Value]) -> String {
+    cells
+        .iter()
+        .map(|cell| tsv_escape(&cell.display()))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Заголовок и переданные строки в формате TSV для вставки в Excel
+fn to_tsv(set: &ResultSet, rows: &[usize]) -> String {
+    let mut out = set
+        .columns
+        .iter()
+        .map(|column| tsv_escape(&column.title))
+        .collect::<Vec<_>>()
+        .join("\t");
+    for &i in rows {
+        out.push('\n');
+        out.push_str(&tsv_line(&set.rows[i]));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_keeps_nulls_last_and_breaks_ties_by_key() {
+        let mut set = ResultSet::new(&["ProcessId", "ThreadCount"]);
+        for (pid, threads) in [(100, Some(4)), (2, None), (10, Some(12)), (1, Some(4))] {
+            set.rows.push(vec![
+                Value::Int(pid),
+                threads.map_or(Value::Null, Value::Int),
+            ]);
+        }
+        let mut view = TableView {
+            sort_column: Some(1),
+            ..TableView::default()
+        };
+        let keys = ["ProcessId"];
+        assert_eq!(view.visible_rows(&set, &keys, 1), [3, 0, 2, 1]);
+        view.sort_descending = true;
+        assert_eq!(view.visible_rows(&set, &keys, 1), [2, 3, 0, 1]);
+        view.sort_column = Some(0);
+        assert_eq!(view.visible_rows(&set, &keys, 1), [0, 2, 1, 3]);
+
+        // Ключ не в первом столбце, и из двух свойств, как у переменных окружения
+        let mut set = ResultSet::new(&["VariableValue", "UserName", "Name"]);
+        for (value, user, name) in [
+            ("1", "<SYSTEM>", "TEMP"),
+            ("1", "WS-01\\user", "PATH"),
+            ("1", "<SYSTEM>", "PATH"),
+            ("0", "<SYSTEM>", "OS"),
+        ] {
+            set.rows.push(vec![value.into(), user.into(), name.into()]);
+        }
+        let view = TableView {
+            sort_column: Some(0),
+            ..TableView::default()
+        };
+        assert_eq!(
+            view.visible_rows(&set, &["Name", "UserName"], 1),
+            [3, 2, 1, 0]
+        );
+        // Без ключа равные строки остаются в порядке запроса
+        assert_eq!(view.visible_rows(&set, &[], 2), [3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn order_is_kept_until_data_changes() {
+        let mut set = ResultSet::new(&["Name"]);
+        set.rows = vec![vec!["b".into()], vec!["a".into()]];
+        let mut view = TableView {
+            sort_column: Some(0),
+            ..TableView::default()
+        };
+        assert_eq!(view.visible_rows(&set, &["Name"], 1), [1, 0]);
+        set.rows.push(vec!["c".into()]);
+        assert_eq!(view.visible_count(&set, &["Name"], 1), 2);
+        assert_eq!(view.visible_rows(&set, &["Name"], 2), [1, 0, 2]);
+        view.filter.text = "c".into();
+        view.filter.update();
+        assert_eq!(view.visible_rows(&set, &["Name"], 2), [2]);
+        let mut calls = 0;
+        for _ in 0..2 {
+            view.row_flags(2, || {
+                calls += 1;
+                HashMap::new()
+            });
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn ctrl_and_shift_extend_selection() {
+        let mut view = TableView::default();
+        let rows = [3, 1, 4, 0, 2]; // Порядок после сортировки
+        let (plain, ctrl, shift) = (
+            egui::Modifiers::NONE,
+            egui::Modifiers::COMMAND,
+            egui::Modifiers::SHIFT,
+        );
+        view.click(1, &rows, plain);
+        view.click(0, &rows, shift);
+        assert_eq!(view.chosen(&rows), [1, 4, 0]);
+        view.click(4, &rows, ctrl);
+        assert_eq!(view.chosen(&rows), [1, 0]);
+        view.click(2, &rows, ctrl);
+        assert_eq!(view.chosen_count(), 3);
+        assert_eq!(view.selected, Some(2));
+        view.select(None);
+        assert_eq!(view.chosen_count(), 0);
+    }
+
+    #[test]
+    fn layout_orders_and_hides_by_name() {
+        let set = ResultSet::new(&["Name", "ProcessId", "CommandLine", "ThreadCount"]);
+        assert_eq!(ColumnLayout::default().visible(&set), [0, 1, 2, 3]);
+        let layout = ColumnLayout {
+            order: vec![
+                "ProcessId".to_string(),
+                "Missing".to_string(),
+                "Name".to_string(),
+            ],
+            hidden: vec!["CommandLine".to_string()],
+        };
+        assert_eq!(layout.ordered(&set), [1, 0, 2, 3]);
+        assert_eq!(layout.visible(&set), [1, 0, 3]);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::findings::Severity;
+use crate::i18n::tr;
+use crate::watch::Change;
+use egui::{Color32, Key, ThemePreference, Visuals};
+use serde::{Deserialize, Serialize};
+
+/// Тема оформления интерфейса
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Theme::System => tr("theme.system"),
+            Theme::Light => tr("theme.light"),
+            Theme::Dark => tr("theme.dark"),
+        }
+    }
+
+    pub fn apply(self, ctx: &egui::Context) {
+        ctx.set_theme(match self {
+            Theme::System => ThemePreference::System,
+            Theme::Light => ThemePreference::Light,
+            Theme::Dark => ThemePreference::Dark,
+        });
+    }
+}
+
+/// Фон строки с находкой. Светлые оттенки для светлой темы, приглушённые для тёмной,
+/// чтобы текст оставался читаемым; информационные находки не подсвечиваются
+pub fn severity_tint(severity: Severity, visuals: &Visuals) -> Option<Color32> {
+    let (light, dark) = match severity {
+        Severity::Info => return None,
+        Severity::Low => ((222, 235, 255), (28, 46, 76)),
+        Severity::Medium => ((255, 243, 176), (78, 66, 20)),
+        Severity::High => ((255, 222, 178), (92, 50, 18)),
+        Severity::Critical => ((255, 205, 210), (98, 28, 34)),
+    };
+    let (r, g, b) = if visuals.dark_mode { dark } else { light };
+    Some(Color32::from_rgb(r, g, b))
+}
+
+/// Фон строки или ячейки, изменившейся за время наблюдения
+pub fn change_tint(change: Change, visuals: &Visuals) -> Color32 {
+    let (light, dark) = match change {
+        Change::Added => ((214, 245, 214), (26, 72, 36)),
+        Change::Removed => ((255, 214, 214), (92, 30, 30)),
+        Change::Changed => ((255, 246, 184), (80, 70, 18)),
+    };
+    let (r, g, b) = if visuals.dark_mode { dark } else { light };
+    Color32::from_rgb(r, g, b)
+}
+
+/// Масштаб интерфейса; хранится между запусками
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Zoom(f32);
+
+impl Default for Zoom {
+    fn default() -> Self {
+        Zoom(1.0)
+    }
+}
+
+impl Zoom {
+    const MIN: f32 = 0.75;
+    const MAX: f32 = 2.0;
+    const STEP: f32 = 0.1;
+
+    pub fn apply(self, ctx: &egui::Context) {
+        // Встроенные сочетания egui меняют масштаб мимо сохранённого значения
+        ctx.options_mut(|options| options.zoom_with_keyboard = false);
+        ctx.set_zoom_factor(self.0.clamp(Self::MIN, Self::MAX));
+    }
+
+    fn set(&mut self, ctx: &egui::Context, factor: f32) {
+        // Округление до процента, чтобы шаги не накапливали погрешность
+        self.0 = ((factor * 100.0).round() / 100.0).clamp(Self::MIN, Self::MAX);
+        self.apply(ctx);
+    }
+
+    /// Ctrl+= / Ctrl+- меняют масштаб, Ctrl+0 сбрасывает на 100%
+    pub fn handle_keys(&mut self, ctx: &egui::Context) {
+        let (plus, minus, reset) = ctx.input(|input| {
+            let command = input.modifiers.command;
+            (
+                command && (input.key_pressed(Key::Equals) || input.key_pressed(Key::Plus)),
+                command && input.key_pressed(Key::Minus),
+                command && input.key_pressed(Key::Num0),
+            )
+        });
+        if plus {
+            self.set(ctx, self.0 + Self::STEP);
+        } else if minus {
+            self.set(ctx, self.0 - Self::STEP);
+        } else if reset {
+            self.set(ctx, 1.0);
+        }
+    }
+
+    /// Ползунок масштаба и кнопка сброса для меню настроек
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("zoom.label"));
+            let mut percent = self.0 * 100.0;
+            let slider = egui::Slider::new(&mut percent, Self::MIN * 100.0..=Self::MAX * 100.0)
+                .step_by(5.0)
+                .suffix("%");
+            // Масштаб применяется по отпусканию, иначе ползунок уезжает из-под курсора
+            let response = ui.add(slider);
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                self.set(ui.ctx(), percent / 100.0);
+            } else if response.changed() {
+                self.0 = percent / 100.0;
+            }
+            if ui
+                .add_enabled(self.0 != 1.0, egui::Button::new("100%"))
+                .on_hover_text(tr("zoom.reset"))
+                .clicked()
+            {
+                self.set(ui.ctx(), 1.0);
+            }
+        });
+    }
+}
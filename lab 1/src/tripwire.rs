@@ -0,0 +1,334 @@
+use crate::apartment::Apartment;
+use crate::connection::{self, Connection, Target};
+use crate::error::AppError;
+use crate::findings::Severity;
+use crate::i18n::tr;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use windows::Win32::System::Com::COINIT_MULTITHREADED;
+use wmi::Variant;
+
+/// Интервал между опросами
+const INTERVAL: Duration = Duration::from_secs(30);
+
+// Встроенная группа «Администраторы» ищется по SID: имя зависит от языка системы
+const GROUP_QUERY: &str =
+    "SELECT Domain, Name FROM Win32_Group WHERE LocalAccount = TRUE AND SID = 'S-1-5-32-544'";
+const CONSUMER_QUERY: &str = "SELECT * FROM __EventConsumer";
+
+/// Изменение, о котором нужно сообщить сразу, не дожидаясь отчёта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trigger {
+    AdminMember,   // Новый участник группы «Администраторы»
+    EventConsumer, // Новый получатель событий WMI — частый способ закрепления
+}
+
+impl Trigger {
+    pub const ALL: [Trigger; 2] = [Trigger::AdminMember, Trigger::EventConsumer];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Trigger::AdminMember => tr("tripwire.admin_member"),
+            Trigger::EventConsumer => tr("tripwire.event_consumer"),
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Trigger::AdminMember => Severity::High,
+            Trigger::EventConsumer => Severity::Critical,
+        }
+    }
+}
+
+/// Появившийся участник группы или получатель событий
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub at: DateTime<Local>,
+    pub trigger: Trigger,
+    pub item: String, // «ДОМЕН\имя» участника или имя получателя с командой
+}
+
+/// Известные значения одного наблюдателя. Первый опрос служит точкой отсчёта
+#[derive(Debug, Default)]
+pub struct Known(Option<HashSet<String>>);
+
+impl Known {
+    /// Значения, которых не было в прошлом опросе, по алфавиту
+    pub fn fresh(&mut self, current: Vec<String>) -> Vec<String> {
+        let current: HashSet<String> = current.into_iter().collect();
+        let mut fresh: Vec<String> = match &self.0 {
+            Some(known) => current.difference(known).cloned().collect(),
+            None => Vec::new(),
+        };
+        fresh.sort_unstable();
+        self.0 = Some(current);
+        fresh
+    }
+}
+
+// Общее состояние потока опроса и окна
+#[derive(Default)]
+struct Shared {
+    alerts: Mutex<Vec<Alert>>, // Ещё не забранные окном
+    stop: AtomicBool,
+}
+
+/// Фоновый поток опроса со своими подключениями к root\cimv2 и root\subscription.
+/// Удаление останавливает поток после текущего опроса
+struct Watcher {
+    target: Target,
+    shared: Arc<Shared>,
+}
+
+impl Watcher {
+    fn start(ctx: &egui::Context, target: Target) -> Self {
+        let shared = Arc::new(Shared::default());
+        let thread_shared = Arc::clone(&shared);
+        let thread_target = target.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || run(&ctx, &thread_target, &thread_shared));
+        Self { target, shared }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Подключения и известные значения одного наблюдателя
+struct Probe {
+    trigger: Trigger,
+    namespace: &'static str,
+    con: Option<Connection>,
+    known: Known,
+    error: Option<String>, // Последняя ошибка пишется в журнал один раз
+}
+
+// Цикл потока опроса. Подключение повторяется на каждом такте, пока не удастся
+fn run(ctx: &egui::Context, target: &Target, shared: &Shared) {
+    // Подключения объявлены после COM и удаляются раньше него
+    let apartment = Apartment::init(COINIT_MULTITHREADED).map_err(AppError::ComInit);
+    let mut probes = [
+        (Trigger::AdminMember, "root\\cimv2"),
+        (Trigger::EventConsumer, "root\\subscription"),
+    ]
+    .map(|(trigger, namespace)| Probe {
+        trigger,
+        namespace,
+        con: None,
+        known: Known::default(),
+        error: None,
+    });
+    let mut next = Instant::now();
+    while !shared.stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        if now < next {
+            std::thread::sleep((next - now).min(Duration::from_millis(200)));
+            continue;
+        }
+        next = now + INTERVAL;
+        for probe in &mut probes {
+            let result = match &probe.con {
+                Some(con) => items(con, probe.trigger),
+                None => apartment
+                    .as_ref()
+                    .map_err(AppError::clone)
+                    .and_then(|apartment| {
+                        connection::connect(apartment.com(), target, probe.namespace)
+                    })
+                    .and_then(|connected| items(probe.con.insert(connected), probe.trigger)),
+            };
+            match result {
+                Ok(current) => {
+                    probe.error = None;
+                    let fresh = probe.known.fresh(current);
+                    if fresh.is_empty() {
+                        continue;
+                    }
+                    let mut alerts = shared.alerts.lock().unwrap_or_else(|e| e.into_inner());
+                    for item in fresh {
+                        tracing::warn!(trigger = ?probe.trigger, %item, "обнаружено новое значение");
+                        alerts.push(Alert {
+                            at: Local::now(),
+                            trigger: probe.trigger,
+                            item,
+                        });
+                    }
+                    ctx.request_repaint();
+                }
+                Err(e) => {
+                    if probe.error.as_deref() != Some(&e.to_string()) {
+                        tracing::warn!(trigger = ?probe.trigger, error = %e, "опрос наблюдателя не удался");
+                    }
+                    probe.error = Some(e.to_string());
+                    probe.con = None;
+                }
+            }
+        }
+    }
+}
+
+// Текущие значения наблюдателя
+fn items(con: &Connection, trigger: Trigger) -> Result<Vec<String>, AppError> {
+    let query = |wql: &str, class: &str| -> Result<Vec<HashMap<String, Variant>>, AppError> {
+        con.poll_query(wql)
+            .map_err(|e| AppError::query(con.host(), class, e))
+    };
+    match trigger {
+        Trigger::AdminMember => {
+            let groups = query(GROUP_QUERY, "Win32_Group")?;
+            let Some(group) = groups.first() else {
+                return Err(AppError::query(
+                    con.host(),
+                    "Win32_Group",
+                    wmi::WMIError::ResultEmpty,
+                ));
+            };
+            let wql = format!(
+                "ASSOCIATORS OF {{{}}} WHERE AssocClass = Win32_GroupUser Role = GroupComponent",
+                group_path(&text(group, "Domain"), &text(group, "Name"))
+            );
+            Ok(query(&wql, "Win32_GroupUser")?
+                .iter()
+                .map(|member| format!("{}\\{}", text(member, "Domain"), text(member, "Name")))
+                .collect())
+        }
+        Trigger::EventConsumer => Ok(query(CONSUMER_QUERY, "__EventConsumer")?
+            .iter()
+            .map(consumer)
+            .collect()),
+    }
+}
+
+fn text(row: &HashMap<String, Variant>, name: &str) -> String {
+    match row.get(name) {
+        Some(Variant::String(text)) => text.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Путь группы для ASSOCIATORS OF
+pub fn group_path(domain: &str, name: &str) -> String {
+    let quote = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        "Win32_Group.Domain=\"{}\",Name=\"{}\"",
+        quote(domain),
+        quote(name)
+    )
+}
+
+/// Имя получателя событий вместе с тем, что он запускает: смена команды
+/// у прежнего имени тоже считается новым получателем
+pub fn consumer(row: &HashMap<String, Variant>) -> String {
+    let name = text(row, "Name");
+    let action = ["CommandLineTemplate", "ScriptFileName", "ScriptText"]
+        .into_iter()
+        .map(|property| text(row, property))
+        .find(|action| !action.is_empty());
+    match action {
+        Some(action) => format!("{name} ({action})"),
+        None => name,
+    }
+}
+
+/// Наблюдатели за группой «Администраторы» и получателями событий WMI
+#[derive(Default)]
+pub struct Tripwire {
+    watcher: Option<Watcher>,
+}
+
+impl Tripwire {
+    /// Запускает или останавливает опрос и забирает новые значения. Вызывается
+    /// на каждом кадре; первый опрос после запуска только запоминает значения
+    pub fn poll(&mut self, ctx: &egui::Context, target: &Target, active: bool) -> Vec<Alert> {
+        if !active {
+            self.watcher = None;
+            return Vec::new();
+        }
+        // Опрашивается компьютер, к которому подключено окно
+        if self.watcher.as_ref().is_none_or(|w| w.target != *target) {
+            self.watcher = Some(Watcher::start(ctx, target.clone()));
+        }
+        let Some(watcher) = &self.watcher else {
+            return Vec::new();
+        };
+        let mut alerts = watcher
+            .shared
+            .alerts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        std::mem::take(&mut *alerts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn first_poll_is_baseline() {
+        let mut known = Known::default();
+        assert!(known
+            .fresh(names(&["WS-01\\Administrator", "CORP\\Domain Admins"]))
+            .is_empty());
+        assert_eq!(
+            known.fresh(names(&[
+                "WS-01\\Administrator",
+                "CORP\\Domain Admins",
+                "WS-01\\backup",
+                "WS-01\\attacker",
+            ])),
+            names(&["WS-01\\attacker", "WS-01\\backup"])
+        );
+        // Удалённый и вернувшийся участник снова новый
+        assert!(known.fresh(names(&["WS-01\\Administrator"])).is_empty());
+        assert_eq!(
+            known.fresh(names(&["WS-01\\Administrator", "WS-01\\backup"])),
+            names(&["WS-01\\backup"])
+        );
+    }
+
+    #[test]
+    fn consumer_includes_action() {
+        let row = |pairs: &[(&str, &str)]| -> HashMap<String, Variant> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), Variant::String(v.to_string())))
+                .collect()
+        };
+        assert_eq!(
+            consumer(&row(&[
+                ("Name", "Updater"),
+                ("CommandLineTemplate", "powershell -enc AAAA"),
+            ])),
+            "Updater (powershell -enc AAAA)"
+        );
+        assert_eq!(
+            consumer(&row(&[("Name", "SCM Event Log Consumer")])),
+            "SCM Event Log Consumer"
+        );
+    }
+
+    #[test]
+    fn group_path_escapes_keys() {
+        assert_eq!(
+            group_path("WS-01", "Администраторы"),
+            "Win32_Group.Domain=\"WS-01\",Name=\"Администраторы\""
+        );
+        assert_eq!(
+            group_path("a\\b", "c\"d"),
+            "Win32_Group.Domain=\"a\\\\b\",Name=\"c\\\"d\""
+        );
+    }
+}
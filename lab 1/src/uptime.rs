@@ -0,0 +1,268 @@
+use crate::i18n::{tr, trf};
+use crate::model::{Property, ResultSet, Value};
+use crate::source::Object;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Win32_OperatingSystem.ProductType рабочей станции
+pub const WORKSTATION: i64 = 1;
+
+/// Сколько дней рабочая станция может работать без перезагрузки
+pub const LONG_UPTIME_DAYS: i64 = 30;
+
+/// Сколько дней после установки обновления перезагрузку можно откладывать
+pub const STALE_REBOOT_DAYS: i64 = 7;
+
+/// Сколько последних неожиданных завершений показывается
+pub const SHUTDOWNS: usize = 3;
+
+/// За сколько дней ищутся неожиданные завершения по умолчанию: журнал System бывает большим
+pub const SHUTDOWN_WINDOW_DAYS: i64 = 90;
+
+static LOOKBACK_DAYS: AtomicI64 = AtomicI64::new(SHUTDOWN_WINDOW_DAYS);
+
+/// Глубина поиска в журнале событий из файла настроек
+pub fn set_lookback_days(days: i64) {
+    LOOKBACK_DAYS.store(days.max(1), Ordering::Relaxed);
+}
+
+pub fn lookback_days() -> i64 {
+    LOOKBACK_DAYS.load(Ordering::Relaxed)
+}
+
+/// Признак ожидаемой перезагрузки в реестре HKEY_LOCAL_MACHINE (раздел по
+/// умолчанию у StdRegProv). Без имени значения признак — сам раздел
+pub struct Pending {
+    pub name: &'static str,
+    pub key: &'static str,
+    pub value: Option<&'static str>,
+}
+
+pub const PENDING: [Pending; 3] = [
+    Pending {
+        name: "PendingFileRenameOperations",
+        key: "SYSTEM\\CurrentControlSet\\Control\\Session Manager",
+        value: Some("PendingFileRenameOperations"),
+    },
+    Pending {
+        name: "Component Based Servicing\\RebootPending",
+        key: "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Component Based Servicing\\RebootPending",
+        value: None,
+    },
+    Pending {
+        name: "Windows Update\\RebootRequired",
+        key: "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\WindowsUpdate\\Auto Update\\RebootRequired",
+        value: None,
+    },
+];
+
+impl Pending {
+    /// Метод StdRegProv и его параметры: значение читается целиком,
+    /// раздел проверяется перечислением его подразделов
+    pub fn request(&self) -> (&'static str, Vec<(&'static str, Value)>) {
+        let mut params = vec![("sSubKeyName", self.key.into())];
+        match self.value {
+            Some(value) => {
+                params.push(("sValueName", value.into()));
+                ("GetMultiStringValue", params)
+            }
+            None => ("EnumKey", params),
+        }
+    }
+
+    /// Есть ли признак по ответу метода. Нет раздела — код 2, нет значения — 1;
+    /// другие коды, например 5 (нет доступа), — ошибка
+    pub fn present(&self, output: &Object) -> Result<bool, String> {
+        match output.get("ReturnValue") {
+            Some(Property::Scalar(Value::Int(0))) => Ok(true),
+            Some(Property::Scalar(Value::Int(1 | 2))) => Ok(false),
+            Some(Property::Scalar(Value::Int(code))) => Err(trf(
+                "uptime.registry_error",
+                &[("key", &self.name), ("code", code)],
+            )),
+            _ => Err(trf(
+                "uptime.registry_error",
+                &[("key", &self.name), ("code", &"?")],
+            )),
+        }
+    }
+}
+
+/// Дата установки обновления из Win32_QuickFixEngineering.InstalledOn: `M/D/YYYY`
+/// независимо от языка системы. У старых обновлений там бывает FILETIME — пропускается
+pub fn installed_on(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.trim(), "%m/%d/%Y").ok()
+}
+
+/// Время неожиданного завершения из события 6008: в параметрах время и дата
+/// в формате системы с метками направления письма
+pub fn shutdown_time(strings: &[Value]) -> Option<String> {
+    let clean = |value: &Value| match value {
+        Value::Text(text) => Some(
+            text.chars()
+                .filter(|c| !matches!(c, '\u{200e}' | '\u{200f}'))
+                .collect::<String>()
+                .trim()
+                .to_string(),
+        ),
+        _ => None,
+    };
+    let time = clean(strings.first()?)?;
+    let date = clean(strings.get(1)?)?;
+    (!time.is_empty() && !date.is_empty()).then(|| format!("{date} {time}"))
+}
+
+/// Длительность работы словами: дни и часы
+pub fn duration(seconds: i64) -> String {
+    let hours = seconds.max(0) / 3600;
+    trf(
+        "uptime.duration",
+        &[("days", &(hours / 24)), ("hours", &(hours % 24))],
+    )
+}
+
+/// Код строки роли рабочей станции в столбце Code
+pub const WORKSTATION_ROLE: &str = "role.workstation";
+
+// Роль словами и код строки: у каждой роли свой, чтобы проверка узнала рабочую станцию
+fn role(product_type: i64) -> (String, &'static str) {
+    match product_type {
+        WORKSTATION => (tr("uptime.workstation").to_string(), WORKSTATION_ROLE),
+        2 => (
+            tr("uptime.domain_controller").to_string(),
+            "role.domain_controller",
+        ),
+        3 => (tr("uptime.server").to_string(), "role.server"),
+        code => (trf("uptime.role_code", &[("code", &code)]), "role"),
+    }
+}
+
+/// Собранные сведения о работе системы
+#[derive(Debug, Clone, PartialEq)]
+pub struct Status {
+    pub now: DateTime<FixedOffset>, // Часы компьютера, а не того, где запущено приложение
+    pub boot: Option<DateTime<FixedOffset>>,
+    pub product_type: Option<i64>,
+    pub pending: Result<Vec<&'static str>, String>, // Найденные признаки по PENDING
+    pub hotfix: Option<NaiveDate>,                  // Последнее установленное обновление
+    pub shutdowns: Vec<String>,                     // Новые первыми
+}
+
+/// Несколько строк «признак — значение» для итогов отчёта. В столбце Days —
+/// число дней работы, давности обновления и ожидания перезагрузки, в столбце
+/// Code — код строки, не зависящий от языка: по нему строки находят проверки
+pub fn result_set(status: &Status) -> ResultSet {
+    let mut set = ResultSet::new(&["Indicator", "Observed", "Days", "Code"]);
+    let mut push = |indicator: &str, code: &str, observed: Value, days: Option<i64>| {
+        let days = days.map_or(Value::Null, Value::Int);
+        set.rows
+            .push(vec![indicator.into(), observed, days, code.into()]);
+    };
+    let today = status.now.date_naive();
+    let hotfix_age = status.hotfix.map(|date| (today - date).num_days());
+    match status.product_type.map(role) {
+        Some((name, code)) => push(tr("uptime.role"), code, name.into(), None),
+        None => push(tr("uptime.role"), "role", Value::Null, None),
+    }
+    match status.boot {
+        Some(boot) => {
+            let seconds = (status.now - boot).num_seconds();
+            push(
+                tr("uptime.last_boot"),
+                "last_boot",
+                boot.format("%Y-%m-%d %H:%M").to_string().into(),
+                None,
+            );
+            push(
+                tr("uptime.uptime"),
+                "uptime",
+                duration(seconds).into(),
+                Some(seconds / 86400),
+            );
+        }
+        None => push(tr("uptime.uptime"), "uptime", Value::Null, None),
+    }
+    match &status.pending {
+        Ok(found) if found.is_empty() => {
+            push(tr("uptime.pending"), "pending", tr("value.no").into(), None)
+        }
+        Ok(found) => push(
+            tr("uptime.pending"),
+            "pending",
+            trf("uptime.pending_yes", &[("reasons", &found.join(", "))]).into(),
+            hotfix_age,
+        ),
+        Err(error) => push(tr("uptime.pending"), "pending", error.as_str().into(), None),
+    }
+    push(
+        tr("uptime.hotfix"),
+        "hotfix",
+        Value::from(
+            status
+                .hotfix
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+        ),
+        hotfix_age,
+    );
+    if status.shutdowns.is_empty() {
+        push(
+            tr("uptime.shutdown"),
+            "shutdown",
+            trf("uptime.no_shutdowns", &[("days", &lookback_days())]).into(),
+            None,
+        );
+    }
+    for time in &status.shutdowns {
+        push(
+            tr("uptime.shutdown"),
+            "shutdown",
+            time.as_str().into(),
+            None,
+        );
+    }
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shutdown_time_drops_direction_marks() {
+        let strings: Vec<Value> = ["3:12:45", "\u{200e}2/\u{200e}14/\u{200e}2026", ""]
+            .into_iter()
+            .map(Value::from)
+            .collect();
+        assert_eq!(
+            shutdown_time(&strings).as_deref(),
+            Some("2/14/2026 3:12:45")
+        );
+        assert_eq!(shutdown_time(&strings[..1]), None);
+        assert_eq!(
+            installed_on("2/24/2026"),
+            NaiveDate::from_ymd_opt(2026, 2, 24)
+        );
+        assert_eq!(installed_on("01cb6ab4d7e1a000"), None);
+    }
+
+    #[test]
+    fn pending_reboot_counts_days_since_hotfix() {
+        let now = DateTime::parse_from_rfc3339("2026-03-10T10:15:00+03:00").unwrap();
+        let status = Status {
+            now,
+            boot: Some(now - chrono::Duration::hours(50)),
+            product_type: Some(3),
+            pending: Ok(vec![PENDING[1].name]),
+            hotfix: NaiveDate::from_ymd_opt(2026, 3, 1),
+            shutdowns: Vec::new(),
+        };
+        let set = result_set(&status);
+        assert_eq!(set.value(0, "Observed"), Some(&tr("uptime.server").into()));
+        assert_eq!(
+            set.value(2, "Observed"),
+            Some(&trf("uptime.duration", &[("days", &2), ("hours", &2)]).into())
+        );
+        assert_eq!(set.value(3, "Days"), Some(&Value::Int(9)));
+        assert_eq!(set.rows.len(), 6);
+    }
+}
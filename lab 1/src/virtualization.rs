@@ -0,0 +1,146 @@
+use crate::i18n::tr;
+
+/// Пространство имён Hyper-V на хосте виртуализации
+pub const HYPERV_NAMESPACE: &str = "root\\virtualization\\v2";
+
+/// Компонент Windows с гипервизором Hyper-V (Win32_OptionalFeature.Name)
+pub const HYPERV_FEATURE: &str = "Microsoft-Hyper-V";
+
+// Признаки оборудования гостевой системы в DeviceID: коды производителей PCI
+// виртуальных устройств и шина VMBus. Имена устройств не учитываются: программы
+// виртуализации на хосте ставят свои адаптеры с теми же словами в названии
+const DEVICES: [(&str, &str); 6] = [
+    ("VMBUS\\", "Hyper-V"),
+    ("VEN_15AD", "VMware"),
+    ("VEN_80EE", "VirtualBox"),
+    ("VEN_1AF4", "KVM/QEMU"),
+    ("VEN_1AB8", "Parallels"),
+    ("XEN\\", "Xen"),
+];
+
+/// Гипервизор по производителю и модели компьютера
+pub fn by_model(manufacturer: &str, model: &str) -> Option<&'static str> {
+    let manufacturer = manufacturer.to_lowercase();
+    let model = model.to_lowercase();
+    let both = format!("{manufacturer} {model}");
+    if both.contains("vmware") {
+        Some("VMware")
+    } else if both.contains("virtualbox") || manufacturer.contains("innotek") {
+        Some("VirtualBox")
+    } else if model.contains("virtual machine") && manufacturer.contains("microsoft") {
+        Some("Hyper-V")
+    } else if ["kvm", "qemu", "bochs"]
+        .iter()
+        .any(|name| both.contains(name))
+    {
+        Some("KVM/QEMU")
+    } else if both.contains("xen") {
+        Some("Xen")
+    } else if both.contains("parallels") {
+        Some("Parallels")
+    } else {
+        None
+    }
+}
+
+/// Гипервизор по серийному номеру BIOS. У Hyper-V номер из семи групп цифр
+/// вида 0000-0000-0000-0000-0000-0000-00
+pub fn by_serial(serial: &str) -> Option<&'static str> {
+    let serial = serial.trim();
+    let groups: Vec<&str> = serial.split('-').collect();
+    let hyperv = groups.len() == 7
+        && groups.iter().enumerate().all(|(i, group)| {
+            group.len() == if i == 6 { 2 } else { 4 } && group.bytes().all(|b| b.is_ascii_digit())
+        });
+    if serial.starts_with("VMware-") {
+        Some("VMware")
+    } else if serial.starts_with("Parallels-") {
+        Some("Parallels")
+    } else if serial.to_lowercase().contains("virtualbox") {
+        Some("VirtualBox")
+    } else if hyperv {
+        Some("Hyper-V")
+    } else {
+        None
+    }
+}
+
+/// Гипервизор, которому принадлежит устройство Plug and Play
+pub fn by_device(device_id: &str) -> Option<&'static str> {
+    let id = device_id.to_uppercase();
+    DEVICES
+        .iter()
+        .find(|(sign, _)| {
+            if sign.ends_with('\\') {
+                id.starts_with(sign)
+            } else {
+                id.contains(sign)
+            }
+        })
+        .map(|&(_, name)| name)
+}
+
+/// Вывод по найденным признакам: гипервизор, на который указывает больше признаков,
+/// при равенстве — первый из них. HypervisorPresent в выводе не участвует: он
+/// истинен и на физическом хосте с Hyper-V или защитой на основе виртуализации
+pub fn verdict(votes: &[&'static str]) -> Option<&'static str> {
+    let count = |name: &str| votes.iter().filter(|vote| **vote == name).count();
+    let mut best: Option<&'static str> = None;
+    for &vote in votes {
+        if best.is_none_or(|best| count(vote) > count(best)) {
+            best = Some(vote);
+        }
+    }
+    best
+}
+
+/// Состояние компонента Windows по Win32_OptionalFeature.InstallState
+pub fn install_state(code: i64) -> &'static str {
+    match code {
+        1 => tr("virt.feature.enabled"),
+        2 => tr("virt.feature.disabled"),
+        3 => tr("virt.feature.absent"),
+        _ => tr("value.na"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hypervisor_signs() {
+        assert_eq!(
+            by_model("Microsoft Corporation", "Virtual Machine"),
+            Some("Hyper-V")
+        );
+        assert_eq!(by_model("innotek GmbH", "VirtualBox"), Some("VirtualBox"));
+        assert_eq!(by_model("Dell Inc.", "Latitude 7420"), None);
+        assert_eq!(
+            by_serial("VMware-56 4d 1a 2b 3c 4d 5e 6f-70 81 92 a3 b4 c5 d6 e7"),
+            Some("VMware")
+        );
+        assert_eq!(
+            by_serial("3714-0449-3679-7565-9840-2339-12"),
+            Some("Hyper-V")
+        );
+        assert_eq!(by_serial("7XK2L93"), None);
+        assert_eq!(
+            by_device("VMBUS\\{F8615163-DF3E-46C5-913F-F2D2F965ED0E}\\{0000}"),
+            Some("Hyper-V")
+        );
+        // Шина VMBus самого хоста — не признак гостевой системы
+        assert_eq!(by_device("ROOT\\VMBUS\\0000"), None);
+        assert_eq!(
+            by_device("PCI\\VEN_15AD&DEV_0405&SUBSYS_040515AD&REV_00\\3&61AAA01&0&78"),
+            Some("VMware")
+        );
+    }
+
+    #[test]
+    fn most_supported_hypervisor_wins() {
+        assert_eq!(verdict(&[]), None);
+        assert_eq!(verdict(&["Hyper-V", "VMware", "VMware"]), Some("VMware"));
+        assert_eq!(verdict(&["KVM/QEMU", "Xen"]), Some("KVM/QEMU"));
+    }
+}
@@ -0,0 +1,410 @@
+use crate::http;
+use crate::i18n::{tr, trf};
+use crate::model::{ColumnKind, ResultSet};
+use crate::profiles;
+use crate::table::is_file_hash;
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+const FILE_NAME: &str = "virustotal.json";
+const API: &str = "https://www.virustotal.com/api/v3/files/";
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Бесплатный ключ: не больше 4 запросов в минуту
+pub const LIMIT: usize = 4;
+pub const WINDOW: Duration = Duration::from_secs(60);
+
+// Сохранённый ответ считается актуальным неделю: оценки антивирусов меняются
+const FRESH: TimeDelta = TimeDelta::days(7);
+
+/// Файл ответов рядом с файлом настроек
+pub fn path(config: &Path) -> PathBuf {
+    config.with_file_name(FILE_NAME)
+}
+
+// Запись диспетчера учётных данных Windows с ключом API
+const CREDENTIAL: &str = "WMI Lab/VirusTotal";
+
+/// Проверка хешей файлов по базе VirusTotal. Ключ API хранится в диспетчере
+/// учётных данных Windows, а не в config.toml. Без ключа ничего не запрашивается;
+/// сами файлы никогда не отправляются
+#[derive(Debug, Default)]
+pub struct Settings {
+    api_key: String, // Сохранённый ключ
+    draft: String,   // Поле ввода до нажатия «Сохранить»
+    error: Option<String>,
+}
+
+impl Settings {
+    /// Ключ из диспетчера учётных данных; без записи проверка выключена
+    pub fn load() -> Self {
+        let api_key = profiles::read_secret(CREDENTIAL).unwrap_or_default();
+        Self {
+            draft: api_key.clone(),
+            api_key,
+            error: None,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.api_key.trim().is_empty()
+    }
+
+    // Пустой ключ удаляет запись
+    fn save(&mut self) {
+        let key = self.draft.trim().to_string();
+        self.error = None;
+        if key.is_empty() {
+            profiles::delete_secret(CREDENTIAL);
+        } else if let Err(e) = profiles::write_secret(CREDENTIAL, "api_key", &key) {
+            tracing::warn!(error = %e, "ключ VirusTotal не сохранён");
+            self.error = Some(e.message());
+            return;
+        }
+        self.api_key = key;
+    }
+
+    /// Поля для меню настроек: ключ, очередь и повтор неудачных проверок
+    pub fn show(&mut self, ui: &mut egui::Ui, lookup: &mut Lookup) {
+        ui.horizontal(|ui| {
+            ui.label(tr("virustotal.key"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.draft)
+                    .password(true)
+                    .desired_width(220.0),
+            )
+            .on_hover_text(tr("virustotal.hint"));
+            let changed = self.draft.trim() != self.api_key;
+            if ui
+                .add_enabled(changed, egui::Button::new(tr("profiles.save")))
+                .clicked()
+            {
+                self.save();
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if !self.enabled() {
+            ui.weak(tr("virustotal.disabled"));
+            return;
+        }
+        let queued = lookup.queued();
+        if queued > 0 {
+            ui.label(trf("virustotal.queue", &[("count", &queued)]));
+        }
+        let failed = lookup.failed.len();
+        if failed > 0
+            && ui
+                .button(trf("virustotal.retry", &[("count", &failed)]))
+                .clicked()
+        {
+            lookup.retry_failed(ui.ctx(), self);
+        }
+    }
+}
+
+/// Ответ VirusTotal о хеше
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Verdict {
+    Detected { malicious: u32, total: u32 }, // Сколько антивирусов из скольких считают файл вредным
+    Unknown,                                 // Хеш VirusTotal не встречался (404)
+    Failed(String),                          // Сбой связи, отказ в ключе или превышение квоты
+}
+
+impl Verdict {
+    /// Разбор ответа на запрос `files/{hash}`
+    pub fn parse(status: u32, body: &str) -> Self {
+        match status {
+            200 => {
+                let json: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+                let stats = &json["data"]["attributes"]["last_analysis_stats"];
+                let count = |name: &str| stats[name].as_u64().unwrap_or(0) as u32;
+                if !stats.is_object() {
+                    return Verdict::Failed(tr("virustotal.bad_reply").to_string());
+                }
+                // Движки, которые не смогли проверить файл (тип не поддерживается,
+                // тайм-аут), в знаменатель не входят — так считает и сайт
+                let malicious = count("malicious");
+                let total =
+                    malicious + count("suspicious") + count("undetected") + count("harmless");
+                Verdict::Detected { malicious, total }
+            }
+            404 => Verdict::Unknown,
+            401 | 403 => Verdict::Failed(tr("virustotal.key_rejected").to_string()),
+            429 => Verdict::Failed(tr("virustotal.quota").to_string()),
+            status => Verdict::Failed(trf("virustotal.status", &[("status", &status)])),
+        }
+    }
+}
+
+/// Подпись рядом с хешем в таблице
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub text: String,
+    pub alarm: bool,          // Хотя бы один антивирус считает файл вредным
+    pub hint: Option<String>, // Причина сбоя
+}
+
+/// Скользящее окно запросов: не больше `LIMIT` за `WINDOW`
+#[derive(Debug, Default)]
+pub struct Limiter {
+    sent: VecDeque<Instant>,
+}
+
+impl Limiter {
+    /// Сколько ждать до следующего запроса
+    pub fn wait(&mut self, now: Instant) -> Duration {
+        while self
+            .sent
+            .front()
+            .is_some_and(|&at| now.duration_since(at) >= WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() < LIMIT {
+            return Duration::ZERO;
+        }
+        (self.sent[0] + WINDOW).saturating_duration_since(now)
+    }
+
+    pub fn record(&mut self, now: Instant) {
+        self.sent.push_back(now);
+    }
+}
+
+// Ответ, сохранённый на диске; сбои не сохраняются
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    verdict: Verdict,
+    time: DateTime<Local>,
+}
+
+struct Job {
+    hash: String,
+    key: String,
+}
+
+/// Очередь проверок и ответы. Запросы идут по одному в фоновом потоке
+/// с учётом ограничения бесплатного ключа; ответы сохраняются на диск,
+/// поэтому повторный запуск не расходует квоту на те же хеши
+pub struct Lookup {
+    path: PathBuf,
+    saved: HashMap<String, Entry>,
+    failed: HashMap<String, String>,
+    queued: HashSet<String>,
+    jobs: Option<Sender<Job>>,
+    results: Receiver<(String, Verdict)>,
+    reply: Sender<(String, Verdict)>,
+}
+
+impl Default for Lookup {
+    fn default() -> Self {
+        Self::open(PathBuf::new())
+    }
+}
+
+impl Lookup {
+    /// Ответы прошлых сеансов из файла; без файла — пустой список
+    pub fn open(path: PathBuf) -> Self {
+        let saved = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let (reply, results) = mpsc::channel();
+        Self {
+            path,
+            saved,
+            failed: HashMap::new(),
+            queued: HashSet::new(),
+            jobs: None,
+            results,
+            reply,
+        }
+    }
+
+    /// Ставит в очередь хеши из столбцов хешей, для которых нет свежего ответа
+    pub fn request(&mut self, ctx: &egui::Context, settings: &Settings, set: &ResultSet) {
+        if !settings.enabled() {
+            return;
+        }
+        let now = Local::now();
+        let columns: Vec<usize> = (0..set.columns.len())
+            .filter(|&i| set.columns[i].kind() == ColumnKind::Hash)
+            .collect();
+        let hashes: Vec<String> = set
+            .rows
+            .iter()
+            .flat_map(|row| columns.iter().filter_map(|&i| row.get(i)))
+            .map(|value| value.display().trim().to_ascii_lowercase())
+            .filter(|hash| is_file_hash(hash))
+            .collect();
+        for hash in hashes {
+            let fresh = self
+                .saved
+                .get(&hash)
+                .is_some_and(|entry| now - entry.time < FRESH);
+            if fresh || self.failed.contains_key(&hash) || self.queued.contains(&hash) {
+                continue;
+            }
+            self.send(ctx, settings, hash);
+        }
+    }
+
+    fn send(&mut self, ctx: &egui::Context, settings: &Settings, hash: String) {
+        let jobs = self.jobs.get_or_insert_with(|| {
+            let (jobs, queue) = mpsc::channel();
+            let (reply, ctx) = (self.reply.clone(), ctx.clone());
+            std::thread::spawn(move || worker(queue, reply, ctx));
+            jobs
+        });
+        let job = Job {
+            hash: hash.clone(),
+            key: settings.api_key.clone(),
+        };
+        if jobs.send(job).is_ok() {
+            self.queued.insert(hash);
+        }
+    }
+
+    /// Повторяет проверки, закончившиеся сбоем
+    pub fn retry_failed(&mut self, ctx: &egui::Context, settings: &Settings) {
+        for (hash, _) in std::mem::take(&mut self.failed) {
+            self.send(ctx, settings, hash);
+        }
+    }
+
+    /// Забирает готовые ответы; новые сохраняются в файл
+    pub fn poll(&mut self) {
+        let mut changed = false;
+        while let Ok((hash, verdict)) = self.results.try_recv() {
+            self.queued.remove(&hash);
+            match verdict {
+                Verdict::Failed(error) => {
+                    self.failed.insert(hash, error);
+                }
+                verdict => {
+                    let entry = Entry {
+                        verdict,
+                        time: Local::now(),
+                    };
+                    self.saved.insert(hash, entry);
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            if let Err(e) = self.save() {
+                tracing::warn!(path = %self.path.display(), error = %e, "не удалось сохранить ответы VirusTotal");
+            }
+        }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.saved)?)
+    }
+
+    /// Хешей в очереди, включая проверяемый сейчас
+    pub fn queued(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Подпись для хеша: результат, «в очереди» или причина сбоя
+    pub fn note(&self, hash: &str) -> Option<Note> {
+        let hash = hash.trim().to_ascii_lowercase();
+        if let Some(error) = self.failed.get(&hash) {
+            return Some(Note {
+                text: tr("virustotal.failed").to_string(),
+                alarm: false,
+                hint: Some(error.clone()),
+            });
+        }
+        if self.queued.contains(&hash) {
+            return Some(Note {
+                text: tr("virustotal.queued").to_string(),
+                alarm: false,
+                hint: None,
+            });
+        }
+        let note = match &self.saved.get(&hash)?.verdict {
+            Verdict::Detected { malicious, total } => Note {
+                text: format!("VT: {malicious}/{total}"),
+                alarm: *malicious > 0,
+                hint: None,
+            },
+            Verdict::Unknown => Note {
+                text: tr("virustotal.unknown").to_string(),
+                alarm: false,
+                hint: None,
+            },
+            Verdict::Failed(error) => Note {
+                text: tr("virustotal.failed").to_string(),
+                alarm: false,
+                hint: Some(error.clone()),
+            },
+        };
+        Some(note)
+    }
+}
+
+// Проверки по одной: только GET по хешу, тело запроса пустое. Ключ приходит
+// с каждым заданием, поэтому смена ключа в настройках действует сразу
+fn worker(queue: Receiver<Job>, reply: Sender<(String, Verdict)>, ctx: egui::Context) {
+    let mut limiter = Limiter::default();
+    for job in queue {
+        std::thread::sleep(limiter.wait(Instant::now()));
+        limiter.record(Instant::now());
+        let headers = format!("x-apikey: {}\r\nAccept: application/json\r\n", job.key);
+        let url = format!("{API}{}", job.hash);
+        let verdict = match http::send("GET", &url, &headers, &[], TIMEOUT) {
+            Ok(response) => Verdict::parse(response.status, &response.body),
+            Err(error) => {
+                tracing::warn!(hash = %job.hash, error = %error, "VirusTotal недоступен");
+                Verdict::Failed(trf("virustotal.network", &[("error", &error)]))
+            }
+        };
+        if reply.send((job.hash, verdict)).is_err() {
+            return;
+        }
+        ctx.request_repaint();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_codes_are_told_apart() {
+        let body = r#"{"data": {"attributes": {"last_analysis_stats": {
+            "malicious": 5, "suspicious": 1, "undetected": 60, "harmless": 6,
+            "timeout": 2, "type-unsupported": 3}}}}"#;
+        assert_eq!(
+            Verdict::parse(200, body),
+            Verdict::Detected {
+                malicious: 5,
+                total: 72
+            }
+        );
+        assert_eq!(Verdict::parse(404, ""), Verdict::Unknown);
+        assert!(matches!(Verdict::parse(401, ""), Verdict::Failed(_)));
+        assert!(matches!(Verdict::parse(200, "<html>"), Verdict::Failed(_)));
+    }
+
+    #[test]
+    fn limiter_allows_four_per_minute() {
+        let start = Instant::now();
+        let mut limiter = Limiter::default();
+        for _ in 0..LIMIT {
+            assert_eq!(limiter.wait(start), Duration::ZERO);
+            limiter.record(start);
+        }
+        let later = start + Duration::from_secs(20);
+        assert_eq!(limiter.wait(later), Duration::from_secs(40));
+        assert_eq!(limiter.wait(start + WINDOW), Duration::ZERO);
+    }
+}
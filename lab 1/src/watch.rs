@@ -0,0 +1,343 @@
+use crate::i18n::{tr, trf};
+use crate::model::{ResultSet, Value};
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Сколько записей истории изменений хранится для категории
+const HISTORY: usize = 200;
+
+/// Период повтора по умолчанию, в секундах
+pub const INTERVAL: u64 = 10;
+
+/// Вид изменения строки или ячейки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Подсветка последнего результата: индексы строк нового результата
+/// и строки, исчезнувшие с прошлого опроса
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Marks {
+    pub added: HashSet<usize>,
+    pub changed: HashMap<usize, HashSet<usize>>, // Строка → изменившиеся столбцы
+    pub removed: Vec<Vec<Value>>,                // Показываются один цикл
+}
+
+/// Запись истории изменений
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub at: DateTime<Local>,
+    pub change: Change,
+    pub row: String,          // Значения ключевых столбцов
+    pub columns: Vec<String>, // Изменившиеся столбцы
+    pub index: Option<usize>, // Строка нового результата; у исчезнувших строк её нет
+}
+
+impl Event {
+    /// Описание изменения без времени
+    pub fn text(&self) -> String {
+        match self.change {
+            Change::Added => trf("watch.added", &[("row", &self.row)]),
+            Change::Removed => trf("watch.removed", &[("row", &self.row)]),
+            Change::Changed => trf(
+                "watch.changed",
+                &[("row", &self.row), ("columns", &self.columns.join(", "))],
+            ),
+        }
+    }
+}
+
+// Ключ строки — значения ключевых столбцов; без них ключом служит вся строка
+fn key(set: &ResultSet, row: usize, keys: &[&str]) -> Vec<String> {
+    let cells: Vec<String> = keys
+        .iter()
+        .filter_map(|&name| set.value(row, name))
+        .map(|value| value.to_string())
+        .collect();
+    if cells.len() == keys.len() && !keys.is_empty() {
+        return cells;
+    }
+    set.rows[row].iter().map(Value::to_string).collect()
+}
+
+fn index(set: &ResultSet, keys: &[&str]) -> HashMap<Vec<String>, usize> {
+    let mut index = HashMap::new();
+    for row in (0..set.rows.len()).rev() {
+        index.insert(key(set, row, keys), row);
+    }
+    index
+}
+
+/// Различия двух результатов одного запроса по ключевым столбцам `keys`
+pub fn diff(old: &ResultSet, new: &ResultSet, keys: &[&str]) -> Marks {
+    let before = index(old, keys);
+    let after = index(new, keys);
+    let mut marks = Marks::default();
+    for (row_key, &row) in &after {
+        let Some(&old_row) = before.get(row_key) else {
+            marks.added.insert(row);
+            continue;
+        };
+        let changed: HashSet<usize> = new
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(column, c)| old.value(old_row, &c.name) != new.rows[row].get(*column))
+            .map(|(column, _)| column)
+            .collect();
+        if !changed.is_empty() {
+            marks.changed.insert(row, changed);
+        }
+    }
+    let mut removed: Vec<usize> = before
+        .iter()
+        .filter(|(row_key, _)| !after.contains_key(*row_key))
+        .map(|(_, &row)| row)
+        .collect();
+    removed.sort_unstable();
+    marks.removed = removed
+        .into_iter()
+        .map(|row| old.rows[row].clone())
+        .collect();
+    marks
+}
+
+/// Строка нового результата с тем же ключом, что у строки `row` прежнего
+pub fn find_row(old: &ResultSet, new: &ResultSet, keys: &[&str], row: usize) -> Option<usize> {
+    if row >= old.rows.len() {
+        return None;
+    }
+    let wanted = key(old, row, keys);
+    (0..new.rows.len()).find(|&i| key(new, i, keys) == wanted)
+}
+
+/// Наблюдение за категорией: повтор запроса через `interval` секунд и подсветка
+/// отличий от прошлого результата. Работает и для неактивных категорий
+pub struct Watch {
+    pub interval: u64, // Хранится в config.toml
+    pub enabled: bool, // Каждый запуск начинается без наблюдения
+    next: Option<Instant>,
+    marks: Marks,
+    history: VecDeque<Event>,
+}
+
+impl Default for Watch {
+    fn default() -> Self {
+        Self::new(INTERVAL)
+    }
+}
+
+impl Watch {
+    pub fn new(interval: u64) -> Self {
+        Self {
+            interval,
+            enabled: false,
+            next: None,
+            marks: Marks::default(),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Пора ли повторить запрос; следующий повтор планируется сразу
+    pub fn due(&mut self, now: Instant) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let interval = Duration::from_secs(self.interval.max(1));
+        match self.next {
+            Some(next) if now < next => false,
+            _ => {
+                self.next = Some(now + interval);
+                true
+            }
+        }
+    }
+
+    /// Время до следующего повтора, чтобы окно перерисовалось вовремя
+    pub fn until_next(&self, now: Instant) -> Option<Duration> {
+        self.next
+            .filter(|_| self.enabled)
+            .map(|next| next.saturating_duration_since(now))
+    }
+
+    /// Сравнивает новый результат с прежним, дописывает историю и возвращает
+    /// новые записи. Первый результат после включения служит точкой отсчёта
+    pub fn apply(&mut self, old: Option<&ResultSet>, new: &ResultSet, keys: &[&str]) -> Vec<Event> {
+        let Some(old) = old.filter(|_| self.enabled) else {
+            self.marks = Marks::default();
+            return Vec::new();
+        };
+        self.marks = diff(old, new, keys);
+        let at = Local::now();
+        let label = |set: &ResultSet, row: usize| key(set, row, keys).join(" / ");
+        let mut events = Vec::new();
+        let mut added: Vec<usize> = self.marks.added.iter().copied().collect();
+        added.sort_unstable();
+        for row in added {
+            events.push(Event {
+                at,
+                change: Change::Added,
+                row: label(new, row),
+                columns: Vec::new(),
+                index: Some(row),
+            });
+        }
+        let mut changed: Vec<_> = self.marks.changed.iter().collect();
+        changed.sort_unstable_by_key(|(&row, _)| row);
+        for (&row, columns) in changed {
+            let mut columns: Vec<usize> = columns.iter().copied().collect();
+            columns.sort_unstable();
+            events.push(Event {
+                at,
+                change: Change::Changed,
+                row: label(new, row),
+                columns: columns
+                    .into_iter()
+                    .map(|c| new.columns[c].title.clone())
+                    .collect(),
+                index: Some(row),
+            });
+        }
+        for cells in &self.marks.removed {
+            let mut ghost = ResultSet {
+                columns: old.columns.clone(),
+                ..ResultSet::default()
+            };
+            ghost.rows.push(cells.clone());
+            events.push(Event {
+                at,
+                change: Change::Removed,
+                row: label(&ghost, 0),
+                columns: Vec::new(),
+                index: None,
+            });
+        }
+        for event in &events {
+            if self.history.len() == HISTORY {
+                self.history.pop_back();
+            }
+            self.history.push_front(event.clone());
+        }
+        events
+    }
+
+    pub fn marks(&self) -> Option<&Marks> {
+        Some(&self.marks).filter(|_| self.enabled)
+    }
+
+    /// Переключатель наблюдения и интервал повтора
+    pub fn controls(&mut self, ui: &mut egui::Ui) {
+        let response = ui
+            .toggle_value(&mut self.enabled, tr("watch.toggle"))
+            .on_hover_text(tr("watch.hint"));
+        if response.changed() {
+            self.next = None;
+            self.marks = Marks::default();
+        }
+        if self.enabled {
+            ui.add(
+                egui::DragValue::new(&mut self.interval)
+                    .range(2..=3600)
+                    .suffix(tr("query.seconds")),
+            );
+        }
+    }
+
+    /// История изменений, новые записи сверху
+    pub fn history(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.strong(tr("watch.history"));
+            if ui.small_button(tr("watch.clear")).clicked() {
+                self.history.clear();
+            }
+        });
+        if self.history.is_empty() {
+            ui.weak(tr("watch.no_changes"));
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("watch_history")
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for event in &self.history {
+                    let time = event.at.format("%H:%M:%S");
+                    ui.label(format!("{time}  {}", event.text()));
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn services(rows: &[(&str, &str)]) -> ResultSet {
+        let mut set = ResultSet::new(&["Name", "State"]);
+        for &(name, state) in rows {
+            set.rows.push(vec![name.into(), state.into()]);
+        }
+        set
+    }
+
+    #[test]
+    fn diff_by_key_column() {
+        let old = services(&[
+            ("Spooler", "Running"),
+            ("W32Time", "Running"),
+            ("Fax", "Stopped"),
+        ]);
+        let new = services(&[
+            ("W32Time", "Stopped"),
+            ("Spooler", "Running"),
+            ("BITS", "Running"),
+        ]);
+        let marks = diff(&old, &new, &["Name"]);
+        assert_eq!(marks.added, HashSet::from([2]));
+        assert_eq!(marks.changed, HashMap::from([(0, HashSet::from([1]))]));
+        assert_eq!(marks.removed, vec![old.rows[2].clone()]);
+    }
+
+    #[test]
+    fn first_result_is_baseline() {
+        let mut watch = Watch {
+            enabled: true,
+            ..Watch::default()
+        };
+        let set = services(&[("Spooler", "Running")]);
+        assert!(watch.apply(None, &set, &["Name"]).is_empty());
+        assert_eq!(watch.marks(), Some(&Marks::default()));
+        assert!(watch.history.is_empty());
+
+        let next = services(&[("Spooler", "Stopped")]);
+        let events = watch.apply(Some(&set), &next, &["Name"]);
+        assert_eq!(events[0].index, Some(0));
+        assert_eq!(watch.history.len(), 1);
+        assert_eq!(watch.history[0].change, Change::Changed);
+        assert_eq!(watch.history[0].row, "Spooler");
+    }
+
+    #[test]
+    fn selection_follows_key() {
+        let old = services(&[("Spooler", "Running"), ("W32Time", "Running")]);
+        let new = services(&[("BITS", "Running"), ("W32Time", "Stopped")]);
+        assert_eq!(find_row(&old, &new, &["Name"], 1), Some(1));
+        assert_eq!(find_row(&old, &new, &["Name"], 0), None);
+    }
+
+    #[test]
+    fn due_schedules_next_run() {
+        let mut watch = Watch {
+            enabled: true,
+            interval: 5,
+            ..Watch::default()
+        };
+        let now = Instant::now();
+        assert!(watch.due(now));
+        assert!(!watch.due(now + Duration::from_secs(4)));
+        assert!(watch.due(now + Duration::from_secs(5)));
+    }
+}
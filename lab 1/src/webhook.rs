@@ -0,0 +1,398 @@
+use crate::export;
+use crate::findings::{Check, Finding, Severity};
+use crate::http;
+use crate::i18n::{tr, trf};
+use crate::tripwire::{Alert, Trigger};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::time::Duration;
+
+/// Время ожидания каждого этапа запроса
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Попыток доставки: первая и две повторные
+pub const ATTEMPTS: usize = 3;
+
+const RETRY: Duration = Duration::from_secs(2);
+
+/// Раздел `[webhook]`: отправка важных находок и событий наблюдения на внешний адрес
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub url: String,
+    pub min_severity: Severity,
+    pub muted: Vec<Check>,            // Находки этих проверок не отправляются
+    pub muted_triggers: Vec<Trigger>, // Эти наблюдатели не запускаются
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            min_severity: Severity::High,
+            muted: Vec::new(),
+            muted_triggers: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Находка достаточно важна и её проверка не заглушена
+    pub fn wants(&self, finding: &Finding) -> bool {
+        self.enabled
+            && !self.url.trim().is_empty()
+            && finding.severity >= self.min_severity
+            && !self.muted.contains(&finding.check)
+    }
+
+    /// Наблюдатель нужно запускать: его сообщения достаточно важны и не заглушены
+    pub fn wants_trigger(&self, trigger: Trigger) -> bool {
+        self.enabled
+            && !self.url.trim().is_empty()
+            && trigger.severity() >= self.min_severity
+            && !self.muted_triggers.contains(&trigger)
+    }
+
+    /// Наибольшая важность среди находок, которые нужно отправить
+    pub fn severity<'a>(&self, found: impl IntoIterator<Item = &'a Finding>) -> Option<Severity> {
+        found
+            .into_iter()
+            .filter(|f| self.wants(f))
+            .map(|f| f.severity)
+            .max()
+    }
+
+    /// Поля для меню настроек: адрес, порог важности, заглушённые проверки и проверка связи
+    pub fn show(&mut self, ui: &mut egui::Ui, notifier: &mut Notifier) {
+        ui.checkbox(&mut self.enabled, tr("webhook.enabled"))
+            .on_hover_text(tr("webhook.hint"));
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("webhook.url"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.url)
+                        .hint_text("https://hooks.slack.com/services/…")
+                        .desired_width(220.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("notify.min_severity"));
+                egui::ComboBox::from_id_salt("webhook_severity")
+                    .selected_text(self.min_severity.title())
+                    .show_ui(ui, |ui| {
+                        for severity in Severity::ALL {
+                            ui.selectable_value(&mut self.min_severity, severity, severity.title());
+                        }
+                    });
+            });
+            ui.label(tr("webhook.muted"));
+            for check in Check::ALL {
+                let mut muted = self.muted.contains(&check);
+                if ui.checkbox(&mut muted, check.title()).changed() {
+                    if muted {
+                        self.muted.push(check);
+                    } else {
+                        self.muted.retain(|&c| c != check);
+                    }
+                }
+            }
+            ui.label(tr("webhook.muted_triggers"));
+            for trigger in Trigger::ALL {
+                let mut muted = self.muted_triggers.contains(&trigger);
+                if ui.checkbox(&mut muted, trigger.title()).changed() {
+                    if muted {
+                        self.muted_triggers.push(trigger);
+                    } else {
+                        self.muted_triggers.retain(|&t| t != trigger);
+                    }
+                }
+            }
+            notifier.controls(ui, self);
+        });
+    }
+}
+
+/// Тело запроса. Поле `text` читают Slack и Teams, остальные — обычные получатели
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Payload {
+    pub hostname: String,
+    pub timestamp: DateTime<Local>,
+    pub category: String,
+    pub title: String,
+    pub detail: String,
+    pub severity: Severity,
+    pub text: String,
+}
+
+impl Payload {
+    pub fn new(
+        hostname: String,
+        timestamp: DateTime<Local>,
+        severity: Severity,
+        category: String,
+        title: String,
+        detail: String,
+    ) -> Self {
+        let mut text = format!("[{severity:?}] {hostname}: {category} — {title}");
+        if !detail.is_empty() {
+            text.push_str(&format!(" ({detail})"));
+        }
+        Self {
+            hostname,
+            timestamp,
+            category,
+            title,
+            detail,
+            severity,
+            text,
+        }
+    }
+
+    pub fn finding(hostname: String, finding: &Finding) -> Self {
+        Self::new(
+            hostname,
+            Local::now(),
+            finding.severity,
+            finding.category.clone(),
+            finding.title.clone(),
+            finding.detail.clone(),
+        )
+    }
+
+    pub fn alert(hostname: String, alert: &Alert) -> Self {
+        Self::new(
+            hostname,
+            alert.at,
+            alert.trigger.severity(),
+            tr("tripwire.category").to_string(),
+            alert.trigger.title().to_string(),
+            alert.item.clone(),
+        )
+    }
+}
+
+struct Job {
+    url: String,
+    body: String,
+    reply: Option<Sender<Result<u16, String>>>, // Только для проверки связи
+}
+
+/// Доставка в отдельном потоке: интерфейс не ждёт сети,
+/// а ошибки попадают в журнал, а не в диалоги
+#[derive(Default)]
+pub struct Notifier {
+    jobs: Option<Sender<Job>>,
+    test: Option<Receiver<Result<u16, String>>>,
+    test_result: Option<Result<String, String>>,
+}
+
+impl Notifier {
+    fn queue(&mut self, job: Job) {
+        if self.jobs.is_none() {
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || run(rx));
+            self.jobs = Some(tx);
+        }
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(job);
+        }
+    }
+
+    /// Отправляет сообщение, если отправка включена
+    pub fn send(&mut self, settings: &Settings, payload: &Payload) {
+        if !settings.enabled || settings.url.trim().is_empty() {
+            return;
+        }
+        let Ok(body) = serde_json::to_string(payload) else {
+            return;
+        };
+        self.queue(Job {
+            url: settings.url.trim().to_string(),
+            body,
+            reply: None,
+        });
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui, settings: &Settings) {
+        if let Some(test) = &self.test {
+            match test.try_recv() {
+                Ok(result) => {
+                    self.test_result =
+                        Some(result.map(|status| trf("webhook.test_sent", &[("status", &status)])));
+                    self.test = None;
+                }
+                Err(TryRecvError::Empty) => {
+                    ui.ctx().request_repaint_after(Duration::from_millis(100))
+                }
+                Err(TryRecvError::Disconnected) => self.test = None,
+            }
+        }
+        ui.horizontal(|ui| {
+            let idle = self.test.is_none() && !settings.url.trim().is_empty();
+            if ui
+                .add_enabled(idle, egui::Button::new(tr("webhook.test")))
+                .clicked()
+            {
+                let payload = Payload::new(
+                    export::hostname(),
+                    Local::now(),
+                    Severity::Info,
+                    String::new(),
+                    tr("webhook.test_text").to_string(),
+                    String::new(),
+                );
+                let (tx, rx) = mpsc::channel();
+                self.queue(Job {
+                    url: settings.url.trim().to_string(),
+                    body: serde_json::to_string(&payload).unwrap_or_default(),
+                    reply: Some(tx),
+                });
+                self.test = Some(rx);
+                self.test_result = None;
+            }
+            if self.test.is_some() {
+                ui.spinner();
+            }
+            match &self.test_result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(error)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                None => {}
+            }
+        });
+    }
+}
+
+// Поток доставки
+fn run(jobs: Receiver<Job>) {
+    while let Ok(job) = jobs.recv() {
+        let result = deliver(|| post(&job.url, &job.body), RETRY);
+        if let Some(reply) = job.reply {
+            let _ = reply.send(result.map_err(|e| trf("webhook.failed", &[("error", &e)])));
+        }
+    }
+}
+
+// До `ATTEMPTS` попыток `send` с паузой `pause` между ними; итог последней попытки
+fn deliver(mut send: impl FnMut() -> Result<u16, String>, pause: Duration) -> Result<u16, String> {
+    let mut result = Err(String::new());
+    for attempt in 1..=ATTEMPTS {
+        result = send();
+        match &result {
+            Ok(_) => break,
+            Err(error) => {
+                tracing::warn!(attempt, error = %error, "веб-хук не доставлен");
+                if attempt < ATTEMPTS {
+                    std::thread::sleep(pause);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// POST с телом JSON; успехом считается любой ответ 2xx. Возвращает код ответа
+fn post(url: &str, body: &str) -> Result<u16, String> {
+    let headers = "Content-Type: application/json; charset=utf-8\r\n";
+    let status = http::send("POST", url, headers, body.as_bytes(), TIMEOUT)?.status;
+    if (200..300).contains(&status) {
+        Ok(status as u16)
+    } else {
+        Err(trf("webhook.status", &[("status", &status)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings;
+    use chrono::TimeZone;
+
+    fn finding(check: Check, severity: Severity) -> Finding {
+        findings::sample(check, severity, "ROOT\\UNKNOWN\\0000")
+    }
+
+    #[test]
+    fn payload_has_fields_and_chat_text() {
+        let time = Local.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+        let payload = Payload {
+            timestamp: time,
+            ..Payload::finding(
+                "WS-01".to_string(),
+                &finding(Check::ProblemDevices, Severity::High),
+            )
+        };
+        let json: serde_json::Value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["hostname"], "WS-01");
+        assert_eq!(json["severity"], "High");
+        assert_eq!(json["detail"], "ROOT\\UNKNOWN\\0000");
+        assert!(json["timestamp"]
+            .as_str()
+            .unwrap()
+            .starts_with("2024-05-01T12:30:00"));
+        assert_eq!(
+            json["text"],
+            "[High] WS-01: Устройства — Код ошибки 28 (ROOT\\UNKNOWN\\0000)"
+        );
+    }
+
+    #[test]
+    fn threshold_and_mute_list_filter_findings() {
+        let settings = Settings {
+            enabled: true,
+            url: "https://example.com/hook".to_string(),
+            min_severity: Severity::Medium,
+            muted: vec![Check::DeviceStatus],
+            muted_triggers: vec![Trigger::AdminMember],
+        };
+        assert!(!settings.wants_trigger(Trigger::AdminMember));
+        assert!(settings.wants_trigger(Trigger::EventConsumer));
+        let found = [
+            finding(Check::DeviceStatus, Severity::Critical),
+            finding(Check::ProblemDevices, Severity::Low),
+        ];
+        assert_eq!(settings.severity(&found), None);
+        let found = [
+            finding(Check::DeviceStatus, Severity::Critical),
+            finding(Check::ProblemDevices, Severity::High),
+        ];
+        assert_eq!(settings.severity(&found), Some(Severity::High));
+        let disabled = Settings {
+            enabled: false,
+            ..settings
+        };
+        assert!(!disabled.wants(&found[1]));
+    }
+
+    #[test]
+    fn delivery_stops_after_success_or_last_attempt() {
+        let mut calls = 0;
+        let result = deliver(
+            || {
+                calls += 1;
+                if calls < 2 {
+                    Err("503".to_string())
+                } else {
+                    Ok(204)
+                }
+            },
+            Duration::ZERO,
+        );
+        assert_eq!((result, calls), (Ok(204), 2));
+        let mut calls = 0;
+        let result = deliver(
+            || {
+                calls += 1;
+                Err(format!("попытка {calls}"))
+            },
+            Duration::ZERO,
+        );
+        assert_eq!(result, Err(format!("попытка {ATTEMPTS}")));
+    }
+}
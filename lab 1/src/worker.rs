@@ -0,0 +1,187 @@
+use crate::apartment::Apartment;
+use crate::connection::{self, Connection, Target};
+use crate::error::AppError;
+use crate::i18n::tr;
+use futures::channel::{mpsc as jobs, oneshot};
+use futures::future::{self, Either, LocalBoxFuture};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::Duration;
+use windows::Win32::System::Com::COINIT_MULTITHREADED;
+
+// Сколько перечислений WMI выполняется одновременно; при большем числе служба
+// начинает отвечать заметно медленнее
+const MAX_PARALLEL: usize = 4;
+
+/// Задание для потока запросов. Получает подключение потока и возвращает future,
+/// которая выполняется вместе с другими заданиями
+type Job = Box<dyn FnOnce(Session) -> LocalBoxFuture<'static, ()> + Send>;
+
+/// Подключение потока запросов или ошибка подключения, общее для всех заданий
+#[derive(Clone)]
+pub struct Session(Rc<Result<Connection, AppError>>);
+
+impl Session {
+    pub fn connection(&self) -> Result<&Connection, AppError> {
+        self.0.as_ref().as_ref().map_err(AppError::clone)
+    }
+}
+
+/// Ожидание результата задания. Удаление отменяет задание: его future удаляется,
+/// не дожидаясь ответа WMI
+pub struct Ticket<T> {
+    result: Receiver<T>,
+    _cancel: oneshot::Receiver<()>,
+}
+
+impl<T> Ticket<T> {
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.result.try_recv()
+    }
+
+    pub fn wait(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.result.recv_timeout(timeout)
+    }
+}
+
+/// Фоновый поток со своей инициализацией COM и подключением к WMI. Задания выполняются
+/// асинхронным API WMI, не больше `MAX_PARALLEL` одновременно, остальные ждут очереди
+pub struct Worker {
+    target: Target,
+    jobs: Option<jobs::UnboundedSender<Job>>,
+}
+
+impl Worker {
+    pub fn new(target: Target) -> Self {
+        Self { target, jobs: None }
+    }
+
+    /// Ставит задание в очередь; поток создаётся при первом задании
+    pub fn submit<T, F>(&mut self, job: impl FnOnce(Session) -> F + Send + 'static) -> Ticket<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + 'static,
+    {
+        let (tx, result) = mpsc::channel();
+        let (mut cancel_tx, cancel) = oneshot::channel::<()>();
+        let mut job: Job = Box::new(move |session| {
+            Box::pin(async move {
+                let work = Box::pin(job(session));
+                match future::select(work, cancel_tx.cancellation()).await {
+                    Either::Left((value, _)) => {
+                        let _ = tx.send(value);
+                    }
+                    Either::Right(_) => tracing::debug!("задание отменено"),
+                }
+            })
+        });
+        // Если поток завершился аварийно, задание возвращается и уходит новому потоку
+        loop {
+            let jobs = match &self.jobs {
+                Some(jobs) => jobs,
+                None => self.jobs.insert(spawn(self.target.clone())),
+            };
+            match jobs.unbounded_send(job) {
+                Ok(()) => {
+                    return Ticket {
+                        result,
+                        _cancel: cancel,
+                    }
+                }
+                Err(e) => {
+                    job = e.into_inner();
+                    self.jobs = None;
+                }
+            }
+        }
+    }
+
+    /// Выполняет задание и ждёт результат не дольше `timeout`.
+    /// `None` — время вышло или поток завершился
+    pub fn run<T, F>(
+        &mut self,
+        timeout: Duration,
+        job: impl FnOnce(Session) -> F + Send + 'static,
+    ) -> Option<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + 'static,
+    {
+        match self.submit(job).wait(timeout) {
+            Ok(result) => Some(result),
+            Err(RecvTimeoutError::Timeout) => {
+                self.recycle();
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Отказ от текущего потока, например после зависшего синхронного запроса.
+    /// Поток доделает уже полученные задания и завершится, следующее задание
+    /// получит новый поток
+    pub fn recycle(&mut self) {
+        self.jobs = None;
+    }
+}
+
+fn spawn(target: Target) -> jobs::UnboundedSender<Job> {
+    let (tx, rx) = jobs::unbounded::<Job>();
+    std::thread::spawn(move || {
+        // Приёмник асинхронных запросов вызывается из потоков WMI, поэтому MTA.
+        // Подключение объявлено после COM и удаляется раньше него
+        let apartment = Apartment::init(COINIT_MULTITHREADED).map_err(AppError::ComInit);
+        let con = apartment
+            .as_ref()
+            .map_err(AppError::clone)
+            .and_then(|apartment| connection::connect(apartment.com(), &target, "root\\cimv2"));
+        let session = Session(Rc::new(con));
+        futures::executor::block_on(
+            rx.map(|job| job(session.clone()))
+                .buffer_unordered(MAX_PARALLEL)
+                .for_each(future::ready),
+        );
+    });
+    tx
+}
+
+/// Время ожидания одного запроса, в секундах
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timeout(u64);
+
+impl Default for Timeout {
+    fn default() -> Self {
+        Timeout(30)
+    }
+}
+
+impl Timeout {
+    pub fn from_secs(secs: u64) -> Self {
+        Timeout(secs)
+    }
+
+    pub fn duration(self) -> Duration {
+        Duration::from_secs(self.0)
+    }
+
+    /// Ошибка для категории или раздела отчёта, не дождавшихся ответа
+    pub fn error(self) -> AppError {
+        AppError::Timeout { secs: self.0 }
+    }
+
+    /// Поле настройки для меню параметров
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("query.timeout_label"));
+            ui.add(
+                egui::DragValue::new(&mut self.0)
+                    .range(5..=600)
+                    .suffix(tr("query.seconds")),
+            );
+        });
+    }
+}
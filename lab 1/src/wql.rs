@@ -0,0 +1,293 @@
+use crate::i18n::{self, tr};
+use crate::model::{ResultSet, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Вид поля условия отбора
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    /// Подстрока: `LIKE '%…%'`
+    Contains,
+    /// Точное совпадение с одним из значений
+    Choice(&'static [&'static str]),
+    /// Логическое свойство: любое, да или нет
+    Bool,
+}
+
+/// Поле условия отбора; подпись берётся из заголовка столбца свойства
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field {
+    pub property: &'static str,
+    pub kind: FieldKind,
+}
+
+/// Класс WMI строк категории и свойства, однозначно определяющие объект
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Identity {
+    pub class: &'static str,
+    pub keys: &'static [&'static str],
+}
+
+impl Identity {
+    /// Запрос всех свойств объекта строки `row`; `None`, если у строки нет
+    /// значения какого-либо ключевого свойства
+    pub fn query(&self, set: &ResultSet, row: usize) -> Option<String> {
+        let cells = set.rows.get(row)?;
+        let conditions = self
+            .keys
+            .iter()
+            .map(|&key| {
+                let index = set.columns.iter().position(|c| c.name == key)?;
+                let literal = match cells.get(index)? {
+                    Value::Text(text) => quote(text),
+                    Value::Int(number) => number.to_string(),
+                    Value::Bool(true) => "TRUE".to_string(),
+                    Value::Bool(false) => "FALSE".to_string(),
+                    Value::Null | Value::Float(_) => return None,
+                };
+                Some(format!("{key} = {literal}"))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(format!(
+            "SELECT * FROM {} WHERE {}",
+            self.class,
+            conditions.join(" AND ")
+        ))
+    }
+}
+
+/// Строковый литерал WQL: обратная косая черта и одинарная кавычка экранируются
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+// Символы шаблона LIKE в пользовательском вводе берутся в квадратные скобки
+fn like_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' | '_' | '[' => {
+                out.push('[');
+                out.push(c);
+                out.push(']');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn condition(field: &Field, input: &str) -> Option<String> {
+    if input.is_empty() {
+        return None;
+    }
+    let property = field.property;
+    match field.kind {
+        FieldKind::Contains => Some(format!(
+            "{property} LIKE {}",
+            quote(&format!("%{}%", like_escape(input)))
+        )),
+        FieldKind::Choice(_) => Some(format!("{property} = {}", quote(input))),
+        FieldKind::Bool => match input {
+            "true" => Some(format!("{property} = TRUE")),
+            "false" => Some(format!("{property} = FALSE")),
+            _ => None,
+        },
+    }
+}
+
+/// Условие WHERE (без самого слова WHERE) по заполненным полям; пустые поля пропускаются
+pub fn where_clause(fields: &[Field], inputs: &HashMap<String, String>) -> Option<String> {
+    let conditions: Vec<_> = fields
+        .iter()
+        .filter_map(|field| condition(field, inputs.get(field.property)?))
+        .collect();
+    (!conditions.is_empty()).then(|| conditions.join(" AND "))
+}
+
+/// Условия отбора категории: вводимые значения и применённое условие
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryFilter {
+    inputs: HashMap<String, String>,
+    applied: Option<String>,
+}
+
+impl QueryFilter {
+    pub fn applied(&self) -> Option<&str> {
+        self.applied.as_deref()
+    }
+
+    /// Поля ввода, кнопки применения и строка применённого условия.
+    /// Возвращает `true`, если условие изменилось и запрос нужно повторить
+    pub fn show(&mut self, ui: &mut egui::Ui, fields: &[Field]) -> bool {
+        let mut changed = false;
+        ui.collapsing(tr("where.title"), |ui| {
+            egui::Grid::new("where_fields")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for field in fields {
+                        ui.label(i18n::column(field.property));
+                        let input = self.inputs.entry(field.property.to_string()).or_default();
+                        match field.kind {
+                            FieldKind::Contains => {
+                                ui.text_edit_singleline(input);
+                            }
+                            FieldKind::Choice(options) => {
+                                choice(ui, field.property, input, options.iter().map(|&o| (o, o)));
+                            }
+                            FieldKind::Bool => {
+                                let options =
+                                    [("true", tr("value.yes")), ("false", tr("value.no"))];
+                                choice(ui, field.property, input, options);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            ui.horizontal(|ui| {
+                if ui.button(tr("where.apply")).clicked() {
+                    let clause = where_clause(fields, &self.inputs);
+                    changed = clause != self.applied;
+                    self.applied = clause;
+                }
+                if ui.button(tr("where.reset")).clicked() {
+                    self.inputs.clear();
+                    changed = self.applied.take().is_some();
+                }
+            });
+        });
+
+        if let Some(clause) = self.applied.clone() {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("WHERE {clause}")).monospace());
+                if ui
+                    .small_button("✖")
+                    .on_hover_text(tr("where.clear"))
+                    .clicked()
+                {
+                    self.applied = None;
+                    changed = true;
+                }
+            });
+        }
+        changed
+    }
+}
+
+// Выпадающий список с пустым вариантом «любое»
+fn choice<'a>(
+    ui: &mut egui::Ui,
+    id: &str,
+    input: &mut String,
+    options: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    let options: Vec<_> = options.into_iter().collect();
+    let selected = options
+        .iter()
+        .find(|(value, _)| value == input)
+        .map(|(_, title)| *title)
+        .unwrap_or_else(|| tr("where.any"));
+    egui::ComboBox::from_id_salt(("where", id))
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(input, String::new(), tr("where.any"));
+            for (value, title) in options {
+                ui.selectable_value(input, value.to_string(), title);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: [Field; 3] = [
+        Field {
+            property: "Name",
+            kind: FieldKind::Contains,
+        },
+        Field {
+            property: "Status",
+            kind: FieldKind::Choice(&["OK", "Error"]),
+        },
+        Field {
+            property: "LocalAccount",
+            kind: FieldKind::Bool,
+        },
+    ];
+
+    fn inputs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn quote_escapes_quotes_and_backslashes() {
+        assert_eq!(quote("O'Brien"), r"'O\'Brien'");
+        assert_eq!(quote(r"C:\Temp"), r"'C:\\Temp'");
+    }
+
+    #[test]
+    fn empty_inputs_give_no_clause() {
+        assert_eq!(where_clause(&FIELDS, &HashMap::new()), None);
+        assert_eq!(where_clause(&FIELDS, &inputs(&[("Name", "")])), None);
+    }
+
+    #[test]
+    fn contains_escapes_like_wildcards() {
+        let clause = where_clause(&FIELDS, &inputs(&[("Name", "50%_it's")]));
+        assert_eq!(clause.as_deref(), Some(r"Name LIKE '%50[%][_]it\'s%'"));
+    }
+
+    #[test]
+    fn identity_query_uses_every_key() {
+        let identity = Identity {
+            class: "Win32_Environment",
+            keys: &["Name", "UserName"],
+        };
+        let mut set = ResultSet::new(&["Name", "VariableValue", "UserName"]);
+        set.rows.push(vec![
+            "TEMP".into(),
+            "C:\\Temp".into(),
+            "WS-01\\Гость".into(),
+        ]);
+        assert_eq!(
+            identity.query(&set, 0).as_deref(),
+            Some(
+                r"SELECT * FROM Win32_Environment WHERE Name = 'TEMP' AND UserName = 'WS-01\\Гость'"
+            )
+        );
+        assert_eq!(identity.query(&set, 1), None);
+    }
+
+    #[test]
+    fn identity_query_needs_key_value() {
+        let identity = Identity {
+            class: "Win32_Process",
+            keys: &["ProcessId"],
+        };
+        let mut set = ResultSet::new(&["ProcessId", "Name"]);
+        set.rows.push(vec![Value::Int(4), "System".into()]);
+        set.rows.push(vec![Value::Null, "Idle".into()]);
+        assert_eq!(
+            identity.query(&set, 0).as_deref(),
+            Some("SELECT * FROM Win32_Process WHERE ProcessId = 4")
+        );
+        assert_eq!(identity.query(&set, 1), None);
+    }
+
+    #[test]
+    fn conditions_are_joined_with_and() {
+        let clause = where_clause(
+            &FIELDS,
+            &inputs(&[("Status", "OK"), ("LocalAccount", "false")]),
+        );
+        assert_eq!(
+            clause.as_deref(),
+            Some("Status = 'OK' AND LocalAccount = FALSE")
+        );
+    }
+}